@@ -1,5 +1,16 @@
 /* auto-generated by NAPI-RS */
 /* eslint-disable */
+/** Animation output options */
+export interface AnimationOptions {
+  /** Number of loops (0 = infinite, default: same as input) */
+  loopCount?: number
+  /**
+   * Frame delays in milliseconds (default: same as input)
+   * A single value applies to every frame; otherwise the last value repeats
+   */
+  delay?: Array<number>
+}
+
 /** Generate blurhash from image asynchronously */
 export declare function blurhash(input: Buffer, componentsX?: number | undefined | null, componentsY?: number | undefined | null): Promise<BlurHashResult>
 
@@ -16,6 +27,73 @@ export interface BlurHashResult {
 /** Generate blurhash from image synchronously */
 export declare function blurhashSync(input: Buffer, componentsX?: number | undefined | null, componentsY?: number | undefined | null): BlurHashResult
 
+/**
+ * Color management - convert decoded pixels from the embedded ICC profile
+ * Images without a profile are treated as sRGB
+ */
+export interface ColorManagementOptions {
+  /** RGB ICC profile to convert to, embedded in the output (default: sRGB, not embedded) */
+  targetProfile?: Array<number>
+  /** Rendering intent (default: Perceptual) */
+  intent?: RenderingIntent
+}
+
+/**
+ * Compare two images asynchronously with SSIM, MS-SSIM, PSNR or DSSIM (default: Ssim)
+ * The second image is resized to the first image's dimensions when they differ
+ */
+export declare function compare(a: Buffer, b: Buffer, metric?: CompareMetric | undefined | null, options?: CompareOptions | undefined | null): Promise<CompareResult>
+
+/** Color space the comparison is computed in */
+export declare const enum CompareColorSpace {
+  /** Luma only (Rec. 601), like the reference SSIM */
+  Luma = 'Luma',
+  /** CIELAB, weighted towards lightness */
+  Lab = 'Lab'
+}
+
+/** Image comparison metric */
+export declare const enum CompareMetric {
+  /** Structural similarity, 0-1 (1 = identical) */
+  Ssim = 'Ssim',
+  /** Multi-scale structural similarity, 0-1 (1 = identical) */
+  MsSsim = 'MsSsim',
+  /** Peak signal-to-noise ratio in dB (Infinity = identical) */
+  Psnr = 'Psnr',
+  /** Structural dissimilarity, 1/SSIM - 1 (0 = identical) */
+  Dssim = 'Dssim'
+}
+
+/** Image comparison options */
+export interface CompareOptions {
+  /** Color space (default: Luma) */
+  colorSpace?: CompareColorSpace
+  /** Return a per-pixel difference heatmap as PNG (default: false) */
+  heatmap?: boolean
+}
+
+/** Image comparison result */
+export interface CompareResult {
+  /** Metric used */
+  metric: CompareMetric
+  /** Score (see CompareMetric for the range) */
+  score: number
+  /** Compared width (dimensions of the first image) */
+  width: number
+  /** Compared height */
+  height: number
+  /** Whether the second image was resized to match the first */
+  resized: boolean
+  /** Difference heatmap PNG (black = identical, white = largest difference) */
+  heatmap?: Array<number>
+}
+
+/**
+ * Compare two images synchronously with SSIM, MS-SSIM, PSNR or DSSIM (default: Ssim)
+ * The second image is resized to the first image's dimensions when they differ
+ */
+export declare function compareSync(a: Buffer, b: Buffer, metric?: CompareMetric | undefined | null, options?: CompareOptions | undefined | null): CompareResult
+
 /** Crop image asynchronously - zero-copy operation */
 export declare function crop(input: Buffer, options: CropOptions): Promise<Buffer>
 
@@ -95,6 +173,50 @@ export interface DominantColorsResult {
  */
 export declare function dominantColorsSync(input: Buffer, count?: number | undefined | null): DominantColorsResult
 
+/** One decoded EXIF tag - exactly one of text, numbers and bytes is set */
+export interface ExifEntry {
+  /** Directory: ifd0, exif, gps or interop */
+  ifd: string
+  /** Tag id */
+  tag: number
+  /** Tag name (e.g. DateTimeOriginal), or hex id for unknown tags */
+  name: string
+  /** ASCII values and text-like UNDEFINED tags (versions, UserComment) */
+  text?: string
+  /** Integer, float and rational values (rationals divided out) */
+  numbers?: Array<number>
+  /** Other UNDEFINED values (MakerNote, ...) */
+  bytes?: Array<number>
+}
+
+/** TIFF field type of an EXIF tag */
+export declare const enum ExifFieldType {
+  /** 8-bit unsigned integers */
+  Byte = 'Byte',
+  /** NUL-terminated text */
+  Ascii = 'Ascii',
+  /** 16-bit unsigned integers */
+  Short = 'Short',
+  /** 32-bit unsigned integers */
+  Long = 'Long',
+  /** Unsigned fractions */
+  Rational = 'Rational',
+  /** Signed fractions */
+  SRational = 'SRational',
+  /** Opaque bytes */
+  Undefined = 'Undefined'
+}
+
+/** EXIF directory a tag is written to */
+export declare const enum ExifIfd {
+  /** Main image directory (Make, Model, Copyright, ...) */
+  Ifd0 = 'Ifd0',
+  /** Exif sub-IFD (DateTimeOriginal, ExposureTime, LensModel, ...) */
+  Exif = 'Exif',
+  /** GPS sub-IFD */
+  Gps = 'Gps'
+}
+
 /** EXIF metadata options for writing */
 export interface ExifOptions {
   /** Image description / caption / AI prompt */
@@ -117,6 +239,41 @@ export interface ExifOptions {
   model?: string
   /** Orientation (1-8) */
   orientation?: number
+  /** GPS latitude in decimal degrees (-90 to 90, negative = south) */
+  gpsLatitude?: number
+  /** GPS longitude in decimal degrees (-180 to 180, negative = west) */
+  gpsLongitude?: number
+  /** GPS altitude in meters (negative = below sea level) */
+  gpsAltitude?: number
+  /** GPS fix time in UTC (YYYY:MM:DD HH:MM:SS or ISO 8601 with Z) */
+  gpsTimestamp?: string
+  /** Arbitrary typed tags, written after (and over) the fields above */
+  tags?: Array<ExifWriteTag>
+  /** XMP packet to embed (JPEG, PNG and WebP) */
+  xmp?: string
+  /** IPTC-IIM datasets to write (JPEG only), replacing any existing IPTC record */
+  iptc?: IptcData
+  /** PNG text chunks to write (PNG only), replacing chunks with the same keyword */
+  text?: Array<PngTextEntry>
+}
+
+/**
+ * Arbitrary EXIF tag to write - `text` for Ascii, `numbers` for numeric types,
+ * `bytes` (or `text`) for Undefined
+ */
+export interface ExifWriteTag {
+  /** Directory (default: Ifd0) */
+  ifd?: ExifIfd
+  /** Tag id, e.g. 0xA434 (LensModel) */
+  tag: number
+  /** Field type */
+  fieldType: ExifFieldType
+  /** Ascii value (Undefined takes its UTF-8 bytes) */
+  text?: string
+  /** Byte, Short, Long, Rational and SRational values - rationals as decimals */
+  numbers?: Array<number>
+  /** Undefined or Byte values */
+  bytes?: Array<number>
 }
 
 /** Image fit mode for resize */
@@ -133,6 +290,26 @@ export declare const enum FitMode {
   Outside = 'Outside'
 }
 
+/** GIF encode options */
+export interface GifOptions {
+  /** Maximum palette size 2-256 (default: 256) */
+  colors?: number
+  /** Floyd-Steinberg dithering amount 0.0-1.0 (default: 1.0) */
+  dither?: number
+}
+
+/** GPS data decoded from the EXIF GPS IFD */
+export interface GpsInfo {
+  /** Decimal degrees, negative = south */
+  latitude?: number
+  /** Decimal degrees, negative = west */
+  longitude?: number
+  /** Meters, negative = below sea level */
+  altitude?: number
+  /** UTC fix time as ISO 8601 (GPSDateStamp + GPSTimeStamp) */
+  timestamp?: string
+}
+
 /** Perceptual hash algorithm */
 export declare const enum HashAlgorithm {
   /** Perceptual hash using DCT (best for most use cases) */
@@ -165,7 +342,14 @@ export declare const enum ImageFormat {
   Ico = 'Ico',
   Tiff = 'Tiff',
   Heic = 'Heic',
-  Avif = 'Avif'
+  Avif = 'Avif',
+  Qoi = 'Qoi',
+  Pnm = 'Pnm',
+  Tga = 'Tga',
+  /** Tightly packed 8-bit pixels (gray, gray+alpha, RGB or RGBA following the source) */
+  Raw = 'Raw',
+  /** Pick JPEG, PNG(-8) or WebP from the image content and `accept` (transform only) */
+  Auto = 'Auto'
 }
 
 /**
@@ -214,7 +398,7 @@ export interface ImageMetadata {
   width: number
   /** Image height in pixels */
   height: number
-  /** Detected format (jpeg, png, webp, gif, bmp, ico, tiff) */
+  /** Detected format (jpeg, png, webp, gif, bmp, ico, tiff, heic, jxl, qoi, pnm, tga, hdr, exr, dds) */
   format: string
   /** File size in bytes (if available) */
   size?: number
@@ -236,31 +420,198 @@ export interface ImageMetadata {
   hasProfile: boolean
   /** EXIF orientation value (1-8, if present) */
   orientation?: number
-  /** Page/frame count for multi-page images (GIF, TIFF) */
+  /** Page/frame count for multi-page images (GIF, WebP, APNG, TIFF) */
   pages?: number
   /** Loop count for animated images */
   loopCount?: number
   /** Delay between frames in ms (for animated images) */
   delay?: Array<number>
+  /** Disposal method per frame: none, background or previous (GIF, WebP, APNG) */
+  disposal?: Array<string>
+  /** Total animation duration in ms (one loop) */
+  duration?: number
   /** Background color (for GIF) */
   background?: Array<number>
   /** Compression type used */
   compression?: string
   /** Density/DPI info */
   density?: number
+  /** Decoded EXIF tags (`exif: true` only) */
+  exif?: Array<ExifEntry>
+  /** TIFF-structured EXIF payload (`exif_raw: true` only) */
+  exifRaw?: Array<number>
+  /** GPS position and fix time (`exif: true` or `gps: true` only) */
+  gps?: GpsInfo
+  /** XMP packet and its common properties (`xmp: true` only) */
+  xmp?: XmpData
+  /** IPTC-IIM datasets from JPEG APP13 (`iptc: true` only) */
+  iptc?: IptcData
+  /** Embedded ICC profile - JPEG APP2 segments are reassembled (`icc: true` only) */
+  iccProfile?: Array<number>
+  /** Description tag of the ICC profile, e.g. "Display P3" (`icc: true` only) */
+  iccDescription?: string
+  /** PNG tEXt, zTXt and iTXt chunks in file order (`text: true` only) */
+  text?: Array<PngTextEntry>
+}
+
+/** IPTC-IIM application record (record 2) datasets */
+export interface IptcData {
+  /** Object Name (2:05) */
+  title?: string
+  /** Keywords (2:25) */
+  keywords?: Array<string>
+  /** By-line / creator (2:80) */
+  byline?: Array<string>
+  /** By-line Title (2:85) */
+  bylineTitle?: string
+  /** City (2:90) */
+  city?: string
+  /** Province/State (2:95) */
+  state?: string
+  /** Country Code (2:100) */
+  countryCode?: string
+  /** Country Name (2:101) */
+  country?: string
+  /** Headline (2:105) */
+  headline?: string
+  /** Credit Line (2:110) */
+  credit?: string
+  /** Source (2:115) */
+  source?: string
+  /** Copyright Notice (2:116) */
+  copyright?: string
+  /** Caption/Abstract (2:120) */
+  caption?: string
+}
+
+/**
+ * Lossless JPEG transform options (no decode/re-encode)
+ * Applied in order: crop → rotate → flip
+ */
+/** JPEG output color space */
+export declare const enum JpegColorSpace {
+  /** YCbCr from RGB */
+  Rgb = 'Rgb',
+  /** Single luma channel */
+  Gray = 'Gray',
+  /** Adobe YCCK from CMYK (print) */
+  Cmyk = 'Cmyk'
 }
 
+export interface JpegLosslessOptions {
+  /** Crop region - x/y must be multiples of the MCU size (8 or 16 px) */
+  crop?: CropOptions
+  /** Rotate degrees (90, 180, 270) */
+  rotate?: number
+  /** Flip horizontally */
+  flipH?: boolean
+  /** Flip vertically */
+  flipV?: boolean
+  /**
+   * Drop partial MCU blocks on the edges that cannot be transformed
+   * When false, transforms that would leave them in place fail instead (default: false)
+   */
+  trim?: boolean
+  /** Discard color data and output grayscale (default: false) */
+  grayscale?: boolean
+  /** Write progressive output (default: false) */
+  progressive?: boolean
+  /** Optimize Huffman tables (default: false) */
+  optimizeCoding?: boolean
+  /** Set the EXIF orientation tag to 1 (normal) after transforming (default: false) */
+  resetOrientation?: boolean
+  /** Drop all extra markers including EXIF and ICC (default: false - markers are copied) */
+  stripMetadata?: boolean
+}
+
+/** Rotate, flip or crop a JPEG losslessly (no re-encode) asynchronously */
+export declare function jpegLosslessTransform(input: Buffer, options: JpegLosslessOptions): Promise<Buffer>
+
+/** Rotate, flip or crop a JPEG losslessly (no re-encode) synchronously */
+export declare function jpegLosslessTransformSync(input: Buffer, options: JpegLosslessOptions): Buffer
+
 /** JPEG encode options */
 export interface JpegOptions {
   /** Quality 1-100 (default: 80) */
   quality?: number
+  /** Progressive scans (default: false, or true with mozjpeg) */
+  progressive?: boolean
+  /** Chroma subsampling: "4:4:4", "4:2:2" or "4:2:0" (default: "4:2:0") */
+  chromaSubsampling?: string
+  /** Optimize Huffman tables (default: false, or true with mozjpeg) */
+  optimizeCoding?: boolean
+  /** Trellis quantisation - mozjpeg only (default: true) */
+  trellisQuantisation?: boolean
+  /** Overshoot deringing for hard edges on white - mozjpeg only (default: true) */
+  overshootDeringing?: boolean
+  /** Base quantization table preset - mozjpeg only (default: ImageMagick) */
+  quantTable?: JpegQuantTable
+  /**
+   * Encode with mozjpeg instead of turbojpeg (default: false)
+   * Slower, but typically produces 10-20% smaller files
+   */
+  mozjpeg?: boolean
+  /** Output color space (default: Gray for grayscale images, Rgb otherwise) */
+  colorSpace?: JpegColorSpace
+  /** ICC profile embedded in the output - Cmyk output is converted with it */
+  iccProfile?: Array<number>
 }
 
-/** Get image metadata asynchronously */
-export declare function metadata(input: Buffer): Promise<ImageMetadata>
+/** mozjpeg base quantization table preset */
+export declare const enum JpegQuantTable {
+  /** JPEG Annex K (libjpeg default) */
+  AnnexK = 'AnnexK',
+  /** Flat table */
+  Flat = 'Flat',
+  /** Tuned for MS-SSIM */
+  MsSsim = 'MsSsim',
+  /** ImageMagick table by N. Robidoux (mozjpeg default) */
+  ImageMagick = 'ImageMagick',
+  /** Tuned for PSNR-HVS-M */
+  PsnrHvsM = 'PsnrHvsM',
+  /** Klein, Silverstein and Carney (1992) */
+  KleinSilversteinCarney = 'KleinSilversteinCarney',
+  /** Watson, Taylor and Borthwick (1997) */
+  WatsonTaylorBorthwick = 'WatsonTaylorBorthwick',
+  /** Ahumada, Watson and Peterson (1993) */
+  AhumadaWatsonPeterson = 'AhumadaWatsonPeterson',
+  /** Peterson, Ahumada and Watson (1993) */
+  PetersonAhumadaWatson = 'PetersonAhumadaWatson'
+}
 
-/** Get image metadata synchronously */
-export declare function metadataSync(input: Buffer): ImageMetadata
+/** Get image metadata asynchronously - `exif: true` also decodes all EXIF tags */
+export declare function metadata(input: Buffer, options?: MetadataOptions | undefined | null): Promise<ImageMetadata>
+
+/** Metadata read options */
+export interface MetadataOptions {
+  /** Decode all EXIF tags (IFD0, Exif, GPS, Interop) (default: false) */
+  exif?: boolean
+  /** Return the raw EXIF payload (TIFF header onwards) (default: false) */
+  exifRaw?: boolean
+  /** Summarize the GPS IFD as decimal coordinates (default: false) */
+  gps?: boolean
+  /** Read the XMP packet and its common properties (default: false) */
+  xmp?: boolean
+  /** Read the IPTC-IIM record of JPEG files (default: false) */
+  iptc?: boolean
+  /** Return the embedded ICC profile (JPEG, PNG, WebP) and its description (default: false) */
+  icc?: boolean
+  /** Read PNG tEXt, zTXt and iTXt chunks (default: false) */
+  text?: boolean
+}
+
+/** Input metadata transform and thumbnail copy to the output, across formats */
+export interface MetadataPolicy {
+  /**
+   * What to keep - everything when unset, nothing when empty
+   * exif, icc, xmp, iptc (JPEG output), text (PNG output), or EXIF categories
+   * (gps, copyright, artist, date, ...) to keep only those EXIF tags
+   */
+  keep?: Array<string>
+}
+
+/** Get image metadata synchronously - `exif: true` also decodes all EXIF tags */
+export declare function metadataSync(input: Buffer, options?: MetadataOptions | undefined | null): ImageMetadata
 
 /** Output format options */
 export interface OutputOptions {
@@ -272,12 +623,76 @@ export interface OutputOptions {
   png?: PngOptions
   /** WebP options (if format is WebP) */
   webp?: WebPOptions
+  /** GIF options (if format is GIF) */
+  gif?: GifOptions
+  /** Maximum output size in bytes - searches the JPEG/WebP quality until it fits */
+  maxBytes?: number
+  /** Step dimensions down when max_bytes can't be met at quality 1 (default: false) */
+  downscaleToFit?: boolean
+  /** Pick the lowest JPEG/WebP quality that still meets a perceptual metric */
+  targetQuality?: TargetQuality
+  /** Formats the client accepts, for `format: Auto` (default: Jpeg, Png, WebP) */
+  accept?: Array<ImageFormat>
 }
 
 /** PNG encode options */
 export interface PngOptions {
   /** Compression level 0-9 (default: 6) */
   compression?: number
+  /** Quantize to an indexed palette (PNG-8) - implied by colors/dither/quality (default: false) */
+  palette?: boolean
+  /** Maximum palette size 2-256 (default: 256) */
+  colors?: number
+  /** Floyd-Steinberg dithering amount 0.0-1.0 (default: 1.0) */
+  dither?: number
+  /** Target quality 0-100 - stops adding colors once reached (default: 100) */
+  quality?: number
+  /** Minimum quality 0-100 - below it a truecolor PNG is written instead (default: 0) */
+  minQuality?: number
+  /** Lossless optimization level 0-6 - reduces color type/bit depth and tries more filters (default: 0) */
+  optimize?: number
+  /** Keep ancillary chunks (text, EXIF, ICC) from a PNG source when optimizing (default: false) */
+  keepMetadata?: boolean
+  /** ICC profile embedded in the output as an iCCP chunk */
+  iccProfile?: Array<number>
+}
+
+/** PNG text chunk (tEXt, zTXt or iTXt) */
+export interface PngTextEntry {
+  /** Keyword, e.g. Title, Author, parameters (Stable Diffusion), prompt/workflow (ComfyUI) */
+  keyword: string
+  /** Text, decompressed */
+  text: string
+  /** Language tag (iTXt only, e.g. "en-US") */
+  language?: string
+  /** Keyword translated into the language (iTXt only) */
+  translatedKeyword?: string
+  /** Stored deflate-compressed (zTXt, or iTXt with the compression flag) (default: false) */
+  compressed?: boolean
+}
+
+/** Raw pixel output */
+export interface RawImage {
+  /** Tightly packed 8-bit pixels, row-major */
+  data: Array<number>
+  /** Width in pixels */
+  width: number
+  /** Height in pixels */
+  height: number
+  /** Channels per pixel (1 = gray, 2 = gray+alpha, 3 = RGB, 4 = RGBA) */
+  channels: number
+}
+
+/** ICC rendering intent used when converting between color profiles */
+export declare const enum RenderingIntent {
+  /** Compress the whole gamut to fit, keeping relations between colors (photos) */
+  Perceptual = 'Perceptual',
+  /** Keep in-gamut colors exact, clip the rest, adapt the white point */
+  RelativeColorimetric = 'RelativeColorimetric',
+  /** Keep colors vivid at the expense of accuracy (charts, graphics) */
+  Saturation = 'Saturation',
+  /** Keep in-gamut colors exact including the white point (proofing) */
+  AbsoluteColorimetric = 'AbsoluteColorimetric'
 }
 
 /** Resize image asynchronously - uses scale-on-decode for JPEG optimization */
@@ -376,10 +791,32 @@ export interface SmartCropOptions {
 export declare function smartCropSync(input: Buffer, options: SmartCropOptions): Buffer
 
 /** Strip EXIF metadata from an image asynchronously */
-export declare function stripExif(input: Buffer): Promise<Buffer>
+export declare function stripExif(input: Buffer, options?: StripExifOptions | undefined | null): Promise<Buffer>
+
+/**
+ * Selective EXIF stripping
+ * Categories: gps, maker_note, serial, copyright, artist, date, camera,
+ * description, software, orientation, thumbnail, other
+ */
+export interface StripExifOptions {
+  /** Keep only these categories (orientation is kept unless removed explicitly) */
+  keep?: Array<string>
+  /** Remove these categories (takes precedence over keep) */
+  remove?: Array<string>
+}
 
 /** Strip EXIF metadata from an image synchronously */
-export declare function stripExifSync(input: Buffer): Buffer
+export declare function stripExifSync(input: Buffer, options?: StripExifOptions | undefined | null): Buffer
+
+/** Perceptual quality target for adaptive encoding */
+export interface TargetQuality {
+  /** Metric to measure (default: Ssim) */
+  metric?: CompareMetric
+  /** Score to reach - minimum for Ssim/MsSsim/Psnr, maximum for Dssim */
+  value: number
+  /** Color space the metric is computed in (default: Luma) */
+  colorSpace?: CompareColorSpace
+}
 
 /** Tensor data type */
 export declare const enum TensorDtype {
@@ -561,6 +998,28 @@ export interface ThumbnailOptions {
    * This can be 2-4x faster than normal mode with slight quality tradeoff
    */
   fastMode?: boolean
+  /**
+   * Keep animation for animated GIF/WebP/APNG input (default: false)
+   * Output defaults to animated WebP; PNG output is APNG; JPEG uses the first frame
+   * Cannot be combined with max_bytes
+   */
+  animated?: boolean
+  /**
+   * Maximum output size in bytes - searches the quality (still JPEG/WebP only)
+   * PNG input defaults to WebP output when set
+   */
+  maxBytes?: number
+  /** Step dimensions down when max_bytes can't be met at quality 1 (default: false) */
+  downscaleToFit?: boolean
+  /**
+   * Copy the input's ICC profile into the output (default: false)
+   * Ignored with color_management - the output is in the target profile
+   */
+  keepIcc?: boolean
+  /** Convert pixels from the embedded ICC profile to sRGB or a target profile */
+  colorManagement?: ColorManagementOptions
+  /** Copy input metadata to the output - without it the output carries none */
+  metadata?: MetadataPolicy
 }
 
 /** Fast thumbnail result with metadata */
@@ -578,6 +1037,10 @@ export interface ThumbnailResult {
   /** Original image dimensions */
   originalWidth: number
   originalHeight: number
+  /** Quality chosen by the max_bytes search */
+  quality?: number
+  /** Number of encodes tried by the max_bytes search */
+  attempts?: number
 }
 
 /**
@@ -599,6 +1062,18 @@ export interface ThumbnailResult {
  */
 export declare function thumbnailSync(input: Buffer, options: ThumbnailOptions): ThumbnailResult
 
+/**
+ * Convert image to animated WebP asynchronously
+ * Animated GIF/WebP input keeps every frame; static input becomes a single frame
+ */
+export declare function toAnimatedWebp(input: Buffer, options?: WebPOptions | undefined | null, animationOptions?: AnimationOptions | undefined | null): Promise<Buffer>
+
+/**
+ * Convert image to animated WebP synchronously
+ * Animated GIF/WebP input keeps every frame; static input becomes a single frame
+ */
+export declare function toAnimatedWebpSync(input: Buffer, options?: WebPOptions | undefined | null, animationOptions?: AnimationOptions | undefined | null): Buffer
+
 /** Convert image to JPEG asynchronously */
 export declare function toJpeg(input: Buffer, options?: JpegOptions | undefined | null): Promise<Buffer>
 
@@ -611,6 +1086,12 @@ export declare function toPng(input: Buffer, options?: PngOptions | undefined |
 /** Convert image to PNG synchronously */
 export declare function toPngSync(input: Buffer, options?: PngOptions | undefined | null): Buffer
 
+/** Decode image to raw 8-bit pixels asynchronously */
+export declare function toRaw(input: Buffer): Promise<RawImage>
+
+/** Decode image to raw 8-bit pixels synchronously */
+export declare function toRawSync(input: Buffer): RawImage
+
 /**
  * Convert image to tensor format asynchronously
  * Optimized for ML preprocessing with SIMD and parallel processing
@@ -656,13 +1137,58 @@ export interface TransformOptions {
   brightness?: number
   /** Contrast adjustment (-100 to 100) */
   contrast?: number
-  /** EXIF metadata to write (for JPEG/WebP output) */
+  /** EXIF metadata to write (JPEG/WebP, text chunks for PNG) - replaces EXIF kept by `metadata` */
   exif?: ExifOptions
+  /** XMP packet to embed in the output (JPEG, PNG and WebP) */
+  xmp?: string
+  /**
+   * Copy the input's ICC profile into the output (JPEG, PNG and WebP) (default: false)
+   * Ignored with color_management - the output is in the target profile
+   */
+  keepIcc?: boolean
+  /** Convert pixels from the embedded ICC profile to sRGB or a target profile */
+  colorManagement?: ColorManagementOptions
+  /** Copy input metadata to the output - without it the output carries none */
+  metadata?: MetadataPolicy
+  /**
+   * Process every frame of animated input (GIF/WebP/APNG) and keep the animation
+   * Only applies when the output format is WebP, GIF or PNG (default: false)
+   * Cannot be combined with output.maxBytes or output.targetQuality
+   */
+  animated?: boolean
+  /** Animation output options (loop count, frame delays) */
+  animation?: AnimationOptions
+}
+
+/** Transform result with encoder details */
+export interface TransformResult {
+  /** The output image data */
+  data: Array<number>
+  /** Output width */
+  width: number
+  /** Output height */
+  height: number
+  /** Quality chosen by the max_bytes or target_quality search */
+  quality?: number
+  /** Number of encodes tried by the search */
+  attempts?: number
+  /** Metric score reached by the target_quality search */
+  score?: number
+  /** Channels per pixel (raw output only) */
+  channels?: number
+  /** Output format (jpeg, png, webp, ...) - the chosen one for `format: Auto` */
+  format: string
 }
 
 /** Transform image with multiple operations synchronously */
 export declare function transformSync(input: Buffer, options: TransformOptions): Buffer
 
+/** Transform image asynchronously, returning output dimensions and the max_bytes/target_quality search result */
+export declare function transformWithInfo(input: Buffer, options: TransformOptions): Promise<TransformResult>
+
+/** Transform image synchronously, returning output dimensions and the max_bytes/target_quality search result */
+export declare function transformWithInfoSync(input: Buffer, options: TransformOptions): TransformResult
+
 /** Get library version */
 export declare function version(): string
 
@@ -672,6 +1198,40 @@ export interface WebPOptions {
   quality?: number
   /** Use lossless compression (default: false) */
   lossless?: boolean
+  /** Speed/size trade-off 0-6, higher is slower and smaller (default: 4) */
+  method?: number
+  /** Alpha plane quality 0-100 (default: 100) */
+  alphaQuality?: number
+  /** Near-lossless preprocessing 0-100, lower is lossier; implies lossless (default: 100 = off) */
+  nearLossless?: number
+  /** Sharp (slower) RGB to YUV conversion for crisper chroma edges (default: false) */
+  smartSubsample?: boolean
+  /** Content preset applied before the other options (default: "Default") */
+  preset?: WebPPreset
+  /** Keep RGB values under fully transparent pixels (default: false) */
+  exact?: boolean
+  /** Target output size in bytes - lossy only, overrides quality (default: off) */
+  targetSize?: number
+  /** Target PSNR in dB - lossy only, takes precedence over targetSize (default: off) */
+  targetPsnr?: number
+  /** ICC profile embedded in the output as an ICCP chunk (still images only) */
+  iccProfile?: Array<number>
+}
+
+/** libwebp encoder preset (tunes filtering and spatial noise shaping for the content) */
+export declare const enum WebPPreset {
+  /** General purpose (libwebp default) */
+  Default = 'Default',
+  /** Digital picture, like portrait or indoor shot */
+  Picture = 'Picture',
+  /** Outdoor photograph with natural lighting */
+  Photo = 'Photo',
+  /** Hand or line drawing with high-contrast details */
+  Drawing = 'Drawing',
+  /** Small-sized colorful image */
+  Icon = 'Icon',
+  /** Text-like content */
+  Text = 'Text'
 }
 
 /** Write EXIF metadata to a WebP image asynchronously */
@@ -679,3 +1239,41 @@ export declare function writeExif(input: Buffer, options: ExifOptions): Promise<
 
 /** Write EXIF metadata to a WebP image synchronously */
 export declare function writeExifSync(input: Buffer, options: ExifOptions): Buffer
+
+/** XMP packet with the common Dublin Core, Photoshop, IPTC Core and XMP Rights properties */
+export interface XmpData {
+  /** Raw XMP packet */
+  packet: string
+  /** Reassembled JPEG extended XMP (packets over 64 KB) */
+  extended?: string
+  /** dc:title (x-default) */
+  title?: string
+  /** dc:description (x-default) */
+  description?: string
+  /** dc:creator */
+  creator?: Array<string>
+  /** dc:rights (x-default) */
+  rights?: string
+  /** dc:subject (keywords) */
+  subject?: Array<string>
+  /** photoshop:Headline */
+  headline?: string
+  /** photoshop:Credit */
+  credit?: string
+  /** photoshop:Source */
+  source?: string
+  /** photoshop:City */
+  city?: string
+  /** photoshop:State */
+  state?: string
+  /** photoshop:Country */
+  country?: string
+  /** Iptc4xmpCore:CountryCode */
+  countryCode?: string
+  /** Iptc4xmpCore:Location */
+  location?: string
+  /** xmpRights:UsageTerms (x-default) */
+  usageTerms?: string
+  /** xmpRights:WebStatement */
+  webStatement?: string
+}
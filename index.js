@@ -578,6 +578,10 @@ if (!nativeBinding) {
 module.exports = nativeBinding
 module.exports.blurhash = nativeBinding.blurhash
 module.exports.blurhashSync = nativeBinding.blurhashSync
+module.exports.compare = nativeBinding.compare
+module.exports.CompareColorSpace = nativeBinding.CompareColorSpace
+module.exports.CompareMetric = nativeBinding.CompareMetric
+module.exports.compareSync = nativeBinding.compareSync
 module.exports.crop = nativeBinding.crop
 module.exports.CropGravity = nativeBinding.CropGravity
 module.exports.cropSync = nativeBinding.cropSync
@@ -591,6 +595,9 @@ module.exports.imageHash = nativeBinding.imageHash
 module.exports.imageHashDistance = nativeBinding.imageHashDistance
 module.exports.imageHashDistanceSync = nativeBinding.imageHashDistanceSync
 module.exports.imageHashSync = nativeBinding.imageHashSync
+module.exports.jpegLosslessTransform = nativeBinding.jpegLosslessTransform
+module.exports.jpegLosslessTransformSync = nativeBinding.jpegLosslessTransformSync
+module.exports.JpegQuantTable = nativeBinding.JpegQuantTable
 module.exports.metadata = nativeBinding.metadata
 module.exports.metadataSync = nativeBinding.metadataSync
 module.exports.resize = nativeBinding.resize
@@ -614,16 +621,23 @@ module.exports.thumbnailBuffer = nativeBinding.thumbnailBuffer
 module.exports.thumbnailBufferSync = nativeBinding.thumbnailBufferSync
 module.exports.ThumbnailFormat = nativeBinding.ThumbnailFormat
 module.exports.thumbnailSync = nativeBinding.thumbnailSync
+module.exports.toAnimatedWebp = nativeBinding.toAnimatedWebp
+module.exports.toAnimatedWebpSync = nativeBinding.toAnimatedWebpSync
 module.exports.toJpeg = nativeBinding.toJpeg
 module.exports.toJpegSync = nativeBinding.toJpegSync
 module.exports.toPng = nativeBinding.toPng
 module.exports.toPngSync = nativeBinding.toPngSync
+module.exports.toRaw = nativeBinding.toRaw
+module.exports.toRawSync = nativeBinding.toRawSync
 module.exports.toTensor = nativeBinding.toTensor
 module.exports.toTensorSync = nativeBinding.toTensorSync
 module.exports.toWebp = nativeBinding.toWebp
 module.exports.toWebpSync = nativeBinding.toWebpSync
 module.exports.transform = nativeBinding.transform
 module.exports.transformSync = nativeBinding.transformSync
+module.exports.transformWithInfo = nativeBinding.transformWithInfo
+module.exports.transformWithInfoSync = nativeBinding.transformWithInfoSync
 module.exports.version = nativeBinding.version
+module.exports.WebPPreset = nativeBinding.WebPPreset
 module.exports.writeExif = nativeBinding.writeExif
 module.exports.writeExifSync = nativeBinding.writeExifSync
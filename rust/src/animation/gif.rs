@@ -0,0 +1,81 @@
+//! Animated GIF decode and encode
+//!
+//! Decoding uses the image crate's GIF codec, which composites each frame onto
+//! the logical screen (disposal methods applied), so frames are canvas-sized.
+//! Encoding quantizes each frame with our palette quantizer via the gif crate.
+
+use image::codecs::gif::GifDecoder;
+use image::{AnimationDecoder, ImageDecoder};
+use std::io::Cursor;
+
+use crate::encode::gif_frame;
+use crate::error::ImageError;
+use crate::metadata::parse_gif_loop_count;
+use crate::GifOptions;
+
+use super::{check_animation_size, Animation, AnimationFrame};
+
+/// Decode every frame of a GIF into full-canvas RGBA frames
+pub fn decode_gif_animation(data: &[u8]) -> Result<Animation, ImageError> {
+  let decoder = GifDecoder::new(Cursor::new(data))
+    .map_err(|e| ImageError::DecodeError(format!("GIF decode failed: {}", e)))?;
+  let (width, height) = decoder.dimensions();
+  check_animation_size(width, height, 1)?;
+
+  let mut frames: Vec<AnimationFrame> = Vec::new();
+  for frame in decoder.into_frames() {
+    let frame = frame.map_err(|e| ImageError::DecodeError(format!("GIF frame decode failed: {}", e)))?;
+    check_animation_size(width, height, frames.len() as u64 + 1)?;
+
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    let delay_ms = numer.checked_div(denom).unwrap_or(0);
+    frames.push(AnimationFrame {
+      image: frame.into_buffer(),
+      delay_ms,
+    });
+  }
+
+  if frames.is_empty() {
+    return Err(ImageError::DecodeError("GIF contains no frames".to_string()));
+  }
+
+  Ok(Animation {
+    width,
+    height,
+    frames,
+    loop_count: parse_gif_loop_count(data).unwrap_or(0),
+  })
+}
+
+/// Encode frames as an animated GIF (local palette per frame)
+pub fn encode_gif_animation(anim: &Animation, options: Option<&GifOptions>) -> Result<Vec<u8>, ImageError> {
+  let (Ok(width), Ok(height)) = (u16::try_from(anim.width), u16::try_from(anim.height)) else {
+    return Err(ImageError::EncodeError(format!(
+      "GIF dimensions are limited to 65535x65535, got {}x{}",
+      anim.width, anim.height
+    )));
+  };
+
+  let mut output: Vec<u8> = Vec::new();
+  {
+    let mut encoder = gif::Encoder::new(&mut output, width, height, &[])
+      .map_err(|e| ImageError::EncodeError(format!("GIF encode failed: {}", e)))?;
+    let repeat = match anim.loop_count {
+      0 => gif::Repeat::Infinite,
+      n => gif::Repeat::Finite(n.min(u16::MAX as u32) as u16),
+    };
+    encoder
+      .set_repeat(repeat)
+      .map_err(|e| ImageError::EncodeError(format!("GIF encode failed: {}", e)))?;
+
+    for frame in &anim.frames {
+      let mut indexed = gif_frame(&frame.image, options)?;
+      // GIF delays are in centiseconds
+      indexed.delay = (frame.delay_ms / 10).min(u16::MAX as u32) as u16;
+      encoder
+        .write_frame(&indexed)
+        .map_err(|e| ImageError::EncodeError(format!("GIF frame encode failed: {}", e)))?;
+    }
+  }
+  Ok(output)
+}
@@ -0,0 +1,224 @@
+//! Animated image support (multi-frame decode and encode)
+//!
+//! Frames are always held as full-canvas RGBA so that every per-frame
+//! operation (resize, crop, rotate, effects) sees the same geometry.
+//! Uses libwebp's AnimDecoder/AnimEncoder for animated WebP and the png crate for APNG output.
+//! Uses the image crate's GIF and APNG decoders (which composite frames for us).
+
+mod gif;
+mod png;
+mod webp;
+
+use image::{DynamicImage, GenericImageView, ImageFormat, RgbaImage};
+use rayon::prelude::*;
+
+use crate::decode;
+use crate::error::ImageError;
+use crate::metadata::parse_actl;
+use crate::AnimationOptions;
+
+pub use gif::{decode_gif_animation, encode_gif_animation};
+pub use png::{decode_png_animation, encode_png_animation};
+pub use webp::{decode_webp_animation, encode_webp_animation};
+
+/// Maximum total pixel count across all frames (100 megapixels)
+/// Animations multiply memory by frame count, so the limit is applied to the sum
+const MAX_ANIMATION_PIXELS: u64 = 100_000_000;
+
+/// A single full-canvas animation frame
+pub struct AnimationFrame {
+  /// Frame pixels (always canvas-sized RGBA)
+  pub image: RgbaImage,
+  /// Display duration in milliseconds
+  pub delay_ms: u32,
+}
+
+/// Decoded animation (all frames composited to the canvas)
+pub struct Animation {
+  /// Canvas width
+  pub width: u32,
+  /// Canvas height
+  pub height: u32,
+  /// Frames in display order
+  pub frames: Vec<AnimationFrame>,
+  /// Loop count (0 = infinite)
+  pub loop_count: u32,
+}
+
+impl Animation {
+  /// Wrap a static image as a single-frame animation
+  pub fn from_image(img: DynamicImage) -> Self {
+    let (width, height) = img.dimensions();
+    Animation {
+      width,
+      height,
+      frames: vec![AnimationFrame {
+        image: img.to_rgba8(),
+        delay_ms: 0,
+      }],
+      loop_count: 0,
+    }
+  }
+
+  /// Apply an operation to every frame (in parallel)
+  /// The canvas size is taken from the first processed frame
+  pub fn map_frames<F>(self, op: F) -> Result<Animation, ImageError>
+  where
+    F: Fn(DynamicImage) -> Result<DynamicImage, ImageError> + Sync + Send,
+  {
+    let frames = self
+      .frames
+      .into_par_iter()
+      .map(|frame| {
+        let processed = op(DynamicImage::ImageRgba8(frame.image))?;
+        Ok(AnimationFrame {
+          image: processed.to_rgba8(),
+          delay_ms: frame.delay_ms,
+        })
+      })
+      .collect::<Result<Vec<_>, ImageError>>()?;
+
+    let (width, height) = frames
+      .first()
+      .map(|f| f.image.dimensions())
+      .unwrap_or((self.width, self.height));
+
+    Ok(Animation {
+      width,
+      height,
+      frames,
+      loop_count: self.loop_count,
+    })
+  }
+
+  /// Apply caller-provided loop count and frame delays
+  /// A single delay applies to every frame; otherwise the last delay repeats
+  pub fn apply_options(&mut self, options: Option<&AnimationOptions>) {
+    let Some(options) = options else {
+      return;
+    };
+
+    if let Some(loop_count) = options.loop_count {
+      self.loop_count = loop_count;
+    }
+
+    if let Some(ref delays) = options.delay {
+      if let Some(&last) = delays.last() {
+        for (i, frame) in self.frames.iter_mut().enumerate() {
+          frame.delay_ms = delays.get(i).copied().unwrap_or(last);
+        }
+      }
+    }
+  }
+}
+
+/// Check the total pixel budget of an animation before allocating frames
+pub(crate) fn check_animation_size(width: u32, height: u32, frame_count: u64) -> Result<(), ImageError> {
+  let total = width as u64 * height as u64 * frame_count.max(1);
+  if total > MAX_ANIMATION_PIXELS {
+    return Err(ImageError::DecodeError(format!(
+      "Animation too large: {}x{} with {} frames ({} megapixels) exceeds limit of {} megapixels",
+      width,
+      height,
+      frame_count,
+      total / 1_000_000,
+      MAX_ANIMATION_PIXELS / 1_000_000
+    )));
+  }
+  Ok(())
+}
+
+/// Check if the input contains more than one frame (animated GIF, WebP or PNG)
+pub fn is_animated(data: &[u8]) -> bool {
+  // Only the first frame of a JPEG XL animation is decoded
+  if decode::is_jxl(data) {
+    return false;
+  }
+  match decode::detect_format(data) {
+    Ok(ImageFormat::WebP) => is_animated_webp(data),
+    Ok(ImageFormat::Png) => parse_actl(data).is_some_and(|(frames, _)| frames > 1),
+    Ok(ImageFormat::Gif) => decode::get_metadata(data)
+      .map(|meta| meta.pages.unwrap_or(1) > 1)
+      .unwrap_or(false),
+    _ => false,
+  }
+}
+
+/// Check the VP8X animation flag of a WebP file
+#[inline]
+fn is_animated_webp(data: &[u8]) -> bool {
+  data.len() > 20 && &data[12..16] == b"VP8X" && (data[20] & 0x02) != 0
+}
+
+/// Decode all frames of an image
+/// Static images are returned as a single-frame animation
+pub fn decode_animation(data: &[u8]) -> Result<Animation, ImageError> {
+  // JPEG XL and raw pixels have no image crate format - decode as a single frame
+  if decode::is_jxl(data) || decode::is_raw(data) {
+    return Ok(Animation::from_image(decode::decode_image(data)?));
+  }
+  match decode::detect_format(data) {
+    Ok(ImageFormat::WebP) if is_animated_webp(data) => decode_webp_animation(data),
+    Ok(ImageFormat::Gif) => decode_gif_animation(data),
+    Ok(ImageFormat::Png) if parse_actl(data).is_some() => decode_png_animation(data),
+    _ => Ok(Animation::from_image(decode::decode_image(data)?)),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn test_animation(frame_count: usize) -> Animation {
+    Animation {
+      width: 2,
+      height: 2,
+      frames: (0..frame_count)
+        .map(|_| AnimationFrame {
+          image: RgbaImage::new(2, 2),
+          delay_ms: 100,
+        })
+        .collect(),
+      loop_count: 0,
+    }
+  }
+
+  #[test]
+  fn test_apply_options_single_delay() {
+    let mut anim = test_animation(3);
+    anim.apply_options(Some(&AnimationOptions {
+      loop_count: Some(2),
+      delay: Some(vec![40]),
+    }));
+    assert_eq!(anim.loop_count, 2);
+    assert!(anim.frames.iter().all(|f| f.delay_ms == 40));
+  }
+
+  #[test]
+  fn test_apply_options_repeats_last_delay() {
+    let mut anim = test_animation(4);
+    anim.apply_options(Some(&AnimationOptions {
+      loop_count: None,
+      delay: Some(vec![10, 20]),
+    }));
+    let delays: Vec<u32> = anim.frames.iter().map(|f| f.delay_ms).collect();
+    assert_eq!(delays, vec![10, 20, 20, 20]);
+    assert_eq!(anim.loop_count, 0);
+  }
+
+  #[test]
+  fn test_check_animation_size() {
+    assert!(check_animation_size(500, 500, 100).is_ok());
+    assert!(check_animation_size(10_000, 10_000, 2).is_err());
+  }
+
+  #[test]
+  fn test_webp_timestamp_overflow() {
+    let mut anim = test_animation(3);
+    anim.frames[0].delay_ms = i32::MAX as u32;
+    assert!(encode_webp_animation(&anim, None).is_err());
+
+    anim.frames[0].delay_ms = u32::MAX;
+    assert!(encode_webp_animation(&anim, None).is_err());
+  }
+}
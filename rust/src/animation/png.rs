@@ -0,0 +1,93 @@
+//! Animated PNG (APNG) decode and encode
+//!
+//! Decoding uses the image crate's APNG decoder, which applies fcTL
+//! dispose/blend ops so frames come out canvas-sized.
+//! Encoding uses the png crate directly (image's PngEncoder is single-frame).
+
+use image::codecs::png::PngDecoder;
+use image::{AnimationDecoder, ImageDecoder};
+use std::io::Cursor;
+
+use crate::error::ImageError;
+use crate::metadata::parse_actl;
+use crate::PngOptions;
+
+use super::{check_animation_size, Animation, AnimationFrame};
+
+/// Decode every frame of an APNG into full-canvas RGBA frames
+pub fn decode_png_animation(data: &[u8]) -> Result<Animation, ImageError> {
+  let decoder = PngDecoder::new(Cursor::new(data))
+    .map_err(|e| ImageError::DecodeError(format!("PNG decode failed: {}", e)))?;
+  let (width, height) = decoder.dimensions();
+  let (frame_count, loop_count) = parse_actl(data).unwrap_or((1, 0));
+  check_animation_size(width, height, frame_count as u64)?;
+
+  let decoder = decoder
+    .apng()
+    .map_err(|e| ImageError::DecodeError(format!("APNG decode failed: {}", e)))?;
+
+  // No preallocation from acTL: the declared count is untrusted, growth is bounded per frame below
+  let mut frames: Vec<AnimationFrame> = Vec::new();
+  for frame in decoder.into_frames() {
+    let frame = frame.map_err(|e| ImageError::DecodeError(format!("APNG frame decode failed: {}", e)))?;
+    check_animation_size(width, height, frames.len() as u64 + 1)?;
+
+    let (numer, denom) = frame.delay().numer_denom_ms();
+    let delay_ms = numer.checked_div(denom).unwrap_or(0);
+    frames.push(AnimationFrame {
+      image: frame.into_buffer(),
+      delay_ms,
+    });
+  }
+
+  if frames.is_empty() {
+    return Err(ImageError::DecodeError("APNG contains no frames".to_string()));
+  }
+
+  Ok(Animation {
+    width,
+    height,
+    frames,
+    loop_count,
+  })
+}
+
+/// Encode frames as an animated PNG (lossless)
+pub fn encode_png_animation(anim: &Animation, options: Option<&PngOptions>) -> Result<Vec<u8>, ImageError> {
+  let compression = match options.and_then(|o| o.compression).unwrap_or(6) {
+    0 => png::Compression::NoCompression,
+    1..=3 => png::Compression::Fast,
+    4..=6 => png::Compression::Balanced,
+    _ => png::Compression::High,
+  };
+
+  let mut output: Vec<u8> = Vec::new();
+  {
+    let mut encoder = png::Encoder::new(&mut output, anim.width, anim.height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(compression);
+    encoder
+      .set_animated(anim.frames.len() as u32, anim.loop_count)
+      .map_err(|e| ImageError::EncodeError(format!("APNG encode failed: {}", e)))?;
+
+    let mut writer = encoder
+      .write_header()
+      .map_err(|e| ImageError::EncodeError(format!("APNG encode failed: {}", e)))?;
+
+    for frame in &anim.frames {
+      // fcTL stores the delay as a u16 fraction of a second
+      writer
+        .set_frame_delay(frame.delay_ms.min(u16::MAX as u32) as u16, 1000)
+        .map_err(|e| ImageError::EncodeError(format!("APNG encode failed: {}", e)))?;
+      writer
+        .write_image_data(frame.image.as_raw())
+        .map_err(|e| ImageError::EncodeError(format!("APNG frame encode failed: {}", e)))?;
+    }
+
+    writer
+      .finish()
+      .map_err(|e| ImageError::EncodeError(format!("APNG encode failed: {}", e)))?;
+  }
+  Ok(output)
+}
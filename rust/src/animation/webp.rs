@@ -0,0 +1,206 @@
+//! Animated WebP decode and encode
+//!
+//! Uses libwebp's AnimDecoder (demux) and AnimEncoder (mux) directly.
+//! The AnimEncoder picks sub-rectangles and blending per frame, so the
+//! output is much smaller than naively storing full-canvas frames.
+
+use std::os::raw::c_int;
+use std::ptr;
+
+use image::RgbaImage;
+use libwebp_sys::{
+  WebPAnimDecoder, WebPAnimDecoderDelete, WebPAnimDecoderGetInfo, WebPAnimDecoderGetNext,
+  WebPAnimDecoderHasMoreFrames, WebPAnimDecoderNew, WebPAnimDecoderOptions,
+  WebPAnimDecoderOptionsInit, WebPAnimEncoder, WebPAnimEncoderAdd, WebPAnimEncoderAssemble,
+  WebPAnimEncoderDelete, WebPAnimEncoderGetError, WebPAnimEncoderNew, WebPAnimEncoderOptions,
+  WebPAnimEncoderOptionsInit, WebPAnimInfo, WebPData, WebPDataClear, WebPPicture, WebPPictureFree,
+  WebPPictureImportRGBA, WebPPictureInit, MODE_RGBA,
+};
+
+use crate::encode::webp_config;
+use crate::error::ImageError;
+use crate::WebPOptions;
+
+use super::{check_animation_size, Animation, AnimationFrame};
+
+/// Owns a libwebp animation decoder and frees it on drop
+struct DecoderGuard(*mut WebPAnimDecoder);
+
+impl Drop for DecoderGuard {
+  fn drop(&mut self) {
+    unsafe { WebPAnimDecoderDelete(self.0) }
+  }
+}
+
+/// Owns a libwebp animation encoder and frees it on drop
+struct EncoderGuard(*mut WebPAnimEncoder);
+
+impl Drop for EncoderGuard {
+  fn drop(&mut self) {
+    unsafe { WebPAnimEncoderDelete(self.0) }
+  }
+}
+
+/// Decode every frame of an animated WebP into full-canvas RGBA frames
+pub fn decode_webp_animation(data: &[u8]) -> Result<Animation, ImageError> {
+  unsafe {
+    let mut options: WebPAnimDecoderOptions = std::mem::zeroed();
+    if WebPAnimDecoderOptionsInit(&mut options) == 0 {
+      return Err(ImageError::DecodeError(
+        "Failed to init WebP animation decoder options".to_string(),
+      ));
+    }
+    options.color_mode = MODE_RGBA;
+    options.use_threads = 1;
+
+    let webp_data = WebPData {
+      bytes: data.as_ptr(),
+      size: data.len(),
+    };
+    let decoder = WebPAnimDecoderNew(&webp_data, &options);
+    if decoder.is_null() {
+      return Err(ImageError::DecodeError(
+        "Failed to create WebP animation decoder".to_string(),
+      ));
+    }
+    let decoder = DecoderGuard(decoder);
+
+    let mut info: WebPAnimInfo = std::mem::zeroed();
+    if WebPAnimDecoderGetInfo(decoder.0, &mut info) == 0 {
+      return Err(ImageError::DecodeError(
+        "Failed to read WebP animation info".to_string(),
+      ));
+    }
+
+    let (width, height) = (info.canvas_width, info.canvas_height);
+    check_animation_size(width, height, info.frame_count as u64)?;
+
+    let frame_bytes = width as usize * height as usize * 4;
+    let mut frames: Vec<AnimationFrame> = Vec::with_capacity(info.frame_count as usize);
+    let mut previous_timestamp: c_int = 0;
+
+    while WebPAnimDecoderHasMoreFrames(decoder.0) != 0 {
+      let mut buf: *mut u8 = ptr::null_mut();
+      let mut timestamp: c_int = 0;
+      if WebPAnimDecoderGetNext(decoder.0, &mut buf, &mut timestamp) == 0 {
+        return Err(ImageError::DecodeError(format!(
+          "WebP animation decode failed at frame {}",
+          frames.len()
+        )));
+      }
+
+      // The buffer is owned by the decoder and reused for the next frame
+      let pixels = std::slice::from_raw_parts(buf, frame_bytes).to_vec();
+      let image = RgbaImage::from_raw(width, height, pixels).ok_or_else(|| {
+        ImageError::DecodeError("Failed to create RGBA frame from WebP".to_string())
+      })?;
+
+      // Timestamps are frame END times, so duration is the difference
+      frames.push(AnimationFrame {
+        image,
+        delay_ms: (timestamp - previous_timestamp).max(0) as u32,
+      });
+      previous_timestamp = timestamp;
+    }
+
+    if frames.is_empty() {
+      return Err(ImageError::DecodeError("WebP animation contains no frames".to_string()));
+    }
+
+    Ok(Animation {
+      width,
+      height,
+      frames,
+      loop_count: info.loop_count,
+    })
+  }
+}
+
+/// Encode frames as an animated WebP
+/// The WebPOptions encoder config applies to every frame
+pub fn encode_webp_animation(
+  anim: &Animation,
+  options: Option<&WebPOptions>,
+) -> Result<Vec<u8>, ImageError> {
+  let config = webp_config(options)?;
+
+  unsafe {
+    let mut enc_options: WebPAnimEncoderOptions = std::mem::zeroed();
+    if WebPAnimEncoderOptionsInit(&mut enc_options) == 0 {
+      return Err(ImageError::EncodeError(
+        "Failed to init WebP animation encoder options".to_string(),
+      ));
+    }
+    enc_options.anim_params.loop_count = anim.loop_count.min(u16::MAX as u32) as c_int;
+
+    let encoder = WebPAnimEncoderNew(anim.width as c_int, anim.height as c_int, &enc_options);
+    if encoder.is_null() {
+      return Err(ImageError::EncodeError(
+        "Failed to create WebP animation encoder".to_string(),
+      ));
+    }
+    let encoder = EncoderGuard(encoder);
+
+    let mut timestamp: c_int = 0;
+    for frame in &anim.frames {
+      let (width, height) = frame.image.dimensions();
+      let mut picture: WebPPicture = std::mem::zeroed();
+      if WebPPictureInit(&mut picture) == 0 {
+        return Err(ImageError::EncodeError("Failed to init WebP picture".to_string()));
+      }
+      picture.use_argb = 1;
+      picture.width = width as c_int;
+      picture.height = height as c_int;
+
+      if WebPPictureImportRGBA(&mut picture, frame.image.as_ptr(), (width * 4) as c_int) == 0 {
+        WebPPictureFree(&mut picture);
+        return Err(ImageError::EncodeError("Failed to import WebP frame".to_string()));
+      }
+
+      let added = WebPAnimEncoderAdd(encoder.0, &mut picture, timestamp, &config);
+      WebPPictureFree(&mut picture);
+      if added == 0 {
+        return Err(ImageError::EncodeError(format!(
+          "WebP animation encode failed: {}",
+          encoder_error(encoder.0)
+        )));
+      }
+
+      timestamp = c_int::try_from(frame.delay_ms)
+        .ok()
+        .and_then(|delay| timestamp.checked_add(delay))
+        .ok_or_else(|| ImageError::EncodeError("WebP animation is too long: timestamps overflow".to_string()))?;
+    }
+
+    // A final NULL frame marks the end time of the last frame
+    if WebPAnimEncoderAdd(encoder.0, ptr::null_mut(), timestamp, ptr::null()) == 0 {
+      return Err(ImageError::EncodeError(format!(
+        "WebP animation encode failed: {}",
+        encoder_error(encoder.0)
+      )));
+    }
+
+    let mut webp_data: WebPData = std::mem::zeroed();
+    if WebPAnimEncoderAssemble(encoder.0, &mut webp_data) == 0 {
+      return Err(ImageError::EncodeError(format!(
+        "WebP animation assemble failed: {}",
+        encoder_error(encoder.0)
+      )));
+    }
+
+    let output = std::slice::from_raw_parts(webp_data.bytes, webp_data.size).to_vec();
+    WebPDataClear(&mut webp_data);
+
+    Ok(output)
+  }
+}
+
+/// Read the last error message from a libwebp animation encoder
+unsafe fn encoder_error(encoder: *mut WebPAnimEncoder) -> String {
+  let message = WebPAnimEncoderGetError(encoder);
+  if message.is_null() {
+    "unknown error".to_string()
+  } else {
+    std::ffi::CStr::from_ptr(message).to_string_lossy().into_owned()
+  }
+}
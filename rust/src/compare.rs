@@ -0,0 +1,351 @@
+//! Perceptual image comparison (SSIM, MS-SSIM, PSNR, DSSIM)
+//!
+//! Both images are converted to normalized planes (luma, or CIELAB for a
+//! perceptual color comparison) and compared with an 11x11 Gaussian window
+//! (sigma 1.5), matching the reference SSIM implementation.
+
+use image::{DynamicImage, GenericImageView, RgbImage};
+use rayon::prelude::*;
+
+use crate::encode;
+use crate::error::ImageError;
+use crate::resize;
+use crate::{CompareColorSpace, CompareMetric, CompareOptions, CompareResult, FitMode, ResizeFilter, ResizeOptions};
+
+const WINDOW_RADIUS: usize = 5;
+const WINDOW_SIGMA: f32 = 1.5;
+
+/// SSIM stabilizers for a dynamic range of 1.0
+const C1: f32 = 0.01 * 0.01;
+const C2: f32 = 0.03 * 0.03;
+
+/// MS-SSIM scale weights (Wang, Simoncelli and Bovik 2003)
+const MS_SSIM_WEIGHTS: [f64; 5] = [0.0448, 0.2856, 0.3001, 0.2363, 0.1333];
+
+/// CIELAB channel weights - lightness dominates perceived difference
+const LAB_WEIGHTS: [f64; 3] = [0.8, 0.1, 0.1];
+
+/// A single image channel with values in 0.0-1.0
+#[derive(Clone)]
+struct Plane {
+  width: usize,
+  height: usize,
+  data: Vec<f32>,
+}
+
+impl Plane {
+  fn map2(&self, other: &Plane, f: impl Fn(f32, f32) -> f32 + Sync) -> Plane {
+    Plane {
+      width: self.width,
+      height: self.height,
+      data: self.data.par_iter().zip(other.data.par_iter()).map(|(&a, &b)| f(a, b)).collect(),
+    }
+  }
+
+  /// Halve both dimensions with a 2x2 box filter
+  fn downsample(&self) -> Plane {
+    let (width, height) = (self.width / 2, self.height / 2);
+    let mut data = Vec::with_capacity(width * height);
+    for y in 0..height {
+      let top = &self.data[(y * 2) * self.width..];
+      let bottom = &self.data[(y * 2 + 1) * self.width..];
+      for x in 0..width {
+        data.push((top[x * 2] + top[x * 2 + 1] + bottom[x * 2] + bottom[x * 2 + 1]) * 0.25);
+      }
+    }
+    Plane { width, height, data }
+  }
+}
+
+fn gaussian_kernel() -> [f32; 2 * WINDOW_RADIUS + 1] {
+  let mut kernel = [0.0f32; 2 * WINDOW_RADIUS + 1];
+  for (i, k) in kernel.iter_mut().enumerate() {
+    let d = i as f32 - WINDOW_RADIUS as f32;
+    *k = (-(d * d) / (2.0 * WINDOW_SIGMA * WINDOW_SIGMA)).exp();
+  }
+  let sum: f32 = kernel.iter().sum();
+  kernel.iter_mut().for_each(|k| *k /= sum);
+  kernel
+}
+
+/// Separable Gaussian blur with clamped edges
+fn blur(plane: &Plane, kernel: &[f32]) -> Plane {
+  let (width, height) = (plane.width, plane.height);
+  let radius = kernel.len() / 2;
+
+  let mut horizontal = vec![0.0f32; width * height];
+  horizontal.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+    let src = &plane.data[y * width..(y + 1) * width];
+    for (x, out) in row.iter_mut().enumerate() {
+      *out = kernel
+        .iter()
+        .enumerate()
+        .map(|(i, k)| k * src[(x + i).saturating_sub(radius).min(width - 1)])
+        .sum();
+    }
+  });
+
+  let mut data = vec![0.0f32; width * height];
+  data.par_chunks_mut(width).enumerate().for_each(|(y, row)| {
+    for (i, k) in kernel.iter().enumerate() {
+      let sy = (y + i).saturating_sub(radius).min(height - 1);
+      let src = &horizontal[sy * width..(sy + 1) * width];
+      for (out, &v) in row.iter_mut().zip(src) {
+        *out += k * v;
+      }
+    }
+  });
+
+  Plane { width, height, data }
+}
+
+/// Per-pixel SSIM and contrast-structure maps
+fn ssim_maps(x: &Plane, y: &Plane, kernel: &[f32]) -> (Vec<f32>, Vec<f32>) {
+  let mu_x = blur(x, kernel);
+  let mu_y = blur(y, kernel);
+  let xx = blur(&x.map2(x, |a, b| a * b), kernel);
+  let yy = blur(&y.map2(y, |a, b| a * b), kernel);
+  let xy = blur(&x.map2(y, |a, b| a * b), kernel);
+
+  (0..x.data.len())
+    .into_par_iter()
+    .map(|i| {
+      let (mx, my) = (mu_x.data[i], mu_y.data[i]);
+      let var_x = (xx.data[i] - mx * mx).max(0.0);
+      let var_y = (yy.data[i] - my * my).max(0.0);
+      let cov = xy.data[i] - mx * my;
+
+      let cs = (2.0 * cov + C2) / (var_x + var_y + C2);
+      let luminance = (2.0 * mx * my + C1) / (mx * mx + my * my + C1);
+      (luminance * cs, cs)
+    })
+    .unzip()
+}
+
+fn mean(values: &[f32]) -> f64 {
+  values.iter().map(|&v| v as f64).sum::<f64>() / values.len().max(1) as f64
+}
+
+/// Multi-scale SSIM - uses fewer scales (renormalized weights) for small images
+/// Also returns the full-resolution SSIM map for the error heatmap
+fn ms_ssim(x: &Plane, y: &Plane, kernel: &[f32]) -> (f64, Vec<f32>) {
+  let min_side = x.width.min(x.height);
+  let mut scales = 1;
+  while scales < MS_SSIM_WEIGHTS.len() && (min_side >> scales) >= kernel.len() {
+    scales += 1;
+  }
+  let weights = &MS_SSIM_WEIGHTS[..scales];
+  let weight_sum: f64 = weights.iter().sum();
+
+  let (mut x, mut y) = (x.clone(), y.clone());
+  let mut score = 1.0;
+  let mut full_map = Vec::new();
+  for (scale, &weight) in weights.iter().enumerate() {
+    let (ssim, cs) = ssim_maps(&x, &y, kernel);
+    let value = if scale + 1 == scales { mean(&ssim) } else { mean(&cs) };
+    score *= value.max(0.0).powf(weight / weight_sum);
+    if scale == 0 {
+      full_map = ssim;
+    }
+    if scale + 1 < scales {
+      x = x.downsample();
+      y = y.downsample();
+    }
+  }
+  (score, full_map)
+}
+
+/// sRGB (0-255) to linear light
+fn srgb_to_linear(v: u8) -> f32 {
+  let c = v as f32 / 255.0;
+  if c <= 0.04045 {
+    c / 12.92
+  } else {
+    ((c + 0.055) / 1.055).powf(2.4)
+  }
+}
+
+/// Normalized CIELAB (D65): L / 100, (a + 128) / 255, (b + 128) / 255
+fn rgb_to_lab(r: u8, g: u8, b: u8) -> [f32; 3] {
+  let (r, g, b) = (srgb_to_linear(r), srgb_to_linear(g), srgb_to_linear(b));
+  let x = (0.4124 * r + 0.3576 * g + 0.1805 * b) / 0.95047;
+  let y = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+  let z = (0.0193 * r + 0.1192 * g + 0.9505 * b) / 1.08883;
+
+  let f = |t: f32| if t > 0.008856 { t.cbrt() } else { 7.787 * t + 16.0 / 116.0 };
+  let (fx, fy, fz) = (f(x), f(y), f(z));
+  [
+    (116.0 * fy - 16.0) / 100.0,
+    (500.0 * (fx - fy) + 128.0) / 255.0,
+    (200.0 * (fy - fz) + 128.0) / 255.0,
+  ]
+}
+
+/// Split an image into weighted planes for the color space
+fn to_planes(img: &RgbImage, color_space: &CompareColorSpace) -> Vec<(Plane, f64)> {
+  let (width, height) = (img.width() as usize, img.height() as usize);
+  let plane = |data: Vec<f32>| Plane { width, height, data };
+
+  match color_space {
+    CompareColorSpace::Luma => {
+      // Rec. 601 luma, as in the reference SSIM
+      let data = img
+        .pixels()
+        .map(|p| (0.299 * p[0] as f32 + 0.587 * p[1] as f32 + 0.114 * p[2] as f32) / 255.0)
+        .collect();
+      vec![(plane(data), 1.0)]
+    }
+    CompareColorSpace::Lab => {
+      let lab: Vec<[f32; 3]> = img.as_raw().par_chunks_exact(3).map(|p| rgb_to_lab(p[0], p[1], p[2])).collect();
+      (0..3)
+        .map(|c| (plane(lab.iter().map(|v| v[c]).collect()), LAB_WEIGHTS[c]))
+        .collect()
+    }
+  }
+}
+
+/// Map an error (0 = identical) to a black-red-yellow-white heat color
+fn heat_color(error: f32) -> [u8; 3] {
+  let v = error.clamp(0.0, 1.0) * 3.0;
+  [
+    (v.min(1.0) * 255.0) as u8,
+    ((v - 1.0).clamp(0.0, 1.0) * 255.0) as u8,
+    ((v - 2.0).clamp(0.0, 1.0) * 255.0) as u8,
+  ]
+}
+
+/// Compare two images with a perceptual metric
+/// `b` is resized to the dimensions of `a` when they differ
+pub fn compare_images(
+  a: &DynamicImage,
+  b: &DynamicImage,
+  metric: &CompareMetric,
+  options: Option<&CompareOptions>,
+) -> Result<CompareResult, ImageError> {
+  let (width, height) = a.dimensions();
+  if width < 2 || height < 2 {
+    return Err(ImageError::InvalidDimensions(format!(
+      "Images must be at least 2x2 to compare, got {}x{}",
+      width, height
+    )));
+  }
+
+  let resized = b.dimensions() != (width, height);
+  let b = if resized {
+    let resize_opts = ResizeOptions {
+      width: Some(width),
+      height: Some(height),
+      filter: Some(ResizeFilter::Lanczos3),
+      fit: Some(FitMode::Fill),
+      background: None,
+    };
+    resize::resize_image(b.clone(), &resize_opts)?
+  } else {
+    b.clone()
+  };
+
+  let color_space = options
+    .and_then(|o| o.color_space.clone())
+    .unwrap_or(CompareColorSpace::Luma);
+  let planes_a = to_planes(&a.to_rgb8(), &color_space);
+  let planes_b = to_planes(&b.to_rgb8(), &color_space);
+  let kernel = gaussian_kernel();
+
+  // Per-pixel error for the heatmap, weighted across planes
+  let mut error_map = vec![0.0f32; width as usize * height as usize];
+  let mut add_error = |errors: &[f32], weight: f64| {
+    for (acc, e) in error_map.iter_mut().zip(errors) {
+      *acc += e * weight as f32;
+    }
+  };
+
+  let score = match metric {
+    CompareMetric::Psnr => {
+      let mut mse = 0.0;
+      for ((x, weight), (y, _)) in planes_a.iter().zip(&planes_b) {
+        let squared = x.map2(y, |a, b| (a - b) * (a - b));
+        mse += mean(&squared.data) * weight;
+        add_error(&squared.data.iter().map(|v| v.sqrt()).collect::<Vec<_>>(), *weight);
+      }
+      if mse == 0.0 {
+        f64::INFINITY
+      } else {
+        10.0 * (1.0 / mse).log10()
+      }
+    }
+    CompareMetric::Ssim | CompareMetric::Dssim | CompareMetric::MsSsim => {
+      let mut ssim = 0.0;
+      for ((x, weight), (y, _)) in planes_a.iter().zip(&planes_b) {
+        let (plane_score, map) = if matches!(metric, CompareMetric::MsSsim) {
+          ms_ssim(x, y, &kernel)
+        } else {
+          let (map, _) = ssim_maps(x, y, &kernel);
+          (mean(&map), map)
+        };
+        ssim += plane_score * weight;
+        add_error(&map.iter().map(|s| 1.0 - s).collect::<Vec<_>>(), *weight);
+      }
+      if matches!(metric, CompareMetric::Dssim) {
+        // Same definition as the dssim tool: 0 = identical
+        1.0 / ssim.max(f64::EPSILON) - 1.0
+      } else {
+        ssim
+      }
+    }
+  };
+
+  let heatmap = if options.and_then(|o| o.heatmap).unwrap_or(false) {
+    // Errors are small in practice - stretch so the worst pixel is white
+    let max_error = error_map.iter().cloned().fold(0.0f32, f32::max).max(f32::EPSILON);
+    let pixels: Vec<u8> = error_map.iter().flat_map(|&e| heat_color(e / max_error)).collect();
+    let img = RgbImage::from_raw(width, height, pixels)
+      .ok_or_else(|| ImageError::ProcessingError("Failed to create heatmap image".to_string()))?;
+    Some(encode::encode_png(&DynamicImage::ImageRgb8(img), None)?)
+  } else {
+    None
+  };
+
+  Ok(CompareResult {
+    metric: metric.clone(),
+    score,
+    width,
+    height,
+    resized,
+    heatmap,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn gradient(width: u32, height: u32, offset: u8) -> DynamicImage {
+    DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+      let v = ((x + y) % 200) as u8 + offset;
+      image::Rgb([v, v / 2, 255 - v])
+    }))
+  }
+
+  #[test]
+  fn test_identical_images() {
+    let img = gradient(64, 48, 0);
+    for metric in [CompareMetric::Ssim, CompareMetric::MsSsim] {
+      let result = compare_images(&img, &img, &metric, None).unwrap();
+      assert!((result.score - 1.0).abs() < 1e-6);
+    }
+    let psnr = compare_images(&img, &img, &CompareMetric::Psnr, None).unwrap();
+    assert!(psnr.score.is_infinite());
+    let dssim = compare_images(&img, &img, &CompareMetric::Dssim, None).unwrap();
+    assert!(dssim.score.abs() < 1e-6);
+  }
+
+  #[test]
+  fn test_different_images() {
+    let a = gradient(64, 48, 0);
+    let b = gradient(64, 48, 40);
+    let ssim = compare_images(&a, &b, &CompareMetric::Ssim, None).unwrap();
+    assert!(ssim.score < 1.0);
+    let psnr = compare_images(&a, &b, &CompareMetric::Psnr, None).unwrap();
+    assert!(psnr.score.is_finite() && psnr.score > 0.0);
+  }
+}
@@ -91,15 +91,17 @@ fn calculate_aspect_ratio_crop(
     }
 }
 
-/// Crop an image - zero-copy when possible
+/// Resolve the crop region (x, y, width, height) for an image of the given size
 ///
 /// Supports three modes:
 /// 1. Explicit coordinates: x, y, width, height
 /// 2. Aspect ratio: aspectRatio with optional gravity
 /// 3. Dimensions with gravity: width, height, gravity (no x,y)
-pub fn crop_image(img: DynamicImage, options: &CropOptions) -> Result<DynamicImage, ImageError> {
-    let (src_width, src_height) = img.dimensions();
-
+pub(crate) fn crop_region(
+    src_width: u32,
+    src_height: u32,
+    options: &CropOptions,
+) -> Result<(u32, u32, u32, u32), ImageError> {
     // Determine crop region
     let (x, y, crop_width, crop_height) = if let Some(ref aspect_ratio) = options.aspect_ratio {
         // Mode 2: Aspect ratio crop
@@ -157,9 +159,17 @@ pub fn crop_image(img: DynamicImage, options: &CropOptions) -> Result<DynamicIma
         ));
     }
 
+    Ok((x, y, actual_width, actual_height))
+}
+
+/// Crop an image - zero-copy when possible
+pub fn crop_image(img: DynamicImage, options: &CropOptions) -> Result<DynamicImage, ImageError> {
+    let (src_width, src_height) = img.dimensions();
+    let (x, y, width, height) = crop_region(src_width, src_height, options)?;
+
     // Zero-copy crop - just creates a view!
     // This is essentially free (pointer arithmetic only)
-    Ok(img.crop_imm(x, y, actual_width, actual_height))
+    Ok(img.crop_imm(x, y, width, height))
 }
 
 #[cfg(test)]
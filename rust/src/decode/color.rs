@@ -0,0 +1,196 @@
+//! ICC color management
+//!
+//! Converts decoded pixels from the source's embedded ICC profile (sRGB when
+//! there is none) to sRGB or a caller-provided RGB profile using moxcms.
+//! 16-bit images are converted at full precision.
+
+use image::{ColorType, DynamicImage, ImageBuffer, Rgb, RgbImage};
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformOptions};
+
+use crate::error::ImageError;
+use crate::{ColorManagementOptions, RenderingIntent};
+
+fn profile_err(e: moxcms::CmsError) -> ImageError {
+  ImageError::ProcessingError(format!("Invalid ICC profile: {}", e))
+}
+
+fn transform_err(e: moxcms::CmsError) -> ImageError {
+  ImageError::ProcessingError(format!("Color conversion failed: {}", e))
+}
+
+/// Profile to convert to - sRGB unless the options give one
+fn target_profile(options: &ColorManagementOptions) -> Result<ColorProfile, ImageError> {
+  let Some(bytes) = options.target_profile.as_deref() else {
+    return Ok(ColorProfile::new_srgb());
+  };
+  let profile = ColorProfile::new_from_slice(bytes).map_err(profile_err)?;
+  if profile.color_space != DataColorSpace::Rgb {
+    return Err(ImageError::ProcessingError(
+      "Target ICC profile must be an RGB profile".to_string(),
+    ));
+  }
+  Ok(profile)
+}
+
+fn transform_options(options: &ColorManagementOptions) -> TransformOptions {
+  let rendering_intent = match options.intent.unwrap_or(RenderingIntent::Perceptual) {
+    RenderingIntent::Perceptual => moxcms::RenderingIntent::Perceptual,
+    RenderingIntent::RelativeColorimetric => moxcms::RenderingIntent::RelativeColorimetric,
+    RenderingIntent::Saturation => moxcms::RenderingIntent::Saturation,
+    RenderingIntent::AbsoluteColorimetric => moxcms::RenderingIntent::AbsoluteColorimetric,
+  };
+  TransformOptions {
+    rendering_intent,
+    ..Default::default()
+  }
+}
+
+/// Whether an ICC profile describes CMYK data
+pub fn is_cmyk_profile(profile: &[u8]) -> bool {
+  ColorProfile::new_from_slice(profile).is_ok_and(|p| p.color_space == DataColorSpace::Cmyk)
+}
+
+/// Convert decoded pixels from the source profile to the target
+/// Missing or unreadable source profiles are treated as sRGB; sources whose
+/// profile doesn't match the decoded pixels (e.g. CMYK) are returned unchanged
+pub fn convert_color(
+  img: DynamicImage,
+  source: Option<&[u8]>,
+  options: &ColorManagementOptions,
+) -> Result<DynamicImage, ImageError> {
+  let source = source.and_then(|bytes| ColorProfile::new_from_slice(bytes).ok());
+  if source.is_none() && options.target_profile.is_none() {
+    return Ok(img);
+  }
+  let source = source.unwrap_or_else(ColorProfile::new_srgb);
+  let gray = match source.color_space {
+    DataColorSpace::Gray => true,
+    DataColorSpace::Rgb => false,
+    _ => return Ok(img),
+  };
+  let target = target_profile(options)?;
+
+  let has_alpha = img.color().has_alpha();
+  let (src_layout, dst_layout) = match (gray, has_alpha) {
+    (true, false) => (Layout::Gray, Layout::Rgb),
+    (true, true) => (Layout::GrayAlpha, Layout::Rgba),
+    (false, false) => (Layout::Rgb, Layout::Rgb),
+    (false, true) => (Layout::Rgba, Layout::Rgba),
+  };
+  let (width, height) = (img.width(), img.height());
+  let dst_len = width as usize * height as usize * if has_alpha { 4 } else { 3 };
+  let high_depth = matches!(
+    img.color(),
+    ColorType::L16 | ColorType::La16 | ColorType::Rgb16 | ColorType::Rgba16 | ColorType::Rgb32F | ColorType::Rgba32F
+  );
+  let invalid = || ImageError::ProcessingError("Color conversion produced an invalid buffer".to_string());
+
+  if high_depth {
+    let src = match src_layout {
+      Layout::Gray => img.to_luma16().into_raw(),
+      Layout::GrayAlpha => img.to_luma_alpha16().into_raw(),
+      Layout::Rgb => img.to_rgb16().into_raw(),
+      _ => img.to_rgba16().into_raw(),
+    };
+    let mut dst = vec![0u16; dst_len];
+    source
+      .create_transform_16bit(src_layout, &target, dst_layout, transform_options(options))
+      .map_err(transform_err)?
+      .transform(&src, &mut dst)
+      .map_err(transform_err)?;
+    return Ok(if has_alpha {
+      DynamicImage::ImageRgba16(ImageBuffer::from_raw(width, height, dst).ok_or_else(invalid)?)
+    } else {
+      DynamicImage::ImageRgb16(ImageBuffer::from_raw(width, height, dst).ok_or_else(invalid)?)
+    });
+  }
+
+  let src = match src_layout {
+    Layout::Gray => img.to_luma8().into_raw(),
+    Layout::GrayAlpha => img.to_luma_alpha8().into_raw(),
+    Layout::Rgb => img.to_rgb8().into_raw(),
+    _ => img.to_rgba8().into_raw(),
+  };
+  let mut dst = vec![0u8; dst_len];
+  source
+    .create_transform_8bit(src_layout, &target, dst_layout, transform_options(options))
+    .map_err(transform_err)?
+    .transform(&src, &mut dst)
+    .map_err(transform_err)?;
+  Ok(if has_alpha {
+    DynamicImage::ImageRgba8(ImageBuffer::from_raw(width, height, dst).ok_or_else(invalid)?)
+  } else {
+    DynamicImage::ImageRgb8(ImageBuffer::from_raw(width, height, dst).ok_or_else(invalid)?)
+  })
+}
+
+/// Convert CMYK ink values (255 = full ink) through a CMYK profile to the target
+pub fn convert_cmyk(
+  cmyk: &[u8],
+  width: u32,
+  height: u32,
+  profile: &[u8],
+  options: &ColorManagementOptions,
+) -> Result<DynamicImage, ImageError> {
+  let source = ColorProfile::new_from_slice(profile).map_err(profile_err)?;
+  let target = target_profile(options)?;
+
+  // moxcms takes 4-channel CMYK through the Rgba layout
+  let mut rgb = vec![0u8; cmyk.len() / 4 * 3];
+  source
+    .create_transform_8bit(Layout::Rgba, &target, Layout::Rgb, transform_options(options))
+    .map_err(transform_err)?
+    .transform(cmyk, &mut rgb)
+    .map_err(transform_err)?;
+
+  let img: RgbImage = ImageBuffer::<Rgb<u8>, _>::from_raw(width, height, rgb)
+    .ok_or_else(|| ImageError::ProcessingError("Color conversion produced an invalid buffer".to_string()))?;
+  Ok(DynamicImage::ImageRgb8(img))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn pixels(img: &DynamicImage) -> Vec<u8> {
+    img.to_rgb8().into_raw()
+  }
+
+  #[test]
+  fn test_convert_color() {
+    let img = DynamicImage::ImageRgb8(RgbImage::from_raw(2, 1, vec![255, 0, 0, 128, 128, 128]).unwrap());
+
+    // sRGB source without a target is left alone
+    let unchanged = convert_color(img.clone(), None, &ColorManagementOptions::default()).unwrap();
+    assert_eq!(pixels(&unchanged), pixels(&img));
+
+    // sRGB red sits inside Display P3 at roughly (234, 51, 35)
+    let p3 = ColorProfile::new_display_p3().encode().unwrap();
+    let options = ColorManagementOptions {
+      target_profile: Some(p3.clone()),
+      intent: Some(RenderingIntent::RelativeColorimetric),
+    };
+    let converted = pixels(&convert_color(img.clone(), None, &options).unwrap());
+    for (value, expected) in converted.iter().zip([234u8, 51, 35, 128, 128, 128]) {
+      assert!(value.abs_diff(expected) <= 3, "{:?}", converted);
+    }
+
+    // And back to sRGB from the embedded profile
+    let p3_img = DynamicImage::ImageRgb8(RgbImage::from_raw(2, 1, converted).unwrap());
+    let back = pixels(&convert_color(p3_img, Some(&p3), &ColorManagementOptions::default()).unwrap());
+    for (value, expected) in back.iter().zip(pixels(&img)) {
+      assert!(value.abs_diff(expected) <= 3, "{:?}", back);
+    }
+  }
+
+  #[test]
+  fn test_target_must_be_rgb() {
+    let gray = ColorProfile::new_gray_with_gamma(2.2).encode().unwrap();
+    let options = ColorManagementOptions {
+      target_profile: Some(gray),
+      intent: None,
+    };
+    let img = DynamicImage::ImageRgb8(RgbImage::new(1, 1));
+    assert!(convert_color(img, None, &options).is_err());
+  }
+}
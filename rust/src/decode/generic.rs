@@ -1,12 +1,14 @@
 //! Generic image decoding fallback
 //!
-//! Uses the image crate for formats without specialized decoders.
+//! Uses the image crate for formats without specialized decoders
+//! (PNG, GIF, BMP, ICO, TIFF, QOI, PNM, TGA, HDR, OpenEXR, DDS).
 //! Includes memory protection for huge images.
 
 use image::{DynamicImage, ImageReader};
 use std::io::Cursor;
 
 use crate::error::ImageError;
+use crate::metadata::detect_format;
 
 /// Maximum pixel count before we require memory protection (100 megapixels)
 const MAX_PIXELS_DEFAULT: u64 = 100_000_000;
@@ -15,10 +17,9 @@ const MAX_PIXELS_DEFAULT: u64 = 100_000_000;
 /// Includes memory protection for huge images
 #[inline]
 pub fn decode_with_image_crate_safe(data: &[u8]) -> Result<DynamicImage, ImageError> {
-  let cursor = Cursor::new(data);
-  let reader = ImageReader::new(cursor)
-    .with_guessed_format()
-    .map_err(|e| ImageError::DecodeError(e.to_string()))?;
+  // detect_format also recognizes TGA, which has no magic bytes
+  let format = detect_format(data)?;
+  let reader = ImageReader::with_format(Cursor::new(data), format);
 
   // Check dimensions before decoding to prevent memory exhaustion
   let (width, height) = reader
@@ -37,10 +38,7 @@ pub fn decode_with_image_crate_safe(data: &[u8]) -> Result<DynamicImage, ImageEr
   }
 
   // Re-read and decode (into_dimensions consumes the reader)
-  let cursor = Cursor::new(data);
-  let reader = ImageReader::new(cursor)
-    .with_guessed_format()
-    .map_err(|e| ImageError::DecodeError(e.to_string()))?;
+  let reader = ImageReader::with_format(Cursor::new(data), format);
 
   reader
     .decode()
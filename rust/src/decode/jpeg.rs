@@ -6,6 +6,7 @@
 use image::{DynamicImage, RgbImage};
 
 use crate::error::ImageError;
+use crate::metadata::utils::jpeg_segments;
 
 /// JPEG shrink-on-load using turbojpeg (libjpeg-turbo with SIMD)
 /// This decodes JPEG at reduced resolution - THE key optimization
@@ -27,6 +28,54 @@ pub fn decode_jpeg_with_shrink_mode(
   target_height: Option<u32>,
   fast_mode: bool,
 ) -> Result<DynamicImage, ImageError> {
+  let (pixels, width, height) =
+    decompress_scaled(data, target_width, target_height, fast_mode, turbojpeg::PixelFormat::RGB)?;
+
+  let img = RgbImage::from_raw(width, height, pixels).ok_or_else(
+    || ImageError::DecodeError("Failed to create image from decoded data".to_string()),
+  )?;
+
+  Ok(DynamicImage::ImageRgb8(img))
+}
+
+/// Decode a CMYK/YCCK JPEG to its CMYK ink values (255 = full ink), with shrink-on-load
+/// Returns None for JPEGs in other color spaces
+pub fn decode_jpeg_cmyk(
+  data: &[u8],
+  target_width: Option<u32>,
+  target_height: Option<u32>,
+  fast_mode: bool,
+) -> Result<Option<(Vec<u8>, u32, u32)>, ImageError> {
+  let header = turbojpeg::read_header(data)
+    .map_err(|e| ImageError::DecodeError(format!("TurboJPEG header failed: {:?}", e)))?;
+  if !matches!(header.colorspace, turbojpeg::Colorspace::CMYK | turbojpeg::Colorspace::YCCK) {
+    return Ok(None);
+  }
+
+  let (mut pixels, width, height) =
+    decompress_scaled(data, target_width, target_height, fast_mode, turbojpeg::PixelFormat::CMYK)?;
+
+  // Adobe applications store inverted CMYK (255 = no ink)
+  let adobe = jpeg_segments(data)
+    .iter()
+    .any(|(marker, segment)| *marker == 0xEE && segment.starts_with(b"Adobe"));
+  if adobe {
+    for v in pixels.iter_mut() {
+      *v = 255 - *v;
+    }
+  }
+
+  Ok(Some((pixels, width, height)))
+}
+
+/// Decompress at the smallest turbojpeg scale that still covers the target
+fn decompress_scaled(
+  data: &[u8],
+  target_width: Option<u32>,
+  target_height: Option<u32>,
+  fast_mode: bool,
+  format: turbojpeg::PixelFormat,
+) -> Result<(Vec<u8>, u32, u32), ImageError> {
   // First, get original dimensions using fast header parsing
   let (src_width, src_height) = get_jpeg_dimensions_fast(data)?;
 
@@ -53,7 +102,7 @@ pub fn decode_jpeg_with_shrink_mode(
   let scaled_height = scaling.scale(header.height);
 
   // Allocate output buffer for scaled image
-  let pitch = scaled_width * 3; // RGB = 3 bytes per pixel
+  let pitch = scaled_width * format.size();
   let mut pixels = vec![0u8; pitch * scaled_height];
 
   // Create output image structure
@@ -62,7 +111,7 @@ pub fn decode_jpeg_with_shrink_mode(
     width: scaled_width,
     pitch,
     height: scaled_height,
-    format: turbojpeg::PixelFormat::RGB,
+    format,
   };
 
   // Decompress at scaled resolution
@@ -70,11 +119,7 @@ pub fn decode_jpeg_with_shrink_mode(
     .decompress(data, output)
     .map_err(|e| ImageError::DecodeError(format!("TurboJPEG decompress failed: {:?}", e)))?;
 
-  let img = RgbImage::from_raw(scaled_width as u32, scaled_height as u32, pixels).ok_or_else(
-    || ImageError::DecodeError("Failed to create image from decoded data".to_string()),
-  )?;
-
-  Ok(DynamicImage::ImageRgb8(img))
+  Ok((pixels, scaled_width as u32, scaled_height as u32))
 }
 
 /// Calculate optimal JPEG scale factor for shrink-on-load
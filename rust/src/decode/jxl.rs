@@ -0,0 +1,62 @@
+//! JPEG XL decoding using jxl-oxide (pure Rust)
+//!
+//! Decodes the first frame of bare codestreams and ISOBMFF containers.
+
+use image::DynamicImage;
+use jxl_oxide::integration::JxlDecoder;
+use std::io::Cursor;
+
+use crate::error::ImageError;
+use crate::metadata::parse_jxl_header;
+
+/// Maximum pixel count before we require memory protection (100 megapixels)
+const MAX_PIXELS_DEFAULT: u64 = 100_000_000;
+
+/// Decode a JPEG XL image
+/// Dimensions are checked from the header before decoding
+pub fn decode_jxl(data: &[u8]) -> Result<DynamicImage, ImageError> {
+  let header = parse_jxl_header(data)?;
+
+  let pixel_count = header.width as u64 * header.height as u64;
+  if pixel_count > MAX_PIXELS_DEFAULT {
+    return Err(ImageError::DecodeError(format!(
+      "JXL image too large: {}x{} ({} megapixels) exceeds limit of {} megapixels",
+      header.width,
+      header.height,
+      pixel_count / 1_000_000,
+      MAX_PIXELS_DEFAULT / 1_000_000
+    )));
+  }
+
+  let decoder = JxlDecoder::new(Cursor::new(data))
+    .map_err(|e| ImageError::DecodeError(format!("JXL decode error: {}", e)))?;
+
+  DynamicImage::from_decoder(decoder).map_err(|e| ImageError::DecodeError(format!("JXL decode error: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::animation::{decode_animation, is_animated};
+
+  // 240x135 RGB bare codestream
+  const SMALL_JXL: [u8; 42] = [
+    0xFF, 0x0A, 0x30, 0x54, 0x10, 0x09, 0x08, 0x06, 0x01, 0x00, 0x78, 0x00, 0x4B, 0x38, 0x41, 0x3C, 0xB6, 0x3A, 0x51,
+    0xFE, 0x00, 0x47, 0x1E, 0xA0, 0x85, 0xB8, 0x27, 0x1A, 0x48, 0x45, 0x84, 0x1B, 0x71, 0x4F, 0xA8, 0x3E, 0x8E, 0x30,
+    0x03, 0x92, 0x84, 0x01,
+  ];
+
+  #[test]
+  fn test_decode_jxl_codestream() {
+    let img = decode_jxl(&SMALL_JXL).unwrap();
+    assert_eq!((img.width(), img.height()), (240, 135));
+    assert_eq!(img.color(), image::ColorType::Rgb8);
+  }
+
+  #[test]
+  fn test_jxl_single_frame_animation() {
+    assert!(!is_animated(&SMALL_JXL));
+    let anim = decode_animation(&SMALL_JXL).unwrap();
+    assert_eq!((anim.width, anim.height, anim.frames.len()), (240, 135, 1));
+  }
+}
@@ -4,24 +4,33 @@
 //! Uses mozjpeg for shrink-on-load when downscaling (decode at reduced resolution).
 //! Uses libwebp for WebP shrink-on-load (decode directly to target resolution).
 //! Uses libheif for HEIC/HEIF decoding (iPhone photos) - optional feature.
+//! Uses jxl-oxide for JPEG XL decoding.
+//! Uses moxcms to convert from the embedded ICC profile when color management is requested.
 
+mod color;
 mod generic;
 mod heic;
 mod jpeg;
+mod jxl;
+mod raw;
 mod webp;
 
 use image::{DynamicImage, ImageFormat};
 
 use crate::error::ImageError;
 use crate::metadata;
+use crate::ColorManagementOptions;
 
+pub use color::convert_color;
 pub use generic::decode_with_image_crate_safe;
 pub use heic::decode_heic_with_target;
-pub use jpeg::{decode_jpeg_fast, decode_jpeg_with_shrink, decode_jpeg_with_shrink_mode};
+pub use jpeg::{decode_jpeg_cmyk, decode_jpeg_fast, decode_jpeg_with_shrink, decode_jpeg_with_shrink_mode};
+pub use jxl::decode_jxl;
+pub use raw::decode_raw;
 pub use webp::{decode_webp_fast, decode_webp_with_target};
 
 // Re-export metadata functions for backward compatibility
-pub use metadata::{detect_format, get_metadata, is_heic};
+pub use metadata::{detect_format, get_metadata, get_metadata_with_options, is_heic, is_jxl, is_raw};
 
 /// Decode image from bytes - uses optimized decoders per format
 #[inline]
@@ -37,23 +46,59 @@ pub fn decode_image_with_target(
   target_width: Option<u32>,
   target_height: Option<u32>,
 ) -> Result<DynamicImage, ImageError> {
-  decode_image_with_target_fast(data, target_width, target_height, false)
+  decode_image_with_target_fast(data, target_width, target_height, false, None)
 }
 
 /// Decode image with target dimensions and fast mode option
 /// Fast mode uses more aggressive shrink-on-load for maximum speed
-#[inline]
+/// With color management, pixels are converted from the embedded ICC profile
+/// before any resize so every output sees the same colors
 pub fn decode_image_with_target_fast(
   data: &[u8],
   target_width: Option<u32>,
   target_height: Option<u32>,
   fast_mode: bool,
+  color: Option<&ColorManagementOptions>,
+) -> Result<DynamicImage, ImageError> {
+  let Some(color) = color else {
+    return decode_pixels(data, target_width, target_height, fast_mode);
+  };
+  let profile = metadata::find_icc_profile(data);
+
+  // CMYK JPEG: decode the ink values and convert them through the profile
+  if let Some(profile) = profile.as_deref().filter(|p| data.starts_with(&[0xFF, 0xD8]) && color::is_cmyk_profile(p)) {
+    if let Some((cmyk, width, height)) = decode_jpeg_cmyk(data, target_width, target_height, fast_mode)? {
+      return color::convert_cmyk(&cmyk, width, height, profile, color);
+    }
+  }
+
+  let img = decode_pixels(data, target_width, target_height, fast_mode)?;
+  color::convert_color(img, profile.as_deref(), color)
+}
+
+/// Decode with the fastest decoder for the format
+#[inline]
+fn decode_pixels(
+  data: &[u8],
+  target_width: Option<u32>,
+  target_height: Option<u32>,
+  fast_mode: bool,
 ) -> Result<DynamicImage, ImageError> {
   // Check for HEIC first - use shrink-on-decode if target provided
   if is_heic(data) {
     return decode_heic_with_target(data, target_width, target_height);
   }
 
+  // JPEG XL has no image crate format - no shrink-on-load, resize handles targets
+  if is_jxl(data) {
+    return decode_jxl(data);
+  }
+
+  // Raw pixels - no decode, resize handles targets
+  if is_raw(data) {
+    return decode_raw(data);
+  }
+
   let format = detect_format(data)?;
 
   match format {
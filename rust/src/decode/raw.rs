@@ -0,0 +1,22 @@
+//! Raw pixel input - wraps tightly packed 8-bit pixels without a codec
+
+use image::{DynamicImage, GrayAlphaImage, GrayImage, RgbImage, RgbaImage};
+
+use crate::error::ImageError;
+use crate::metadata::{parse_raw_header, RAW_HEADER_LEN};
+
+/// Build an image from the raw pixel container (1 = gray, 2 = gray+alpha, 3 = RGB, 4 = RGBA)
+pub fn decode_raw(data: &[u8]) -> Result<DynamicImage, ImageError> {
+  let header = parse_raw_header(data)?;
+  let (width, height) = (header.width, header.height);
+  let pixels = data[RAW_HEADER_LEN..].to_vec();
+
+  // parse_raw_header checked the length, so from_raw can't fail
+  let invalid = || ImageError::DecodeError("Raw pixel buffer size mismatch".to_string());
+  Ok(match header.channels {
+    1 => DynamicImage::ImageLuma8(GrayImage::from_raw(width, height, pixels).ok_or_else(invalid)?),
+    2 => DynamicImage::ImageLumaA8(GrayAlphaImage::from_raw(width, height, pixels).ok_or_else(invalid)?),
+    3 => DynamicImage::ImageRgb8(RgbImage::from_raw(width, height, pixels).ok_or_else(invalid)?),
+    _ => DynamicImage::ImageRgba8(RgbaImage::from_raw(width, height, pixels).ok_or_else(invalid)?),
+  })
+}
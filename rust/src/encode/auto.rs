@@ -0,0 +1,269 @@
+//! Content-aware output format selection (`format: Auto`)
+//!
+//! Picks JPEG, PNG(-8) or WebP from alpha, color count and a photo/graphic
+//! estimate (luma entropy and neighbour statistics), limited to the formats
+//! the client accepts. AVIF is never picked - there is no AVIF encoder.
+
+use std::collections::HashSet;
+
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+use crate::error::ImageError;
+use crate::{ImageFormat, OutputOptions, PngOptions, WebPOptions};
+
+/// Longest side of the sample the content statistics are computed on
+const SAMPLE_SIZE: u32 = 256;
+
+/// Photos rarely have identical neighbours; flat graphics have many
+const PHOTO_MAX_FLAT_RATIO: f64 = 0.3;
+
+/// Minimum luma entropy (bits) of photographic content
+const PHOTO_MIN_ENTROPY: f64 = 5.0;
+
+/// Neighbour difference counted as a hard edge
+const EDGE_THRESHOLD: u8 = 64;
+
+/// Hard edges typical of text, line art and UI screenshots
+const GRAPHIC_MIN_EDGE_RATIO: f64 = 0.08;
+
+/// Content statistics used to pick a format
+#[derive(Debug)]
+pub struct ContentStats {
+  /// At least one pixel is not fully opaque
+  pub has_alpha: bool,
+  /// Distinct RGBA colors, capped at 257
+  pub colors: usize,
+  /// Shannon entropy of the luma histogram (0-8 bits)
+  pub entropy: f64,
+  /// Share of horizontally adjacent pixels with identical luma
+  pub flat_ratio: f64,
+  /// Share of horizontally adjacent pixels with a hard luma edge
+  pub edge_ratio: f64,
+}
+
+impl ContentStats {
+  /// Continuous-tone content that compresses well with lossy codecs
+  pub fn is_photographic(&self) -> bool {
+    self.flat_ratio < PHOTO_MAX_FLAT_RATIO
+      && self.entropy >= PHOTO_MIN_ENTROPY
+      && self.edge_ratio < GRAPHIC_MIN_EDGE_RATIO
+  }
+}
+
+/// Count distinct colors, stopping once there are more than 256
+fn count_colors(rgba: &RgbaImage) -> usize {
+  let mut colors = HashSet::with_capacity(257);
+  for pixel in rgba.pixels() {
+    colors.insert(pixel.0);
+    if colors.len() > 256 {
+      break;
+    }
+  }
+  colors.len()
+}
+
+/// Gather alpha, color count and photo/graphic statistics
+pub fn analyze(img: &DynamicImage) -> ContentStats {
+  let rgba = img.to_rgba8();
+  let has_alpha = img.color().has_alpha() && rgba.pixels().any(|p| p[3] < 255);
+
+  let (width, height) = img.dimensions();
+  let sample = if width.max(height) > SAMPLE_SIZE {
+    img.thumbnail(SAMPLE_SIZE, SAMPLE_SIZE)
+  } else {
+    img.clone()
+  };
+  let luma = sample.to_luma8();
+
+  let mut histogram = [0u64; 256];
+  for pixel in luma.pixels() {
+    histogram[pixel[0] as usize] += 1;
+  }
+  let total = luma.len() as f64;
+  let entropy = histogram
+    .iter()
+    .filter(|&&count| count > 0)
+    .map(|&count| {
+      let p = count as f64 / total;
+      -p * p.log2()
+    })
+    .sum();
+
+  let (mut pairs, mut flat, mut edges) = (0u64, 0u64, 0u64);
+  for row in luma.rows() {
+    let row: Vec<u8> = row.map(|p| p[0]).collect();
+    for pair in row.windows(2) {
+      let diff = pair[0].abs_diff(pair[1]);
+      pairs += 1;
+      flat += (diff == 0) as u64;
+      edges += (diff > EDGE_THRESHOLD) as u64;
+    }
+  }
+  let ratio = |count: u64| if pairs == 0 { 0.0 } else { count as f64 / pairs as f64 };
+
+  ContentStats {
+    has_alpha,
+    colors: count_colors(&rgba),
+    entropy,
+    flat_ratio: ratio(flat),
+    edge_ratio: ratio(edges),
+  }
+}
+
+/// Format chosen for the content
+#[derive(Debug, PartialEq)]
+pub enum AutoChoice {
+  Jpeg,
+  /// Exact indexed palette (256 colors or fewer)
+  Png8,
+  Png,
+  WebP,
+  WebPLossless,
+}
+
+/// Pick the best accepted format
+/// `lossy_only` restricts the choice to JPEG/WebP (for max_bytes/target_quality)
+pub fn choose_format(stats: &ContentStats, accept: &[ImageFormat], lossy_only: bool) -> Result<AutoChoice, ImageError> {
+  let jpeg = accept.iter().any(|f| matches!(f, ImageFormat::Jpeg));
+  let png = accept.iter().any(|f| matches!(f, ImageFormat::Png));
+  let webp = accept.iter().any(|f| matches!(f, ImageFormat::WebP));
+
+  let candidates: Vec<(bool, AutoChoice)> = if lossy_only {
+    vec![(webp, AutoChoice::WebP), (jpeg, AutoChoice::Jpeg)]
+  } else if stats.colors <= 256 {
+    // Lossless palette output is smallest for flat graphics and icons
+    vec![(png, AutoChoice::Png8), (webp, AutoChoice::WebPLossless), (jpeg, AutoChoice::Jpeg)]
+  } else if stats.is_photographic() {
+    // Lossy WebP keeps alpha; JPEG would flatten it
+    vec![
+      (webp, AutoChoice::WebP),
+      (jpeg && !stats.has_alpha, AutoChoice::Jpeg),
+      (png, AutoChoice::Png),
+      (jpeg, AutoChoice::Jpeg),
+    ]
+  } else {
+    // Screenshots, text and line art: lossless keeps edges sharp
+    vec![
+      (webp, AutoChoice::WebPLossless),
+      (png, AutoChoice::Png),
+      (jpeg, AutoChoice::Jpeg),
+    ]
+  };
+
+  candidates
+    .into_iter()
+    .find(|(accepted, _)| *accepted)
+    .map(|(_, choice)| choice)
+    .ok_or_else(|| {
+      ImageError::UnsupportedFormat(if lossy_only {
+        "No accepted lossy output format - accept JPEG or WebP".to_string()
+      } else {
+        "No accepted output format - accept JPEG, PNG or WebP (AVIF encoding not supported)".to_string()
+      })
+    })
+}
+
+/// Replace `format: Auto` with the format chosen for the image
+/// Without `accept`, JPEG, PNG and WebP are all allowed
+pub fn resolve_auto_format(img: &DynamicImage, options: &OutputOptions) -> Result<OutputOptions, ImageError> {
+  let accept = options
+    .accept
+    .clone()
+    .unwrap_or_else(|| vec![ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP]);
+  let lossy_only = options.max_bytes.is_some() || options.target_quality.is_some();
+
+  let mut resolved = options.clone();
+  match choose_format(&analyze(img), &accept, lossy_only)? {
+    AutoChoice::Jpeg => resolved.format = ImageFormat::Jpeg,
+    AutoChoice::Png => resolved.format = ImageFormat::Png,
+    AutoChoice::Png8 => {
+      resolved.format = ImageFormat::Png;
+      // No dithering - the palette holds every color exactly
+      resolved.png = Some(PngOptions {
+        palette: Some(true),
+        dither: Some(0.0),
+        ..options.png.clone().unwrap_or_default()
+      });
+    }
+    AutoChoice::WebP => resolved.format = ImageFormat::WebP,
+    AutoChoice::WebPLossless => {
+      resolved.format = ImageFormat::WebP;
+      resolved.webp = Some(WebPOptions {
+        lossless: Some(true),
+        ..options.webp.clone().unwrap_or_default()
+      });
+    }
+  }
+  Ok(resolved)
+}
+
+/// Lowercase format name reported with the output
+pub fn format_name(format: &ImageFormat) -> &'static str {
+  match format {
+    ImageFormat::Jpeg => "jpeg",
+    ImageFormat::Png => "png",
+    ImageFormat::WebP => "webp",
+    ImageFormat::Gif => "gif",
+    ImageFormat::Bmp => "bmp",
+    ImageFormat::Ico => "ico",
+    ImageFormat::Tiff => "tiff",
+    ImageFormat::Heic => "heic",
+    ImageFormat::Avif => "avif",
+    ImageFormat::Qoi => "qoi",
+    ImageFormat::Pnm => "pnm",
+    ImageFormat::Tga => "tga",
+    ImageFormat::Raw => "raw",
+    ImageFormat::Auto => "auto",
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use image::{Rgb, RgbImage, Rgba};
+
+  const ALL: [ImageFormat; 3] = [ImageFormat::Jpeg, ImageFormat::Png, ImageFormat::WebP];
+
+  /// Smooth gradient with pseudo-random grain, like a photo
+  fn photo() -> DynamicImage {
+    let mut seed = 12345u32;
+    DynamicImage::ImageRgb8(RgbImage::from_fn(128, 128, |x, y| {
+      seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+      let noise = (seed >> 16) as u8 % 24;
+      let base = ((x + y) / 2) as u8;
+      Rgb([base.saturating_add(noise), base / 2 + noise, 200 - base / 2])
+    }))
+  }
+
+  #[test]
+  fn test_photo_prefers_webp_then_jpeg() {
+    let stats = analyze(&photo());
+    assert!(stats.is_photographic(), "{:?}", stats);
+    assert_eq!(choose_format(&stats, &ALL, false).unwrap(), AutoChoice::WebP);
+    assert_eq!(
+      choose_format(&stats, &[ImageFormat::Jpeg, ImageFormat::Png], false).unwrap(),
+      AutoChoice::Jpeg
+    );
+  }
+
+  #[test]
+  fn test_few_colors_use_palette() {
+    let img = DynamicImage::ImageRgba8(RgbaImage::from_fn(64, 64, |x, _| {
+      if x < 32 {
+        Rgba([255, 0, 0, 255])
+      } else {
+        Rgba([0, 0, 0, 0])
+      }
+    }));
+    let stats = analyze(&img);
+    assert!(stats.has_alpha);
+    assert_eq!(stats.colors, 2);
+    assert_eq!(choose_format(&stats, &ALL, false).unwrap(), AutoChoice::Png8);
+    // JPEG-only clients still get an image, AVIF-only ones an error
+    assert_eq!(
+      choose_format(&stats, &[ImageFormat::Jpeg, ImageFormat::Avif], false).unwrap(),
+      AutoChoice::Jpeg
+    );
+    assert!(choose_format(&stats, &[ImageFormat::Avif], false).is_err());
+  }
+}
@@ -0,0 +1,258 @@
+//! Encode to a byte budget (`max_bytes`) or a perceptual quality target (`target_quality`)
+//!
+//! Both binary-search the lossy quality: `max_bytes` for the highest quality
+//! that fits (optionally stepping the dimensions down), `target_quality` for
+//! the lowest quality whose decoded output still meets the metric.
+
+use image::{DynamicImage, GenericImageView};
+
+use crate::compare;
+use crate::decode;
+use crate::error::ImageError;
+use crate::resize;
+use crate::{
+  CompareMetric, CompareOptions, FitMode, ImageFormat, JpegOptions, ResizeFilter, ResizeOptions, TargetQuality,
+  WebPOptions,
+};
+
+use super::{encode_jpeg, encode_webp};
+
+/// Each downscale step keeps 80% of the width and height
+const DOWNSCALE_FACTOR: f64 = 0.8;
+
+/// Stop downscaling below this size (in pixels, shorter side)
+const MIN_DIMENSION: u32 = 16;
+
+/// Output of a quality search
+pub struct FittedOutput {
+  pub data: Vec<u8>,
+  /// Quality the output was encoded with
+  pub quality: u8,
+  /// Number of encodes tried
+  pub attempts: u32,
+  pub width: u32,
+  pub height: u32,
+  /// Metric score of the output (target_quality only)
+  pub score: Option<f64>,
+}
+
+/// JPEG or WebP encoder with a variable quality
+struct LossyEncoder<'a> {
+  format: &'a ImageFormat,
+  jpeg_opts: Option<&'a JpegOptions>,
+  webp_opts: Option<&'a WebPOptions>,
+}
+
+impl<'a> LossyEncoder<'a> {
+  fn new(
+    format: &'a ImageFormat,
+    jpeg_opts: Option<&'a JpegOptions>,
+    webp_opts: Option<&'a WebPOptions>,
+    option_name: &str,
+  ) -> Result<Self, ImageError> {
+    match format {
+      ImageFormat::Jpeg | ImageFormat::WebP => Ok(LossyEncoder {
+        format,
+        jpeg_opts,
+        webp_opts,
+      }),
+      ImageFormat::Avif => Err(ImageError::UnsupportedFormat(format!(
+        "AVIF encoding not supported - use JPEG or WebP with {}",
+        option_name
+      ))),
+      _ => Err(ImageError::UnsupportedFormat(format!(
+        "{} requires lossy output (JPEG or WebP)",
+        option_name
+      ))),
+    }
+  }
+
+  /// Quality set in the format options
+  fn configured_quality(&self) -> Option<u8> {
+    match self.format {
+      ImageFormat::Jpeg => self.jpeg_opts.and_then(|o| o.quality),
+      _ => self.webp_opts.and_then(|o| o.quality),
+    }
+  }
+
+  fn encode(&self, img: &DynamicImage, quality: u8) -> Result<Vec<u8>, ImageError> {
+    match self.format {
+      ImageFormat::Jpeg => {
+        let opts = JpegOptions {
+          quality: Some(quality),
+          ..self.jpeg_opts.cloned().unwrap_or_default()
+        };
+        encode_jpeg(img, Some(&opts))
+      }
+      _ => {
+        // Quality search needs plain lossy encoding
+        let opts = WebPOptions {
+          quality: Some(quality),
+          lossless: Some(false),
+          near_lossless: None,
+          target_size: None,
+          target_psnr: None,
+          ..self.webp_opts.cloned().unwrap_or_default()
+        };
+        encode_webp(img, Some(&opts))
+      }
+    }
+  }
+}
+
+/// Encode as JPEG or WebP no larger than `max_bytes`
+/// The format options' quality (default 80) is the upper bound of the search
+pub fn encode_to_max_bytes(
+  img: &DynamicImage,
+  format: &ImageFormat,
+  jpeg_opts: Option<&JpegOptions>,
+  webp_opts: Option<&WebPOptions>,
+  max_bytes: u32,
+  downscale: bool,
+) -> Result<FittedOutput, ImageError> {
+  let encoder = LossyEncoder::new(format, jpeg_opts, webp_opts, "max_bytes")?;
+  let max_quality = encoder.configured_quality().unwrap_or(80).clamp(1, 100);
+
+  let mut attempts = 0;
+  let mut scaled: Option<DynamicImage> = None;
+  loop {
+    let current = scaled.as_ref().unwrap_or(img);
+    let (width, height) = current.dimensions();
+
+    let (data, quality, smallest) = search_quality(current, max_bytes, max_quality, &mut attempts, &encoder)?;
+    if let Some(data) = data {
+      return Ok(FittedOutput {
+        data,
+        quality,
+        attempts,
+        width,
+        height,
+        score: None,
+      });
+    }
+
+    let next_width = (width as f64 * DOWNSCALE_FACTOR).round() as u32;
+    let next_height = (height as f64 * DOWNSCALE_FACTOR).round() as u32;
+    if !downscale || next_width.min(next_height) < MIN_DIMENSION {
+      return Err(ImageError::EncodeError(format!(
+        "Cannot fit {}x{} image in {} bytes (smallest output: {} bytes at quality 1)",
+        width, height, max_bytes, smallest
+      )));
+    }
+
+    // Always resample from the original to avoid compounding blur
+    let resize_opts = ResizeOptions {
+      width: Some(next_width),
+      height: Some(next_height),
+      filter: Some(ResizeFilter::Lanczos3),
+      fit: Some(FitMode::Fill),
+      background: None,
+    };
+    scaled = Some(resize::resize_image(img.clone(), &resize_opts)?);
+  }
+}
+
+/// Largest quality in 1..=max_quality whose output fits
+/// Returns (output, quality, size at quality 1) - output is None when nothing fits
+fn search_quality(
+  img: &DynamicImage,
+  max_bytes: u32,
+  max_quality: u8,
+  attempts: &mut u32,
+  encoder: &LossyEncoder,
+) -> Result<(Option<Vec<u8>>, u8, usize), ImageError> {
+  let max_bytes = max_bytes as usize;
+  let mut try_quality = |quality: u8| {
+    *attempts += 1;
+    encoder.encode(img, quality)
+  };
+
+  let best = try_quality(max_quality)?;
+  if best.len() <= max_bytes {
+    return Ok((Some(best), max_quality, 0));
+  }
+  if max_quality == 1 {
+    return Ok((None, 1, best.len()));
+  }
+
+  let lowest = try_quality(1)?;
+  if lowest.len() > max_bytes {
+    return Ok((None, 1, lowest.len()));
+  }
+
+  // Invariant: `fits` fits, `too_big` doesn't
+  let (mut fits, mut too_big) = (1u8, max_quality);
+  let mut output = lowest;
+  while too_big - fits > 1 {
+    let mid = fits + (too_big - fits) / 2;
+    let data = try_quality(mid)?;
+    if data.len() <= max_bytes {
+      fits = mid;
+      output = data;
+    } else {
+      too_big = mid;
+    }
+  }
+  Ok((Some(output), fits, 0))
+}
+
+/// Encode as JPEG or WebP at the lowest quality whose decoded output meets the target
+/// The format options' quality (default 100) is the upper bound; if even that misses
+/// the target, the upper bound output is returned with its score
+pub fn encode_to_target_quality(
+  img: &DynamicImage,
+  format: &ImageFormat,
+  jpeg_opts: Option<&JpegOptions>,
+  webp_opts: Option<&WebPOptions>,
+  target: &TargetQuality,
+) -> Result<FittedOutput, ImageError> {
+  let encoder = LossyEncoder::new(format, jpeg_opts, webp_opts, "target_quality")?;
+  let max_quality = encoder.configured_quality().unwrap_or(100).clamp(1, 100);
+
+  let metric = target.metric.clone().unwrap_or(CompareMetric::Ssim);
+  let compare_opts = CompareOptions {
+    color_space: target.color_space.clone(),
+    heatmap: None,
+  };
+  // DSSIM is a distance (lower is better), the other metrics are similarities
+  let meets = |score: f64| match metric {
+    CompareMetric::Dssim => score <= target.value,
+    _ => score >= target.value,
+  };
+
+  let mut attempts = 0;
+  let mut try_quality = |quality: u8| -> Result<(Vec<u8>, f64), ImageError> {
+    attempts += 1;
+    let data = encoder.encode(img, quality)?;
+    let decoded = decode::decode_image(&data)?;
+    let score = compare::compare_images(img, &decoded, &metric, Some(&compare_opts))?.score;
+    Ok((data, score))
+  };
+
+  // Invariant: `passes` meets the target, `fails` doesn't (quality 0 is never tried)
+  let (mut output, mut score) = try_quality(max_quality)?;
+  let (mut fails, mut passes) = (0u8, max_quality);
+  if meets(score) {
+    while passes - fails > 1 {
+      let mid = fails + (passes - fails) / 2;
+      let (data, mid_score) = try_quality(mid)?;
+      if meets(mid_score) {
+        passes = mid;
+        output = data;
+        score = mid_score;
+      } else {
+        fails = mid;
+      }
+    }
+  }
+
+  let (width, height) = img.dimensions();
+  Ok(FittedOutput {
+    data: output,
+    quality: passes,
+    attempts,
+    width,
+    height,
+    score: Some(score),
+  })
+}
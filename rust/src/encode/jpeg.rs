@@ -0,0 +1,434 @@
+//! JPEG encoding
+//!
+//! Uses turbojpeg (libjpeg-turbo with SIMD) by default for maximum speed.
+//! Uses mozjpeg (trellis quantization, deringing, tuned tables) when
+//! `mozjpeg: true` is set - slower, but typically 10-20% smaller files.
+//! Grayscale images are written as single-channel JPEGs; `color_space: Cmyk`
+//! writes Adobe CMYK (YCCK) for print, converted with the given ICC profile.
+
+use image::{ColorType, DynamicImage};
+use moxcms::{ColorProfile, DataColorSpace, Layout, TransformOptions};
+use mozjpeg_sys::*;
+use std::mem;
+use std::os::raw::c_ulong;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::error::ImageError;
+use crate::metadata_write;
+use crate::{JpegColorSpace, JpegOptions, JpegQuantTable};
+
+extern "C" {
+  fn free(ptr: *mut c_void);
+}
+
+/// Chroma subsampling as (luma h_samp, luma v_samp)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub(crate) enum ChromaSubsampling {
+  /// 4:4:4 - no subsampling
+  Yuv444,
+  /// 4:2:2 - half horizontal chroma resolution
+  Yuv422,
+  /// 4:2:0 - half horizontal and vertical chroma resolution
+  Yuv420,
+}
+
+impl ChromaSubsampling {
+  fn samp_factors(self) -> (c_int, c_int) {
+    match self {
+      ChromaSubsampling::Yuv444 => (1, 1),
+      ChromaSubsampling::Yuv422 => (2, 1),
+      ChromaSubsampling::Yuv420 => (2, 2),
+    }
+  }
+
+  fn turbojpeg(self) -> turbojpeg::Subsamp {
+    match self {
+      ChromaSubsampling::Yuv444 => turbojpeg::Subsamp::None,
+      ChromaSubsampling::Yuv422 => turbojpeg::Subsamp::Sub2x1,
+      ChromaSubsampling::Yuv420 => turbojpeg::Subsamp::Sub2x2,
+    }
+  }
+}
+
+/// Parse chroma subsampling string ("4:4:4", "4:2:2", "4:2:0")
+pub(crate) fn parse_chroma_subsampling(value: &str) -> Result<ChromaSubsampling, ImageError> {
+  match value.trim() {
+    "4:4:4" | "444" => Ok(ChromaSubsampling::Yuv444),
+    "4:2:2" | "422" => Ok(ChromaSubsampling::Yuv422),
+    "4:2:0" | "420" => Ok(ChromaSubsampling::Yuv420),
+    other => Err(ImageError::EncodeError(format!(
+      "Invalid chroma subsampling: {}. Use '4:4:4', '4:2:2' or '4:2:0'",
+      other
+    ))),
+  }
+}
+
+/// Packed pixels handed to turbojpeg/mozjpeg
+struct JpegPixels {
+  data: Vec<u8>,
+  color_space: JpegColorSpace,
+}
+
+impl JpegPixels {
+  fn components(&self) -> usize {
+    match self.color_space {
+      JpegColorSpace::Gray => 1,
+      JpegColorSpace::Rgb => 3,
+      JpegColorSpace::Cmyk => 4,
+    }
+  }
+
+  fn turbojpeg_format(&self) -> turbojpeg::PixelFormat {
+    match self.color_space {
+      JpegColorSpace::Gray => turbojpeg::PixelFormat::GRAY,
+      JpegColorSpace::Rgb => turbojpeg::PixelFormat::RGB,
+      JpegColorSpace::Cmyk => turbojpeg::PixelFormat::CMYK,
+    }
+  }
+
+  fn in_color_space(&self) -> J_COLOR_SPACE {
+    match self.color_space {
+      JpegColorSpace::Gray => JCS_GRAYSCALE,
+      JpegColorSpace::Rgb => JCS_RGB,
+      JpegColorSpace::Cmyk => JCS_CMYK,
+    }
+  }
+}
+
+/// Gray for grayscale sources unless a color space is set
+fn output_color_space(img: &DynamicImage, options: Option<&JpegOptions>) -> JpegColorSpace {
+  options.and_then(|o| o.color_space).unwrap_or(match img.color() {
+    ColorType::L8 | ColorType::La8 | ColorType::L16 | ColorType::La16 => JpegColorSpace::Gray,
+    _ => JpegColorSpace::Rgb,
+  })
+}
+
+/// Convert to CMYK through the profile, or with the naive formula without one
+/// Values are inverted (255 = no ink) as Adobe CMYK JPEGs store them
+fn to_cmyk(img: &DynamicImage, profile: Option<&[u8]>) -> Result<Vec<u8>, ImageError> {
+  let rgb = img.to_rgb8();
+
+  let mut cmyk = match profile {
+    Some(profile) => {
+      let profile_err = |e: moxcms::CmsError| ImageError::EncodeError(format!("Invalid ICC profile: {}", e));
+      let target = ColorProfile::new_from_slice(profile).map_err(profile_err)?;
+      if target.color_space != DataColorSpace::Cmyk {
+        return Err(ImageError::EncodeError(
+          "CMYK output requires a CMYK ICC profile".to_string(),
+        ));
+      }
+      let transform = ColorProfile::new_srgb()
+        .create_transform_8bit(Layout::Rgb, &target, Layout::Rgba, TransformOptions::default())
+        .map_err(profile_err)?;
+      let mut cmyk = vec![0u8; rgb.len() / 3 * 4];
+      transform
+        .transform(rgb.as_raw(), &mut cmyk)
+        .map_err(|e| ImageError::EncodeError(format!("CMYK conversion failed: {}", e)))?;
+      cmyk
+    }
+    None => rgb
+      .pixels()
+      .flat_map(|p| {
+        let [r, g, b] = p.0.map(|v| v as f32 / 255.0);
+        let k = 1.0 - r.max(g).max(b);
+        let ink = |v: f32| if k >= 1.0 { 0.0 } else { (1.0 - v - k) / (1.0 - k) };
+        [ink(r), ink(g), ink(b), k].map(|v| (v * 255.0).round() as u8)
+      })
+      .collect(),
+  };
+
+  for v in cmyk.iter_mut() {
+    *v = 255 - *v;
+  }
+  Ok(cmyk)
+}
+
+/// mozjpeg base quantization table index (JINT_BASE_QUANT_TBL_IDX)
+fn quant_table_index(table: &JpegQuantTable) -> c_int {
+  match table {
+    JpegQuantTable::AnnexK => 0,
+    JpegQuantTable::Flat => 1,
+    JpegQuantTable::MsSsim => 2,
+    JpegQuantTable::ImageMagick => 3,
+    JpegQuantTable::PsnrHvsM => 4,
+    JpegQuantTable::KleinSilversteinCarney => 5,
+    JpegQuantTable::WatsonTaylorBorthwick => 6,
+    JpegQuantTable::AhumadaWatsonPeterson => 7,
+    JpegQuantTable::PetersonAhumadaWatson => 8,
+  }
+}
+
+/// Encode image to JPEG
+/// turbojpeg by default; mozjpeg when `mozjpeg: true`
+#[inline(always)]
+pub fn encode_jpeg(img: &DynamicImage, options: Option<&JpegOptions>) -> Result<Vec<u8>, ImageError> {
+  let quality = options.and_then(|o| o.quality).unwrap_or(80) as i32;
+  let quality = quality.clamp(1, 100);
+  let subsampling = match options.and_then(|o| o.chroma_subsampling.as_deref()) {
+    Some(value) => parse_chroma_subsampling(value)?,
+    None => ChromaSubsampling::Yuv420,
+  };
+
+  let color_space = output_color_space(img, options);
+  let icc_profile = options.and_then(|o| o.icc_profile.as_deref());
+  let data = match color_space {
+    JpegColorSpace::Gray => img.to_luma8().into_raw(),
+    JpegColorSpace::Rgb => img.to_rgb8().into_raw(),
+    JpegColorSpace::Cmyk => to_cmyk(img, icc_profile)?,
+  };
+  let pixels = JpegPixels { data, color_space };
+  let (width, height) = (img.width(), img.height());
+
+  let output = if options.and_then(|o| o.mozjpeg).unwrap_or(false) {
+    encode_jpeg_mozjpeg(&pixels, width, height, quality, subsampling, options)?
+  } else {
+    encode_jpeg_turbojpeg(&pixels, width, height, quality, subsampling, options)?
+  };
+
+  match icc_profile {
+    Some(profile) => metadata_write::write_jpeg_icc(&output, profile),
+    None => Ok(output),
+  }
+}
+
+/// Encode packed pixels with turbojpeg
+fn encode_jpeg_turbojpeg(
+  pixels: &JpegPixels,
+  width: u32,
+  height: u32,
+  quality: i32,
+  subsampling: ChromaSubsampling,
+  options: Option<&JpegOptions>,
+) -> Result<Vec<u8>, ImageError> {
+  // Create turbojpeg image structure
+  let image = turbojpeg::Image {
+    pixels: pixels.data.as_slice(),
+    width: width as usize,
+    pitch: width as usize * pixels.components(),
+    height: height as usize,
+    format: pixels.turbojpeg_format(),
+  };
+  // Grayscale has no chroma to subsample
+  let subsamp = match pixels.color_space {
+    JpegColorSpace::Gray => turbojpeg::Subsamp::Gray,
+    _ => subsampling.turbojpeg(),
+  };
+
+  let progressive = options.and_then(|o| o.progressive).unwrap_or(false);
+  let optimize_coding = options.and_then(|o| o.optimize_coding).unwrap_or(false);
+
+  // Fast path: one-shot compress with default settings
+  if !progressive && !optimize_coding {
+    let output = turbojpeg::compress(image, quality, subsamp)
+      .map_err(|e| ImageError::EncodeError(format!("TurboJPEG encode failed: {:?}", e)))?;
+    return Ok(output.to_vec());
+  }
+
+  // Encode with turbojpeg - uses SIMD for maximum speed
+  let mut compressor = turbojpeg::Compressor::new()
+    .map_err(|e| ImageError::EncodeError(format!("TurboJPEG init failed: {:?}", e)))?;
+  let config_err = |e: turbojpeg::Error| ImageError::EncodeError(format!("TurboJPEG config failed: {:?}", e));
+  compressor.set_quality(quality).map_err(config_err)?;
+  compressor.set_subsamp(subsamp).map_err(config_err)?;
+  compressor.set_optimize(optimize_coding).map_err(config_err)?;
+  compressor.set_progressive(progressive).map_err(config_err)?;
+
+  compressor
+    .compress_to_vec(image)
+    .map_err(|e| ImageError::EncodeError(format!("TurboJPEG encode failed: {:?}", e)))
+}
+
+/// Encode packed pixels with mozjpeg
+/// libjpeg reports fatal errors through error_exit, which unwinds back here
+fn encode_jpeg_mozjpeg(
+  pixels: &JpegPixels,
+  width: u32,
+  height: u32,
+  quality: i32,
+  subsampling: ChromaSubsampling,
+  options: Option<&JpegOptions>,
+) -> Result<Vec<u8>, ImageError> {
+  let result = catch_unwind(AssertUnwindSafe(|| unsafe {
+    compress_mozjpeg(pixels, width, height, quality, subsampling, options)
+  }));
+
+  result.map_err(|payload| {
+    let message = payload
+      .downcast_ref::<String>()
+      .cloned()
+      .unwrap_or_else(|| "unknown error".to_string());
+    ImageError::EncodeError(format!("MozJPEG encode failed: {}", message))
+  })
+}
+
+/// libjpeg error_exit replacement: format the message and unwind
+unsafe extern "C-unwind" fn unwind_error_exit(cinfo: &mut jpeg_common_struct) {
+  let mut buffer = [0u8; 80];
+  if let Some(format_message) = (*cinfo.err).format_message {
+    // The binding declares the output buffer as a shared ref, but libjpeg writes into it
+    let format_message = mem::transmute::<
+      unsafe extern "C-unwind" fn(&mut jpeg_common_struct, &[u8; 80]),
+      unsafe extern "C-unwind" fn(&mut jpeg_common_struct, *mut u8),
+    >(format_message);
+    format_message(cinfo, buffer.as_mut_ptr());
+  }
+  let len = buffer.iter().position(|&b| b == 0).unwrap_or(buffer.len());
+  let message = String::from_utf8_lossy(&buffer[..len]).into_owned();
+  std::panic::resume_unwind(Box::new(message));
+}
+
+/// Frees the libjpeg compressor and the jpeg_mem_dest buffer on drop (including unwinds)
+struct CompressGuard {
+  cinfo: *mut jpeg_compress_struct,
+  outbuffer: *mut *mut u8,
+}
+
+impl Drop for CompressGuard {
+  fn drop(&mut self) {
+    unsafe {
+      jpeg_destroy_compress(&mut *self.cinfo);
+      if !(*self.outbuffer).is_null() {
+        free(*self.outbuffer as *mut c_void);
+      }
+    }
+  }
+}
+
+unsafe fn compress_mozjpeg(
+  pixels: &JpegPixels,
+  width: u32,
+  height: u32,
+  quality: i32,
+  subsampling: ChromaSubsampling,
+  options: Option<&JpegOptions>,
+) -> Vec<u8> {
+  let progressive = options.and_then(|o| o.progressive).unwrap_or(true);
+  let optimize_coding = options.and_then(|o| o.optimize_coding).unwrap_or(true);
+  let trellis = options.and_then(|o| o.trellis_quantisation).unwrap_or(true);
+  let deringing = options.and_then(|o| o.overshoot_deringing).unwrap_or(true);
+  let quant_table = options
+    .and_then(|o| o.quant_table.as_ref())
+    .map(quant_table_index)
+    .unwrap_or(3);
+
+  let mut err: jpeg_error_mgr = mem::zeroed();
+  let mut cinfo: jpeg_compress_struct = mem::zeroed();
+  let mut outbuffer: *mut u8 = std::ptr::null_mut();
+  let mut outsize: c_ulong = 0;
+
+  jpeg_std_error(&mut err);
+  err.error_exit = Some(unwind_error_exit);
+  cinfo.common.err = &mut err;
+  jpeg_create_compress(&mut cinfo);
+  let _guard = CompressGuard {
+    cinfo: &mut cinfo,
+    outbuffer: &mut outbuffer,
+  };
+
+  jpeg_mem_dest(&mut cinfo, &mut outbuffer, &mut outsize);
+
+  cinfo.image_width = width;
+  cinfo.image_height = height;
+  cinfo.input_components = pixels.components() as c_int;
+  cinfo.in_color_space = pixels.in_color_space();
+  // mozjpeg defaults to its max-compression profile (progressive, trellis, deringing)
+  jpeg_set_defaults(&mut cinfo);
+  // Store CMYK as Adobe YCCK like turbojpeg, so C/M/Y become chroma and K keeps luma sampling
+  if pixels.color_space == JpegColorSpace::Cmyk {
+    jpeg_set_colorspace(&mut cinfo, JCS_YCCK);
+  }
+
+  // Base table must be set before quality scaling
+  jpeg_c_set_int_param(&mut cinfo, JINT_BASE_QUANT_TBL_IDX, quant_table);
+  jpeg_set_quality(&mut cinfo, quality, 0);
+
+  jpeg_c_set_bool_param(&mut cinfo, JBOOLEAN_TRELLIS_QUANT, trellis as boolean);
+  jpeg_c_set_bool_param(&mut cinfo, JBOOLEAN_TRELLIS_QUANT_DC, trellis as boolean);
+  jpeg_c_set_bool_param(&mut cinfo, JBOOLEAN_OVERSHOOT_DERINGING, deringing as boolean);
+  cinfo.optimize_coding = optimize_coding as boolean;
+
+  if progressive {
+    jpeg_simple_progression(&mut cinfo);
+  } else {
+    // Baseline: drop the progressive scan script set by the defaults
+    jpeg_c_set_bool_param(&mut cinfo, JBOOLEAN_OPTIMIZE_SCANS, 0);
+    cinfo.num_scans = 0;
+    cinfo.scan_info = std::ptr::null();
+  }
+
+  // Luma (and YCCK black) carries the sampling factors, chroma stays at 1x1
+  let (h_samp, v_samp) = subsampling.samp_factors();
+  let components = std::slice::from_raw_parts_mut(cinfo.comp_info, cinfo.num_components as usize);
+  for (i, component) in components.iter_mut().enumerate() {
+    let (h, v) = if i == 0 || i == 3 { (h_samp, v_samp) } else { (1, 1) };
+    component.h_samp_factor = h;
+    component.v_samp_factor = v;
+  }
+
+  jpeg_start_compress(&mut cinfo, 1);
+
+  let row_stride = width as usize * pixels.components();
+  let rows: Vec<JSAMPROW> = pixels.data.chunks_exact(row_stride).map(|row| row.as_ptr()).collect();
+  while cinfo.next_scanline < cinfo.image_height {
+    let next = cinfo.next_scanline as usize;
+    jpeg_write_scanlines(&mut cinfo, rows[next..].as_ptr(), (rows.len() - next) as JDIMENSION);
+  }
+
+  jpeg_finish_compress(&mut cinfo);
+
+  std::slice::from_raw_parts(outbuffer, outsize as usize).to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_parse_chroma_subsampling() {
+    assert_eq!(parse_chroma_subsampling("4:4:4").unwrap(), ChromaSubsampling::Yuv444);
+    assert_eq!(parse_chroma_subsampling("4:2:2").unwrap(), ChromaSubsampling::Yuv422);
+    assert_eq!(parse_chroma_subsampling("420").unwrap(), ChromaSubsampling::Yuv420);
+    assert!(parse_chroma_subsampling("4:1:1").is_err());
+  }
+
+  #[test]
+  fn test_output_color_space() {
+    let gray = DynamicImage::new_luma8(4, 4);
+    let rgb = DynamicImage::new_rgb8(4, 4);
+    let cmyk = JpegOptions {
+      color_space: Some(JpegColorSpace::Cmyk),
+      ..Default::default()
+    };
+    assert!(output_color_space(&gray, None) == JpegColorSpace::Gray);
+    assert!(output_color_space(&rgb, None) == JpegColorSpace::Rgb);
+    assert!(output_color_space(&gray, Some(&cmyk)) == JpegColorSpace::Cmyk);
+  }
+
+  #[test]
+  fn test_naive_cmyk_is_inverted() {
+    let img = DynamicImage::ImageRgb8(image::RgbImage::from_raw(3, 1, vec![255, 255, 255, 0, 0, 0, 255, 0, 0]).unwrap());
+    let cmyk = to_cmyk(&img, None).unwrap();
+    // White: no ink, black: full K, red: full magenta and yellow
+    assert_eq!(cmyk, vec![255, 255, 255, 255, 255, 255, 255, 0, 255, 0, 0, 255]);
+  }
+
+  #[test]
+  fn test_mozjpeg_cmyk_roundtrip() {
+    let img = DynamicImage::ImageRgb8(image::RgbImage::from_pixel(16, 16, image::Rgb([200, 40, 90])));
+    let options = JpegOptions {
+      color_space: Some(JpegColorSpace::Cmyk),
+      mozjpeg: Some(true),
+      quality: Some(95),
+      ..Default::default()
+    };
+    let output = encode_jpeg(&img, Some(&options)).unwrap();
+
+    let header = turbojpeg::read_header(&output).unwrap();
+    assert_eq!(header.colorspace, turbojpeg::Colorspace::YCCK);
+    let decoded = turbojpeg::decompress(&output, turbojpeg::PixelFormat::CMYK).unwrap();
+    let expected = to_cmyk(&img, None).unwrap();
+    for (got, want) in decoded.pixels.iter().zip(&expected) {
+      assert!(got.abs_diff(*want) <= 3, "{} vs {}", got, want);
+    }
+  }
+}
@@ -1,42 +1,56 @@
 //! Image encoding functions - optimized for performance
 //! Uses turbojpeg (libjpeg-turbo with SIMD) for fastest JPEG encoding
+//! Uses mozjpeg for smallest JPEG output when requested
+
+mod auto;
+mod fit;
+mod jpeg;
+mod optimize;
+mod palette;
+mod quantize;
+mod webp;
 
 use image::{DynamicImage, GenericImageView, ImageEncoder, ExtendedColorType};
 use image::codecs::png::{PngEncoder, CompressionType, FilterType};
 
 use crate::error::ImageError;
-use crate::{JpegOptions, PngOptions, WebPOptions};
-
-/// Encode image to JPEG - optimized using turbojpeg (libjpeg-turbo with SIMD)
-/// 2-6x faster than pure Rust encoders thanks to SSE2/AVX2/NEON
-#[inline(always)]
-pub fn encode_jpeg(img: &DynamicImage, options: Option<&JpegOptions>) -> Result<Vec<u8>, ImageError> {
-  let quality = options.and_then(|o| o.quality).unwrap_or(80) as i32;
-  let quality = quality.clamp(1, 100);
-
-  let rgb = img.to_rgb8();
-  let (width, height) = (rgb.width(), rgb.height());
-
-  // Create turbojpeg image structure
-  let image = turbojpeg::Image {
-    pixels: rgb.as_raw().as_slice(),
-    width: width as usize,
-    pitch: width as usize * 3, // RGB = 3 bytes per pixel
-    height: height as usize,
-    format: turbojpeg::PixelFormat::RGB,
-  };
-
-  // Encode with turbojpeg - uses SIMD for maximum speed
-  let output = turbojpeg::compress(image, quality, turbojpeg::Subsamp::Sub2x2)
-    .map_err(|e| ImageError::EncodeError(format!("TurboJPEG encode failed: {:?}", e)))?;
-
-  Ok(output.to_vec())
+use crate::metadata_write;
+use crate::{GifOptions, JpegOptions, PngOptions, WebPOptions};
+
+pub use auto::{format_name, resolve_auto_format};
+pub use fit::{encode_to_max_bytes, encode_to_target_quality};
+pub use jpeg::encode_jpeg;
+pub use optimize::keep_png_metadata;
+pub use palette::encode_gif;
+pub(crate) use palette::gif_frame;
+pub use webp::encode_webp;
+pub(crate) use webp::webp_config;
+
+/// Encode image to PNG, embedding the ICC profile when one is given
+pub fn encode_png(img: &DynamicImage, options: Option<&PngOptions>) -> Result<Vec<u8>, ImageError> {
+  let output = encode_png_data(img, options)?;
+  match options.and_then(|o| o.icc_profile.as_deref()) {
+    Some(profile) => metadata_write::write_png_icc(&output, profile),
+    None => Ok(output),
+  }
 }
 
 /// Encode image to PNG - optimized to avoid unnecessary clones
 /// Uses RGB when no alpha channel present (25% less data to process)
 #[inline(always)]
-pub fn encode_png(img: &DynamicImage, options: Option<&PngOptions>) -> Result<Vec<u8>, ImageError> {
+fn encode_png_data(img: &DynamicImage, options: Option<&PngOptions>) -> Result<Vec<u8>, ImageError> {
+  // Palette (PNG-8) output - falls through to truecolor if min_quality isn't reached
+  if let Some(opts) = options.filter(|o| palette::wants_palette(o)) {
+    if let Some(output) = palette::encode_png_palette(img, opts)? {
+      return Ok(output);
+    }
+  }
+
+  // Lossless optimization - smallest color type, best filter, max deflate
+  if let Some(level) = options.and_then(|o| o.optimize).filter(|&level| level > 0) {
+    return optimize::encode_png_optimized(&img.to_rgba8(), level);
+  }
+
   let compression = options.and_then(|o| o.compression).unwrap_or(6);
 
   // Compression type based on level
@@ -85,38 +99,6 @@ pub fn encode_png(img: &DynamicImage, options: Option<&PngOptions>) -> Result<Ve
   Ok(output)
 }
 
-/// Encode image to WebP - optimized to avoid unnecessary clones
-#[inline(always)]
-pub fn encode_webp(img: &DynamicImage, options: Option<&WebPOptions>) -> Result<Vec<u8>, ImageError> {
-  let quality = options.and_then(|o| o.quality).unwrap_or(80) as f32;
-  let lossless = options.and_then(|o| o.lossless).unwrap_or(false);
-  let (width, height) = img.dimensions();
-
-  // Try to use existing format without conversion (zero-copy path)
-  // This avoids cloning when image is already RGB8 or RGBA8
-  let webp_data = if let Some(rgba) = img.as_rgba8() {
-    // Image is already RGBA8 - use directly without clone
-    let encoder = webp::Encoder::from_rgba(rgba, width, height);
-    if lossless { encoder.encode_lossless() } else { encoder.encode(quality) }
-  } else if let Some(rgb) = img.as_rgb8() {
-    // Image is already RGB8 - use directly without clone
-    let encoder = webp::Encoder::from_rgb(rgb, width, height);
-    if lossless { encoder.encode_lossless() } else { encoder.encode(quality) }
-  } else if img.color().has_alpha() {
-    // Need to convert to RGBA8
-    let rgba = img.to_rgba8();
-    let encoder = webp::Encoder::from_rgba(&rgba, width, height);
-    if lossless { encoder.encode_lossless() } else { encoder.encode(quality) }
-  } else {
-    // Need to convert to RGB8
-    let rgb = img.to_rgb8();
-    let encoder = webp::Encoder::from_rgb(&rgb, width, height);
-    if lossless { encoder.encode_lossless() } else { encoder.encode(quality) }
-  };
-
-  Ok(webp_data.to_vec())
-}
-
 /// Encode image to specified format based on OutputOptions
 pub fn encode_to_format(
   img: &DynamicImage,
@@ -124,23 +106,13 @@ pub fn encode_to_format(
   jpeg_opts: Option<&JpegOptions>,
   png_opts: Option<&PngOptions>,
   webp_opts: Option<&WebPOptions>,
+  gif_opts: Option<&GifOptions>,
 ) -> Result<Vec<u8>, ImageError> {
   match format {
     crate::ImageFormat::Jpeg => encode_jpeg(img, jpeg_opts),
     crate::ImageFormat::Png => encode_png(img, png_opts),
     crate::ImageFormat::WebP => encode_webp(img, webp_opts),
-    crate::ImageFormat::Gif => {
-      // Use image crate's GIF encoder
-      let rgba = img.to_rgba8();
-      let mut output: Vec<u8> = Vec::new();
-      {
-        let mut encoder = image::codecs::gif::GifEncoder::new(&mut output);
-        let frame = image::Frame::new(rgba);
-        encoder.encode_frames(std::iter::once(frame))
-          .map_err(|e| ImageError::EncodeError(format!("GIF encode failed: {}", e)))?;
-      }
-      Ok(output)
-    }
+    crate::ImageFormat::Gif => encode_gif(img, gif_opts),
     crate::ImageFormat::Bmp => {
       let rgb = img.to_rgb8();
       let (width, height) = (rgb.width(), rgb.height());
@@ -150,6 +122,39 @@ pub fn encode_to_format(
         .map_err(|e| ImageError::EncodeError(format!("BMP encode failed: {}", e)))?;
       Ok(output)
     }
+    crate::ImageFormat::Qoi => {
+      let img = to_8bit(img, false);
+      let (width, height) = img.dimensions();
+      let mut output: Vec<u8> = Vec::new();
+      image::codecs::qoi::QoiEncoder::new(&mut output)
+        .write_image(img.as_bytes(), width, height, img.color().into())
+        .map_err(|e| ImageError::EncodeError(format!("QOI encode failed: {}", e)))?;
+      Ok(output)
+    }
+    crate::ImageFormat::Pnm => {
+      // Binary PGM/PPM, or PAM with alpha
+      let img = to_8bit(img, true);
+      let (width, height) = img.dimensions();
+      let mut output: Vec<u8> = Vec::new();
+      image::codecs::pnm::PnmEncoder::new(&mut output)
+        .write_image(img.as_bytes(), width, height, img.color().into())
+        .map_err(|e| ImageError::EncodeError(format!("PNM encode failed: {}", e)))?;
+      Ok(output)
+    }
+    crate::ImageFormat::Tga => {
+      // Run-length encoded
+      let img = to_8bit(img, true);
+      let (width, height) = img.dimensions();
+      let mut output: Vec<u8> = Vec::new();
+      image::codecs::tga::TgaEncoder::new(&mut output)
+        .write_image(img.as_bytes(), width, height, img.color().into())
+        .map_err(|e| ImageError::EncodeError(format!("TGA encode failed: {}", e)))?;
+      Ok(output)
+    }
+    crate::ImageFormat::Raw => Ok(encode_raw(img).data),
+    crate::ImageFormat::Auto => {
+      Err(ImageError::UnsupportedFormat("Auto format is only supported by transform".to_string()))
+    }
     crate::ImageFormat::Ico => {
       Err(ImageError::UnsupportedFormat("ICO encoding not supported".to_string()))
     }
@@ -164,3 +169,27 @@ pub fn encode_to_format(
     }
   }
 }
+
+/// Raw 8-bit pixels in the source's channel layout - no codec
+pub fn encode_raw(img: &DynamicImage) -> crate::RawImage {
+  let img = to_8bit(img, true);
+  let (width, height) = img.dimensions();
+  let channels = img.color().channel_count() as u32;
+  crate::RawImage {
+    data: img.into_bytes(),
+    width,
+    height,
+    channels,
+  }
+}
+
+/// Convert to 8-bit RGB(A), or gray(+alpha) for grayscale sources when the encoder supports it
+fn to_8bit(img: &DynamicImage, allow_gray: bool) -> DynamicImage {
+  let color = img.color();
+  match (allow_gray && !color.has_color(), color.has_alpha()) {
+    (true, false) => DynamicImage::ImageLuma8(img.to_luma8()),
+    (true, true) => DynamicImage::ImageLumaA8(img.to_luma_alpha8()),
+    (false, false) => DynamicImage::ImageRgb8(img.to_rgb8()),
+    (false, true) => DynamicImage::ImageRgba8(img.to_rgba8()),
+  }
+}
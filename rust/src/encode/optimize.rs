@@ -0,0 +1,322 @@
+//! Lossless PNG optimization (`PngOptions.optimize`)
+//!
+//! Reduces to the smallest color type / bit depth that preserves every pixel
+//! (gray, palette, no alpha when fully opaque), tries several filter
+//! strategies in parallel and keeps the smallest result at maximum deflate.
+//! Ancillary chunks are dropped unless `keep_metadata` copies them from the source.
+
+use image::RgbaImage;
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+use crate::error::ImageError;
+use crate::PngOptions;
+
+use super::palette::{bit_depth, pack_indices};
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+/// Raw PNG image data in a specific color type / bit depth
+pub(super) struct PngData {
+  pub width: u32,
+  pub height: u32,
+  pub color: png::ColorType,
+  pub depth: png::BitDepth,
+  /// Packed scanlines (unfiltered)
+  pub data: Vec<u8>,
+  pub palette: Option<Vec<u8>>,
+  pub trns: Option<Vec<u8>>,
+}
+
+impl PngData {
+  /// Indexed image from an RGBA palette (translucent entries first keeps tRNS short)
+  pub fn indexed(width: u32, height: u32, palette: &[[u8; 4]], indices: &[u8]) -> Self {
+    let depth = bit_depth(palette.len());
+    let trns: Vec<u8> = palette.iter().map(|c| c[3]).take_while(|&a| a < 255).collect();
+    PngData {
+      width,
+      height,
+      color: png::ColorType::Indexed,
+      depth,
+      data: pack_indices(indices, width as usize, depth as u8),
+      palette: Some(palette.iter().flat_map(|c| [c[0], c[1], c[2]]).collect()),
+      trns: if trns.is_empty() { None } else { Some(trns) },
+    }
+  }
+
+  fn is_low_bit(&self) -> bool {
+    self.color == png::ColorType::Indexed || !matches!(self.depth, png::BitDepth::Eight)
+  }
+}
+
+/// Map the 0-9 compression level to a deflate setting
+pub(super) fn deflate_level(compression: u8) -> png::DeflateCompression {
+  match compression {
+    0 => png::DeflateCompression::NoCompression,
+    1..=3 => png::DeflateCompression::FdeflateUltraFast,
+    4..=6 => png::DeflateCompression::Level(6),
+    _ => png::DeflateCompression::Level(9),
+  }
+}
+
+/// Write PNG data with one filter strategy
+pub(super) fn write_png(
+  image: &PngData,
+  filter: png::Filter,
+  deflate: png::DeflateCompression,
+) -> Result<Vec<u8>, ImageError> {
+  let mut output: Vec<u8> = Vec::new();
+  {
+    let mut encoder = png::Encoder::new(&mut output, image.width, image.height);
+    encoder.set_color(image.color);
+    encoder.set_depth(image.depth);
+    encoder.set_deflate_compression(deflate);
+    encoder.set_filter(filter);
+    if let Some(ref palette) = image.palette {
+      encoder.set_palette(palette.as_slice());
+    }
+    if let Some(ref trns) = image.trns {
+      encoder.set_trns(trns.as_slice());
+    }
+
+    let mut writer = encoder
+      .write_header()
+      .map_err(|e| ImageError::EncodeError(format!("PNG encode failed: {}", e)))?;
+    writer
+      .write_image_data(&image.data)
+      .map_err(|e| ImageError::EncodeError(format!("PNG encode failed: {}", e)))?;
+    writer
+      .finish()
+      .map_err(|e| ImageError::EncodeError(format!("PNG encode failed: {}", e)))?;
+  }
+  Ok(output)
+}
+
+/// Filter strategies to try for an optimization level
+fn filter_strategies(level: u8, image: &PngData) -> Vec<png::Filter> {
+  match level {
+    // Indexed and sub-byte data rarely benefit from filtering
+    1 if image.is_low_bit() => vec![png::Filter::NoFilter],
+    1 => vec![png::Filter::Adaptive],
+    2..=3 => vec![png::Filter::NoFilter, png::Filter::Adaptive, png::Filter::MinEntropy],
+    _ => vec![
+      png::Filter::NoFilter,
+      png::Filter::Sub,
+      png::Filter::Up,
+      png::Filter::Avg,
+      png::Filter::Paeth,
+      png::Filter::Adaptive,
+      png::Filter::MinEntropy,
+    ],
+  }
+}
+
+/// Try every candidate with the level's filter strategies and keep the smallest file
+pub(super) fn write_smallest(candidates: &[PngData], level: u8) -> Result<Vec<u8>, ImageError> {
+  let trials: Vec<(&PngData, png::Filter)> = candidates
+    .iter()
+    .flat_map(|image| filter_strategies(level, image).into_iter().map(move |f| (image, f)))
+    .collect();
+
+  let outputs = trials
+    .into_par_iter()
+    .map(|(image, filter)| write_png(image, filter, png::DeflateCompression::Level(9)))
+    .collect::<Result<Vec<_>, _>>()?;
+
+  outputs
+    .into_iter()
+    .min_by_key(|output| output.len())
+    .ok_or_else(|| ImageError::EncodeError("PNG optimization produced no output".to_string()))
+}
+
+/// Smallest bit depth (1, 2, 4, 8) that represents every gray value exactly
+fn gray_bit_depth(values: impl Iterator<Item = u8>) -> u8 {
+  let mut bits = 1;
+  for v in values {
+    while bits < 8 && v % (255 / ((1u16 << bits) - 1)) as u8 != 0 {
+      bits *= 2;
+    }
+    if bits == 8 {
+      break;
+    }
+  }
+  bits
+}
+
+/// Lossless color type reductions for an RGBA image
+/// `exhaustive` returns every applicable candidate, otherwise only the likely smallest
+pub(super) fn reduce(rgba: &RgbaImage, exhaustive: bool) -> Vec<PngData> {
+  let (width, height) = rgba.dimensions();
+  let mut opaque = true;
+  let mut gray = true;
+  let mut colors: HashMap<[u8; 4], u8> = HashMap::new();
+  let mut too_many_colors = false;
+
+  for pixel in rgba.pixels() {
+    let [r, g, b, a] = pixel.0;
+    opaque &= a == 255;
+    gray &= r == g && g == b;
+    if !too_many_colors && !colors.contains_key(&pixel.0) {
+      if colors.len() == 256 {
+        too_many_colors = true;
+        colors.clear();
+      } else {
+        colors.insert(pixel.0, 0);
+      }
+    }
+  }
+
+  let mut candidates: Vec<PngData> = Vec::new();
+
+  // Opaque gray at the smallest exact bit depth
+  if gray && opaque {
+    let bits = gray_bit_depth(rgba.pixels().map(|p| p[0]));
+    let scale = 255 / ((1u16 << bits) - 1) as u8;
+    let values: Vec<u8> = rgba.pixels().map(|p| p[0] / scale).collect();
+    candidates.push(PngData {
+      width,
+      height,
+      color: png::ColorType::Grayscale,
+      depth: bit_depth(1 << bits),
+      data: pack_indices(&values, width as usize, bits),
+      palette: None,
+      trns: None,
+    });
+    if !exhaustive {
+      return candidates;
+    }
+  }
+
+  // Palette when there are at most 256 distinct colors
+  if !too_many_colors && !colors.is_empty() {
+    let mut palette: Vec<[u8; 4]> = colors.keys().copied().collect();
+    palette.sort_by_key(|c| (c[3], *c));
+    for (i, color) in palette.iter().enumerate() {
+      colors.insert(*color, i as u8);
+    }
+    let indices: Vec<u8> = rgba.pixels().map(|p| colors[&p.0]).collect();
+    candidates.push(PngData::indexed(width, height, &palette, &indices));
+    if !exhaustive {
+      return candidates;
+    }
+  }
+
+  // Truecolor / gray with alpha, dropping the alpha channel when fully opaque
+  let (color, data): (png::ColorType, Vec<u8>) = match (gray, opaque) {
+    (true, true) => return candidates,
+    (true, false) => (
+      png::ColorType::GrayscaleAlpha,
+      rgba.pixels().flat_map(|p| [p[0], p[3]]).collect(),
+    ),
+    (false, true) => (
+      png::ColorType::Rgb,
+      rgba.pixels().flat_map(|p| [p[0], p[1], p[2]]).collect(),
+    ),
+    (false, false) => (png::ColorType::Rgba, rgba.as_raw().clone()),
+  };
+  candidates.push(PngData {
+    width,
+    height,
+    color,
+    depth: png::BitDepth::Eight,
+    data,
+    palette: None,
+    trns: None,
+  });
+  candidates
+}
+
+/// Encode an image as the smallest lossless PNG for the optimization level (1-6)
+/// Only critical chunks (plus tRNS) are written
+pub(super) fn encode_png_optimized(rgba: &RgbaImage, level: u8) -> Result<Vec<u8>, ImageError> {
+  let candidates = reduce(rgba, level >= 3);
+  write_smallest(&candidates, level)
+}
+
+/// Restore the source's ancillary chunks into optimized PNG output when `keep_metadata` is set
+pub fn keep_png_metadata(source: &[u8], output: Vec<u8>, options: Option<&PngOptions>) -> Vec<u8> {
+  let keep = options.is_some_and(|o| o.optimize.unwrap_or(0) > 0 && o.keep_metadata.unwrap_or(false));
+  if keep && source.starts_with(PNG_SIGNATURE) {
+    copy_ancillary_chunks(source, &output)
+  } else {
+    output
+  }
+}
+
+/// Copy ancillary chunks (text, EXIF, color space, ...) from a source PNG into an optimized one
+/// Chunks tied to the source color type or animation (tRNS, bKGD, sBIT, APNG) are left out
+fn copy_ancillary_chunks(source: &[u8], optimized: &[u8]) -> Vec<u8> {
+  const SKIP: [&[u8; 4]; 7] = [b"tRNS", b"bKGD", b"hIST", b"sBIT", b"acTL", b"fcTL", b"fdAT"];
+
+  let mut ancillary: Vec<u8> = Vec::new();
+  let mut pos = 8;
+  while pos + 12 <= source.len() {
+    let length =
+      u32::from_be_bytes([source[pos], source[pos + 1], source[pos + 2], source[pos + 3]]) as usize;
+    let end = pos + 12 + length;
+    if end > source.len() {
+      break;
+    }
+    let chunk_type = &source[pos + 4..pos + 8];
+    // Critical chunks have an uppercase first letter
+    if chunk_type[0].is_ascii_lowercase() && !SKIP.iter().any(|skip| skip.as_slice() == chunk_type) {
+      ancillary.extend_from_slice(&source[pos..end]);
+    }
+    pos = end;
+  }
+
+  // Signature (8) + IHDR (25); every kept chunk is valid right after IHDR
+  let ihdr_end = 33;
+  if ancillary.is_empty() || optimized.len() < ihdr_end {
+    return optimized.to_vec();
+  }
+  let mut output = Vec::with_capacity(optimized.len() + ancillary.len());
+  output.extend_from_slice(&optimized[..ihdr_end]);
+  output.extend_from_slice(&ancillary);
+  output.extend_from_slice(&optimized[ihdr_end..]);
+  output
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_gray_bit_depth() {
+    assert_eq!(gray_bit_depth([0u8, 255].into_iter()), 1);
+    assert_eq!(gray_bit_depth([0u8, 85, 170].into_iter()), 2);
+    assert_eq!(gray_bit_depth([17u8, 255].into_iter()), 4);
+    assert_eq!(gray_bit_depth([1u8].into_iter()), 8);
+  }
+
+  #[test]
+  fn test_reduce_color_types() {
+    let gray = RgbaImage::from_pixel(4, 4, image::Rgba([255, 255, 255, 255]));
+    let candidates = reduce(&gray, false);
+    assert_eq!(candidates.len(), 1);
+    assert_eq!(candidates[0].color, png::ColorType::Grayscale);
+    assert!(matches!(candidates[0].depth, png::BitDepth::One));
+
+    let mut two_colors = RgbaImage::from_pixel(4, 4, image::Rgba([255, 0, 0, 255]));
+    two_colors.put_pixel(0, 0, image::Rgba([0, 0, 255, 128]));
+    let candidates = reduce(&two_colors, true);
+    assert_eq!(candidates[0].color, png::ColorType::Indexed);
+    assert_eq!(candidates[0].trns, Some(vec![128]));
+    assert_eq!(candidates[1].color, png::ColorType::Rgba);
+  }
+
+  #[test]
+  fn test_copy_ancillary_chunks() {
+    let rgba = RgbaImage::from_pixel(2, 2, image::Rgba([0, 0, 0, 255]));
+    let optimized = encode_png_optimized(&rgba, 1).unwrap();
+
+    // Source: signature + IHDR + tEXt + tRNS (the tRNS must not be copied)
+    let mut source = optimized[..33].to_vec();
+    source.extend_from_slice(&[0, 0, 0, 3, b't', b'E', b'X', b't', b'a', 0, b'b', 0, 0, 0, 0]);
+    source.extend_from_slice(&[0, 0, 0, 1, b't', b'R', b'N', b'S', 0, 0, 0, 0, 0]);
+
+    let output = copy_ancillary_chunks(&source, &optimized);
+    assert_eq!(output.len(), optimized.len() + 15);
+    assert_eq!(&output[37..41], b"tEXt");
+  }
+}
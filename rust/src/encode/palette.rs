@@ -0,0 +1,158 @@
+//! Palette (indexed color) output - PNG-8 and GIF
+//!
+//! Both use the median cut quantizer in `quantize.rs` instead of NeuQuant.
+
+use image::{DynamicImage, RgbaImage};
+
+use crate::error::ImageError;
+use crate::{GifOptions, PngOptions};
+
+use super::optimize::{deflate_level, write_png, write_smallest, PngData};
+use super::quantize::{quantize, QuantizeSettings, QuantizedImage};
+
+/// Validate a palette size option (2-256, default 256)
+fn max_colors(colors: Option<u32>) -> Result<usize, ImageError> {
+  match colors {
+    None => Ok(256),
+    Some(n @ 2..=256) => Ok(n as usize),
+    Some(n) => Err(ImageError::EncodeError(format!(
+      "Invalid palette size: {}. Use 2-256 colors",
+      n
+    ))),
+  }
+}
+
+/// Whether PNG options ask for palette output
+/// `colors`, `dither` and `quality` imply `palette: true`
+pub(crate) fn wants_palette(options: &PngOptions) -> bool {
+  options.palette.unwrap_or(false)
+    || options.colors.is_some()
+    || options.dither.is_some()
+    || options.quality.is_some()
+    || options.min_quality.is_some()
+}
+
+/// Smallest PNG bit depth that can index the palette
+pub(super) fn bit_depth(palette_len: usize) -> png::BitDepth {
+  match palette_len {
+    0..=2 => png::BitDepth::One,
+    3..=4 => png::BitDepth::Two,
+    5..=16 => png::BitDepth::Four,
+    _ => png::BitDepth::Eight,
+  }
+}
+
+/// Pack 8-bit indices into rows of `bits`-wide samples (rows are byte-aligned)
+pub(super) fn pack_indices(indices: &[u8], width: usize, bits: u8) -> Vec<u8> {
+  if bits == 8 {
+    return indices.to_vec();
+  }
+
+  let per_byte = (8 / bits) as usize;
+  let row_bytes = width.div_ceil(per_byte);
+  let mut packed = Vec::with_capacity(row_bytes * (indices.len() / width.max(1)));
+  for row in indices.chunks_exact(width) {
+    for group in row.chunks(per_byte) {
+      let mut byte = 0u8;
+      for (i, &index) in group.iter().enumerate() {
+        byte |= index << (8 - bits as usize * (i + 1));
+      }
+      packed.push(byte);
+    }
+  }
+  packed
+}
+
+/// Encode as an indexed PNG (PNG-8) with tRNS alpha
+/// Returns None when the palette can't reach `min_quality` (caller writes truecolor instead)
+pub(crate) fn encode_png_palette(img: &DynamicImage, options: &PngOptions) -> Result<Option<Vec<u8>>, ImageError> {
+  let settings = QuantizeSettings {
+    max_colors: max_colors(options.colors)?,
+    dither: options.dither.unwrap_or(1.0) as f32,
+    target_quality: options.quality.unwrap_or(100).min(100),
+  };
+
+  let rgba = img.to_rgba8();
+  let quantized = quantize(&rgba, &settings);
+  if quantized.quality < options.min_quality.unwrap_or(0) {
+    return Ok(None);
+  }
+
+  let indexed = PngData::indexed(rgba.width(), rgba.height(), &quantized.palette, &quantized.indices);
+  match options.optimize.unwrap_or(0) {
+    0 => {
+      let deflate = deflate_level(options.compression.unwrap_or(6));
+      // Filters rarely help indexed data
+      write_png(&indexed, png::Filter::NoFilter, deflate).map(Some)
+    }
+    level => write_smallest(&[indexed], level).map(Some),
+  }
+}
+
+/// Quantize an RGBA frame for GIF (1-bit transparency, alpha < 128 is transparent)
+pub(crate) fn gif_frame(rgba: &RgbaImage, options: Option<&GifOptions>) -> Result<gif::Frame<'static>, ImageError> {
+  let (width, height) = rgba.dimensions();
+  if width > u16::MAX as u32 || height > u16::MAX as u32 {
+    return Err(ImageError::EncodeError(format!(
+      "GIF dimensions {}x{} exceed the 65535 limit",
+      width, height
+    )));
+  }
+
+  let mut binary_alpha = rgba.clone();
+  for pixel in binary_alpha.pixels_mut() {
+    pixel.0 = if pixel[3] < 128 { [0, 0, 0, 0] } else { [pixel[0], pixel[1], pixel[2], 255] };
+  }
+
+  let settings = QuantizeSettings {
+    max_colors: max_colors(options.and_then(|o| o.colors))?,
+    dither: options.and_then(|o| o.dither).unwrap_or(1.0) as f32,
+    target_quality: 100,
+  };
+  let QuantizedImage { palette, indices, .. } = quantize(&binary_alpha, &settings);
+
+  let transparent = palette.iter().position(|c| c[3] == 0).map(|i| i as u8);
+  let rgb_palette: Vec<u8> = palette.iter().flat_map(|c| [c[0], c[1], c[2]]).collect();
+
+  let mut frame = gif::Frame::from_palette_pixels(width as u16, height as u16, indices, rgb_palette, transparent);
+  // Frames cover the whole canvas, so clear before drawing the next one
+  frame.dispose = gif::DisposalMethod::Background;
+  Ok(frame)
+}
+
+/// Encode a single-frame GIF
+pub fn encode_gif(img: &DynamicImage, options: Option<&GifOptions>) -> Result<Vec<u8>, ImageError> {
+  let rgba = img.to_rgba8();
+  let frame = gif_frame(&rgba, options)?;
+
+  let mut output: Vec<u8> = Vec::new();
+  {
+    let mut encoder = gif::Encoder::new(&mut output, frame.width, frame.height, &[])
+      .map_err(|e| ImageError::EncodeError(format!("GIF encode failed: {}", e)))?;
+    encoder
+      .write_frame(&frame)
+      .map_err(|e| ImageError::EncodeError(format!("GIF encode failed: {}", e)))?;
+  }
+  Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_pack_indices() {
+    // 1-bit, width 3: row padded to one byte
+    assert_eq!(pack_indices(&[1, 0, 1, 0, 1, 1], 3, 1), vec![0b1010_0000, 0b0110_0000]);
+    // 4-bit
+    assert_eq!(pack_indices(&[0x1, 0xF, 0x3], 3, 4), vec![0x1F, 0x30]);
+  }
+
+  #[test]
+  fn test_max_colors() {
+    assert_eq!(max_colors(None).unwrap(), 256);
+    assert_eq!(max_colors(Some(16)).unwrap(), 16);
+    assert!(max_colors(Some(1)).is_err());
+    assert!(max_colors(Some(300)).is_err());
+  }
+}
@@ -0,0 +1,330 @@
+//! Palette quantization for PNG-8 and GIF output
+//!
+//! Median cut over the RGBA color histogram, stopping early once the target
+//! quality is reached, then remapping with optional Floyd-Steinberg dithering.
+//! Fully transparent pixels always share one dedicated palette entry.
+
+use image::RgbaImage;
+use std::collections::HashMap;
+
+/// Quantization settings
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct QuantizeSettings {
+  /// Maximum palette size (2-256)
+  pub max_colors: usize,
+  /// Dithering amount (0.0 = none, 1.0 = full Floyd-Steinberg)
+  pub dither: f32,
+  /// Stop adding colors once this quality (0-100) is reached
+  pub target_quality: u8,
+}
+
+/// Palette image produced by `quantize`
+pub(crate) struct QuantizedImage {
+  /// RGBA palette, translucent entries first (keeps PNG tRNS short)
+  pub palette: Vec<[u8; 4]>,
+  /// One palette index per pixel
+  pub indices: Vec<u8>,
+  /// Estimated quality of the palette (0-100)
+  pub quality: u8,
+}
+
+#[derive(Clone, Copy)]
+struct ColorCount {
+  color: [u8; 4],
+  count: u32,
+}
+
+/// A range of histogram entries with its weighted statistics
+struct ColorBox {
+  start: usize,
+  end: usize,
+  mean: [f64; 4],
+  /// Weighted sum of squared deviations per channel
+  sse: [f64; 4],
+}
+
+impl ColorBox {
+  fn new(entries: &[ColorCount], start: usize, end: usize) -> Self {
+    let mut weight = 0.0;
+    let mut sum = [0.0f64; 4];
+    let mut sum_sq = [0.0f64; 4];
+    for entry in &entries[start..end] {
+      let w = entry.count as f64;
+      weight += w;
+      for c in 0..4 {
+        let v = entry.color[c] as f64;
+        sum[c] += w * v;
+        sum_sq[c] += w * v * v;
+      }
+    }
+
+    let mut mean = [0.0; 4];
+    let mut sse = [0.0; 4];
+    for c in 0..4 {
+      mean[c] = sum[c] / weight;
+      sse[c] = (sum_sq[c] - sum[c] * sum[c] / weight).max(0.0);
+    }
+    ColorBox { start, end, mean, sse }
+  }
+
+  fn total_sse(&self) -> f64 {
+    self.sse.iter().sum()
+  }
+
+  fn can_split(&self) -> bool {
+    self.end - self.start > 1 && self.total_sse() > 0.0
+  }
+
+  fn color(&self) -> [u8; 4] {
+    self.mean.map(|v| v.round().clamp(0.0, 255.0) as u8)
+  }
+}
+
+/// Map a 0-100 quality to a normalized mean squared error (pngquant's curve)
+fn quality_to_mse(quality: u8) -> f64 {
+  if quality == 0 {
+    return f64::INFINITY;
+  }
+  if quality >= 100 {
+    return 0.0;
+  }
+  let q = quality as f64;
+  let extra_low_quality_fudge = (0.016 / (0.001 + q) - 0.001).max(0.0);
+  extra_low_quality_fudge + 2.5 / (210.0 + q).powf(1.2) * (100.1 - q) / 100.0
+}
+
+/// Highest quality whose error budget covers `mse`
+fn mse_to_quality(mse: f64) -> u8 {
+  (0..=100u8).rev().find(|&q| quality_to_mse(q) >= mse).unwrap_or(0)
+}
+
+/// Split a box at the weighted median of its widest channel
+fn split_box(entries: &mut [ColorCount], color_box: &ColorBox) -> (ColorBox, ColorBox) {
+  let channel = (0..4)
+    .max_by(|&a, &b| color_box.sse[a].total_cmp(&color_box.sse[b]))
+    .unwrap_or(0);
+
+  let slice = &mut entries[color_box.start..color_box.end];
+  slice.sort_unstable_by_key(|e| e.color[channel]);
+
+  let half = slice.iter().map(|e| e.count as u64).sum::<u64>() / 2;
+  let mut acc = 0u64;
+  let mut mid = slice.len() / 2;
+  for (i, entry) in slice.iter().enumerate() {
+    acc += entry.count as u64;
+    if acc >= half {
+      mid = i + 1;
+      break;
+    }
+  }
+  let mid = color_box.start + mid.clamp(1, slice.len() - 1);
+
+  (
+    ColorBox::new(entries, color_box.start, mid),
+    ColorBox::new(entries, mid, color_box.end),
+  )
+}
+
+fn nearest(palette: &[[u8; 4]], color: [u8; 4]) -> u8 {
+  let mut best = 0;
+  let mut best_dist = u32::MAX;
+  for (i, entry) in palette.iter().enumerate() {
+    let mut dist = 0u32;
+    for c in 0..4 {
+      let d = entry[c] as i32 - color[c] as i32;
+      dist += (d * d) as u32;
+    }
+    if dist < best_dist {
+      best_dist = dist;
+      best = i;
+    }
+  }
+  best as u8
+}
+
+/// Quantize an RGBA image to at most `max_colors` palette entries
+pub(crate) fn quantize(img: &RgbaImage, settings: &QuantizeSettings) -> QuantizedImage {
+  let pixels: &[[u8; 4]] = as_pixels(img);
+  let total_pixels = pixels.len().max(1) as f64;
+
+  // Histogram of visible colors; fully transparent pixels are counted separately
+  let mut histogram: HashMap<[u8; 4], u32> = HashMap::new();
+  let mut has_transparent = false;
+  for &pixel in pixels {
+    if pixel[3] == 0 {
+      has_transparent = true;
+    } else {
+      *histogram.entry(pixel).or_insert(0) += 1;
+    }
+  }
+  let mut entries: Vec<ColorCount> = histogram
+    .into_iter()
+    .map(|(color, count)| ColorCount { color, count })
+    .collect();
+
+  let max_colors = settings.max_colors.clamp(2, 256) - has_transparent as usize;
+  let target_sse = quality_to_mse(settings.target_quality) * total_pixels * 4.0 * 255.0 * 255.0;
+
+  let mut boxes: Vec<ColorBox> = Vec::new();
+  if !entries.is_empty() {
+    let len = entries.len();
+    boxes.push(ColorBox::new(&entries, 0, len));
+  }
+  while boxes.len() < max_colors {
+    let total_sse: f64 = boxes.iter().map(|b| b.total_sse()).sum();
+    if total_sse <= target_sse {
+      break;
+    }
+    // Split the box contributing the most error
+    let worst = boxes
+      .iter()
+      .enumerate()
+      .filter(|(_, b)| b.can_split())
+      .max_by(|(_, a), (_, b)| a.total_sse().total_cmp(&b.total_sse()))
+      .map(|(i, _)| i);
+    let Some(worst) = worst else { break };
+
+    let color_box = boxes.swap_remove(worst);
+    let (left, right) = split_box(&mut entries, &color_box);
+    boxes.push(left);
+    boxes.push(right);
+  }
+
+  let final_sse: f64 = boxes.iter().map(|b| b.total_sse()).sum();
+  let quality = mse_to_quality(final_sse / (total_pixels * 4.0 * 255.0 * 255.0));
+
+  let mut palette: Vec<[u8; 4]> = boxes.iter().map(|b| b.color()).collect();
+  if has_transparent {
+    palette.push([0, 0, 0, 0]);
+  }
+  if palette.is_empty() {
+    palette.push([0, 0, 0, 255]);
+  }
+  palette.sort_by_key(|c| c[3]);
+  let transparent_index = palette.iter().position(|c| c[3] == 0).unwrap_or(0) as u8;
+
+  let indices = remap(img, pixels, &palette, transparent_index, settings.dither.clamp(0.0, 1.0));
+
+  QuantizedImage {
+    palette,
+    indices,
+    quality,
+  }
+}
+
+/// Map every pixel to its nearest palette entry, diffusing the error when dithering
+fn remap(img: &RgbaImage, pixels: &[[u8; 4]], palette: &[[u8; 4]], transparent_index: u8, dither: f32) -> Vec<u8> {
+  let mut cache: HashMap<[u8; 4], u8> = HashMap::new();
+  let mut lookup = |color: [u8; 4]| *cache.entry(color).or_insert_with(|| nearest(palette, color));
+
+  if dither <= 0.0 {
+    return pixels
+      .iter()
+      .map(|&p| if p[3] == 0 { transparent_index } else { lookup(p) })
+      .collect();
+  }
+
+  let width = img.width() as usize;
+  if width == 0 {
+    return Vec::new();
+  }
+  let mut indices = Vec::with_capacity(pixels.len());
+  // RGB error rows padded by one pixel on each side - alpha is never diffused
+  let mut current = vec![[0.0f32; 3]; width + 2];
+  let mut next = vec![[0.0f32; 3]; width + 2];
+
+  for row in pixels.chunks_exact(width) {
+    for (x, &pixel) in row.iter().enumerate() {
+      if pixel[3] == 0 {
+        indices.push(transparent_index);
+        continue;
+      }
+
+      let err = current[x + 1];
+      let mut color = [0u8, 0, 0, pixel[3]];
+      for c in 0..3 {
+        color[c] = (pixel[c] as f32 + err[c]).round().clamp(0.0, 255.0) as u8;
+      }
+      let index = lookup(color);
+      indices.push(index);
+
+      let chosen = palette[index as usize];
+      for c in 0..3 {
+        let e = (color[c] as f32 - chosen[c] as f32) * dither;
+        current[x + 2][c] += e * 7.0 / 16.0;
+        next[x][c] += e * 3.0 / 16.0;
+        next[x + 1][c] += e * 5.0 / 16.0;
+        next[x + 2][c] += e * 1.0 / 16.0;
+      }
+    }
+    std::mem::swap(&mut current, &mut next);
+    next.iter_mut().for_each(|e| *e = [0.0; 3]);
+  }
+
+  indices
+}
+
+/// View RGBA8 pixel data as [u8; 4] pixels
+fn as_pixels(img: &RgbaImage) -> &[[u8; 4]] {
+  let raw = img.as_raw();
+  // SAFETY: [u8; 4] has alignment 1 and the buffer length is a multiple of 4
+  unsafe { std::slice::from_raw_parts(raw.as_ptr() as *const [u8; 4], raw.len() / 4) }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_quality_to_mse() {
+    assert_eq!(quality_to_mse(100), 0.0);
+    assert!(quality_to_mse(90) < quality_to_mse(50));
+    assert_eq!(mse_to_quality(0.0), 100);
+    assert_eq!(mse_to_quality(quality_to_mse(70)), 70);
+  }
+
+  #[test]
+  fn test_quantize_keeps_exact_colors() {
+    let mut img = RgbaImage::new(4, 4);
+    for (x, _, pixel) in img.enumerate_pixels_mut() {
+      *pixel = if x < 2 {
+        image::Rgba([255, 0, 0, 255])
+      } else {
+        image::Rgba([0, 0, 255, 0])
+      };
+    }
+    let settings = QuantizeSettings {
+      max_colors: 16,
+      dither: 1.0,
+      target_quality: 100,
+    };
+    let result = quantize(&img, &settings);
+
+    assert_eq!(result.palette, vec![[0, 0, 0, 0], [255, 0, 0, 255]]);
+    assert_eq!(result.quality, 100);
+    assert_eq!(&result.indices[0..4], &[1, 1, 0, 0]);
+  }
+
+  #[test]
+  fn test_dither_keeps_source_alpha() {
+    // Faint red with varying alpha, next to fully transparent pixels
+    let img = RgbaImage::from_fn(32, 32, |x, y| {
+      if x == 0 {
+        image::Rgba([0, 0, 0, 0])
+      } else {
+        image::Rgba([255, (x * 8) as u8, 0, (8 + (x + y) % 24) as u8])
+      }
+    });
+    let settings = QuantizeSettings {
+      max_colors: 4,
+      dither: 1.0,
+      target_quality: 100,
+    };
+    let result = quantize(&img, &settings);
+    let transparent = result.palette.iter().position(|c| c[3] == 0).unwrap() as u8;
+
+    for (pixel, &index) in img.pixels().zip(&result.indices) {
+      assert_eq!(pixel[3] == 0, index == transparent);
+    }
+  }
+}
@@ -0,0 +1,156 @@
+//! WebP encoding through libwebp's advanced `WebPConfig` API
+//!
+//! The same config is used for still and animated WebP output.
+
+use std::os::raw::{c_int, c_void};
+
+use image::{DynamicImage, GenericImageView};
+use libwebp_sys::{
+  WebPConfig, WebPConfigPreset, WebPEncode, WebPPicture, WebPPictureFree, WebPPictureImportRGB,
+  WebPPictureImportRGBA, WebPPictureInit, WebPValidateConfig, WEBP_PRESET_DEFAULT,
+  WEBP_PRESET_DRAWING, WEBP_PRESET_ICON, WEBP_PRESET_PHOTO, WEBP_PRESET_PICTURE, WEBP_PRESET_TEXT,
+};
+
+use crate::error::ImageError;
+use crate::metadata_write;
+use crate::{WebPOptions, WebPPreset};
+
+/// Build a libwebp config from WebPOptions
+pub(crate) fn webp_config(options: Option<&WebPOptions>) -> Result<WebPConfig, ImageError> {
+  let quality = options.and_then(|o| o.quality).unwrap_or(80) as f32;
+  let near_lossless = options.and_then(|o| o.near_lossless);
+  // Near-lossless is a preprocessing step of the lossless encoder
+  let lossless = options.and_then(|o| o.lossless).unwrap_or(false) || near_lossless.is_some_and(|n| n < 100);
+
+  let preset = match options.and_then(|o| o.preset.as_ref()) {
+    None | Some(WebPPreset::Default) => WEBP_PRESET_DEFAULT,
+    Some(WebPPreset::Picture) => WEBP_PRESET_PICTURE,
+    Some(WebPPreset::Photo) => WEBP_PRESET_PHOTO,
+    Some(WebPPreset::Drawing) => WEBP_PRESET_DRAWING,
+    Some(WebPPreset::Icon) => WEBP_PRESET_ICON,
+    Some(WebPPreset::Text) => WEBP_PRESET_TEXT,
+  };
+
+  unsafe {
+    let mut config: WebPConfig = std::mem::zeroed();
+    if WebPConfigPreset(&mut config, preset, quality.clamp(0.0, 100.0)) == 0 {
+      return Err(ImageError::EncodeError("Failed to init WebP config".to_string()));
+    }
+
+    config.lossless = lossless as c_int;
+    if lossless {
+      // Quality is compression effort for lossless - keep libwebp's default
+      config.quality = 75.0;
+    }
+
+    if let Some(opts) = options {
+      if let Some(method) = opts.method {
+        config.method = method as c_int;
+      }
+      if let Some(alpha_quality) = opts.alpha_quality {
+        config.alpha_quality = alpha_quality as c_int;
+      }
+      if let Some(near_lossless) = near_lossless {
+        config.near_lossless = near_lossless as c_int;
+      }
+      if let Some(smart_subsample) = opts.smart_subsample {
+        config.use_sharp_yuv = smart_subsample as c_int;
+      }
+      if let Some(exact) = opts.exact {
+        config.exact = exact as c_int;
+      }
+      if let Some(target_size) = opts.target_size {
+        config.target_size = target_size.min(c_int::MAX as u32) as c_int;
+      }
+      if let Some(target_psnr) = opts.target_psnr {
+        config.target_PSNR = target_psnr as f32;
+      }
+      // Size/PSNR targets search the quality over several passes (cwebp uses 6)
+      if (config.target_size > 0 || config.target_PSNR > 0.0) && config.pass < 6 {
+        config.pass = 6;
+      }
+    }
+
+    if WebPValidateConfig(&config) == 0 {
+      return Err(ImageError::EncodeError(
+        "Invalid WebP options: method must be 0-6, alphaQuality and nearLossless 0-100".to_string(),
+      ));
+    }
+    Ok(config)
+  }
+}
+
+/// libwebp writer callback appending to the Vec<u8> in `custom_ptr`
+extern "C" fn write_to_vec(data: *const u8, data_size: usize, picture: *const WebPPicture) -> c_int {
+  if data_size > 0 {
+    unsafe {
+      let output = &mut *((*picture).custom_ptr as *mut Vec<u8>);
+      output.extend_from_slice(std::slice::from_raw_parts(data, data_size));
+    }
+  }
+  1
+}
+
+/// Encode image to WebP, embedding the ICC profile when one is given
+pub fn encode_webp(img: &DynamicImage, options: Option<&WebPOptions>) -> Result<Vec<u8>, ImageError> {
+  let output = encode_webp_data(img, options)?;
+  match options.and_then(|o| o.icc_profile.as_deref()) {
+    Some(profile) => metadata_write::write_webp_icc(&output, profile),
+    None => Ok(output),
+  }
+}
+
+/// Encode image to WebP - avoids converting when already RGB8/RGBA8
+fn encode_webp_data(img: &DynamicImage, options: Option<&WebPOptions>) -> Result<Vec<u8>, ImageError> {
+  let config = webp_config(options)?;
+  let (width, height) = img.dimensions();
+
+  let converted: Vec<u8>;
+  let (pixels, has_alpha): (&[u8], bool) = if let Some(rgba) = img.as_rgba8() {
+    (rgba.as_raw(), true)
+  } else if let Some(rgb) = img.as_rgb8() {
+    (rgb.as_raw(), false)
+  } else if img.color().has_alpha() {
+    converted = img.to_rgba8().into_raw();
+    (&converted, true)
+  } else {
+    converted = img.to_rgb8().into_raw();
+    (&converted, false)
+  };
+
+  let mut output: Vec<u8> = Vec::new();
+  unsafe {
+    let mut picture: WebPPicture = std::mem::zeroed();
+    if WebPPictureInit(&mut picture) == 0 {
+      return Err(ImageError::EncodeError("Failed to init WebP picture".to_string()));
+    }
+    picture.use_argb = 1;
+    picture.width = width as c_int;
+    picture.height = height as c_int;
+
+    let imported = if has_alpha {
+      WebPPictureImportRGBA(&mut picture, pixels.as_ptr(), (width * 4) as c_int)
+    } else {
+      WebPPictureImportRGB(&mut picture, pixels.as_ptr(), (width * 3) as c_int)
+    };
+    if imported == 0 {
+      WebPPictureFree(&mut picture);
+      return Err(ImageError::EncodeError("Failed to import WebP picture".to_string()));
+    }
+
+    picture.writer = Some(write_to_vec);
+    picture.custom_ptr = &mut output as *mut Vec<u8> as *mut c_void;
+
+    let encoded = WebPEncode(&config, &mut picture);
+    let error_code = picture.error_code;
+    WebPPictureFree(&mut picture);
+    if encoded == 0 {
+      return Err(ImageError::EncodeError(format!(
+        "WebP encode failed (libwebp error {})",
+        error_code
+      )));
+    }
+  }
+
+  Ok(output)
+}
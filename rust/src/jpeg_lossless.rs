@@ -0,0 +1,205 @@
+//! Lossless JPEG transforms (rotate, flip, crop) using libjpeg-turbo's tjTransform
+//!
+//! Works directly on the DCT coefficients, so there is no generation loss from
+//! decoding and re-encoding. EXIF, ICC and other markers are copied to the output.
+
+use std::borrow::Cow;
+use turbojpeg::{DecompressHeader, Transform, TransformCrop, TransformOp};
+
+use crate::crop;
+use crate::error::ImageError;
+use crate::metadata_write::set_tiff_orientation;
+use crate::{CropOptions, ImageFormat, JpegLosslessOptions, TransformOptions};
+
+/// Combine rotate + flips (applied in that order) into a single lossless op
+/// Returns None for rotations other than multiples of 90 degrees
+pub(crate) fn combine_ops(rotate: i32, flip_h: bool, flip_v: bool) -> Option<TransformOp> {
+  let rotate = match rotate {
+    0 => 0,
+    90 | -270 => 90,
+    180 | -180 => 180,
+    270 | -90 => 270,
+    _ => return None,
+  };
+
+  Some(match (rotate, flip_h, flip_v) {
+    (0, false, false) | (180, true, true) => TransformOp::None,
+    (0, true, false) | (180, false, true) => TransformOp::Hflip,
+    (0, false, true) | (180, true, false) => TransformOp::Vflip,
+    (0, true, true) | (180, false, false) => TransformOp::Rot180,
+    (90, false, false) | (270, true, true) => TransformOp::Rot90,
+    (270, false, false) | (90, true, true) => TransformOp::Rot270,
+    (90, true, false) | (270, false, true) => TransformOp::Transpose,
+    _ => TransformOp::Transverse, // (90, false, true) | (270, true, false)
+  })
+}
+
+/// Resolve a crop region and check that its origin sits on an MCU boundary
+fn mcu_aligned_crop(header: &DecompressHeader, options: &CropOptions) -> Result<TransformCrop, ImageError> {
+  let (x, y, width, height) = crop::crop_region(header.width as u32, header.height as u32, options)?;
+  let (mcu_width, mcu_height) = header.subsamp.mcu_size();
+
+  if !(x as usize).is_multiple_of(mcu_width) || !(y as usize).is_multiple_of(mcu_height) {
+    return Err(ImageError::InvalidDimensions(format!(
+      "Lossless crop origin ({}, {}) must be a multiple of the {}x{} MCU size",
+      x, y, mcu_width, mcu_height
+    )));
+  }
+
+  Ok(TransformCrop {
+    x: x as usize,
+    y: y as usize,
+    width: Some(width as usize),
+    height: Some(height as usize),
+  })
+}
+
+fn run_transform(transform: &Transform, data: &[u8]) -> Result<Vec<u8>, ImageError> {
+  turbojpeg::transform(transform, data)
+    .map(|output| output.to_vec())
+    .map_err(|e| ImageError::ProcessingError(format!("TurboJPEG transform failed: {:?}", e)))
+}
+
+/// Rotate, flip and/or crop a JPEG without recompressing it
+pub fn jpeg_lossless_transform(input: &[u8], options: &JpegLosslessOptions) -> Result<Vec<u8>, ImageError> {
+  let rotate = options.rotate.unwrap_or(0);
+  let op = combine_ops(rotate, options.flip_h.unwrap_or(false), options.flip_v.unwrap_or(false))
+    .ok_or_else(|| ImageError::ProcessingError(format!("Invalid rotation: {}. Use 90, 180 or 270", rotate)))?;
+
+  let header = turbojpeg::read_header(input)
+    .map_err(|e| ImageError::DecodeError(format!("TurboJPEG header read failed: {:?}", e)))?;
+  let mut crop = match options.crop {
+    Some(ref crop_opts) => Some(mcu_aligned_crop(&header, crop_opts)?),
+    None => None,
+  };
+
+  // tjTransform crops the transformed image, so crop first in its own pass
+  // to keep the region in input coordinates
+  let mut source = Cow::Borrowed(input);
+  if crop.is_some() && op != TransformOp::None {
+    let mut crop_only = Transform::default();
+    crop_only.crop = crop.take();
+    source = Cow::Owned(run_transform(&crop_only, input)?);
+  }
+
+  let trim = options.trim.unwrap_or(false);
+  let mut transform = Transform::op(op);
+  transform.crop = crop;
+  // Without trim, fail rather than leave untransformed partial MCUs on the edges
+  transform.perfect = !trim;
+  transform.trim = trim;
+  transform.gray = options.grayscale.unwrap_or(false);
+  transform.progressive = options.progressive.unwrap_or(false);
+  transform.optimize = options.optimize_coding.unwrap_or(false);
+  transform.copy_none = options.strip_metadata.unwrap_or(false);
+
+  let mut output = run_transform(&transform, &source)?;
+
+  if options.reset_orientation.unwrap_or(false) {
+    reset_orientation(&mut output);
+  }
+
+  Ok(output)
+}
+
+/// Set the EXIF orientation tag to 1 (normal) in place, if present
+fn reset_orientation(data: &mut [u8]) {
+  let mut pos = 2; // Skip SOI marker
+
+  while pos + 4 <= data.len() {
+    if data[pos] != 0xFF {
+      return;
+    }
+    let marker = data[pos + 1];
+    // SOS - no more header segments
+    if marker == 0xDA {
+      return;
+    }
+
+    let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+    let end = (pos + 2 + length).min(data.len());
+    if marker == 0xE1 && length > 8 && &data[pos + 4..(pos + 10).min(end)] == b"Exif\0\0" {
+      set_tiff_orientation(&mut data[pos + 10..end], 1);
+      return;
+    }
+    pos += 2 + length;
+  }
+}
+
+/// Fast path for `transform`: JPEG → JPEG with only rotate/flip/crop (and grayscale)
+/// Returns None when the request needs a full decode, or the lossless transform fails
+/// (unaligned crop, partial MCUs, unsupported JPEG) so the caller can fall back
+pub(crate) fn try_lossless_transform(input: &[u8], options: &TransformOptions) -> Option<Vec<u8>> {
+  if input.len() < 3 || input[0..3] != [0xFF, 0xD8, 0xFF] {
+    return None;
+  }
+
+  let output = options.output.as_ref()?;
+  if !matches!(output.format, ImageFormat::Jpeg) {
+    return None;
+  }
+
+  // Settings that only make sense when re-encoding
+  if let Some(ref jpeg) = output.jpeg {
+    if jpeg.mozjpeg.unwrap_or(false)
+      || jpeg.chroma_subsampling.is_some()
+      || jpeg.color_space.is_some()
+      || jpeg.icc_profile.is_some()
+    {
+      return None;
+    }
+  }
+
+  let has_pixel_ops = options.resize.is_some()
+    || options.exif.is_some()
+    || options.blur.unwrap_or(0) > 0
+    || options.sharpen.unwrap_or(0) > 0
+    || options.brightness.unwrap_or(0) != 0
+    || options.contrast.unwrap_or(0) != 0;
+  if has_pixel_ops {
+    return None;
+  }
+
+  let op = combine_ops(
+    options.rotate.unwrap_or(0),
+    options.flip_h.unwrap_or(false),
+    options.flip_v.unwrap_or(false),
+  )?;
+  if op == TransformOp::None && options.crop.is_none() {
+    // Nothing geometric to do - the caller asked for a re-encode
+    return None;
+  }
+
+  let jpeg = output.jpeg.as_ref();
+  let lossless_opts = JpegLosslessOptions {
+    crop: options.crop.clone(),
+    rotate: options.rotate,
+    flip_h: options.flip_h,
+    flip_v: options.flip_v,
+    grayscale: options.grayscale,
+    progressive: jpeg.and_then(|o| o.progressive),
+    optimize_coding: jpeg.and_then(|o| o.optimize_coding),
+    // transform output carries no metadata - a policy re-adds what it keeps afterwards
+    strip_metadata: Some(true),
+    ..Default::default()
+  };
+
+  jpeg_lossless_transform(input, &lossless_opts).ok()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_combine_ops() {
+    assert_eq!(combine_ops(0, false, false), Some(TransformOp::None));
+    assert_eq!(combine_ops(90, false, false), Some(TransformOp::Rot90));
+    assert_eq!(combine_ops(-90, false, false), Some(TransformOp::Rot270));
+    assert_eq!(combine_ops(0, true, true), Some(TransformOp::Rot180));
+    assert_eq!(combine_ops(180, true, true), Some(TransformOp::None));
+    assert_eq!(combine_ops(90, true, false), Some(TransformOp::Transpose));
+    assert_eq!(combine_ops(270, true, false), Some(TransformOp::Transverse));
+    assert_eq!(combine_ops(45, false, false), None);
+  }
+}
@@ -6,10 +6,13 @@ use napi::bindgen_prelude::*;
 use napi_derive::napi;
 
 // Internal modules
+mod animation;
+mod compare;
 mod crop;
 mod decode;
 mod encode;
 mod error;
+mod jpeg_lossless;
 mod metadata;
 mod metadata_write;
 mod resize;
@@ -26,10 +29,14 @@ use error::ImageError;
 // SYNC FUNCTIONS
 // ============================================
 
-/// Get image metadata synchronously
+/// Get image metadata synchronously - `exif: true` also decodes all EXIF tags
 #[napi]
-pub fn metadata_sync(input: Buffer) -> Result<ImageMetadata> {
-  decode::get_metadata(&input).map_err(|e| e.into())
+pub fn metadata_sync(input: Buffer, options: Option<MetadataOptions>) -> Result<ImageMetadata> {
+  match options {
+    Some(options) => decode::get_metadata_with_options(&input, &options),
+    None => decode::get_metadata(&input),
+  }
+  .map_err(|e| e.into())
 }
 
 /// Resize image synchronously - uses scale-on-decode for JPEG optimization
@@ -66,6 +73,7 @@ pub fn to_jpeg_sync(input: Buffer, options: Option<JpegOptions>) -> Result<Buffe
 pub fn to_png_sync(input: Buffer, options: Option<PngOptions>) -> Result<Buffer> {
   let img = decode::decode_image(&input)?;
   let output = encode::encode_png(&img, options.as_ref())?;
+  let output = encode::keep_png_metadata(&input, output, options.as_ref());
   Ok(Buffer::from(output))
 }
 
@@ -77,12 +85,46 @@ pub fn to_webp_sync(input: Buffer, options: Option<WebPOptions>) -> Result<Buffe
   Ok(Buffer::from(output))
 }
 
+/// Decode image to raw 8-bit pixels synchronously
+#[napi]
+pub fn to_raw_sync(input: Buffer) -> Result<RawImage> {
+  let img = decode::decode_image(&input)?;
+  Ok(encode::encode_raw(&img))
+}
+
+/// Convert image to animated WebP synchronously
+/// Animated GIF/WebP input keeps every frame; static input becomes a single frame
+#[napi]
+pub fn to_animated_webp_sync(
+  input: Buffer,
+  options: Option<WebPOptions>,
+  animation_options: Option<AnimationOptions>,
+) -> Result<Buffer> {
+  let mut anim = animation::decode_animation(&input)?;
+  anim.apply_options(animation_options.as_ref());
+  let output = animation::encode_webp_animation(&anim, options.as_ref())?;
+  Ok(Buffer::from(output))
+}
+
+/// Rotate, flip or crop a JPEG losslessly (no re-encode) synchronously
+#[napi]
+pub fn jpeg_lossless_transform_sync(input: Buffer, options: JpegLosslessOptions) -> Result<Buffer> {
+  let output = jpeg_lossless::jpeg_lossless_transform(&input, &options)?;
+  Ok(Buffer::from(output))
+}
+
 /// Transform image with multiple operations synchronously
 #[napi]
 pub fn transform_sync(input: Buffer, options: TransformOptions) -> Result<Buffer> {
   transform::transform_image(&input, &options).map_err(|e| e.into())
 }
 
+/// Transform image synchronously, returning output dimensions and the max_bytes/target_quality search result
+#[napi]
+pub fn transform_with_info_sync(input: Buffer, options: TransformOptions) -> Result<TransformResult> {
+  transform::transform_image_with_info(&input, &options).map_err(|e| e.into())
+}
+
 /// Generate blurhash from image synchronously
 #[napi]
 pub fn blurhash_sync(input: Buffer, components_x: Option<u32>, components_y: Option<u32>) -> Result<BlurHashResult> {
@@ -228,11 +270,12 @@ pub fn thumbhash_to_rgba_sync(hash: Buffer) -> Result<ThumbHashDecodeResult> {
 // ASYNC FUNCTIONS
 // ============================================
 
-/// Get image metadata asynchronously
+/// Get image metadata asynchronously - `exif: true` also decodes all EXIF tags
 #[napi]
-pub async fn metadata(input: Buffer) -> Result<ImageMetadata> {
-  tokio::task::spawn_blocking(move || {
-    decode::get_metadata(&input)
+pub async fn metadata(input: Buffer, options: Option<MetadataOptions>) -> Result<ImageMetadata> {
+  tokio::task::spawn_blocking(move || match options {
+    Some(options) => decode::get_metadata_with_options(&input, &options),
+    None => decode::get_metadata(&input),
   })
   .await
   .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
@@ -287,6 +330,7 @@ pub async fn to_png(input: Buffer, options: Option<PngOptions>) -> Result<Buffer
   tokio::task::spawn_blocking(move || {
     let img = decode::decode_image(&input)?;
     let output = encode::encode_png(&img, options.as_ref())?;
+    let output = encode::keep_png_metadata(&input, output, options.as_ref());
     Ok::<Buffer, ImageError>(Buffer::from(output))
   })
   .await
@@ -307,6 +351,49 @@ pub async fn to_webp(input: Buffer, options: Option<WebPOptions>) -> Result<Buff
   .map_err(|e| e.into())
 }
 
+/// Decode image to raw 8-bit pixels asynchronously
+#[napi]
+pub async fn to_raw(input: Buffer) -> Result<RawImage> {
+  tokio::task::spawn_blocking(move || {
+    let img = decode::decode_image(&input)?;
+    Ok::<RawImage, ImageError>(encode::encode_raw(&img))
+  })
+  .await
+  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+  .map_err(|e| e.into())
+}
+
+/// Convert image to animated WebP asynchronously
+/// Animated GIF/WebP input keeps every frame; static input becomes a single frame
+#[napi]
+pub async fn to_animated_webp(
+  input: Buffer,
+  options: Option<WebPOptions>,
+  animation_options: Option<AnimationOptions>,
+) -> Result<Buffer> {
+  tokio::task::spawn_blocking(move || {
+    let mut anim = animation::decode_animation(&input)?;
+    anim.apply_options(animation_options.as_ref());
+    let output = animation::encode_webp_animation(&anim, options.as_ref())?;
+    Ok::<Buffer, ImageError>(Buffer::from(output))
+  })
+  .await
+  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+  .map_err(|e| e.into())
+}
+
+/// Rotate, flip or crop a JPEG losslessly (no re-encode) asynchronously
+#[napi]
+pub async fn jpeg_lossless_transform(input: Buffer, options: JpegLosslessOptions) -> Result<Buffer> {
+  tokio::task::spawn_blocking(move || {
+    let output = jpeg_lossless::jpeg_lossless_transform(&input, &options)?;
+    Ok::<Buffer, ImageError>(Buffer::from(output))
+  })
+  .await
+  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+  .map_err(|e| e.into())
+}
+
 /// Transform image with multiple operations asynchronously
 #[napi]
 pub async fn transform(input: Buffer, options: TransformOptions) -> Result<Buffer> {
@@ -318,6 +405,17 @@ pub async fn transform(input: Buffer, options: TransformOptions) -> Result<Buffe
   .map_err(|e| e.into())
 }
 
+/// Transform image asynchronously, returning output dimensions and the max_bytes/target_quality search result
+#[napi]
+pub async fn transform_with_info(input: Buffer, options: TransformOptions) -> Result<TransformResult> {
+  tokio::task::spawn_blocking(move || {
+    transform::transform_image_with_info(&input, &options)
+  })
+  .await
+  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+  .map_err(|e| e.into())
+}
+
 /// Generate blurhash from image asynchronously
 #[napi]
 pub async fn blurhash(input: Buffer, components_x: Option<u32>, components_y: Option<u32>) -> Result<BlurHashResult> {
@@ -490,6 +588,45 @@ pub fn version() -> String {
   env!("CARGO_PKG_VERSION").to_string()
 }
 
+// ============================================
+// COMPARE FUNCTIONS
+// ============================================
+
+/// Compare two images synchronously with SSIM, MS-SSIM, PSNR or DSSIM (default: Ssim)
+/// The second image is resized to the first image's dimensions when they differ
+#[napi]
+pub fn compare_sync(
+  a: Buffer,
+  b: Buffer,
+  metric: Option<CompareMetric>,
+  options: Option<CompareOptions>,
+) -> Result<CompareResult> {
+  let img_a = decode::decode_image(&a)?;
+  let img_b = decode::decode_image(&b)?;
+  let metric = metric.unwrap_or(CompareMetric::Ssim);
+  compare::compare_images(&img_a, &img_b, &metric, options.as_ref()).map_err(|e| e.into())
+}
+
+/// Compare two images asynchronously with SSIM, MS-SSIM, PSNR or DSSIM (default: Ssim)
+/// The second image is resized to the first image's dimensions when they differ
+#[napi]
+pub async fn compare(
+  a: Buffer,
+  b: Buffer,
+  metric: Option<CompareMetric>,
+  options: Option<CompareOptions>,
+) -> Result<CompareResult> {
+  tokio::task::spawn_blocking(move || {
+    let img_a = decode::decode_image(&a)?;
+    let img_b = decode::decode_image(&b)?;
+    let metric = metric.unwrap_or(CompareMetric::Ssim);
+    compare::compare_images(&img_a, &img_b, &metric, options.as_ref())
+  })
+  .await
+  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+  .map_err(|e| e.into())
+}
+
 // ============================================
 // SMART CROP FUNCTIONS
 // ============================================
@@ -866,8 +1003,101 @@ pub async fn to_tensor(input: Buffer, options: Option<TensorOptions>) -> Result<
 // ============================================
 
 /// Convert ExifOptions to internal ExifWriteOptions
-fn exif_options_to_internal(options: &ExifOptions) -> metadata_write::ExifWriteOptions {
-  metadata_write::ExifWriteOptions {
+/// Validate an arbitrary EXIF tag and encode its value for its field type
+fn exif_tag_to_internal(tag: &ExifWriteTag) -> std::result::Result<metadata_write::ExifTagWrite, ImageError> {
+  use metadata_write::ExifValue;
+
+  let err = |message: String| ImageError::ProcessingError(format!("EXIF tag 0x{:04X}: {}", tag.tag, message));
+  let id = u16::try_from(tag.tag).map_err(|_| err("tag id must be at most 0xFFFF".to_string()))?;
+  let numbers = || {
+    tag
+      .numbers
+      .as_deref()
+      .filter(|numbers| !numbers.is_empty())
+      .ok_or_else(|| err(format!("{:?} needs numbers", tag.field_type)))
+  };
+  // Integers within [min, max]
+  let integers = |min: f64, max: f64| -> std::result::Result<Vec<f64>, ImageError> {
+    numbers()?
+      .iter()
+      .map(|&n| {
+        if n.is_finite() && n.fract() == 0.0 && (min..=max).contains(&n) {
+          Ok(n)
+        } else {
+          Err(err(format!("{} is not an integer between {} and {}", n, min, max)))
+        }
+      })
+      .collect()
+  };
+  let finite = || -> std::result::Result<Vec<f64>, ImageError> {
+    numbers()?
+      .iter()
+      .map(|&n| {
+        if n.is_finite() {
+          Ok(n)
+        } else {
+          Err(err(format!("{} is not a finite number", n)))
+        }
+      })
+      .collect()
+  };
+
+  let value = match tag.field_type {
+    ExifFieldType::Ascii => {
+      let text = tag.text.clone().ok_or_else(|| err("Ascii needs text".to_string()))?;
+      if text.contains('\0') {
+        return Err(err("Ascii text can't contain NUL".to_string()));
+      }
+      ExifValue::Ascii(text)
+    }
+    ExifFieldType::Undefined => match (&tag.bytes, &tag.text) {
+      (Some(bytes), _) => ExifValue::Undefined(bytes.clone()),
+      (None, Some(text)) => ExifValue::Undefined(text.as_bytes().to_vec()),
+      (None, None) => return Err(err("Undefined needs bytes or text".to_string())),
+    },
+    ExifFieldType::Byte => match tag.bytes {
+      Some(ref bytes) => ExifValue::Byte(bytes.clone()),
+      None => ExifValue::Byte(integers(0.0, u8::MAX as f64)?.into_iter().map(|n| n as u8).collect()),
+    },
+    ExifFieldType::Short => ExifValue::Short(integers(0.0, u16::MAX as f64)?.into_iter().map(|n| n as u16).collect()),
+    ExifFieldType::Long => ExifValue::Long(integers(0.0, u32::MAX as f64)?.into_iter().map(|n| n as u32).collect()),
+    ExifFieldType::Rational => {
+      let values = finite()?;
+      if let Some(n) = values.iter().find(|&&n| n < 0.0) {
+        return Err(err(format!("Rational can't be negative, got {}", n)));
+      }
+      ExifValue::Rational(
+        values
+          .into_iter()
+          .map(|n| metadata_write::approximate_fraction(n, u32::MAX))
+          .collect(),
+      )
+    }
+    ExifFieldType::SRational => ExifValue::SRational(
+      finite()?
+        .into_iter()
+        .map(|n| {
+          let (num, den) = metadata_write::approximate_fraction(n.abs(), i32::MAX as u32);
+          (if n < 0.0 { -(num as i32) } else { num as i32 }, den as i32)
+        })
+        .collect(),
+    ),
+  };
+
+  let ifd = tag.ifd.unwrap_or(ExifIfd::Ifd0);
+  Ok(metadata_write::ExifTagWrite::new(ifd, id, value))
+}
+
+fn exif_options_to_internal(options: &ExifOptions) -> std::result::Result<metadata_write::ExifWriteOptions, ImageError> {
+  let in_range = |value: Option<f64>, limit: f64, name: &str| match value {
+    Some(v) if !v.is_finite() || v.abs() > limit => Err(ImageError::ProcessingError(format!(
+      "{} must be between -{} and {}, got {}",
+      name, limit, limit, v
+    ))),
+    _ => Ok(value),
+  };
+
+  Ok(metadata_write::ExifWriteOptions {
     image_description: options.image_description.clone(),
     artist: options.artist.clone(),
     copyright: options.copyright.clone(),
@@ -878,75 +1108,125 @@ fn exif_options_to_internal(options: &ExifOptions) -> metadata_write::ExifWriteO
     make: options.make.clone(),
     model: options.model.clone(),
     orientation: options.orientation,
-  }
+    gps_latitude: in_range(options.gps_latitude, 90.0, "gpsLatitude")?,
+    gps_longitude: in_range(options.gps_longitude, 180.0, "gpsLongitude")?,
+    // Altitude is stored as millimeters in a u32 rational
+    gps_altitude: in_range(options.gps_altitude, 4_000_000.0, "gpsAltitude")?,
+    gps_timestamp: options
+      .gps_timestamp
+      .as_deref()
+      .map(metadata_write::GpsTimestamp::parse)
+      .transpose()?,
+    tags: options
+      .tags
+      .iter()
+      .flatten()
+      .map(exif_tag_to_internal)
+      .collect::<std::result::Result<_, _>>()?,
+  })
 }
 
-/// Write EXIF metadata to a WebP image synchronously
-#[napi]
-pub fn write_exif_sync(input: Buffer, options: ExifOptions) -> Result<Buffer> {
-  let format = decode::detect_format(&input)?;
-  let internal_opts = exif_options_to_internal(&options);
+/// Write EXIF metadata to a JPEG, PNG, WebP or TIFF image (merged into existing EXIF), plus optional IPTC (JPEG) and XMP
+fn write_exif_internal(input: &[u8], options: &ExifOptions) -> std::result::Result<Vec<u8>, ImageError> {
+  if decode::is_jxl(input) {
+    return Err(ImageError::UnsupportedFormat(
+      "EXIF writing is not supported for JPEG XL (decode-only)".to_string(),
+    ));
+  }
+  let format = decode::detect_format(input)?;
+  let internal_opts = exif_options_to_internal(options)?;
 
   let output = match format {
-    image::ImageFormat::Jpeg => metadata_write::write_jpeg_exif(&input, &internal_opts)?,
-    image::ImageFormat::WebP => metadata_write::write_webp_exif(&input, &internal_opts)?,
-    _ => return Err(Error::from_reason("EXIF writing only supported for JPEG and WebP formats")),
+    image::ImageFormat::Jpeg => metadata_write::write_jpeg_exif(input, &internal_opts)?,
+    image::ImageFormat::WebP => metadata_write::write_webp_exif(input, &internal_opts)?,
+    // PNG gets an eXIf chunk plus text chunks under the standard keywords
+    image::ImageFormat::Png => metadata_write::write_png_metadata(input, &internal_opts)?,
+    image::ImageFormat::Tiff => metadata_write::write_tiff_exif(input, &internal_opts)?,
+    _ => {
+      return Err(ImageError::UnsupportedFormat(
+        "EXIF writing only supported for JPEG, PNG, WebP and TIFF formats".to_string(),
+      ))
+    }
   };
 
+  let output = match options.text {
+    Some(ref text) if format == image::ImageFormat::Png => metadata_write::write_png_text(&output, text)?,
+    Some(_) => {
+      return Err(ImageError::UnsupportedFormat(
+        "Text chunk writing only supported for PNG format".to_string(),
+      ))
+    }
+    None => output,
+  };
+
+  let output = match options.iptc {
+    Some(ref iptc) if format == image::ImageFormat::Jpeg => metadata_write::write_jpeg_iptc(&output, iptc)?,
+    Some(_) => {
+      return Err(ImageError::UnsupportedFormat(
+        "IPTC writing only supported for JPEG format".to_string(),
+      ))
+    }
+    None => output,
+  };
+
+  match options.xmp {
+    Some(ref xmp) => metadata_write::write_xmp(&output, xmp),
+    None => Ok(output),
+  }
+}
+
+/// Write EXIF metadata to a WebP image synchronously
+#[napi]
+pub fn write_exif_sync(input: Buffer, options: ExifOptions) -> Result<Buffer> {
+  let output = write_exif_internal(&input, &options)?;
   Ok(Buffer::from(output))
 }
 
 /// Write EXIF metadata to a WebP image asynchronously
 #[napi]
 pub async fn write_exif(input: Buffer, options: ExifOptions) -> Result<Buffer> {
-  tokio::task::spawn_blocking(move || {
-    let format = decode::detect_format(&input)?;
-    let internal_opts = exif_options_to_internal(&options);
+  tokio::task::spawn_blocking(move || write_exif_internal(&input, &options).map(Buffer::from))
+    .await
+    .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+    .map_err(|e| e.into())
+}
 
-    let output = match format {
-      image::ImageFormat::Jpeg => metadata_write::write_jpeg_exif(&input, &internal_opts)?,
-      image::ImageFormat::WebP => metadata_write::write_webp_exif(&input, &internal_opts)?,
-      _ => return Err(ImageError::UnsupportedFormat("EXIF writing only supported for JPEG and WebP formats".to_string())),
-    };
+/// Strip all EXIF metadata, or only the selected categories
+fn strip_exif_internal(input: &[u8], options: Option<&StripExifOptions>) -> std::result::Result<Vec<u8>, ImageError> {
+  if decode::is_jxl(input) {
+    return Err(ImageError::UnsupportedFormat(
+      "EXIF stripping is not supported for JPEG XL (decode-only)".to_string(),
+    ));
+  }
+  if let Some(opts) = options.filter(|o| o.keep.is_some() || o.remove.is_some()) {
+    let filter = metadata_write::ExifFilter::new(opts.keep.as_deref(), opts.remove.as_deref())?;
+    return metadata_write::filter_exif(input, &filter);
+  }
 
-    Ok::<Buffer, ImageError>(Buffer::from(output))
-  })
-  .await
-  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
-  .map_err(|e| e.into())
+  let format = decode::detect_format(input)?;
+  match format {
+    image::ImageFormat::Jpeg => metadata_write::strip_jpeg_exif(input),
+    image::ImageFormat::WebP => metadata_write::strip_webp_exif(input),
+    _ => Err(ImageError::UnsupportedFormat(
+      "EXIF stripping only supported for JPEG and WebP formats".to_string(),
+    )),
+  }
 }
 
 /// Strip EXIF metadata from an image synchronously
 #[napi]
-pub fn strip_exif_sync(input: Buffer) -> Result<Buffer> {
-  let format = decode::detect_format(&input)?;
-
-  let output = match format {
-    image::ImageFormat::Jpeg => metadata_write::strip_jpeg_exif(&input)?,
-    image::ImageFormat::WebP => metadata_write::strip_webp_exif(&input)?,
-    _ => return Err(Error::from_reason("EXIF stripping only supported for JPEG and WebP formats")),
-  };
-
+pub fn strip_exif_sync(input: Buffer, options: Option<StripExifOptions>) -> Result<Buffer> {
+  let output = strip_exif_internal(&input, options.as_ref())?;
   Ok(Buffer::from(output))
 }
 
 /// Strip EXIF metadata from an image asynchronously
 #[napi]
-pub async fn strip_exif(input: Buffer) -> Result<Buffer> {
-  tokio::task::spawn_blocking(move || {
-    let format = decode::detect_format(&input)?;
-
-    let output = match format {
-      image::ImageFormat::Jpeg => metadata_write::strip_jpeg_exif(&input)?,
-      image::ImageFormat::WebP => metadata_write::strip_webp_exif(&input)?,
-      _ => return Err(ImageError::UnsupportedFormat("EXIF stripping only supported for JPEG and WebP formats".to_string())),
-    };
-
-    Ok::<Buffer, ImageError>(Buffer::from(output))
-  })
-  .await
-  .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
-  .map_err(|e| e.into())
+pub async fn strip_exif(input: Buffer, options: Option<StripExifOptions>) -> Result<Buffer> {
+  tokio::task::spawn_blocking(move || strip_exif_internal(&input, options.as_ref()).map(Buffer::from))
+    .await
+    .map_err(|e| Error::from_reason(format!("Task error: {}", e)))?
+    .map_err(|e| e.into())
 }
 
 // ============================================
@@ -958,6 +1238,28 @@ fn generate_thumbnail_internal(
   input: &[u8],
   options: &ThumbnailOptions,
 ) -> std::result::Result<ThumbnailResult, ImageError> {
+  let mut result = generate_thumbnail_pixels(input, options)?;
+  if let Some(ref policy) = options.metadata {
+    let mut keep = metadata_write::MetadataKeep::new(policy.keep.as_deref())?;
+    keep.icc &= options.color_management.is_none();
+    result.data = metadata_write::copy_metadata(input, result.data, &keep, result.width, result.height, false)?;
+  }
+  result.data =
+    transform::embed_output_profile(input, result.data, options.keep_icc, options.color_management.as_ref())?;
+  Ok(result)
+}
+
+/// Decode, resize and encode the thumbnail - everything except embedding metadata
+fn generate_thumbnail_pixels(
+  input: &[u8],
+  options: &ThumbnailOptions,
+) -> std::result::Result<ThumbnailResult, ImageError> {
+  if options.max_bytes.is_some() && matches!(options.format, Some(ThumbnailFormat::Png)) {
+    return Err(ImageError::UnsupportedFormat(
+      "maxBytes requires lossy thumbnail output (JPEG or WebP)".to_string(),
+    ));
+  }
+
   // Get original dimensions first (fast header-only read)
   let meta = decode::get_metadata(input)?;
   let original_width = meta.width;
@@ -977,14 +1279,31 @@ fn generate_thumbnail_internal(
     }
   };
 
+  // Animated input: resize every frame and keep the animation (WebP or APNG output)
+  let animated_output = !matches!(options.format, Some(ThumbnailFormat::Jpeg));
+  if options.animated.unwrap_or(false) && animated_output && animation::is_animated(input) {
+    if options.max_bytes.is_some() {
+      return Err(ImageError::ProcessingError(
+        "maxBytes is not supported for animated thumbnails".to_string(),
+      ));
+    }
+    return generate_animated_thumbnail(input, options, target_width, target_height);
+  }
+
   // Determine if shrink-on-load should be used
   let use_shrink = options.shrink_on_load.unwrap_or(true);
 
   // Decode with or without shrink-on-load (use fast mode if enabled)
   let img = if use_shrink {
-    decode::decode_image_with_target_fast(input, Some(target_width), Some(target_height), fast_mode)?
+    decode::decode_image_with_target_fast(
+      input,
+      Some(target_width),
+      Some(target_height),
+      fast_mode,
+      options.color_management.as_ref(),
+    )?
   } else {
-    decode::decode_image(input)?
+    decode::decode_image_with_target_fast(input, None, None, false, options.color_management.as_ref())?
   };
 
   let (decoded_w, decoded_h) = image::GenericImageView::dimensions(&img);
@@ -1038,6 +1357,8 @@ fn generate_thumbnail_internal(
       match input_format.as_str() {
         "jpeg" | "jpg" => "jpeg",
         "webp" => "webp",
+        // A byte budget needs a lossy format - WebP keeps the alpha channel
+        "png" if options.max_bytes.is_some() => "webp",
         "png" => "png",
         _ => "jpeg", // Default to JPEG for unknown formats
       }
@@ -1047,11 +1368,40 @@ fn generate_thumbnail_internal(
   // Encode output (fast mode uses lower quality for speed)
   let default_quality = if fast_mode { 70 } else { 80 };
   let quality = options.quality.unwrap_or(default_quality);
+
+  // Byte budget: search the quality (and optionally dimensions) until the output fits
+  if let Some(max_bytes) = options.max_bytes {
+    let format = match output_format {
+      "webp" => ImageFormat::WebP,
+      "png" => ImageFormat::Png,
+      _ => ImageFormat::Jpeg,
+    };
+    let fitted = encode::encode_to_max_bytes(
+      &resized,
+      &format,
+      Some(&JpegOptions { quality: Some(quality), ..Default::default() }),
+      Some(&WebPOptions { quality: Some(quality), ..Default::default() }),
+      max_bytes,
+      options.downscale_to_fit.unwrap_or(false),
+    )?;
+    return Ok(ThumbnailResult {
+      data: fitted.data,
+      width: fitted.width,
+      height: fitted.height,
+      format: output_format.to_string(),
+      shrink_on_load_used,
+      original_width,
+      original_height,
+      quality: Some(fitted.quality as u32),
+      attempts: Some(fitted.attempts),
+    });
+  }
+
   let data = match output_format {
-    "jpeg" => encode::encode_jpeg(&resized, Some(&JpegOptions { quality: Some(quality) }))?,
-    "webp" => encode::encode_webp(&resized, Some(&WebPOptions { quality: Some(quality), lossless: Some(false) }))?,
+    "jpeg" => encode::encode_jpeg(&resized, Some(&JpegOptions { quality: Some(quality), ..Default::default() }))?,
+    "webp" => encode::encode_webp(&resized, Some(&WebPOptions { quality: Some(quality), lossless: Some(false), ..Default::default() }))?,
     "png" => encode::encode_png(&resized, None)?,
-    _ => encode::encode_jpeg(&resized, Some(&JpegOptions { quality: Some(quality) }))?,
+    _ => encode::encode_jpeg(&resized, Some(&JpegOptions { quality: Some(quality), ..Default::default() }))?,
   };
 
   Ok(ThumbnailResult {
@@ -1062,6 +1412,66 @@ fn generate_thumbnail_internal(
     shrink_on_load_used,
     original_width,
     original_height,
+    quality: None,
+    attempts: None,
+  })
+}
+
+/// Animated thumbnail: resize every frame to the target and encode as animated WebP or APNG
+fn generate_animated_thumbnail(
+  input: &[u8],
+  options: &ThumbnailOptions,
+  target_width: u32,
+  target_height: u32,
+) -> std::result::Result<ThumbnailResult, ImageError> {
+  let anim = animation::decode_animation(input)?;
+  let (original_width, original_height) = (anim.width, anim.height);
+  let profile = options.color_management.as_ref().and_then(|_| metadata::find_icc_profile(input));
+
+  let fast_mode = options.fast_mode.unwrap_or(false);
+  let filter = if fast_mode {
+    Some(ResizeFilter::Nearest)
+  } else {
+    options.filter.clone()
+  };
+  let resize_opts = ResizeOptions {
+    width: Some(target_width),
+    height: Some(target_height),
+    filter,
+    fit: Some(FitMode::Fill),
+    background: None,
+  };
+  let anim = anim.map_frames(|frame| {
+    let frame = match options.color_management {
+      Some(ref color) => decode::convert_color(frame, profile.as_deref(), color)?,
+      None => frame,
+    };
+    resize::resize_image(frame, &resize_opts)
+  })?;
+
+  let default_quality = if fast_mode { 70 } else { 80 };
+  let quality = options.quality.unwrap_or(default_quality);
+  let (data, output_format) = match options.format {
+    Some(ThumbnailFormat::Png) => (animation::encode_png_animation(&anim, None)?, "png"),
+    _ => (
+      animation::encode_webp_animation(
+        &anim,
+        Some(&WebPOptions { quality: Some(quality), lossless: Some(false), ..Default::default() }),
+      )?,
+      "webp",
+    ),
+  };
+
+  Ok(ThumbnailResult {
+    data,
+    width: anim.width,
+    height: anim.height,
+    format: output_format.to_string(),
+    shrink_on_load_used: false,
+    original_width,
+    original_height,
+    quality: None,
+    attempts: None,
   })
 }
 
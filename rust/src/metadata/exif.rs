@@ -0,0 +1,722 @@
+//! Full EXIF reading
+//!
+//! Locates the TIFF-structured EXIF payload in JPEG (APP1), WebP (EXIF chunk),
+//! PNG (eXIf), TIFF and HEIC/AVIF (Exif item) files and decodes every tag of
+//! IFD0 and the Exif, GPS and Interop sub-IFDs into typed values.
+//! GPS tags are additionally summarized as signed decimal coordinates.
+
+use std::collections::HashSet;
+
+use crate::{ExifEntry, GpsInfo};
+
+use super::utils::{find_png_chunk, find_webp_chunk};
+
+/// Exif sub-IFD pointer (IFD0)
+const EXIF_IFD_POINTER: u16 = 0x8769;
+/// GPS sub-IFD pointer (IFD0)
+const GPS_IFD_POINTER: u16 = 0x8825;
+/// Interoperability sub-IFD pointer (Exif IFD)
+const INTEROP_IFD_POINTER: u16 = 0xA005;
+
+/// Entries read per IFD at most - real files have well under 100
+const MAX_IFD_ENTRIES: usize = 512;
+
+/// Directory a tag belongs to
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Ifd {
+  Ifd0,
+  Exif,
+  Gps,
+  Interop,
+}
+
+impl Ifd {
+  fn name(self) -> &'static str {
+    match self {
+      Ifd::Ifd0 => "ifd0",
+      Ifd::Exif => "exif",
+      Ifd::Gps => "gps",
+      Ifd::Interop => "interop",
+    }
+  }
+}
+
+/// Byte-order aware reads over a TIFF payload
+struct TiffReader<'a> {
+  data: &'a [u8],
+  big_endian: bool,
+}
+
+impl<'a> TiffReader<'a> {
+  fn new(data: &'a [u8]) -> Option<Self> {
+    let big_endian = match data.get(0..4)? {
+      b"MM\0*" => true,
+      b"II*\0" => false,
+      _ => return None,
+    };
+    Some(TiffReader { data, big_endian })
+  }
+
+  fn u16(&self, offset: usize) -> Option<u16> {
+    let bytes: [u8; 2] = self.data.get(offset..offset + 2)?.try_into().ok()?;
+    Some(if self.big_endian {
+      u16::from_be_bytes(bytes)
+    } else {
+      u16::from_le_bytes(bytes)
+    })
+  }
+
+  fn u32(&self, offset: usize) -> Option<u32> {
+    let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+    Some(if self.big_endian {
+      u32::from_be_bytes(bytes)
+    } else {
+      u32::from_le_bytes(bytes)
+    })
+  }
+
+  fn u64(&self, offset: usize) -> Option<u64> {
+    let bytes: [u8; 8] = self.data.get(offset..offset + 8)?.try_into().ok()?;
+    Some(if self.big_endian {
+      u64::from_be_bytes(bytes)
+    } else {
+      u64::from_le_bytes(bytes)
+    })
+  }
+}
+
+/// Size in bytes of one value of a TIFF field type
+pub fn tiff_type_size(field_type: u16) -> Option<usize> {
+  match field_type {
+    1 | 2 | 6 | 7 => Some(1),
+    3 | 8 => Some(2),
+    4 | 9 | 11 | 13 => Some(4),
+    5 | 10 | 12 => Some(8),
+    _ => None,
+  }
+}
+
+/// Decode one value of a numeric field type
+fn read_number(reader: &TiffReader, field_type: u16, offset: usize) -> Option<f64> {
+  Some(match field_type {
+    1 | 7 => *reader.data.get(offset)? as f64,
+    6 => *reader.data.get(offset)? as i8 as f64,
+    3 => reader.u16(offset)? as f64,
+    8 => reader.u16(offset)? as i16 as f64,
+    4 | 13 => reader.u32(offset)? as f64,
+    9 => reader.u32(offset)? as i32 as f64,
+    5 | 10 => {
+      let (num, den) = (reader.u32(offset)?, reader.u32(offset + 4)?);
+      let (num, den) = if field_type == 10 {
+        (num as i32 as f64, den as i32 as f64)
+      } else {
+        (num as f64, den as f64)
+      };
+      if den == 0.0 {
+        0.0
+      } else {
+        num / den
+      }
+    }
+    11 => f32::from_bits(reader.u32(offset)?) as f64,
+    12 => f64::from_bits(reader.u64(offset)?),
+    _ => return None,
+  })
+}
+
+/// ASCII value up to the first NUL, trailing spaces removed
+fn decode_text(bytes: &[u8]) -> String {
+  let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+  String::from_utf8_lossy(&bytes[..end]).trim_end().to_string()
+}
+
+/// UNDEFINED tags that hold text: versions and comments
+fn undefined_as_text(ifd: Ifd, tag: u16, bytes: &[u8]) -> Option<String> {
+  match (ifd, tag) {
+    // ExifVersion, FlashpixVersion, InteroperabilityVersion
+    (Ifd::Exif, 0x9000) | (Ifd::Exif, 0xA000) | (Ifd::Interop, 0x0002) => Some(decode_text(bytes)),
+    // UserComment, GPSProcessingMethod, GPSAreaInformation: 8-byte charset prefix
+    (Ifd::Exif, 0x9286) | (Ifd::Gps, 0x001B) | (Ifd::Gps, 0x001C) if bytes.len() >= 8 => {
+      let text = &bytes[8..];
+      Some(match &bytes[..8] {
+        b"UNICODE\0" => {
+          let units: Vec<u16> = text.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+          String::from_utf16_lossy(&units).trim_end_matches(['\0', ' ']).to_string()
+        }
+        _ => decode_text(text),
+      })
+    }
+    _ => None,
+  }
+}
+
+/// Walk one IFD, recursing into the sub-IFDs it points to
+fn read_ifd(reader: &TiffReader, offset: usize, ifd: Ifd, visited: &mut HashSet<usize>, entries: &mut Vec<ExifEntry>) {
+  // Guard against IFD loops in malformed files
+  if !visited.insert(offset) {
+    return;
+  }
+  let Some(count) = reader.u16(offset) else {
+    return;
+  };
+
+  for i in 0..(count as usize).min(MAX_IFD_ENTRIES) {
+    let entry = offset + 2 + i * 12;
+    let (Some(tag), Some(field_type), Some(value_count)) =
+      (reader.u16(entry), reader.u16(entry + 2), reader.u32(entry + 4))
+    else {
+      break;
+    };
+    let Some(size) = tiff_type_size(field_type) else {
+      continue;
+    };
+
+    // Values of up to 4 bytes are stored in the entry itself
+    let total = size.saturating_mul(value_count as usize);
+    let value_offset = if total <= 4 {
+      entry + 8
+    } else {
+      match reader.u32(entry + 8) {
+        Some(offset) => offset as usize,
+        None => continue,
+      }
+    };
+    let Some(bytes) = reader.data.get(value_offset..value_offset.saturating_add(total)) else {
+      continue;
+    };
+
+    // Sub-IFD pointers are followed, not reported
+    let sub_ifd = match (ifd, tag) {
+      (Ifd::Ifd0, EXIF_IFD_POINTER) => Some(Ifd::Exif),
+      (Ifd::Ifd0, GPS_IFD_POINTER) => Some(Ifd::Gps),
+      (Ifd::Exif, INTEROP_IFD_POINTER) => Some(Ifd::Interop),
+      _ => None,
+    };
+    if let Some(sub_ifd) = sub_ifd {
+      if let Some(pointer) = read_number(reader, field_type, value_offset) {
+        read_ifd(reader, pointer as usize, sub_ifd, visited, entries);
+      }
+      continue;
+    }
+
+    let (mut text, mut numbers, mut raw) = (None, None, None);
+    match field_type {
+      2 => text = Some(decode_text(bytes)),
+      7 => match undefined_as_text(ifd, tag, bytes) {
+        Some(value) => text = Some(value),
+        None => raw = Some(bytes.to_vec()),
+      },
+      _ => {
+        numbers = (0..value_count as usize)
+          .map(|n| read_number(reader, field_type, value_offset + n * size))
+          .collect::<Option<Vec<f64>>>();
+      }
+    }
+
+    entries.push(ExifEntry {
+      ifd: ifd.name().to_string(),
+      tag: tag as u32,
+      name: tag_name(ifd, tag).map_or_else(|| format!("0x{:04X}", tag), str::to_string),
+      text,
+      numbers,
+      bytes: raw,
+    });
+  }
+}
+
+/// Decode all IFD0, Exif, GPS and Interop tags of a TIFF-structured EXIF payload
+pub fn parse_exif(tiff: &[u8]) -> Vec<ExifEntry> {
+  let mut entries = Vec::new();
+  let Some(reader) = TiffReader::new(tiff) else {
+    return entries;
+  };
+  if let Some(ifd0) = reader.u32(4) {
+    read_ifd(&reader, ifd0 as usize, Ifd::Ifd0, &mut HashSet::new(), &mut entries);
+  }
+  entries
+}
+
+/// Drop an optional "Exif\0\0" prefix (JPEG APP1 style, also seen in WebP/PNG)
+fn strip_exif_header(data: &[u8]) -> &[u8] {
+  data.strip_prefix(b"Exif\0\0").unwrap_or(data)
+}
+
+/// TIFF payload of the JPEG APP1 Exif segment
+fn find_jpeg_exif(data: &[u8]) -> Option<&[u8]> {
+  let mut pos = 2;
+  while pos + 4 <= data.len() {
+    if data[pos] != 0xFF {
+      return None;
+    }
+    let marker = data[pos + 1];
+    if marker == 0xFF {
+      pos += 1;
+      continue;
+    }
+    // Headers end at start of scan
+    if marker == 0xDA || marker == 0xD9 {
+      return None;
+    }
+    if (0xD0..=0xD8).contains(&marker) || marker == 0x01 {
+      pos += 2;
+      continue;
+    }
+    let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+    let segment = data.get(pos + 4..pos + 2 + length)?;
+    if marker == 0xE1 && segment.starts_with(b"Exif\0\0") {
+      return Some(&segment[6..]);
+    }
+    pos += 2 + length;
+  }
+  None
+}
+
+/// Read an ISO BMFF box header: (type, payload start, box end)
+fn read_box(data: &[u8], pos: usize, end: usize) -> Option<([u8; 4], usize, usize)> {
+  let size = u32::from_be_bytes(data.get(pos..pos + 4)?.try_into().ok()?) as u64;
+  let box_type: [u8; 4] = data.get(pos + 4..pos + 8)?.try_into().ok()?;
+  let (header, size) = match size {
+    0 => (8, (end - pos) as u64),
+    1 => (16, u64::from_be_bytes(data.get(pos + 8..pos + 16)?.try_into().ok()?)),
+    size => (8, size),
+  };
+  let box_end = pos.checked_add(usize::try_from(size).ok()?)?;
+  if size < header as u64 || box_end > end {
+    return None;
+  }
+  Some((box_type, pos + header, box_end))
+}
+
+/// Find a child box by type within [start, end)
+fn find_box(data: &[u8], mut pos: usize, end: usize, wanted: &[u8; 4]) -> Option<(usize, usize)> {
+  while pos < end {
+    let (box_type, payload, box_end) = read_box(data, pos, end)?;
+    if &box_type == wanted {
+      return Some((payload, box_end));
+    }
+    pos = box_end;
+  }
+  None
+}
+
+/// Big-endian unsigned integer of 0, 4 or 8 bytes (iloc field sizes)
+fn read_sized(data: &[u8], pos: &mut usize, size: usize) -> Option<u64> {
+  let value = match size {
+    0 => 0,
+    4 => u32::from_be_bytes(data.get(*pos..*pos + 4)?.try_into().ok()?) as u64,
+    8 => u64::from_be_bytes(data.get(*pos..*pos + 8)?.try_into().ok()?),
+    _ => return None,
+  };
+  *pos += size;
+  Some(value)
+}
+
+/// TIFF payload of the HEIF `Exif` item (meta → iinf/iloc)
+fn find_heif_exif(data: &[u8]) -> Option<&[u8]> {
+  let (meta, meta_end) = find_box(data, 0, data.len(), b"meta")?;
+  // meta is a FullBox: skip version and flags
+  let children = meta + 4;
+
+  // Item id of the Exif item
+  let (iinf, iinf_end) = find_box(data, children, meta_end, b"iinf")?;
+  let iinf_version = *data.get(iinf)?;
+  let mut pos = iinf + 4 + if iinf_version == 0 { 2 } else { 4 };
+  let mut exif_id = None;
+  while pos < iinf_end {
+    let (box_type, infe, infe_end) = read_box(data, pos, iinf_end)?;
+    if &box_type == b"infe" && *data.get(infe)? >= 2 {
+      let version = data[infe];
+      let (id, type_pos) = if version == 2 {
+        (u16::from_be_bytes(data.get(infe + 4..infe + 6)?.try_into().ok()?) as u32, infe + 8)
+      } else {
+        (u32::from_be_bytes(data.get(infe + 4..infe + 8)?.try_into().ok()?), infe + 10)
+      };
+      if data.get(type_pos..type_pos + 4)? == b"Exif" {
+        exif_id = Some(id);
+        break;
+      }
+    }
+    pos = infe_end;
+  }
+  let exif_id = exif_id?;
+
+  // File offset of the item's first extent
+  let (iloc, _) = find_box(data, children, meta_end, b"iloc")?;
+  let version = *data.get(iloc)?;
+  let sizes = *data.get(iloc + 4)?;
+  let sizes2 = *data.get(iloc + 5)?;
+  let (offset_size, length_size) = ((sizes >> 4) as usize, (sizes & 0x0F) as usize);
+  let (base_offset_size, index_size) = ((sizes2 >> 4) as usize, (sizes2 & 0x0F) as usize);
+  let mut pos = iloc + 6;
+  let item_count = if version < 2 {
+    read_u16(data, &mut pos)? as u32
+  } else {
+    read_u32(data, &mut pos)?
+  };
+
+  for _ in 0..item_count {
+    let id = if version < 2 {
+      read_u16(data, &mut pos)? as u32
+    } else {
+      read_u32(data, &mut pos)?
+    };
+    let construction_method = if version >= 1 { read_u16(data, &mut pos)? & 0x0F } else { 0 };
+    pos += 2; // data_reference_index
+    let base_offset = read_sized(data, &mut pos, base_offset_size)?;
+    let extent_count = read_u16(data, &mut pos)?;
+    let mut first_extent = None;
+    for _ in 0..extent_count {
+      if version >= 1 {
+        read_sized(data, &mut pos, index_size)?;
+      }
+      let offset = read_sized(data, &mut pos, offset_size)?;
+      let length = read_sized(data, &mut pos, length_size)?;
+      if first_extent.is_none() {
+        first_extent = Some((offset, length));
+      }
+    }
+
+    if id == exif_id {
+      // Only items stored in the file itself (construction method 0)
+      if construction_method != 0 {
+        return None;
+      }
+      let (offset, length) = first_extent?;
+      let start = usize::try_from(base_offset + offset).ok()?;
+      let end = if length == 0 {
+        data.len()
+      } else {
+        start.checked_add(usize::try_from(length).ok()?)?
+      };
+      let item = data.get(start..end)?;
+      // Exif item: 4-byte offset to the TIFF header, then the payload
+      let tiff_offset = u32::from_be_bytes(item.get(0..4)?.try_into().ok()?) as usize;
+      return item.get(4 + tiff_offset..);
+    }
+  }
+  None
+}
+
+fn read_u16(data: &[u8], pos: &mut usize) -> Option<u16> {
+  let value = u16::from_be_bytes(data.get(*pos..*pos + 2)?.try_into().ok()?);
+  *pos += 2;
+  Some(value)
+}
+
+fn read_u32(data: &[u8], pos: &mut usize) -> Option<u32> {
+  let value = u32::from_be_bytes(data.get(*pos..*pos + 4)?.try_into().ok()?);
+  *pos += 4;
+  Some(value)
+}
+
+/// Locate the TIFF-structured EXIF payload in a JPEG, WebP, PNG, TIFF or HEIF file
+pub fn find_exif(data: &[u8]) -> Option<&[u8]> {
+  if data.starts_with(&[0xFF, 0xD8]) {
+    return find_jpeg_exif(data);
+  }
+  if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+    let offset = find_webp_chunk(data, b"EXIF")?;
+    let length = u32::from_le_bytes(data.get(offset - 4..offset)?.try_into().ok()?) as usize;
+    return data.get(offset..offset.checked_add(length)?).map(strip_exif_header);
+  }
+  if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+    let offset = find_png_chunk(data, b"eXIf")?;
+    let length = u32::from_be_bytes(data.get(offset - 8..offset - 4)?.try_into().ok()?) as usize;
+    return data.get(offset..offset.checked_add(length)?).map(strip_exif_header);
+  }
+  if TiffReader::new(data).is_some() {
+    return Some(data);
+  }
+  if super::is_heic(data) {
+    return find_heif_exif(data);
+  }
+  None
+}
+
+/// Name of a known tag
+fn tag_name(ifd: Ifd, tag: u16) -> Option<&'static str> {
+  let name = match ifd {
+    Ifd::Gps => match tag {
+      0x0000 => "GPSVersionID",
+      0x0001 => "GPSLatitudeRef",
+      0x0002 => "GPSLatitude",
+      0x0003 => "GPSLongitudeRef",
+      0x0004 => "GPSLongitude",
+      0x0005 => "GPSAltitudeRef",
+      0x0006 => "GPSAltitude",
+      0x0007 => "GPSTimeStamp",
+      0x0008 => "GPSSatellites",
+      0x0009 => "GPSStatus",
+      0x000A => "GPSMeasureMode",
+      0x000B => "GPSDOP",
+      0x000C => "GPSSpeedRef",
+      0x000D => "GPSSpeed",
+      0x000E => "GPSTrackRef",
+      0x000F => "GPSTrack",
+      0x0010 => "GPSImgDirectionRef",
+      0x0011 => "GPSImgDirection",
+      0x0012 => "GPSMapDatum",
+      0x0013 => "GPSDestLatitudeRef",
+      0x0014 => "GPSDestLatitude",
+      0x0015 => "GPSDestLongitudeRef",
+      0x0016 => "GPSDestLongitude",
+      0x0017 => "GPSDestBearingRef",
+      0x0018 => "GPSDestBearing",
+      0x0019 => "GPSDestDistanceRef",
+      0x001A => "GPSDestDistance",
+      0x001B => "GPSProcessingMethod",
+      0x001C => "GPSAreaInformation",
+      0x001D => "GPSDateStamp",
+      0x001E => "GPSDifferential",
+      0x001F => "GPSHPositioningError",
+      _ => return None,
+    },
+    Ifd::Interop => match tag {
+      0x0001 => "InteroperabilityIndex",
+      0x0002 => "InteroperabilityVersion",
+      0x1000 => "RelatedImageFileFormat",
+      0x1001 => "RelatedImageWidth",
+      0x1002 => "RelatedImageHeight",
+      _ => return None,
+    },
+    Ifd::Ifd0 | Ifd::Exif => match tag {
+      // IFD0 (TIFF) tags
+      0x00FE => "NewSubfileType",
+      0x0100 => "ImageWidth",
+      0x0101 => "ImageLength",
+      0x0102 => "BitsPerSample",
+      0x0103 => "Compression",
+      0x0106 => "PhotometricInterpretation",
+      0x010E => "ImageDescription",
+      0x010F => "Make",
+      0x0110 => "Model",
+      0x0111 => "StripOffsets",
+      0x0112 => "Orientation",
+      0x0115 => "SamplesPerPixel",
+      0x0116 => "RowsPerStrip",
+      0x0117 => "StripByteCounts",
+      0x011A => "XResolution",
+      0x011B => "YResolution",
+      0x011C => "PlanarConfiguration",
+      0x0128 => "ResolutionUnit",
+      0x012D => "TransferFunction",
+      0x0131 => "Software",
+      0x0132 => "DateTime",
+      0x013B => "Artist",
+      0x013E => "WhitePoint",
+      0x013F => "PrimaryChromaticities",
+      0x0201 => "JPEGInterchangeFormat",
+      0x0202 => "JPEGInterchangeFormatLength",
+      0x0211 => "YCbCrCoefficients",
+      0x0212 => "YCbCrSubSampling",
+      0x0213 => "YCbCrPositioning",
+      0x0214 => "ReferenceBlackWhite",
+      0x02BC => "XMLPacket",
+      0x4746 => "Rating",
+      0x4749 => "RatingPercent",
+      0x8298 => "Copyright",
+      0x83BB => "IPTCNAA",
+      0x8773 => "InterColorProfile",
+      0x9C9B => "XPTitle",
+      0x9C9C => "XPComment",
+      0x9C9D => "XPAuthor",
+      0x9C9E => "XPKeywords",
+      0x9C9F => "XPSubject",
+      0xC4A5 => "PrintImageMatching",
+      // Exif IFD tags
+      0x829A => "ExposureTime",
+      0x829D => "FNumber",
+      0x8822 => "ExposureProgram",
+      0x8824 => "SpectralSensitivity",
+      0x8827 => "ISOSpeedRatings",
+      0x8828 => "OECF",
+      0x8830 => "SensitivityType",
+      0x8832 => "RecommendedExposureIndex",
+      0x9000 => "ExifVersion",
+      0x9003 => "DateTimeOriginal",
+      0x9004 => "DateTimeDigitized",
+      0x9010 => "OffsetTime",
+      0x9011 => "OffsetTimeOriginal",
+      0x9012 => "OffsetTimeDigitized",
+      0x9101 => "ComponentsConfiguration",
+      0x9102 => "CompressedBitsPerPixel",
+      0x9201 => "ShutterSpeedValue",
+      0x9202 => "ApertureValue",
+      0x9203 => "BrightnessValue",
+      0x9204 => "ExposureBiasValue",
+      0x9205 => "MaxApertureValue",
+      0x9206 => "SubjectDistance",
+      0x9207 => "MeteringMode",
+      0x9208 => "LightSource",
+      0x9209 => "Flash",
+      0x920A => "FocalLength",
+      0x9214 => "SubjectArea",
+      0x927C => "MakerNote",
+      0x9286 => "UserComment",
+      0x9290 => "SubSecTime",
+      0x9291 => "SubSecTimeOriginal",
+      0x9292 => "SubSecTimeDigitized",
+      0xA000 => "FlashpixVersion",
+      0xA001 => "ColorSpace",
+      0xA002 => "PixelXDimension",
+      0xA003 => "PixelYDimension",
+      0xA004 => "RelatedSoundFile",
+      0xA20B => "FlashEnergy",
+      0xA20E => "FocalPlaneXResolution",
+      0xA20F => "FocalPlaneYResolution",
+      0xA210 => "FocalPlaneResolutionUnit",
+      0xA214 => "SubjectLocation",
+      0xA215 => "ExposureIndex",
+      0xA217 => "SensingMethod",
+      0xA300 => "FileSource",
+      0xA301 => "SceneType",
+      0xA302 => "CFAPattern",
+      0xA401 => "CustomRendered",
+      0xA402 => "ExposureMode",
+      0xA403 => "WhiteBalance",
+      0xA404 => "DigitalZoomRatio",
+      0xA405 => "FocalLengthIn35mmFilm",
+      0xA406 => "SceneCaptureType",
+      0xA407 => "GainControl",
+      0xA408 => "Contrast",
+      0xA409 => "Saturation",
+      0xA40A => "Sharpness",
+      0xA40C => "SubjectDistanceRange",
+      0xA420 => "ImageUniqueID",
+      0xA430 => "CameraOwnerName",
+      0xA431 => "BodySerialNumber",
+      0xA432 => "LensSpecification",
+      0xA433 => "LensMake",
+      0xA434 => "LensModel",
+      0xA435 => "LensSerialNumber",
+      0xA460 => "CompositeImage",
+      0xA500 => "Gamma",
+      _ => return None,
+    },
+  };
+  Some(name)
+}
+
+/// Signed decimal position, altitude and UTC fix time from decoded GPS tags
+/// Returns None when the payload has no GPS position, altitude or time
+pub fn gps_info(entries: &[ExifEntry]) -> Option<GpsInfo> {
+  let gps = |name: &str| entries.iter().find(|e| e.ifd == "gps" && e.name == name);
+  let text = |name: &str| gps(name).and_then(|e| e.text.as_deref());
+  let numbers = |name: &str| gps(name).and_then(|e| e.numbers.as_deref());
+
+  // Degrees, minutes and seconds, negated for the south/west references
+  let coordinate = |value: &str, reference: &str, negative: &str| {
+    let dms = numbers(value)?;
+    let degrees = dms.first()? + dms.get(1).unwrap_or(&0.0) / 60.0 + dms.get(2).unwrap_or(&0.0) / 3600.0;
+    let negate = text(reference).is_some_and(|r| r.trim().eq_ignore_ascii_case(negative));
+    degrees.is_finite().then_some(if negate { -degrees } else { degrees })
+  };
+
+  let latitude = coordinate("GPSLatitude", "GPSLatitudeRef", "S");
+  let longitude = coordinate("GPSLongitude", "GPSLongitudeRef", "W");
+  let altitude = numbers("GPSAltitude").and_then(|v| v.first().copied()).filter(|v| v.is_finite()).map(|v| {
+    // AltitudeRef 1 = below sea level
+    let below = numbers("GPSAltitudeRef").and_then(|r| r.first().copied()) == Some(1.0);
+    if below {
+      -v
+    } else {
+      v
+    }
+  });
+
+  let timestamp = text("GPSDateStamp").zip(numbers("GPSTimeStamp")).and_then(|(date, time)| {
+    let date: Vec<&str> = date.trim().split(':').collect();
+    let (&[year, month, day], &[hour, minute, second]) = (&date[..], time) else {
+      return None;
+    };
+    let whole = second.trunc();
+    let millis = ((second - whole) * 1000.0).round() as u32;
+    let mut iso = format!(
+      "{}-{}-{}T{:02}:{:02}:{:02}",
+      year, month, day, hour as u32, minute as u32, whole as u32
+    );
+    if millis > 0 {
+      iso.push_str(&format!(".{:03}", millis.min(999)));
+    }
+    iso.push('Z');
+    Some(iso)
+  });
+
+  if latitude.is_none() && longitude.is_none() && altitude.is_none() && timestamp.is_none() {
+    return None;
+  }
+  Some(GpsInfo {
+    latitude,
+    longitude,
+    altitude,
+    timestamp,
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Little-endian TIFF: IFD0 with Make, Orientation and an Exif pointer;
+  /// Exif IFD with ExposureTime (rational) and ExifVersion
+  fn sample_tiff() -> Vec<u8> {
+    let mut tiff = b"II*\0".to_vec();
+    tiff.extend_from_slice(&8u32.to_le_bytes());
+    // IFD0 at 8: 3 entries
+    tiff.extend_from_slice(&3u16.to_le_bytes());
+    let entry = |tiff: &mut Vec<u8>, tag: u16, field_type: u16, count: u32, value: [u8; 4]| {
+      tiff.extend_from_slice(&tag.to_le_bytes());
+      tiff.extend_from_slice(&field_type.to_le_bytes());
+      tiff.extend_from_slice(&count.to_le_bytes());
+      tiff.extend_from_slice(&value);
+    };
+    entry(&mut tiff, 0x010F, 2, 4, *b"ACME");
+    entry(&mut tiff, 0x0112, 3, 1, [6, 0, 0, 0]);
+    // Exif IFD follows IFD0 (8 + 2 + 36 + 4 = 50)
+    entry(&mut tiff, 0x8769, 4, 1, 50u32.to_le_bytes());
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    // Exif IFD at 50: 2 entries, rational stored at 50 + 2 + 24 + 4 = 80
+    tiff.extend_from_slice(&2u16.to_le_bytes());
+    entry(&mut tiff, 0x829A, 5, 1, 80u32.to_le_bytes());
+    entry(&mut tiff, 0x9000, 7, 4, *b"0232");
+    tiff.extend_from_slice(&0u32.to_le_bytes());
+    tiff.extend_from_slice(&1u32.to_le_bytes());
+    tiff.extend_from_slice(&250u32.to_le_bytes());
+    tiff
+  }
+
+  #[test]
+  fn test_parse_exif() {
+    let entries = parse_exif(&sample_tiff());
+    let find = |name: &str| entries.iter().find(|e| e.name == name).unwrap();
+
+    assert_eq!(entries.len(), 4);
+    assert_eq!(find("Make").text.as_deref(), Some("ACME"));
+    assert_eq!(find("Orientation").numbers, Some(vec![6.0]));
+    assert_eq!(find("ExposureTime").ifd, "exif");
+    assert_eq!(find("ExposureTime").numbers, Some(vec![0.004]));
+    assert_eq!(find("ExifVersion").text.as_deref(), Some("0232"));
+  }
+
+  #[test]
+  fn test_find_exif_in_containers() {
+    let tiff = sample_tiff();
+
+    let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+    jpeg.extend_from_slice(&((tiff.len() + 8) as u16).to_be_bytes());
+    jpeg.extend_from_slice(b"Exif\0\0");
+    jpeg.extend_from_slice(&tiff);
+    jpeg.extend_from_slice(&[0xFF, 0xDA]);
+    assert_eq!(find_exif(&jpeg), Some(tiff.as_slice()));
+
+    let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+    png.extend_from_slice(&(tiff.len() as u32).to_be_bytes());
+    png.extend_from_slice(b"eXIf");
+    png.extend_from_slice(&tiff);
+    png.extend_from_slice(&[0; 4]);
+    assert_eq!(find_exif(&png), Some(tiff.as_slice()));
+
+    assert_eq!(find_exif(&tiff), Some(tiff.as_slice()));
+    assert!(find_exif(b"GIF89a").is_none());
+  }
+}
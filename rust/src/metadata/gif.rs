@@ -1,13 +1,126 @@
 //! GIF metadata parsing
+//!
+//! Walks the full block structure (extensions, image descriptors, sub-blocks)
+//! without decoding any image data, so frame counts and delays are exact even
+//! for long animations.
 
 use crate::ImageMetadata;
 
+/// Disposal method name of a Graphic Control Extension packed field
+fn disposal_name(packed: u8) -> &'static str {
+  match (packed >> 2) & 0x07 {
+    2 => "background",
+    3 => "previous",
+    // 0 (unspecified), 1 (keep) and reserved values all leave the frame in place
+    _ => "none",
+  }
+}
+
+/// Offset after a run of data sub-blocks, None if truncated
+fn skip_sub_blocks(data: &[u8], mut pos: usize) -> Option<usize> {
+  loop {
+    let len = *data.get(pos)? as usize;
+    pos += 1;
+    if len == 0 {
+      return Some(pos);
+    }
+    pos += len;
+  }
+}
+
+/// Frame timing and looping of a GIF
+#[derive(Default)]
+struct GifFrames {
+  /// Delay per frame in ms
+  delays: Vec<u32>,
+  /// Disposal method per frame
+  disposal: Vec<&'static str>,
+  /// NETSCAPE2.0 loop count (0 = infinite), None plays once
+  loop_count: Option<u32>,
+  /// Whether any frame has a Graphic Control Extension
+  has_control: bool,
+}
+
+/// Walk the blocks after the global color table; a truncated file keeps the complete frames
+fn scan_frames(data: &[u8], mut pos: usize) -> GifFrames {
+  let mut frames = GifFrames::default();
+  // Graphic Control Extension for the next image: (delay in ms, disposal)
+  let mut control: Option<(u32, &'static str)> = None;
+
+  while let Some(&block) = data.get(pos) {
+    match block {
+      // Extension: label, then sub-blocks
+      0x21 => {
+        let Some(&label) = data.get(pos + 1) else {
+          break;
+        };
+        match (label, data.get(pos + 2..pos + 14)) {
+          // Block size 4: packed, delay (1/100 s), transparent index
+          (0xF9, _) if data.get(pos + 2) == Some(&4) => {
+            if let Some(gce) = data.get(pos + 3..pos + 6) {
+              let delay = u16::from_le_bytes([gce[1], gce[2]]) as u32 * 10;
+              control = Some((delay, disposal_name(gce[0])));
+              frames.has_control = true;
+            }
+          }
+          // Application extension: size 11, identifier, then a [1, loop count] sub-block
+          (0xFF, Some(app)) if app[0] == 11 && matches!(&app[1..], b"NETSCAPE2.0" | b"ANIMEXTS1.0") => {
+            if let Some(sub) = data.get(pos + 14..pos + 18) {
+              if sub[0] >= 3 && sub[1] == 1 {
+                frames.loop_count = Some(u16::from_le_bytes([sub[2], sub[3]]) as u32);
+              }
+            }
+          }
+          _ => {}
+        }
+        let Some(next) = skip_sub_blocks(data, pos + 2) else {
+          break;
+        };
+        pos = next;
+      }
+      // Image descriptor: position and size (8 bytes), packed field
+      0x2C => {
+        let Some(&packed) = data.get(pos + 9) else {
+          break;
+        };
+        pos += 10;
+        if packed & 0x80 != 0 {
+          pos += 3 << ((packed & 0x07) + 1);
+        }
+        // LZW minimum code size, then the image data sub-blocks
+        let Some(next) = skip_sub_blocks(data, pos + 1) else {
+          break;
+        };
+        pos = next;
+
+        let (delay, disposal) = control.take().unwrap_or((0, "none"));
+        frames.delays.push(delay);
+        frames.disposal.push(disposal);
+      }
+      // Trailer or garbage
+      _ => break,
+    }
+  }
+
+  frames
+}
+
+/// Offset of the first block, after the header and global color table
+fn blocks_start(data: &[u8]) -> usize {
+  match data.get(10) {
+    Some(&flags) if flags & 0x80 != 0 => 13 + (3 << ((flags & 0x07) + 1)),
+    _ => 13,
+  }
+}
+
+/// NETSCAPE2.0 loop count (0 = infinite), None if the GIF has no looping extension
+pub fn parse_gif_loop_count(data: &[u8]) -> Option<u32> {
+  scan_frames(data, blocks_start(data)).loop_count
+}
+
 /// Parse GIF header for detailed metadata
 pub fn parse_gif_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
   let mut background: Option<Vec<u8>> = None;
-  let mut pages: u32 = 0;
-  let loop_count: Option<u32> = None;
-  let mut delays: Vec<u32> = Vec::new();
 
   if data.len() > 13 {
     let flags = data[10];
@@ -27,40 +140,12 @@ pub fn parse_gif_metadata(data: &[u8], width: u32, height: u32, size: u32) -> Im
     }
   }
 
-  // Simplified frame counting - just count image descriptors
-  let mut pos = 13;
-  if data.len() > 13 {
-    let flags = data[10];
-    if (flags & 0x80) != 0 {
-      pos += 3 * (1 << ((flags & 0x07) + 1));
-    }
-  }
-
-  // Only scan first 64KB for frame info
-  let scan_limit = data.len().min(65536);
-  while pos < scan_limit {
-    match data.get(pos) {
-      Some(0x2C) => {
-        pages += 1;
-        pos += 10;
-      }
-      Some(0x21) => {
-        if let Some(&ext_type) = data.get(pos + 1) {
-          if ext_type == 0xF9 && pos + 5 < data.len() {
-            let delay = u16::from_le_bytes([data[pos + 4], data[pos + 5]]) as u32 * 10;
-            delays.push(delay);
-          }
-        }
-        pos += 2;
-        while pos < data.len() && data[pos] != 0 {
-          pos += data[pos] as usize + 1;
-        }
-        pos += 1;
-      }
-      Some(0x3B) => break,
-      _ => pos += 1,
-    }
-  }
+  let frames = scan_frames(data, blocks_start(data));
+  let pages = frames.delays.len() as u32;
+  // Timing is reported for animations and for stills that carry a control extension
+  let timed = pages > 1 || frames.has_control;
+  let duration = frames.delays.iter().fold(0u32, |total, &d| total.saturating_add(d));
+  let disposal = frames.disposal.iter().map(|d| d.to_string()).collect();
 
   ImageMetadata {
     width,
@@ -77,10 +162,80 @@ pub fn parse_gif_metadata(data: &[u8], width: u32, height: u32, size: u32) -> Im
     has_profile: false,
     orientation: None,
     pages: Some(pages.max(1)),
-    loop_count,
-    delay: if delays.is_empty() { None } else { Some(delays) },
+    loop_count: frames.loop_count,
+    delay: timed.then_some(frames.delays),
+    disposal: timed.then_some(disposal),
+    duration: timed.then_some(duration),
     background,
     compression: Some("lzw".to_string()),
     density: None,
+    ..Default::default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn animated_gif(frames: usize, repeat: Option<gif::Repeat>) -> Vec<u8> {
+    let mut output = Vec::new();
+    {
+      let mut encoder = gif::Encoder::new(&mut output, 4, 4, &[0, 0, 0, 255, 255, 255]).unwrap();
+      if let Some(repeat) = repeat {
+        encoder.set_repeat(repeat).unwrap();
+      }
+      for i in 0..frames {
+        let mut frame = gif::Frame::from_indexed_pixels(4, 4, vec![(i % 2) as u8; 16], None);
+        frame.delay = 5 + i as u16;
+        frame.dispose = if i % 2 == 0 {
+          gif::DisposalMethod::Background
+        } else {
+          gif::DisposalMethod::Previous
+        };
+        encoder.write_frame(&frame).unwrap();
+      }
+    }
+    output
+  }
+
+  #[test]
+  fn test_parse_gif_frames() {
+    // Enough frames to run well past the old 64KB scan window
+    let data = animated_gif(5000, Some(gif::Repeat::Finite(3)));
+    assert!(data.len() > 65536);
+    let meta = parse_gif_metadata(&data, 4, 4, data.len() as u32);
+
+    assert_eq!(meta.pages, Some(5000));
+    assert_eq!(meta.loop_count, Some(3));
+    let delay = meta.delay.unwrap();
+    assert_eq!(&delay[..3], &[50, 60, 70]);
+    assert_eq!(meta.duration, Some(delay.iter().sum()));
+    assert_eq!(&meta.disposal.unwrap()[..2], &["background", "previous"]);
+  }
+
+  #[test]
+  fn test_parse_gif_infinite_and_truncated() {
+    let data = animated_gif(4, Some(gif::Repeat::Infinite));
+    let meta = parse_gif_metadata(&data, 4, 4, data.len() as u32);
+    assert_eq!((meta.pages, meta.loop_count), (Some(4), Some(0)));
+
+    // Cut inside the last frame: only complete frames count
+    let truncated = &data[..data.len() - 4];
+    assert_eq!(parse_gif_metadata(truncated, 4, 4, 0).pages, Some(3));
+  }
+
+  #[test]
+  fn test_loop_count_only_from_application_extension() {
+    let mut data = animated_gif(2, None);
+    assert_eq!(parse_gif_loop_count(&data), None);
+
+    // The signature inside a comment extension is not a looping extension
+    let mut comment = vec![0x21, 0xFE, 15];
+    comment.extend_from_slice(b"NETSCAPE2.0\x03\x01\x05\x00");
+    comment.push(0);
+    let start = blocks_start(&data);
+    data.splice(start..start, comment);
+    assert_eq!(parse_gif_loop_count(&data), None);
+    assert_eq!(parse_gif_metadata(&data, 4, 4, 0).pages, Some(2));
   }
 }
@@ -53,6 +53,7 @@ pub fn get_heic_metadata(data: &[u8], size: u32) -> Result<ImageMetadata, ImageE
     background: None,
     compression: Some("hevc".to_string()),
     density: None,
+    ..Default::default()
   })
 }
 
@@ -0,0 +1,103 @@
+//! ICC profile reading
+//!
+//! Extracts the embedded profile from JPEG (reassembled APP2 `ICC_PROFILE`
+//! segments), PNG (`iCCP`) and WebP (`ICCP`) and reads its description tag.
+
+use img_parts::{Bytes, DynImage, ImageICC};
+
+/// Size of the fixed ICC profile header; the tag table follows it
+const HEADER_LEN: usize = 128;
+
+/// Embedded ICC profile of a JPEG, PNG or WebP file
+pub fn find_icc_profile(data: &[u8]) -> Option<Vec<u8>> {
+  let image = DynImage::from_bytes(Bytes::copy_from_slice(data)).ok()??;
+  image
+    .icc_profile()
+    .filter(|profile| !profile.is_empty())
+    .map(|profile| profile.to_vec())
+}
+
+#[inline]
+fn read_u32(data: &[u8], pos: usize) -> Option<u32> {
+  let bytes = data.get(pos..pos + 4)?;
+  Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Profile description ('desc' tag) - v2 textDescriptionType or v4 multiLocalizedUnicodeType
+pub fn icc_description(profile: &[u8]) -> Option<String> {
+  let count = read_u32(profile, HEADER_LEN)? as usize;
+  let tag = (0..count.min(1024)).find_map(|i| {
+    let entry = HEADER_LEN + 4 + i * 12;
+    (profile.get(entry..entry + 4)? == b"desc").then_some(entry)
+  })?;
+  let offset = read_u32(profile, tag + 4)? as usize;
+  let size = read_u32(profile, tag + 8)? as usize;
+  let element = profile.get(offset..offset.checked_add(size)?)?;
+
+  let text = match element.get(0..4)? {
+    b"desc" => {
+      // Signature, reserved, ASCII count (including the NUL), ASCII text
+      let len = read_u32(element, 8)? as usize;
+      let ascii = element.get(12..12 + len)?;
+      ascii.iter().take_while(|&&b| b != 0).map(|&b| b as char).collect()
+    }
+    b"mluc" => {
+      // Signature, reserved, record count, record size, then records of
+      // language, country, byte length and offset of UTF-16BE text
+      let records = read_u32(element, 8)? as usize;
+      let record_size = read_u32(element, 12)? as usize;
+      let record = (0..records.min(256))
+        .map(|i| 16 + i * record_size)
+        .find(|&pos| element.get(pos..pos + 2) == Some(&b"en"[..]))
+        .unwrap_or(16);
+      let len = read_u32(element, record + 4)? as usize;
+      let start = read_u32(element, record + 8)? as usize;
+      let utf16: Vec<u16> = element
+        .get(start..start.checked_add(len)?)?
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+      String::from_utf16_lossy(&utf16)
+    }
+    _ => return None,
+  };
+
+  let text = text.trim_end_matches('\0').trim().to_string();
+  (!text.is_empty()).then_some(text)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  /// Minimal profile with a single 'desc' tag holding `element`
+  fn profile_with_desc(element: &[u8]) -> Vec<u8> {
+    let mut profile = vec![0u8; HEADER_LEN];
+    profile.extend_from_slice(&1u32.to_be_bytes());
+    profile.extend_from_slice(b"desc");
+    profile.extend_from_slice(&((HEADER_LEN + 16) as u32).to_be_bytes());
+    profile.extend_from_slice(&(element.len() as u32).to_be_bytes());
+    profile.extend_from_slice(element);
+    profile
+  }
+
+  #[test]
+  fn test_icc_description() {
+    let mut v2 = b"desc\0\0\0\0".to_vec();
+    v2.extend_from_slice(&5u32.to_be_bytes());
+    v2.extend_from_slice(b"sRGB\0");
+    assert_eq!(icc_description(&profile_with_desc(&v2)).as_deref(), Some("sRGB"));
+
+    let text: Vec<u8> = "Display P3".encode_utf16().flat_map(|c| c.to_be_bytes()).collect();
+    let mut v4 = b"mluc\0\0\0\0".to_vec();
+    v4.extend_from_slice(&1u32.to_be_bytes());
+    v4.extend_from_slice(&12u32.to_be_bytes());
+    v4.extend_from_slice(b"enUS");
+    v4.extend_from_slice(&(text.len() as u32).to_be_bytes());
+    v4.extend_from_slice(&28u32.to_be_bytes());
+    v4.extend_from_slice(&text);
+    assert_eq!(icc_description(&profile_with_desc(&v4)).as_deref(), Some("Display P3"));
+
+    assert!(icc_description(&[0u8; 64]).is_none());
+  }
+}
@@ -0,0 +1,244 @@
+//! IPTC-IIM reading
+//!
+//! Reads the IPTC-NAA record (Photoshop image resource 0x0404) from the JPEG
+//! APP13 "Photoshop 3.0" segments and decodes the common record 2 datasets.
+
+use crate::IptcData;
+
+use super::utils::jpeg_segments;
+
+/// Signature opening the JPEG APP13 Photoshop segment
+pub const PHOTOSHOP_SIGNATURE: &[u8] = b"Photoshop 3.0\0";
+/// Image resource holding the IPTC-NAA record
+pub const IPTC_RESOURCE_ID: u16 = 0x0404;
+/// Image resource holding the MD5 digest of the IPTC-NAA record
+pub const IPTC_DIGEST_RESOURCE_ID: u16 = 0x0425;
+
+/// Record 2 (application record) dataset numbers
+pub mod datasets {
+  pub const RECORD_VERSION: u8 = 0;
+  pub const OBJECT_NAME: u8 = 5;
+  pub const KEYWORDS: u8 = 25;
+  pub const BYLINE: u8 = 80;
+  pub const BYLINE_TITLE: u8 = 85;
+  pub const CITY: u8 = 90;
+  pub const STATE: u8 = 95;
+  pub const COUNTRY_CODE: u8 = 100;
+  pub const COUNTRY: u8 = 101;
+  pub const HEADLINE: u8 = 105;
+  pub const CREDIT: u8 = 110;
+  pub const SOURCE: u8 = 115;
+  pub const COPYRIGHT: u8 = 116;
+  pub const CAPTION: u8 = 120;
+}
+
+/// Record 1 CodedCharacterSet dataset
+const CODED_CHARACTER_SET: u8 = 90;
+/// ISO 2022 escape sequence declaring UTF-8
+pub const UTF8_ESCAPE: &[u8] = b"\x1b%G";
+
+/// One Photoshop image resource
+pub struct PhotoshopResource<'a> {
+  pub id: u16,
+  /// The whole resource including its 8BIM header and padding
+  pub raw: &'a [u8],
+  pub data: &'a [u8],
+}
+
+/// Split a Photoshop image resource block into its 8BIM resources
+pub fn parse_resources(block: &[u8]) -> Vec<PhotoshopResource<'_>> {
+  let mut resources = Vec::new();
+  let mut pos = 0;
+  while pos + 12 <= block.len() && &block[pos..pos + 4] == b"8BIM" {
+    let start = pos;
+    let id = u16::from_be_bytes([block[pos + 4], block[pos + 5]]);
+    // Pascal string name, padded so length byte + name is even
+    let name_len = block[pos + 6] as usize;
+    pos += 6 + ((name_len + 2) & !1);
+    let Some(size) = block.get(pos..pos + 4) else {
+      break;
+    };
+    let size = u32::from_be_bytes([size[0], size[1], size[2], size[3]]) as usize;
+    pos += 4;
+    let Some(data) = block.get(pos..pos + size) else {
+      break;
+    };
+    pos = (pos + size + (size & 1)).min(block.len());
+    resources.push(PhotoshopResource {
+      id,
+      raw: &block[start..pos],
+      data,
+    });
+  }
+  resources
+}
+
+/// Resource block of the JPEG APP13 Photoshop segments (split segments are joined)
+pub fn find_photoshop_resources(data: &[u8]) -> Option<Vec<u8>> {
+  if !data.starts_with(&[0xFF, 0xD8]) {
+    return None;
+  }
+  let mut block: Option<Vec<u8>> = None;
+  for (marker, segment) in jpeg_segments(data) {
+    if marker != 0xED {
+      continue;
+    }
+    if let Some(payload) = segment.strip_prefix(PHOTOSHOP_SIGNATURE) {
+      block.get_or_insert_with(Vec::new).extend_from_slice(payload);
+    }
+  }
+  block
+}
+
+/// IPTC-NAA record of a JPEG file
+pub fn find_iptc(data: &[u8]) -> Option<Vec<u8>> {
+  let block = find_photoshop_resources(data)?;
+  let resources = parse_resources(&block);
+  resources
+    .iter()
+    .find(|resource| resource.id == IPTC_RESOURCE_ID)
+    .map(|resource| resource.data.to_vec())
+}
+
+/// Decode a dataset value - UTF-8 when declared or valid, Latin-1 otherwise
+fn decode_value(value: &[u8], utf8: bool) -> String {
+  let text = match std::str::from_utf8(value) {
+    Ok(text) => text.to_string(),
+    Err(_) if utf8 => String::from_utf8_lossy(value).into_owned(),
+    Err(_) => value.iter().map(|&b| b as char).collect(),
+  };
+  text.trim_end_matches('\0').trim().to_string()
+}
+
+/// Decode the common record 2 datasets of an IPTC-NAA record
+/// Returns None when none of them are present
+pub fn parse_iptc(record: &[u8]) -> Option<IptcData> {
+  let mut utf8 = false;
+  let mut values: Vec<(u8, String)> = Vec::new();
+
+  let mut pos = 0;
+  while pos + 5 <= record.len() && record[pos] == 0x1C {
+    let (record_number, dataset) = (record[pos + 1], record[pos + 2]);
+    let size = u16::from_be_bytes([record[pos + 3], record[pos + 4]]) as usize;
+    pos += 5;
+    // Extended datasets (size high bit set) only carry binary data - stop there
+    if size & 0x8000 != 0 {
+      break;
+    }
+    let Some(value) = record.get(pos..pos + size) else {
+      break;
+    };
+    pos += size;
+
+    match (record_number, dataset) {
+      (1, CODED_CHARACTER_SET) => utf8 = value == UTF8_ESCAPE,
+      (2, datasets::RECORD_VERSION) => {}
+      (2, dataset) => values.push((dataset, decode_value(value, utf8))),
+      _ => {}
+    }
+  }
+
+  let all = |dataset: u8| -> Option<Vec<String>> {
+    let found: Vec<String> = values
+      .iter()
+      .filter(|(d, _)| *d == dataset)
+      .map(|(_, v)| v.clone())
+      .filter(|v| !v.is_empty())
+      .collect();
+    (!found.is_empty()).then_some(found)
+  };
+  let one = |dataset: u8| all(dataset).and_then(|v| v.into_iter().next());
+
+  let iptc = IptcData {
+    title: one(datasets::OBJECT_NAME),
+    keywords: all(datasets::KEYWORDS),
+    byline: all(datasets::BYLINE),
+    byline_title: one(datasets::BYLINE_TITLE),
+    city: one(datasets::CITY),
+    state: one(datasets::STATE),
+    country_code: one(datasets::COUNTRY_CODE),
+    country: one(datasets::COUNTRY),
+    headline: one(datasets::HEADLINE),
+    credit: one(datasets::CREDIT),
+    source: one(datasets::SOURCE),
+    copyright: one(datasets::COPYRIGHT),
+    caption: one(datasets::CAPTION),
+  };
+  has_fields(&iptc).then_some(iptc)
+}
+
+/// Whether any dataset of the record is set
+pub fn has_fields(iptc: &IptcData) -> bool {
+  let texts = [
+    &iptc.title,
+    &iptc.byline_title,
+    &iptc.city,
+    &iptc.state,
+    &iptc.country_code,
+    &iptc.country,
+    &iptc.headline,
+    &iptc.credit,
+    &iptc.source,
+    &iptc.copyright,
+    &iptc.caption,
+  ];
+  texts.iter().any(|v| v.is_some()) || iptc.keywords.is_some() || iptc.byline.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn dataset(record: u8, dataset: u8, value: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x1C, record, dataset];
+    out.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    out.extend_from_slice(value);
+    out
+  }
+
+  #[test]
+  fn test_parse_iptc() {
+    let record = [
+      dataset(1, CODED_CHARACTER_SET, UTF8_ESCAPE),
+      dataset(2, datasets::RECORD_VERSION, &[0, 4]),
+      dataset(2, datasets::KEYWORDS, b"harbour"),
+      dataset(2, datasets::KEYWORDS, b"sunset"),
+      dataset(2, datasets::CAPTION, "Quai à Marseille".as_bytes()),
+      dataset(2, datasets::CITY, b"Marseille"),
+    ]
+    .concat();
+
+    let iptc = parse_iptc(&record).unwrap();
+    assert_eq!(iptc.keywords, Some(vec!["harbour".to_string(), "sunset".to_string()]));
+    assert_eq!(iptc.caption.as_deref(), Some("Quai à Marseille"));
+    assert_eq!(iptc.city.as_deref(), Some("Marseille"));
+    assert!(iptc.byline.is_none());
+
+    // Latin-1 without a charset declaration
+    let latin1 = dataset(2, datasets::CITY, b"M\xfcnchen");
+    assert_eq!(parse_iptc(&latin1).unwrap().city.as_deref(), Some("München"));
+    assert!(parse_iptc(&dataset(2, datasets::RECORD_VERSION, &[0, 4])).is_none());
+  }
+
+  #[test]
+  fn test_parse_resources() {
+    let mut block = b"8BIM".to_vec();
+    block.extend_from_slice(&0x0425u16.to_be_bytes());
+    block.extend_from_slice(&[0, 0]);
+    block.extend_from_slice(&3u32.to_be_bytes());
+    block.extend_from_slice(&[1, 2, 3, 0]);
+    block.extend_from_slice(b"8BIM");
+    block.extend_from_slice(&IPTC_RESOURCE_ID.to_be_bytes());
+    // Name "ab" - length byte + 2 chars, padded to 4
+    block.extend_from_slice(&[2, b'a', b'b', 0]);
+    block.extend_from_slice(&2u32.to_be_bytes());
+    block.extend_from_slice(&[9, 9]);
+
+    let resources = parse_resources(&block);
+    assert_eq!(resources.len(), 2);
+    assert_eq!(resources[0].data, &[1, 2, 3]);
+    assert_eq!(resources[0].raw.len(), 16);
+    assert_eq!(resources[1].id, IPTC_RESOURCE_ID);
+    assert_eq!(resources[1].data, &[9, 9]);
+  }
+}
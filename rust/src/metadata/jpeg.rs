@@ -109,7 +109,11 @@ pub fn get_jpeg_metadata_fast(data: &[u8], size: u32) -> Result<ImageMetadata, I
     ));
   }
 
-  let space = if channels == 1 { "grayscale" } else { "srgb" };
+  let space = match channels {
+    1 => "grayscale",
+    4 => "cmyk",
+    _ => "srgb",
+  };
 
   Ok(ImageMetadata {
     width,
@@ -138,5 +142,6 @@ pub fn get_jpeg_metadata_fast(data: &[u8], size: u32) -> Result<ImageMetadata, I
       .to_string(),
     ),
     density,
+    ..Default::default()
   })
 }
@@ -0,0 +1,357 @@
+//! JPEG XL header parsing (bare codestream and ISOBMFF container)
+//!
+//! Reads the SizeHeader and ImageMetadata bundles of the codestream -
+//! no entropy decoding is needed for dimensions, bit depth, alpha,
+//! animation and ICC presence.
+
+use crate::error::ImageError;
+use crate::ImageMetadata;
+
+/// Bare codestream signature
+const CODESTREAM_SIGNATURE: [u8; 2] = [0xFF, 0x0A];
+
+/// ISOBMFF container signature box
+const CONTAINER_SIGNATURE: [u8; 12] = [0, 0, 0, 0x0C, b'J', b'X', b'L', b' ', 0x0D, 0x0A, 0x87, 0x0A];
+
+/// Check for a JPEG XL codestream or container signature
+#[inline]
+pub fn is_jxl(data: &[u8]) -> bool {
+  data.starts_with(&CODESTREAM_SIGNATURE) || data.starts_with(&CONTAINER_SIGNATURE)
+}
+
+/// Header fields of a JPEG XL image
+#[derive(Debug, PartialEq)]
+pub struct JxlHeader {
+  pub width: u32,
+  pub height: u32,
+  pub bits_per_sample: u32,
+  pub float_samples: bool,
+  pub has_alpha: bool,
+  pub animated: bool,
+  /// Loop count of an animation (0 = infinite)
+  pub loop_count: Option<u32>,
+  pub has_icc: bool,
+  pub grayscale: bool,
+  /// Orientation (1-8) from the codestream header
+  pub orientation: u32,
+  pub xyb_encoded: bool,
+}
+
+/// LSB-first bit reader over the codestream
+struct BitReader<'a> {
+  data: &'a [u8],
+  pos: usize,
+}
+
+/// One of the four U32() distributions selected by a 2-bit prefix
+#[derive(Clone, Copy)]
+enum U32Dist {
+  Val(u32),
+  Bits(u32),
+  BitsOffset(u32, u32),
+}
+
+use U32Dist::{Bits, BitsOffset, Val};
+
+impl<'a> BitReader<'a> {
+  fn new(data: &'a [u8]) -> Self {
+    BitReader { data, pos: 0 }
+  }
+
+  fn bits(&mut self, count: u32) -> Option<u32> {
+    let mut value = 0u64;
+    for i in 0..count {
+      let byte = *self.data.get(self.pos / 8)?;
+      let bit = (byte >> (self.pos % 8)) & 1;
+      value |= (bit as u64) << i;
+      self.pos += 1;
+    }
+    Some(value as u32)
+  }
+
+  fn bool(&mut self) -> Option<bool> {
+    self.bits(1).map(|b| b == 1)
+  }
+
+  fn u32(&mut self, dists: [U32Dist; 4]) -> Option<u32> {
+    match dists[self.bits(2)? as usize] {
+      Val(v) => Some(v),
+      Bits(n) => self.bits(n),
+      BitsOffset(n, offset) => self.bits(n).map(|v| v.wrapping_add(offset)),
+    }
+  }
+
+  fn skip(&mut self, count: u64) -> Option<()> {
+    let pos = self.pos.checked_add(usize::try_from(count).ok()?)?;
+    if pos > self.data.len() * 8 {
+      return None;
+    }
+    self.pos = pos;
+    Some(())
+  }
+}
+
+/// Width from the aspect ratio code of a SizeHeader/PreviewHeader
+fn ratio_width(ratio: u32, height: u32) -> u32 {
+  let (num, den) = match ratio {
+    1 => (1, 1),
+    2 => (12, 10),
+    3 => (4, 3),
+    4 => (3, 2),
+    5 => (16, 9),
+    6 => (5, 4),
+    _ => (2, 1),
+  };
+  (height as u64 * num / den) as u32
+}
+
+/// SizeHeader -> (width, height)
+fn read_size_header(r: &mut BitReader) -> Option<(u32, u32)> {
+  let dist = [BitsOffset(9, 1), BitsOffset(13, 1), BitsOffset(18, 1), BitsOffset(30, 1)];
+  let small = r.bool()?;
+  let height = if small { (r.bits(5)? + 1) * 8 } else { r.u32(dist)? };
+  let ratio = r.bits(3)?;
+  let width = match ratio {
+    0 if small => (r.bits(5)? + 1) * 8,
+    0 => r.u32(dist)?,
+    _ => ratio_width(ratio, height),
+  };
+  Some((width, height))
+}
+
+/// Skip a PreviewHeader
+fn skip_preview_header(r: &mut BitReader) -> Option<()> {
+  let div8 = [Val(16), Val(32), BitsOffset(5, 1), BitsOffset(9, 33)];
+  let exact = [BitsOffset(6, 1), BitsOffset(8, 65), BitsOffset(10, 321), BitsOffset(12, 1345)];
+  let dist = if r.bool()? { div8 } else { exact };
+  r.u32(dist)?;
+  if r.bits(3)? == 0 {
+    r.u32(dist)?;
+  }
+  Some(())
+}
+
+/// BitDepth -> (bits per sample, float samples)
+fn read_bit_depth(r: &mut BitReader) -> Option<(u32, bool)> {
+  if r.bool()? {
+    let bits = r.u32([Val(32), Val(16), Val(24), BitsOffset(6, 1)])?;
+    r.bits(4)?; // exponent bits
+    Some((bits, true))
+  } else {
+    Some((r.u32([Val(8), Val(10), Val(12), BitsOffset(6, 1)])?, false))
+  }
+}
+
+/// ExtraChannelInfo -> whether the channel is alpha
+fn read_extra_channel(r: &mut BitReader) -> Option<bool> {
+  if r.bool()? {
+    // all_default: 8-bit alpha
+    return Some(true);
+  }
+  let channel_type = r.u32([Val(0), Val(1), BitsOffset(4, 2), BitsOffset(6, 18)])?;
+  read_bit_depth(r)?;
+  r.u32([Val(0), Val(3), Val(4), BitsOffset(3, 1)])?; // dim_shift
+  let name_len = r.u32([Val(0), Bits(4), BitsOffset(5, 16), BitsOffset(10, 48)])?;
+  r.skip(name_len as u64 * 8)?;
+  match channel_type {
+    0 => {
+      r.bool()?; // alpha_associated
+    }
+    2 => {
+      r.skip(4 * 16)?; // spot color (4 x F16)
+    }
+    5 => {
+      r.u32([Val(1), Bits(2), BitsOffset(4, 3), BitsOffset(8, 19)])?; // CFA channel
+    }
+    _ => {}
+  }
+  Some(channel_type == 0)
+}
+
+/// Parse the SizeHeader and ImageMetadata of a bare codestream
+pub fn parse_jxl_codestream(codestream: &[u8]) -> Option<JxlHeader> {
+  if !codestream.starts_with(&CODESTREAM_SIGNATURE) {
+    return None;
+  }
+  let mut r = BitReader::new(&codestream[2..]);
+  let (width, height) = read_size_header(&mut r)?;
+
+  let mut header = JxlHeader {
+    width,
+    height,
+    bits_per_sample: 8,
+    float_samples: false,
+    has_alpha: false,
+    animated: false,
+    loop_count: None,
+    has_icc: false,
+    grayscale: false,
+    orientation: 1,
+    xyb_encoded: true,
+  };
+
+  // ImageMetadata
+  if r.bool()? {
+    // all_default: 8-bit sRGB, no alpha, no animation
+    return Some(header);
+  }
+
+  if r.bool()? {
+    // extra_fields
+    header.orientation = r.bits(3)? + 1;
+    if r.bool()? {
+      read_size_header(&mut r)?; // intrinsic size
+    }
+    if r.bool()? {
+      skip_preview_header(&mut r)?;
+    }
+    if r.bool()? {
+      // AnimationHeader
+      header.animated = true;
+      r.u32([Val(100), Val(1000), BitsOffset(10, 1), BitsOffset(30, 1)])?; // tps numerator
+      r.u32([Val(1), Val(1001), BitsOffset(8, 1), BitsOffset(10, 1)])?; // tps denominator
+      header.loop_count = Some(r.u32([Val(0), Bits(3), Bits(16), Bits(32)])?);
+      r.bool()?; // have_timecodes
+    }
+  }
+
+  let (bits_per_sample, float_samples) = read_bit_depth(&mut r)?;
+  header.bits_per_sample = bits_per_sample;
+  header.float_samples = float_samples;
+  r.bool()?; // modular_16bit_buffers
+
+  let num_extra = r.u32([Val(0), Val(1), BitsOffset(4, 2), BitsOffset(12, 1)])?;
+  for _ in 0..num_extra {
+    if read_extra_channel(&mut r)? {
+      header.has_alpha = true;
+    }
+  }
+
+  header.xyb_encoded = r.bool()?;
+
+  // ColorEncoding
+  if !r.bool()? {
+    header.has_icc = r.bool()?;
+    // Enum(ColourSpace): kRGB = 0, kGrey = 1
+    let color_space = r.u32([Val(0), Val(1), BitsOffset(4, 2), BitsOffset(6, 18)])?;
+    header.grayscale = color_space == 1;
+  }
+
+  Some(header)
+}
+
+/// Find the codestream in a container (jxlc box, or the first jxlp box)
+/// Bare codestreams are returned as-is
+pub fn find_jxl_codestream(data: &[u8]) -> Option<&[u8]> {
+  if data.starts_with(&CODESTREAM_SIGNATURE) {
+    return Some(data);
+  }
+  if !data.starts_with(&CONTAINER_SIGNATURE) {
+    return None;
+  }
+
+  let mut pos = 0;
+  while pos + 8 <= data.len() {
+    let size = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as u64;
+    let box_type = &data[pos + 4..pos + 8];
+    let (header_len, box_len) = match size {
+      0 => (8, (data.len() - pos) as u64),
+      1 => {
+        let large = data.get(pos + 8..pos + 16)?;
+        (16, u64::from_be_bytes(large.try_into().ok()?))
+      }
+      _ => (8, size),
+    };
+    if box_len < header_len as u64 {
+      return None;
+    }
+    let end = (pos as u64 + box_len).min(data.len() as u64) as usize;
+    let payload = data.get(pos + header_len..end)?;
+    match box_type {
+      b"jxlc" => return Some(payload),
+      // Partial codestream: 4-byte sequence index, then the codestream bytes
+      b"jxlp" => return payload.get(4..),
+      _ => {}
+    }
+    pos = end;
+  }
+  None
+}
+
+/// Parse a JPEG XL header (codestream or container)
+pub fn parse_jxl_header(data: &[u8]) -> Result<JxlHeader, ImageError> {
+  find_jxl_codestream(data)
+    .and_then(parse_jxl_codestream)
+    .ok_or_else(|| ImageError::DecodeError("Invalid JPEG XL header".to_string()))
+}
+
+/// Get JPEG XL metadata from the headers
+pub fn get_jxl_metadata(data: &[u8], size: u32) -> Result<ImageMetadata, ImageError> {
+  let header = parse_jxl_header(data)?;
+
+  let color_channels = if header.grayscale { 1 } else { 3 };
+  let channels = color_channels + header.has_alpha as u8;
+
+  Ok(ImageMetadata {
+    width: header.width,
+    height: header.height,
+    format: "jxl".to_string(),
+    size: Some(size),
+    space: if header.grayscale {
+      "grayscale".to_string()
+    } else {
+      "srgb".to_string()
+    },
+    channels,
+    depth: if header.float_samples {
+      "float".to_string()
+    } else if header.bits_per_sample > 8 {
+      "ushort".to_string()
+    } else {
+      "uchar".to_string()
+    },
+    has_alpha: header.has_alpha,
+    bits_per_sample: header.bits_per_sample.min(u8::MAX as u32) as u8,
+    is_progressive: false,
+    is_palette: false,
+    has_profile: header.has_icc,
+    orientation: Some(header.orientation as u8),
+    pages: None,
+    loop_count: header.loop_count,
+    delay: None,
+    background: None,
+    compression: Some(if header.xyb_encoded { "vardct" } else { "modular" }.to_string()),
+    density: None,
+    ..Default::default()
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  // 8x8, small SizeHeader with 1:1 ratio, all_default ImageMetadata
+  const SMALL_CODESTREAM: [u8; 4] = [0xFF, 0x0A, 0x41, 0x02];
+
+  #[test]
+  fn test_parse_default_codestream() {
+    let header = parse_jxl_codestream(&SMALL_CODESTREAM).unwrap();
+    assert_eq!((header.width, header.height), (8, 8));
+    assert_eq!(header.bits_per_sample, 8);
+    assert!(!header.has_alpha && !header.animated && !header.has_icc);
+  }
+
+  #[test]
+  fn test_find_codestream_in_container() {
+    let mut data = CONTAINER_SIGNATURE.to_vec();
+    data.extend_from_slice(&[0, 0, 0, 20]);
+    data.extend_from_slice(b"ftypjxl \0\0\0\0jxl ");
+    data.extend_from_slice(&[0, 0, 0, 12]);
+    data.extend_from_slice(b"jxlc");
+    data.extend_from_slice(&SMALL_CODESTREAM);
+
+    assert!(is_jxl(&data));
+    assert_eq!(find_jxl_codestream(&data), Some(&SMALL_CODESTREAM[..]));
+    assert_eq!(parse_jxl_header(&data).unwrap().width, 8);
+  }
+}
@@ -3,27 +3,44 @@
 //! Provides fast, header-only metadata extraction for various image formats.
 //! Optimized for performance - only parses necessary header bytes.
 
+mod exif;
 mod gif;
 mod heic;
+mod icc;
+pub mod iptc;
 mod jpeg;
+mod jxl;
 mod other;
 mod png;
+mod raw;
+mod text;
 pub mod utils;
 mod webp;
+mod xmp;
 
 use image::ImageFormat;
 use std::io::Cursor;
 
 use crate::error::ImageError;
-use crate::ImageMetadata;
+use crate::{ImageMetadata, MetadataOptions};
 
-pub use gif::parse_gif_metadata;
+pub use exif::{find_exif, gps_info, parse_exif, tiff_type_size};
+pub use gif::{parse_gif_loop_count, parse_gif_metadata};
 pub use heic::get_heic_metadata;
+pub use icc::{find_icc_profile, icc_description};
+pub use iptc::{find_iptc, parse_iptc};
 pub use jpeg::get_jpeg_metadata_fast;
-pub use other::{parse_bmp_metadata, parse_ico_metadata, parse_tiff_metadata};
-pub use png::parse_png_metadata;
+pub use jxl::{get_jxl_metadata, is_jxl, parse_jxl_header};
+pub use other::{
+  is_tga, parse_bmp_metadata, parse_dds_metadata, parse_exr_metadata, parse_hdr_metadata, parse_ico_metadata,
+  parse_pnm_metadata, parse_qoi_metadata, parse_tga_metadata, parse_tiff_metadata,
+};
+pub use png::{parse_actl, parse_png_metadata};
+pub use raw::{get_raw_metadata, is_raw, parse_raw_header, RAW_HEADER_LEN};
+pub use text::read_png_text;
 pub use utils::create_default_metadata;
 pub use webp::parse_webp_metadata;
+pub use xmp::{find_xmp, parse_xmp, JPEG_XMP_EXTENSION_NAMESPACE, JPEG_XMP_NAMESPACE, PNG_XMP_KEYWORD};
 
 /// Check if data is HEIC/HEIF format by examining ftyp box
 #[inline]
@@ -48,6 +65,7 @@ pub fn is_heic(data: &[u8]) -> bool {
 }
 
 /// Detect image format from bytes (fast - only reads magic bytes)
+/// JPEG XL and raw pixel input have no `ImageFormat` variant - check `is_jxl`/`is_raw` first
 #[inline]
 pub fn detect_format(data: &[u8]) -> Result<ImageFormat, ImageError> {
   // Check for HEIC first (not detected by image crate)
@@ -56,8 +74,26 @@ pub fn detect_format(data: &[u8]) -> Result<ImageFormat, ImageError> {
     return Ok(ImageFormat::Avif); // Use Avif as marker for HEIC (both are similar)
   }
 
-  image::guess_format(data)
-    .map_err(|e| ImageError::DecodeError(format!("Cannot detect format: {}", e)))
+  if is_jxl(data) {
+    return Err(ImageError::UnsupportedFormat(
+      "JPEG XL is decode-only and has no image crate format".to_string(),
+    ));
+  }
+
+  if is_raw(data) {
+    return Err(ImageError::UnsupportedFormat(
+      "Raw pixel input has no image crate format".to_string(),
+    ));
+  }
+
+  // TGA has no magic bytes - fall back to a header plausibility check
+  image::guess_format(data).or_else(|e| {
+    if is_tga(data) {
+      Ok(ImageFormat::Tga)
+    } else {
+      Err(ImageError::DecodeError(format!("Cannot detect format: {}", e)))
+    }
+  })
 }
 
 /// Get image metadata WITHOUT fully decoding (reads headers only)
@@ -70,6 +106,14 @@ pub fn get_metadata(data: &[u8]) -> Result<ImageMetadata, ImageError> {
     return get_heic_metadata(data, size);
   }
 
+  if is_jxl(data) {
+    return get_jxl_metadata(data, size);
+  }
+
+  if is_raw(data) {
+    return get_raw_metadata(data, size);
+  }
+
   let format = detect_format(data)?;
 
   // For JPEG, use fast header-only parsing
@@ -79,9 +123,7 @@ pub fn get_metadata(data: &[u8]) -> Result<ImageMetadata, ImageError> {
 
   // For other formats, use image crate's header reading
   let cursor = Cursor::new(data);
-  let reader = image::ImageReader::new(cursor)
-    .with_guessed_format()
-    .map_err(|e| ImageError::DecodeError(e.to_string()))?;
+  let reader = image::ImageReader::with_format(cursor, format);
 
   let (width, height) = reader
     .into_dimensions()
@@ -94,8 +136,58 @@ pub fn get_metadata(data: &[u8]) -> Result<ImageMetadata, ImageError> {
     ImageFormat::Bmp => parse_bmp_metadata(data, width, height, size),
     ImageFormat::Tiff => parse_tiff_metadata(data, width, height, size),
     ImageFormat::Ico => parse_ico_metadata(data, width, height, size),
+    ImageFormat::Qoi => parse_qoi_metadata(data, width, height, size),
+    ImageFormat::Pnm => parse_pnm_metadata(data, width, height, size),
+    ImageFormat::Tga => parse_tga_metadata(data, width, height, size),
+    ImageFormat::Hdr => parse_hdr_metadata(data, width, height, size),
+    ImageFormat::OpenExr => parse_exr_metadata(data, width, height, size),
+    ImageFormat::Dds => parse_dds_metadata(data, width, height, size),
     _ => create_default_metadata("unknown", width, height, size, false, 8, 3),
   };
 
   Ok(metadata)
 }
+
+/// Get image metadata plus the optional EXIF/XMP/IPTC/ICC detail
+pub fn get_metadata_with_options(data: &[u8], options: &MetadataOptions) -> Result<ImageMetadata, ImageError> {
+  let mut metadata = get_metadata(data)?;
+
+  let want_exif = options.exif.unwrap_or(false);
+  let want_raw = options.exif_raw.unwrap_or(false);
+  let want_gps = options.gps.unwrap_or(false);
+  if want_exif || want_raw || want_gps {
+    if let Some(tiff) = find_exif(data) {
+      if want_exif || want_gps {
+        let entries = parse_exif(tiff);
+        metadata.gps = gps_info(&entries);
+        if want_exif {
+          metadata.exif = Some(entries);
+        }
+      }
+      if want_raw {
+        metadata.exif_raw = Some(tiff.to_vec());
+      }
+    }
+  }
+
+  if options.xmp.unwrap_or(false) {
+    metadata.xmp = find_xmp(data).map(|(packet, extended)| parse_xmp(packet, extended));
+  }
+
+  if options.iptc.unwrap_or(false) {
+    metadata.iptc = find_iptc(data).and_then(|record| parse_iptc(&record));
+  }
+
+  if options.icc.unwrap_or(false) {
+    if let Some(profile) = find_icc_profile(data) {
+      metadata.icc_description = icc_description(&profile);
+      metadata.icc_profile = Some(profile);
+    }
+  }
+
+  if options.text.unwrap_or(false) && metadata.format == "png" {
+    metadata.text = Some(read_png_text(data));
+  }
+
+  Ok(metadata)
+}
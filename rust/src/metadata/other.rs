@@ -1,4 +1,4 @@
-//! Metadata parsing for BMP, TIFF, and ICO formats
+//! Metadata parsing for BMP, TIFF, ICO, QOI, PNM, TGA, HDR, OpenEXR and DDS formats
 
 use crate::ImageMetadata;
 
@@ -48,6 +48,7 @@ pub fn parse_bmp_metadata(data: &[u8], width: u32, height: u32, size: u32) -> Im
     background: None,
     compression: Some(compression.to_string()),
     density: None,
+    ..Default::default()
   }
 }
 
@@ -84,5 +85,280 @@ pub fn parse_ico_metadata(data: &[u8], width: u32, height: u32, size: u32) -> Im
     background: None,
     compression: None,
     density: None,
+    ..Default::default()
+  }
+}
+
+/// Parse QOI header (channels and colorspace bytes)
+pub fn parse_qoi_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
+  let channels = data.get(12).copied().filter(|&c| c == 3 || c == 4).unwrap_or(4);
+  // Colorspace 1 = all channels linear
+  let linear = data.get(13) == Some(&1);
+
+  ImageMetadata {
+    space: if linear { "rgb" } else { "srgb" }.to_string(),
+    compression: Some("qoi".to_string()),
+    ..create_default_metadata("qoi", width, height, size, channels == 4, 8, channels)
+  }
+}
+
+/// Read the next header token of a PNM file, skipping whitespace and comments
+fn next_pnm_token<'a>(data: &'a [u8], pos: &mut usize) -> Option<&'a [u8]> {
+  loop {
+    match data.get(*pos)? {
+      b'#' => {
+        while *data.get(*pos)? != b'\n' {
+          *pos += 1;
+        }
+      }
+      c if c.is_ascii_whitespace() => *pos += 1,
+      _ => break,
+    }
+  }
+  let start = *pos;
+  while data.get(*pos).is_some_and(|c| !c.is_ascii_whitespace()) {
+    *pos += 1;
+  }
+  Some(&data[start..*pos])
+}
+
+fn parse_pnm_number(token: Option<&[u8]>) -> Option<u32> {
+  std::str::from_utf8(token?).ok()?.parse().ok()
+}
+
+/// Parse PNM header (PBM/PGM/PPM/PAM)
+pub fn parse_pnm_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
+  let kind = data.get(1).copied().unwrap_or(b'6');
+  let mut pos = 2;
+
+  let (channels, max_value, has_alpha) = match kind {
+    b'1' | b'4' => (1, 1, false),
+    b'7' => {
+      // PAM: KEY value lines up to ENDHDR
+      let (mut depth, mut max_value, mut tuple_type) = (3u32, 255u32, Vec::new());
+      while let Some(token) = next_pnm_token(data, &mut pos) {
+        match token {
+          b"DEPTH" => depth = parse_pnm_number(next_pnm_token(data, &mut pos)).unwrap_or(depth),
+          b"MAXVAL" => max_value = parse_pnm_number(next_pnm_token(data, &mut pos)).unwrap_or(max_value),
+          b"TUPLTYPE" => tuple_type = next_pnm_token(data, &mut pos).unwrap_or_default().to_vec(),
+          b"ENDHDR" => break,
+          _ => {}
+        }
+      }
+      (depth.min(4) as u8, max_value, tuple_type.ends_with(b"_ALPHA"))
+    }
+    _ => {
+      next_pnm_token(data, &mut pos); // width
+      next_pnm_token(data, &mut pos); // height
+      let max_value = parse_pnm_number(next_pnm_token(data, &mut pos)).unwrap_or(255);
+      let channels = if matches!(kind, b'2' | b'5') { 1 } else { 3 };
+      (channels, max_value, false)
+    }
+  };
+
+  let bit_depth = match max_value {
+    1 => 1,
+    2..=255 => 8,
+    _ => 16,
+  };
+
+  ImageMetadata {
+    compression: matches!(kind, b'1' | b'2' | b'3').then(|| "ascii".to_string()),
+    ..create_default_metadata("pnm", width, height, size, has_alpha, bit_depth, channels)
+  }
+}
+
+/// Check for a plausible TGA header (TGA has no magic bytes)
+pub fn is_tga(data: &[u8]) -> bool {
+  // TGA 2.0 files end with a signature footer
+  if data.ends_with(b"TRUEVISION-XFILE.\0") {
+    return true;
+  }
+  if data.len() < 18 {
+    return false;
+  }
+
+  let color_map_type = data[1];
+  let image_type = data[2];
+  let color_map_depth = data[7];
+  let width = u16::from_le_bytes([data[12], data[13]]);
+  let height = u16::from_le_bytes([data[14], data[15]]);
+  let pixel_depth = data[16];
+  let color_mapped = matches!(image_type, 1 | 9);
+
+  matches!(color_map_type, 0 | 1)
+    && matches!(image_type, 1 | 2 | 3 | 9 | 10 | 11)
+    && (color_map_type == 1) == color_mapped
+    && (!color_mapped || matches!(color_map_depth, 15 | 16 | 24 | 32))
+    && matches!(pixel_depth, 8 | 15 | 16 | 24 | 32)
+    && width > 0
+    && height > 0
+}
+
+/// Parse TGA header
+pub fn parse_tga_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
+  let image_type = data.get(2).copied().unwrap_or(2);
+  let color_map_depth = data.get(7).copied().unwrap_or(0);
+  let alpha_bits = data.get(17).map(|d| d & 0x0F).unwrap_or(0);
+
+  let (channels, has_alpha) = match image_type & !8 {
+    1 => {
+      let has_alpha = color_map_depth == 32;
+      (3 + has_alpha as u8, has_alpha)
+    }
+    3 => (1 + (alpha_bits > 0) as u8, alpha_bits > 0),
+    _ => (3 + (alpha_bits > 0) as u8, alpha_bits > 0),
+  };
+
+  ImageMetadata {
+    is_palette: image_type & !8 == 1,
+    compression: Some(if image_type & 8 != 0 { "rle" } else { "none" }.to_string()),
+    ..create_default_metadata("tga", width, height, size, has_alpha, 8, channels)
+  }
+}
+
+/// Parse Radiance HDR header
+pub fn parse_hdr_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
+  // FORMAT=32-bit_rle_rgbe or 32-bit_rle_xyze
+  let header = &data[..data.len().min(1024)];
+  let xyze = header.windows(15).any(|w| w == b"32-bit_rle_xyze");
+
+  ImageMetadata {
+    space: if xyze { "xyz" } else { "rgb" }.to_string(),
+    depth: "float".to_string(),
+    bits_per_sample: 32,
+    compression: Some("rle".to_string()),
+    ..create_default_metadata("hdr", width, height, size, false, 8, 3)
+  }
+}
+
+/// Parse OpenEXR header attributes (channels and compression)
+pub fn parse_exr_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
+  const COMPRESSION: [&str; 10] = ["none", "rle", "zips", "zip", "piz", "pxr24", "b44", "b44a", "dwaa", "dwab"];
+
+  let read_cstr = |pos: &mut usize| -> Option<&[u8]> {
+    let len = data.get(*pos..)?.iter().position(|&b| b == 0)?;
+    let value = &data[*pos..*pos + len];
+    *pos += len + 1;
+    Some(value)
+  };
+  let read_i32 = |pos: usize| -> Option<i32> { Some(i32::from_le_bytes(data.get(pos..pos + 4)?.try_into().ok()?)) };
+
+  let mut channel_names: Vec<&[u8]> = Vec::new();
+  let mut bits_per_sample = 16;
+  let mut compression = None;
+
+  // Magic (4) + version/flags (4), then name\0 type\0 size value... up to an empty name
+  let mut pos = 8;
+  while let Some(name) = read_cstr(&mut pos) {
+    if name.is_empty() {
+      break;
+    }
+    let Some(_attr_type) = read_cstr(&mut pos) else { break };
+    let Some(attr_size) = read_i32(pos).and_then(|s| usize::try_from(s).ok()) else { break };
+    pos += 4;
+    let end = pos + attr_size;
+
+    match name {
+      b"channels" => {
+        // chlist: name\0 pixel_type(i32) pLinear(u8) reserved(3) xSampling(i32) ySampling(i32), ends with \0
+        let mut channel_pos = pos;
+        while channel_pos < end {
+          let Some(channel) = read_cstr(&mut channel_pos) else { break };
+          if channel.is_empty() {
+            break;
+          }
+          // Pixel type: 0 = UINT, 1 = HALF, 2 = FLOAT
+          if read_i32(channel_pos).is_some_and(|t| t != 1) {
+            bits_per_sample = 32;
+          }
+          channel_names.push(channel);
+          channel_pos += 16;
+        }
+      }
+      b"compression" => {
+        compression = data.get(pos).and_then(|&c| COMPRESSION.get(c as usize)).map(|c| c.to_string());
+      }
+      _ => {}
+    }
+    pos = end;
+  }
+
+  let has_alpha = channel_names.iter().any(|n| exr_base_name(n) == b"A");
+  let grayscale = !channel_names.is_empty() && channel_names.iter().all(|n| matches!(exr_base_name(n), b"Y" | b"A"));
+  let channels = if channel_names.is_empty() {
+    3
+  } else {
+    channel_names.len().min(4) as u8
+  };
+
+  ImageMetadata {
+    space: if grayscale { "grayscale" } else { "rgb" }.to_string(),
+    depth: "float".to_string(),
+    bits_per_sample,
+    compression,
+    ..create_default_metadata("exr", width, height, size, has_alpha, 8, channels)
+  }
+}
+
+/// Channel name without its layer prefix ("diffuse.R" -> "R")
+fn exr_base_name(name: &[u8]) -> &[u8] {
+  name.rsplit(|&b| b == b'.').next().unwrap_or(name)
+}
+
+/// Parse DDS header (pixel format)
+pub fn parse_dds_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
+  let read_u32 = |pos: usize| -> u32 {
+    data
+      .get(pos..pos + 4)
+      .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+      .unwrap_or(0)
+  };
+
+  // "DDS " + DDS_HEADER: pixel format at offset 76
+  let pixel_flags = read_u32(80);
+  let four_cc = data.get(84..88).unwrap_or_default();
+
+  // DDPF_FOURCC = 0x4 (block compressed), DDPF_ALPHAPIXELS = 0x1
+  let compression = if pixel_flags & 0x4 != 0 {
+    String::from_utf8_lossy(four_cc).trim_end_matches('\0').to_lowercase()
+  } else {
+    "none".to_string()
+  };
+  let has_alpha = pixel_flags & 0x1 != 0 || matches!(four_cc, b"DXT2" | b"DXT3" | b"DXT4" | b"DXT5");
+
+  ImageMetadata {
+    compression: Some(compression),
+    ..create_default_metadata("dds", width, height, size, has_alpha, 8, 3 + has_alpha as u8)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_pnm_header_with_comments() {
+    let data = b"P5\n# created by a scanner\n4 2\n65535\n";
+    let meta = parse_pnm_metadata(data, 4, 2, data.len() as u32);
+    assert_eq!(meta.channels, 1);
+    assert_eq!(meta.bits_per_sample, 16);
+
+    let pam = b"P7\nWIDTH 1\nHEIGHT 1\nDEPTH 4\nMAXVAL 255\nTUPLTYPE RGB_ALPHA\nENDHDR\n";
+    let meta = parse_pnm_metadata(pam, 1, 1, pam.len() as u32);
+    assert_eq!(meta.channels, 4);
+    assert!(meta.has_alpha);
+  }
+
+  #[test]
+  fn test_tga_detection() {
+    // Uncompressed 24-bit truecolor, 2x1
+    let mut tga = vec![0, 0, 2, 0, 0, 0, 0, 0, 0, 0, 0, 0, 2, 0, 1, 0, 24, 0];
+    tga.extend_from_slice(&[0; 6]);
+    assert!(is_tga(&tga));
+
+    // Color-mapped image type without a color map
+    tga[2] = 1;
+    assert!(!is_tga(&tga));
   }
 }
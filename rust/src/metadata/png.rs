@@ -53,6 +53,14 @@ pub fn parse_png_metadata(data: &[u8], width: u32, height: u32, size: u32) -> Im
     }
   });
 
+  // APNG: acTL carries frame and loop counts, each fcTL a frame delay
+  let animation = parse_actl(data);
+  let frames = animation.map(|_| parse_fctl_frames(data));
+  let duration = frames
+    .as_ref()
+    .map(|(delays, _)| delays.iter().fold(0u32, |total, &d| total.saturating_add(d)));
+  let (delay, disposal) = frames.unzip();
+
   let depth_str = match bit_depth {
     1 | 2 | 4 | 8 => "uchar",
     16 => "ushort",
@@ -73,11 +81,62 @@ pub fn parse_png_metadata(data: &[u8], width: u32, height: u32, size: u32) -> Im
     is_palette,
     has_profile,
     orientation: None,
-    pages: None,
-    loop_count: None,
-    delay: None,
+    pages: animation.map(|(frames, _)| frames),
+    loop_count: animation.map(|(_, plays)| plays),
+    delay,
+    disposal,
+    duration,
     background: None,
     compression: Some("deflate".to_string()),
     density,
+    ..Default::default()
+  }
+}
+
+/// Read the APNG acTL chunk: (frame count, loop count where 0 = infinite)
+pub fn parse_actl(data: &[u8]) -> Option<(u32, u32)> {
+  let offset = find_png_chunk(data, b"acTL")?;
+  let chunk = data.get(offset..offset + 8)?;
+  Some((
+    u32::from_be_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]),
+    u32::from_be_bytes([chunk[4], chunk[5], chunk[6], chunk[7]]),
+  ))
+}
+
+/// Collect frame delays in ms and disposal methods from every APNG fcTL chunk
+fn parse_fctl_frames(data: &[u8]) -> (Vec<u32>, Vec<String>) {
+  let mut delays = Vec::new();
+  let mut disposal = Vec::new();
+  let mut pos = 8;
+  while pos + 12 <= data.len() {
+    let length =
+      u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    let chunk_type = &data[pos + 4..pos + 8];
+
+    if chunk_type == b"IEND" {
+      break;
+    }
+
+    // fcTL: sequence, width, height, x, y (4 bytes each), delay_num, delay_den (2 bytes each),
+    // dispose_op, blend_op
+    if chunk_type == b"fcTL" && length >= 26 && pos + 8 + 26 <= data.len() {
+      let body = &data[pos + 8..];
+      let num = u16::from_be_bytes([body[20], body[21]]) as u32;
+      let den = u16::from_be_bytes([body[22], body[23]]) as u32;
+      // A zero denominator means 1/100 second units
+      let den = if den == 0 { 100 } else { den };
+      delays.push(num * 1000 / den);
+      disposal.push(
+        match body[24] {
+          1 => "background",
+          2 => "previous",
+          _ => "none",
+        }
+        .to_string(),
+      );
+    }
+
+    pos += 12 + length;
   }
+  (delays, disposal)
 }
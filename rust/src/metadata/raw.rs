@@ -0,0 +1,106 @@
+//! Raw pixel input header
+//!
+//! Raw pixels travel through the Buffer-based API in a minimal container built
+//! by the TS layer from `{ data, width, height, channels }`:
+//! 8-byte magic, width and height (u32 LE), channel count, 3 reserved bytes,
+//! then tightly packed 8-bit pixels.
+
+use crate::error::ImageError;
+use crate::ImageMetadata;
+
+use super::utils::create_default_metadata;
+
+/// Raw pixel container magic
+pub const RAW_MAGIC: [u8; 8] = *b"IMGKRAW1";
+
+/// Raw pixel container header length
+pub const RAW_HEADER_LEN: usize = 20;
+
+/// Dimensions and layout of raw pixel input
+pub struct RawHeader {
+  pub width: u32,
+  pub height: u32,
+  pub channels: u8,
+}
+
+/// Check for the raw pixel container magic
+#[inline]
+pub fn is_raw(data: &[u8]) -> bool {
+  data.starts_with(&RAW_MAGIC)
+}
+
+/// Parse and validate the raw pixel container header
+pub fn parse_raw_header(data: &[u8]) -> Result<RawHeader, ImageError> {
+  if !is_raw(data) || data.len() < RAW_HEADER_LEN {
+    return Err(ImageError::DecodeError("Invalid raw pixel header".to_string()));
+  }
+
+  let width = u32::from_le_bytes([data[8], data[9], data[10], data[11]]);
+  let height = u32::from_le_bytes([data[12], data[13], data[14], data[15]]);
+  let channels = data[16];
+
+  if width == 0 || height == 0 {
+    return Err(ImageError::InvalidDimensions(format!(
+      "Raw pixels need non-zero dimensions, got {}x{}",
+      width, height
+    )));
+  }
+  if !(1..=4).contains(&channels) {
+    return Err(ImageError::DecodeError(format!(
+      "Raw pixels must have 1-4 channels, got {}",
+      channels
+    )));
+  }
+
+  let expected = width as u64 * height as u64 * channels as u64;
+  let actual = (data.len() - RAW_HEADER_LEN) as u64;
+  if actual != expected {
+    return Err(ImageError::DecodeError(format!(
+      "Raw pixel data is {} bytes, expected {} for {}x{}x{}",
+      actual, expected, width, height, channels
+    )));
+  }
+
+  Ok(RawHeader {
+    width,
+    height,
+    channels,
+  })
+}
+
+/// Get raw pixel input metadata from the header
+pub fn get_raw_metadata(data: &[u8], size: u32) -> Result<ImageMetadata, ImageError> {
+  let header = parse_raw_header(data)?;
+  let has_alpha = header.channels == 2 || header.channels == 4;
+  Ok(ImageMetadata {
+    space: if header.channels <= 2 { "grayscale" } else { "srgb" }.to_string(),
+    ..create_default_metadata("raw", header.width, header.height, size, has_alpha, 8, header.channels)
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn raw_input(width: u32, height: u32, channels: u8, pixel_bytes: usize) -> Vec<u8> {
+    let mut data = RAW_MAGIC.to_vec();
+    data.extend_from_slice(&width.to_le_bytes());
+    data.extend_from_slice(&height.to_le_bytes());
+    data.extend_from_slice(&[channels, 0, 0, 0]);
+    data.resize(RAW_HEADER_LEN + pixel_bytes, 0);
+    data
+  }
+
+  #[test]
+  fn test_parse_raw_header() {
+    let header = parse_raw_header(&raw_input(3, 2, 4, 24)).unwrap();
+    assert_eq!((header.width, header.height, header.channels), (3, 2, 4));
+  }
+
+  #[test]
+  fn test_rejects_wrong_length_and_channels() {
+    assert!(parse_raw_header(&raw_input(3, 2, 4, 23)).is_err());
+    assert!(parse_raw_header(&raw_input(3, 2, 5, 30)).is_err());
+    assert!(parse_raw_header(&raw_input(0, 2, 1, 0)).is_err());
+  }
+}
@@ -0,0 +1,145 @@
+//! PNG text chunk reading
+//!
+//! Decodes tEXt (Latin-1), zTXt (deflate-compressed Latin-1) and iTXt (UTF-8,
+//! optionally compressed) chunks. Stable Diffusion WebUI stores its generation
+//! `parameters` this way, ComfyUI its `prompt` and `workflow` JSON.
+
+use std::io::Read;
+
+use flate2::read::ZlibDecoder;
+
+use crate::PngTextEntry;
+
+/// Inflate budget shared by all compressed chunks of a file - chunks past it are skipped
+const MAX_TEXT_LEN: usize = 64 * 1024 * 1024;
+
+fn latin1(bytes: &[u8]) -> String {
+  bytes.iter().map(|&b| b as char).collect()
+}
+
+/// Inflate within the remaining budget, which is charged for the output
+fn inflate(data: &[u8], budget: &mut usize) -> Option<Vec<u8>> {
+  let mut text = Vec::new();
+  ZlibDecoder::new(data)
+    .take(*budget as u64 + 1)
+    .read_to_end(&mut text)
+    .ok()?;
+  *budget = budget.checked_sub(text.len())?;
+  Some(text)
+}
+
+/// Split a NUL-terminated field off the front
+fn split_field(data: &[u8]) -> Option<(&[u8], &[u8])> {
+  let end = data.iter().position(|&b| b == 0)?;
+  Some((&data[..end], &data[end + 1..]))
+}
+
+fn parse_text_chunk(kind: &[u8], chunk: &[u8], budget: &mut usize) -> Option<PngTextEntry> {
+  let (keyword, rest) = split_field(chunk)?;
+  if keyword.is_empty() {
+    return None;
+  }
+  let keyword = latin1(keyword);
+
+  match kind {
+    b"tEXt" => Some(PngTextEntry {
+      keyword,
+      text: latin1(rest),
+      language: None,
+      translated_keyword: None,
+      compressed: Some(false),
+    }),
+    b"zTXt" => {
+      // compression method (0 = deflate), compressed text
+      let (&method, text) = rest.split_first()?;
+      if method != 0 {
+        return None;
+      }
+      Some(PngTextEntry {
+        keyword,
+        text: latin1(&inflate(text, budget)?),
+        language: None,
+        translated_keyword: None,
+        compressed: Some(true),
+      })
+    }
+    b"iTXt" => {
+      // compression flag, method, language \0 translated keyword \0 text
+      let (&flag, rest) = rest.split_first()?;
+      let (&method, rest) = rest.split_first()?;
+      let (language, rest) = split_field(rest)?;
+      let (translated, text) = split_field(rest)?;
+      let compressed = flag != 0;
+      let text = match (compressed, method) {
+        (false, _) => text.to_vec(),
+        (true, 0) => inflate(text, budget)?,
+        (true, _) => return None,
+      };
+      Some(PngTextEntry {
+        keyword,
+        text: String::from_utf8_lossy(&text).into_owned(),
+        language: (!language.is_empty()).then(|| latin1(language)),
+        translated_keyword: (!translated.is_empty()).then(|| String::from_utf8_lossy(translated).into_owned()),
+        compressed: Some(compressed),
+      })
+    }
+    _ => None,
+  }
+}
+
+/// All text chunks of a PNG in file order - malformed chunks are skipped
+pub fn read_png_text(data: &[u8]) -> Vec<PngTextEntry> {
+  let mut entries = Vec::new();
+  if !data.starts_with(b"\x89PNG\r\n\x1a\n") {
+    return entries;
+  }
+
+  // Text chunks may sit before or after the image data, so walk to IEND
+  let mut pos = 8;
+  let mut budget = MAX_TEXT_LEN;
+  while pos + 12 <= data.len() {
+    let length = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    let kind = &data[pos + 4..pos + 8];
+    if kind == b"IEND" {
+      break;
+    }
+    let Some(chunk) = data.get(pos + 8..pos + 8 + length) else {
+      break;
+    };
+    if let Some(entry) = parse_text_chunk(kind, chunk, &mut budget) {
+      entries.push(entry);
+    }
+    pos += 12 + length;
+  }
+  entries
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use flate2::write::ZlibEncoder;
+  use flate2::Compression;
+  use std::io::Write;
+
+  fn ztxt(keyword: &str, len: usize) -> Vec<u8> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::best());
+    encoder.write_all(&vec![b'a'; len]).unwrap();
+    let mut chunk = keyword.as_bytes().to_vec();
+    chunk.extend_from_slice(&[0, 0]);
+    chunk.extend_from_slice(&encoder.finish().unwrap());
+    chunk
+  }
+
+  #[test]
+  fn test_inflate_budget_is_shared() {
+    let mut budget = MAX_TEXT_LEN;
+    let half = ztxt("a", MAX_TEXT_LEN / 2);
+    assert!(parse_text_chunk(b"zTXt", &half, &mut budget).is_some());
+    assert!(parse_text_chunk(b"zTXt", &half, &mut budget).is_some());
+    assert_eq!(budget, 0);
+
+    // Exhausted: further compressed text is skipped, plain text still reads
+    assert!(parse_text_chunk(b"zTXt", &ztxt("b", 1), &mut budget).is_none());
+    assert!(parse_text_chunk(b"tEXt", b"c\0plain", &mut budget).is_some());
+  }
+}
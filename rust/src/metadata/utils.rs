@@ -40,6 +40,7 @@ pub fn create_default_metadata(
     background: None,
     compression: None,
     density: None,
+    ..Default::default()
   }
 }
 
@@ -71,6 +72,54 @@ pub fn find_webp_chunk(data: &[u8], chunk_type: &[u8; 4]) -> Option<usize> {
   None
 }
 
+/// Marker and payload of every JPEG header segment (up to start of scan)
+pub fn jpeg_segments(data: &[u8]) -> Vec<(u8, &[u8])> {
+  let mut segments = Vec::new();
+  let mut pos = 2;
+  while pos + 4 <= data.len() {
+    if data[pos] != 0xFF {
+      break;
+    }
+    let marker = data[pos + 1];
+    if marker == 0xFF {
+      pos += 1;
+      continue;
+    }
+    // Headers end at start of scan
+    if marker == 0xDA || marker == 0xD9 {
+      break;
+    }
+    if (0xD0..=0xD8).contains(&marker) || marker == 0x01 {
+      pos += 2;
+      continue;
+    }
+    let length = u16::from_be_bytes([data[pos + 2], data[pos + 3]]) as usize;
+    let Some(segment) = data.get(pos + 4..pos + 2 + length) else {
+      break;
+    };
+    segments.push((marker, segment));
+    pos += 2 + length;
+  }
+  segments
+}
+
+/// Payloads of every PNG chunk of a type, in file order
+pub fn png_chunks<'a>(data: &'a [u8], chunk_type: &[u8; 4]) -> Vec<&'a [u8]> {
+  let mut chunks = Vec::new();
+  let mut pos = 8;
+  while pos + 12 <= data.len() {
+    let length = u32::from_be_bytes([data[pos], data[pos + 1], data[pos + 2], data[pos + 3]]) as usize;
+    if &data[pos + 4..pos + 8] == chunk_type {
+      match data.get(pos + 8..pos + 8 + length) {
+        Some(chunk) => chunks.push(chunk),
+        None => break,
+      }
+    }
+    pos += 12 + length;
+  }
+  chunks
+}
+
 /// Parse EXIF orientation tag from TIFF data
 pub fn parse_exif_orientation(data: &[u8]) -> Option<u8> {
   if data.len() < 8 {
@@ -4,6 +4,25 @@ use crate::ImageMetadata;
 
 use super::utils::find_webp_chunk;
 
+/// Frame timing of an animated WebP: (delay in ms, disposal) per ANMF chunk
+fn scan_anmf_frames(data: &[u8]) -> (Vec<u32>, Vec<String>) {
+  let (mut delays, mut disposal) = (Vec::new(), Vec::new());
+  let mut pos = 12;
+  while pos + 8 <= data.len() {
+    let length = u32::from_le_bytes([data[pos + 4], data[pos + 5], data[pos + 6], data[pos + 7]]) as usize;
+    // ANMF: X, Y, width - 1, height - 1 (3 bytes each), duration (3 bytes), flags
+    if &data[pos..pos + 4] == b"ANMF" {
+      let Some(frame) = data.get(pos + 8..pos + 24) else {
+        break;
+      };
+      delays.push(u32::from_le_bytes([frame[12], frame[13], frame[14], 0]));
+      disposal.push(if frame[15] & 0x01 != 0 { "background" } else { "none" }.to_string());
+    }
+    pos = pos.saturating_add(8 + length + (length & 1));
+  }
+  (delays, disposal)
+}
+
 /// Parse WebP header for detailed metadata
 pub fn parse_webp_metadata(data: &[u8], width: u32, height: u32, size: u32) -> ImageMetadata {
   let mut has_alpha = false;
@@ -36,6 +55,13 @@ pub fn parse_webp_metadata(data: &[u8], width: u32, height: u32, size: u32) -> I
     }
   }
 
+  let (delays, disposal) = if is_animated {
+    scan_anmf_frames(data)
+  } else {
+    (Vec::new(), Vec::new())
+  };
+  let duration = delays.iter().fold(0u32, |total, &d| total.saturating_add(d));
+
   let channels = if has_alpha { 4 } else { 3 };
 
   ImageMetadata {
@@ -52,11 +78,61 @@ pub fn parse_webp_metadata(data: &[u8], width: u32, height: u32, size: u32) -> I
     is_palette: false,
     has_profile,
     orientation: None,
-    pages: if is_animated { Some(1) } else { None },
+    pages: is_animated.then_some((delays.len() as u32).max(1)),
     loop_count,
-    delay: None,
+    delay: is_animated.then_some(delays),
+    disposal: is_animated.then_some(disposal),
+    duration: is_animated.then_some(duration),
     background: None,
     compression: Some("webp".to_string()),
     density: None,
+    ..Default::default()
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn chunk(id: &[u8; 4], payload: &[u8]) -> Vec<u8> {
+    let mut bytes = id.to_vec();
+    bytes.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(payload);
+    if payload.len() % 2 != 0 {
+      bytes.push(0);
+    }
+    bytes
+  }
+
+  fn anmf(duration: u32, flags: u8) -> Vec<u8> {
+    let mut payload = vec![0u8; 12];
+    payload.extend_from_slice(&duration.to_le_bytes()[..3]);
+    payload.push(flags);
+    // Frame data is not read
+    payload.extend_from_slice(&chunk(b"VP8L", &[0x2F; 5]));
+    chunk(b"ANMF", &payload)
+  }
+
+  #[test]
+  fn test_parse_animated_webp() {
+    let mut vp8x = vec![0x02 | 0x10, 0, 0, 0];
+    vp8x.extend_from_slice(&[3, 0, 0, 3, 0, 0]);
+    let mut body = b"WEBP".to_vec();
+    body.extend_from_slice(&chunk(b"VP8X", &vp8x));
+    // Background color (BGRA), loop count
+    body.extend_from_slice(&chunk(b"ANIM", &[0, 0, 0, 0, 2, 0]));
+    body.extend_from_slice(&anmf(100, 0x01));
+    body.extend_from_slice(&anmf(250, 0x02));
+    body.extend_from_slice(&anmf(70_000, 0));
+    let mut data = b"RIFF".to_vec();
+    data.extend_from_slice(&(body.len() as u32).to_le_bytes());
+    data.extend_from_slice(&body);
+
+    let meta = parse_webp_metadata(&data, 4, 4, data.len() as u32);
+    assert_eq!(meta.pages, Some(3));
+    assert_eq!(meta.loop_count, Some(2));
+    assert_eq!(meta.delay, Some(vec![100, 250, 70_000]));
+    assert_eq!(meta.duration, Some(70_350));
+    assert_eq!(meta.disposal.unwrap(), ["background", "none", "none"]);
   }
 }
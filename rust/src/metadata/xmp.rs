@@ -0,0 +1,331 @@
+//! XMP reading
+//!
+//! Locates the XMP packet in JPEG (APP1, plus extended XMP split over several
+//! APP1 segments), PNG (iTXt `XML:com.adobe.xmp`) and WebP (`XMP ` chunk) files
+//! and pulls the common Dublin Core, Photoshop, IPTC Core and XMP Rights
+//! properties out of it. This is a property scanner, not a full RDF parser.
+
+use crate::XmpData;
+
+use super::utils::{find_webp_chunk, jpeg_segments, png_chunks};
+
+/// JPEG APP1 namespace of the main XMP packet
+pub const JPEG_XMP_NAMESPACE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+/// JPEG APP1 namespace of extended XMP chunks
+pub const JPEG_XMP_EXTENSION_NAMESPACE: &[u8] = b"http://ns.adobe.com/xmp/extension/\0";
+/// PNG iTXt keyword holding the XMP packet
+pub const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+
+/// Extended XMP larger than this is ignored
+const MAX_EXTENDED_XMP: usize = 64 * 1024 * 1024;
+
+/// Main packet and reassembled extended XMP of a JPEG
+fn find_jpeg_xmp(data: &[u8]) -> Option<(String, Option<String>)> {
+  let segments = jpeg_segments(data);
+  let packet = segments
+    .iter()
+    .find(|(marker, segment)| *marker == 0xE1 && segment.starts_with(JPEG_XMP_NAMESPACE))
+    .map(|(_, segment)| String::from_utf8_lossy(&segment[JPEG_XMP_NAMESPACE.len()..]).into_owned())?;
+
+  // Extended chunks: GUID (32 hex chars), full length, offset, data
+  // Only chunks whose GUID the main packet names belong to it
+  let extended = property(&packet, "xmpNote:HasExtendedXMP")
+    .and_then(|guids| guids.into_iter().next())
+    .and_then(|guid| {
+      let mut buffer: Vec<u8> = Vec::new();
+      for (marker, segment) in &segments {
+        let Some(chunk) = segment.strip_prefix(JPEG_XMP_EXTENSION_NAMESPACE) else {
+          continue;
+        };
+        if *marker != 0xE1 || chunk.len() < 40 || chunk[..32] != *guid.as_bytes() {
+          continue;
+        }
+        let total = u32::from_be_bytes(chunk[32..36].try_into().ok()?) as usize;
+        let offset = u32::from_be_bytes(chunk[36..40].try_into().ok()?) as usize;
+        let part = &chunk[40..];
+        if total > MAX_EXTENDED_XMP || offset + part.len() > total {
+          continue;
+        }
+        buffer.resize(total, 0);
+        buffer[offset..offset + part.len()].copy_from_slice(part);
+      }
+      (!buffer.is_empty()).then(|| String::from_utf8_lossy(&buffer).into_owned())
+    });
+
+  Some((packet, extended))
+}
+
+/// Packet of the first uncompressed PNG iTXt chunk with the XMP keyword
+fn find_png_xmp(data: &[u8]) -> Option<String> {
+  png_chunks(data, b"iTXt").into_iter().find_map(|chunk| {
+    // keyword \0 compression-flag method language \0 translated-keyword \0 text
+    let rest = chunk.strip_prefix(PNG_XMP_KEYWORD)?.strip_prefix(b"\0")?;
+    let (&compressed, rest) = rest.split_first()?;
+    // The XMP spec forbids compressing the packet
+    if compressed != 0 {
+      return None;
+    }
+    let rest = rest.get(1..)?;
+    let language_end = rest.iter().position(|&b| b == 0)?;
+    let rest = &rest[language_end + 1..];
+    let translated_end = rest.iter().position(|&b| b == 0)?;
+    Some(String::from_utf8_lossy(&rest[translated_end + 1..]).into_owned())
+  })
+}
+
+/// Packet of the WebP `XMP ` chunk
+fn find_webp_xmp(data: &[u8]) -> Option<String> {
+  let offset = find_webp_chunk(data, b"XMP ")?;
+  let length = u32::from_le_bytes(data.get(offset - 4..offset)?.try_into().ok()?) as usize;
+  let packet = data.get(offset..offset.checked_add(length)?)?;
+  Some(String::from_utf8_lossy(packet).into_owned())
+}
+
+/// Locate the XMP packet (and JPEG extended XMP) in a JPEG, PNG or WebP file
+pub fn find_xmp(data: &[u8]) -> Option<(String, Option<String>)> {
+  if data.starts_with(&[0xFF, 0xD8]) {
+    return find_jpeg_xmp(data);
+  }
+  if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+    return find_webp_xmp(data).map(|packet| (packet, None));
+  }
+  if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+    return find_png_xmp(data).map(|packet| (packet, None));
+  }
+  None
+}
+
+/// Decode the five predefined XML entities and numeric character references
+fn decode_entities(text: &str) -> String {
+  let mut out = String::with_capacity(text.len());
+  let mut rest = text;
+  while let Some(start) = rest.find('&') {
+    out.push_str(&rest[..start]);
+    rest = &rest[start..];
+    let Some(end) = rest.find(';') else {
+      break;
+    };
+    let entity = &rest[1..end];
+    let decoded = match entity {
+      "amp" => Some('&'),
+      "lt" => Some('<'),
+      "gt" => Some('>'),
+      "quot" => Some('"'),
+      "apos" => Some('\''),
+      _ => entity
+        .strip_prefix("#x")
+        .map(|hex| u32::from_str_radix(hex, 16))
+        .or_else(|| entity.strip_prefix('#').map(str::parse))
+        .and_then(Result::ok)
+        .and_then(char::from_u32),
+    };
+    match decoded {
+      Some(c) => {
+        out.push(c);
+        rest = &rest[end + 1..];
+      }
+      None => {
+        out.push('&');
+        rest = &rest[1..];
+      }
+    }
+  }
+  out.push_str(rest);
+  out
+}
+
+/// Text content of an element with all markup removed
+fn strip_tags(xml: &str) -> String {
+  let mut out = String::with_capacity(xml.len());
+  let mut in_tag = false;
+  for c in xml.chars() {
+    match c {
+      '<' => in_tag = true,
+      '>' => in_tag = false,
+      _ if !in_tag => out.push(c),
+      _ => {}
+    }
+  }
+  decode_entities(out.trim())
+}
+
+/// `rdf:li` items of an array (Bag, Seq, Alt), x-default first for language alternatives
+fn list_items(inner: &str) -> Vec<String> {
+  let mut items = Vec::new();
+  let mut rest = inner;
+  while let Some(start) = rest.find("<rdf:li") {
+    let after = &rest[start..];
+    let Some(open_end) = after.find('>') else {
+      break;
+    };
+    let open = &after[..open_end];
+    if open.ends_with('/') {
+      rest = &after[open_end + 1..];
+      continue;
+    }
+    let Some(close) = after.find("</rdf:li>") else {
+      break;
+    };
+    let value = strip_tags(&after[open_end + 1..close]);
+    if open.contains("xml:lang=\"x-default\"") || open.contains("xml:lang='x-default'") {
+      items.insert(0, value);
+    } else {
+      items.push(value);
+    }
+    rest = &after[close + "</rdf:li>".len()..];
+  }
+  items
+}
+
+/// Values of a property, written either as an attribute of rdf:Description
+/// or as an element (simple text or rdf:Bag/Seq/Alt array)
+fn property(packet: &str, name: &str) -> Option<Vec<String>> {
+  // Element form: <name ...>value</name>
+  let open = format!("<{}", name);
+  let mut search = 0;
+  while let Some(found) = packet[search..].find(&open) {
+    let start = search + found + open.len();
+    search = start;
+    let Some(next) = packet[start..].chars().next() else {
+      break;
+    };
+    if !(next == '>' || next == '/' || next.is_whitespace()) {
+      continue;
+    }
+    let open_end = start + packet[start..].find('>')?;
+    // <name rdf:resource="..."/> holds URI values
+    if packet[..open_end].ends_with('/') {
+      let tag = &packet[start..open_end];
+      if let Some(value) = attribute(tag, "rdf:resource") {
+        return Some(vec![value]);
+      }
+      continue;
+    }
+    let close = format!("</{}>", name);
+    let inner_end = open_end + packet[open_end..].find(&close)?;
+    let inner = &packet[open_end + 1..inner_end];
+    let values = if inner.contains("<rdf:li") {
+      list_items(inner)
+    } else {
+      vec![strip_tags(inner)]
+    };
+    return Some(values).filter(|v| !v.is_empty());
+  }
+
+  // Attribute form: name="value"
+  attribute(packet, name).map(|value| vec![value])
+}
+
+/// Value of a `name="..."` / `name='...'` attribute
+fn attribute(xml: &str, name: &str) -> Option<String> {
+  let mut search = 0;
+  while let Some(found) = xml[search..].find(name) {
+    let start = search + found;
+    search = start + name.len();
+    let preceded_by_space = xml[..start].chars().next_back().is_some_and(char::is_whitespace);
+    let rest = xml[search..].trim_start();
+    let Some(rest) = rest.strip_prefix('=') else {
+      continue;
+    };
+    let rest = rest.trim_start();
+    let Some(quote) = rest.chars().next().filter(|c| *c == '"' || *c == '\'') else {
+      continue;
+    };
+    if !preceded_by_space {
+      continue;
+    }
+    let value = &rest[1..];
+    let end = value.find(quote)?;
+    return Some(decode_entities(&value[..end]));
+  }
+  None
+}
+
+/// Parse the common properties of an XMP packet (extended XMP is searched too)
+pub fn parse_xmp(packet: String, extended: Option<String>) -> XmpData {
+  let get = |name: &str| {
+    property(&packet, name).or_else(|| extended.as_deref().and_then(|e| property(e, name)))
+  };
+  let text = |name: &str| get(name).and_then(|values| values.into_iter().next());
+
+  XmpData {
+    title: text("dc:title"),
+    description: text("dc:description"),
+    creator: get("dc:creator"),
+    rights: text("dc:rights"),
+    subject: get("dc:subject"),
+    headline: text("photoshop:Headline"),
+    credit: text("photoshop:Credit"),
+    source: text("photoshop:Source"),
+    city: text("photoshop:City"),
+    state: text("photoshop:State"),
+    country: text("photoshop:Country"),
+    country_code: text("Iptc4xmpCore:CountryCode"),
+    location: text("Iptc4xmpCore:Location"),
+    usage_terms: text("xmpRights:UsageTerms"),
+    web_statement: text("xmpRights:WebStatement"),
+    packet,
+    extended,
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  const PACKET: &str = r#"<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:photoshop="http://ns.adobe.com/photoshop/1.0/"
+    xmlns:xmpRights="http://ns.adobe.com/xap/1.0/rights/"
+    photoshop:City="Paris" photoshop:Credit='Agence &amp; Co'>
+   <dc:title><rdf:Alt><rdf:li xml:lang="fr">Tour</rdf:li><rdf:li xml:lang="x-default">Tower</rdf:li></rdf:Alt></dc:title>
+   <dc:creator><rdf:Seq><rdf:li>Jane Doe</rdf:li></rdf:Seq></dc:creator>
+   <dc:subject><rdf:Bag><rdf:li>paris</rdf:li><rdf:li>night</rdf:li></rdf:Bag></dc:subject>
+   <xmpRights:WebStatement rdf:resource="https://example.com/license"/>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>"#;
+
+  #[test]
+  fn test_parse_xmp() {
+    let xmp = parse_xmp(PACKET.to_string(), None);
+
+    assert_eq!(xmp.title.as_deref(), Some("Tower"));
+    assert_eq!(xmp.creator, Some(vec!["Jane Doe".to_string()]));
+    assert_eq!(xmp.subject, Some(vec!["paris".to_string(), "night".to_string()]));
+    assert_eq!(xmp.city.as_deref(), Some("Paris"));
+    assert_eq!(xmp.credit.as_deref(), Some("Agence & Co"));
+    assert_eq!(xmp.web_statement.as_deref(), Some("https://example.com/license"));
+    assert!(xmp.rights.is_none());
+  }
+
+  #[test]
+  fn test_find_jpeg_extended_xmp() {
+    let main = r#"<x:xmpmeta><rdf:Description xmpNote:HasExtendedXMP="0123456789ABCDEF0123456789ABCDEF"/></x:xmpmeta>"#;
+    let extension = b"<x:xmpmeta>extended</x:xmpmeta>";
+
+    let mut jpeg = vec![0xFF, 0xD8];
+    let mut push_app1 = |payload: Vec<u8>| {
+      jpeg.extend_from_slice(&[0xFF, 0xE1]);
+      jpeg.extend_from_slice(&((payload.len() + 2) as u16).to_be_bytes());
+      jpeg.extend_from_slice(&payload);
+    };
+    push_app1([JPEG_XMP_NAMESPACE, main.as_bytes()].concat());
+    // Extension split into two chunks, stored out of order
+    for (offset, part) in [(12usize, &extension[12..]), (0, &extension[..12])] {
+      let mut payload = JPEG_XMP_EXTENSION_NAMESPACE.to_vec();
+      payload.extend_from_slice(b"0123456789ABCDEF0123456789ABCDEF");
+      payload.extend_from_slice(&(extension.len() as u32).to_be_bytes());
+      payload.extend_from_slice(&(offset as u32).to_be_bytes());
+      payload.extend_from_slice(part);
+      push_app1(payload);
+    }
+    jpeg.extend_from_slice(&[0xFF, 0xDA]);
+
+    let (packet, extended) = find_xmp(&jpeg).unwrap();
+    assert_eq!(packet, main);
+    assert_eq!(extended.as_deref(), Some("<x:xmpmeta>extended</x:xmpmeta>"));
+  }
+}
@@ -1,9 +1,27 @@
 //! Metadata/EXIF writing support for images
-//! Supports writing EXIF data to WebP, JPEG, and PNG formats
+//! Supports writing EXIF and XMP data to WebP, JPEG, and PNG formats, and IPTC-IIM to JPEG
 //! Uses img-parts crate for chunk manipulation
 
+use std::collections::{BTreeMap, HashSet};
+use std::io::Write;
+
+use flate2::write::ZlibEncoder;
+use flate2::Compression;
+
+use img_parts::jpeg::markers::{APP0, APP1, APP13, APP2};
+use img_parts::jpeg::{Jpeg, JpegSegment};
+use img_parts::png::{Png, PngChunk};
+use img_parts::riff::{RiffChunk, RiffContent};
+use img_parts::webp::{WebP, CHUNK_ALPH, CHUNK_ANIM, CHUNK_EXIF, CHUNK_ICCP, CHUNK_VP8L, CHUNK_VP8X, CHUNK_XMP};
+use img_parts::{Bytes, DynImage, ImageEXIF, ImageICC};
+
 use crate::error::ImageError;
-use img_parts::{jpeg::Jpeg, png::Png, webp::WebP, Bytes, ImageEXIF};
+use crate::metadata::iptc::{self, datasets};
+use crate::metadata::{
+    find_exif, find_icc_profile, find_xmp, read_png_text, JPEG_XMP_EXTENSION_NAMESPACE, JPEG_XMP_NAMESPACE,
+    PNG_XMP_KEYWORD,
+};
+use crate::{ExifIfd, IptcData, PngTextEntry};
 
 /// EXIF field IDs (TIFF tags)
 pub mod exif_tags {
@@ -18,6 +36,16 @@ pub mod exif_tags {
     pub const EXIF_IFD: u16 = 0x8769;
     pub const DATE_TIME_ORIGINAL: u16 = 0x9003;
     pub const USER_COMMENT: u16 = 0x9286;
+    pub const GPS_IFD: u16 = 0x8825;
+    pub const GPS_VERSION_ID: u16 = 0x0000;
+    pub const GPS_LATITUDE_REF: u16 = 0x0001;
+    pub const GPS_LATITUDE: u16 = 0x0002;
+    pub const GPS_LONGITUDE_REF: u16 = 0x0003;
+    pub const GPS_LONGITUDE: u16 = 0x0004;
+    pub const GPS_ALTITUDE_REF: u16 = 0x0005;
+    pub const GPS_ALTITUDE: u16 = 0x0006;
+    pub const GPS_TIME_STAMP: u16 = 0x0007;
+    pub const GPS_DATE_STAMP: u16 = 0x001D;
 }
 
 /// User-provided EXIF fields for writing
@@ -33,6 +61,68 @@ pub struct ExifWriteOptions {
     pub make: Option<String>,
     pub model: Option<String>,
     pub orientation: Option<u16>,
+    /// Decimal degrees, negative = south
+    pub gps_latitude: Option<f64>,
+    /// Decimal degrees, negative = west
+    pub gps_longitude: Option<f64>,
+    /// Meters, negative = below sea level
+    pub gps_altitude: Option<f64>,
+    pub gps_timestamp: Option<GpsTimestamp>,
+    /// Arbitrary tags, applied after the fields above
+    pub tags: Vec<ExifTagWrite>,
+}
+
+/// UTC GPS fix time, split the way GPSDateStamp/GPSTimeStamp store it
+#[derive(Debug, Clone, PartialEq)]
+pub struct GpsTimestamp {
+    /// YYYY:MM:DD
+    pub date: String,
+    pub hour: u32,
+    pub minute: u32,
+    pub second: f64,
+}
+
+impl GpsTimestamp {
+    /// Parse "YYYY:MM:DD HH:MM:SS" or ISO 8601 UTC ("YYYY-MM-DDTHH:MM:SS(.sss)Z")
+    pub fn parse(value: &str) -> Result<Self, ImageError> {
+        let invalid = || {
+            ImageError::ProcessingError(format!(
+                "Invalid GPS timestamp '{}': expected YYYY:MM:DD HH:MM:SS or an ISO 8601 UTC time",
+                value
+            ))
+        };
+
+        let value = value.trim();
+        let (date, time) = value.split_once(['T', ' ']).ok_or_else(invalid)?;
+        let time = time.strip_suffix('Z').unwrap_or(time);
+
+        let date_parts: Vec<u32> = date
+            .split([':', '-'])
+            .map(|part| part.parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        let [year, month, day] = date_parts[..] else {
+            return Err(invalid());
+        };
+
+        let time_parts: Vec<&str> = time.split(':').collect();
+        let [hour, minute, second] = time_parts[..] else {
+            return Err(invalid());
+        };
+        let hour: u32 = hour.parse().map_err(|_| invalid())?;
+        let minute: u32 = minute.parse().map_err(|_| invalid())?;
+        let second: f64 = second.parse().map_err(|_| invalid())?;
+
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) || hour > 23 || minute > 59 || !(0.0..61.0).contains(&second) {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            date: format!("{:04}:{:02}:{:02}", year, month, day),
+            hour,
+            minute,
+            second,
+        })
+    }
 }
 
 impl ExifWriteOptions {
@@ -47,207 +137,442 @@ impl ExifWriteOptions {
             && self.make.is_none()
             && self.model.is_none()
             && self.orientation.is_none()
+            && !self.has_gps()
+            && self.tags.is_empty()
+    }
+
+    /// Whether any GPS field is set
+    pub fn has_gps(&self) -> bool {
+        self.gps_latitude.is_some()
+            || self.gps_longitude.is_some()
+            || self.gps_altitude.is_some()
+            || self.gps_timestamp.is_some()
     }
 }
 
-/// Build raw EXIF bytes from ExifWriteOptions
-/// Creates a minimal valid EXIF structure with IFD0 entries
-pub fn build_exif_bytes(options: &ExifWriteOptions) -> Vec<u8> {
-    let mut entries: Vec<(u16, Vec<u8>)> = Vec::new();
+/// TIFF field types used when building IFD entries
+pub mod field_types {
+    pub const BYTE: u16 = 1;
+    pub const ASCII: u16 = 2;
+    pub const SHORT: u16 = 3;
+    pub const LONG: u16 = 4;
+    pub const RATIONAL: u16 = 5;
+    pub const UNDEFINED: u16 = 7;
+    pub const SRATIONAL: u16 = 10;
+}
 
-    // Add string fields
-    if let Some(ref desc) = options.image_description {
-        entries.push((exif_tags::IMAGE_DESCRIPTION, string_to_ascii(desc)));
-    }
-    if let Some(ref artist) = options.artist {
-        entries.push((exif_tags::ARTIST, string_to_ascii(artist)));
-    }
-    if let Some(ref copyright) = options.copyright {
-        entries.push((exif_tags::COPYRIGHT, string_to_ascii(copyright)));
-    }
-    if let Some(ref software) = options.software {
-        entries.push((exif_tags::SOFTWARE, string_to_ascii(software)));
-    }
-    if let Some(ref dt) = options.date_time {
-        entries.push((exif_tags::DATE_TIME, string_to_ascii(dt)));
+/// Typed value of an EXIF tag to write
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExifValue {
+    Byte(Vec<u8>),
+    Ascii(String),
+    Short(Vec<u16>),
+    Long(Vec<u32>),
+    Rational(Vec<(u32, u32)>),
+    SRational(Vec<(i32, i32)>),
+    Undefined(Vec<u8>),
+}
+
+impl ExifValue {
+    fn field_type(&self) -> u16 {
+        match self {
+            ExifValue::Byte(_) => field_types::BYTE,
+            ExifValue::Ascii(_) => field_types::ASCII,
+            ExifValue::Short(_) => field_types::SHORT,
+            ExifValue::Long(_) => field_types::LONG,
+            ExifValue::Rational(_) => field_types::RATIONAL,
+            ExifValue::SRational(_) => field_types::SRATIONAL,
+            ExifValue::Undefined(_) => field_types::UNDEFINED,
+        }
     }
-    if let Some(ref make) = options.make {
-        entries.push((exif_tags::MAKE, string_to_ascii(make)));
+
+    fn count(&self) -> u32 {
+        let count = match self {
+            ExifValue::Byte(values) | ExifValue::Undefined(values) => values.len(),
+            // Including the NUL terminator
+            ExifValue::Ascii(text) => text.len() + 1,
+            ExifValue::Short(values) => values.len(),
+            ExifValue::Long(values) => values.len(),
+            ExifValue::Rational(values) => values.len(),
+            ExifValue::SRational(values) => values.len(),
+        };
+        count as u32
     }
-    if let Some(ref model) = options.model {
-        entries.push((exif_tags::MODEL, string_to_ascii(model)));
+
+    /// Value bytes in the payload's byte order
+    fn encode(&self, big_endian: bool) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        match self {
+            ExifValue::Byte(values) | ExifValue::Undefined(values) => bytes.extend_from_slice(values),
+            ExifValue::Ascii(text) => {
+                bytes.extend_from_slice(text.as_bytes());
+                bytes.push(0);
+            }
+            ExifValue::Short(values) => values.iter().for_each(|&v| put_u16(&mut bytes, v, big_endian)),
+            ExifValue::Long(values) => values.iter().for_each(|&v| put_u32(&mut bytes, v, big_endian)),
+            ExifValue::Rational(values) => values.iter().for_each(|&(num, den)| {
+                put_u32(&mut bytes, num, big_endian);
+                put_u32(&mut bytes, den, big_endian);
+            }),
+            ExifValue::SRational(values) => values.iter().for_each(|&(num, den)| {
+                put_u32(&mut bytes, num as u32, big_endian);
+                put_u32(&mut bytes, den as u32, big_endian);
+            }),
+        }
+        bytes
     }
+}
+
+/// One tag to write
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExifTagWrite {
+    pub ifd: ExifIfd,
+    pub tag: u16,
+    pub value: ExifValue,
+}
 
-    // Orientation (SHORT type)
-    if let Some(orientation) = options.orientation {
-        entries.push((exif_tags::ORIENTATION, vec![
-            (orientation & 0xFF) as u8,
-            ((orientation >> 8) & 0xFF) as u8,
-        ]));
+impl ExifTagWrite {
+    pub fn new(ifd: ExifIfd, tag: u16, value: ExifValue) -> Self {
+        Self { ifd, tag, value }
     }
+}
 
-    // Build minimal EXIF structure
-    build_tiff_exif(&entries, options)
+fn put_u16(bytes: &mut Vec<u8>, value: u16, big_endian: bool) {
+    bytes.extend_from_slice(&if big_endian {
+        value.to_be_bytes()
+    } else {
+        value.to_le_bytes()
+    });
 }
 
-/// Convert string to null-terminated ASCII bytes
-fn string_to_ascii(s: &str) -> Vec<u8> {
-    let mut bytes = s.as_bytes().to_vec();
-    bytes.push(0); // Null terminator
-    bytes
+fn put_u32(bytes: &mut Vec<u8>, value: u32, big_endian: bool) {
+    bytes.extend_from_slice(&if big_endian {
+        value.to_be_bytes()
+    } else {
+        value.to_le_bytes()
+    });
 }
 
-/// Build a minimal TIFF/EXIF structure
-fn build_tiff_exif(ifd0_entries: &[(u16, Vec<u8>)], options: &ExifWriteOptions) -> Vec<u8> {
-    let mut exif = Vec::new();
+impl ExifWriteOptions {
+    /// The fields as tags, followed by the arbitrary tags
+    pub fn to_tags(&self) -> Vec<ExifTagWrite> {
+        let mut tags = Vec::new();
 
-    // TIFF header (little-endian)
-    exif.extend_from_slice(b"II");           // Little-endian marker
-    exif.extend_from_slice(&42u16.to_le_bytes()); // TIFF magic number
-    exif.extend_from_slice(&8u32.to_le_bytes());  // Offset to IFD0
+        let strings = [
+            (exif_tags::IMAGE_DESCRIPTION, &self.image_description),
+            (exif_tags::ARTIST, &self.artist),
+            (exif_tags::COPYRIGHT, &self.copyright),
+            (exif_tags::SOFTWARE, &self.software),
+            (exif_tags::DATE_TIME, &self.date_time),
+            (exif_tags::MAKE, &self.make),
+            (exif_tags::MODEL, &self.model),
+        ];
+        for (tag, value) in strings {
+            if let Some(value) = value {
+                tags.push(ExifTagWrite::new(ExifIfd::Ifd0, tag, ExifValue::Ascii(value.clone())));
+            }
+        }
+        if let Some(orientation) = self.orientation {
+            tags.push(ExifTagWrite::new(
+                ExifIfd::Ifd0,
+                exif_tags::ORIENTATION,
+                ExifValue::Short(vec![orientation]),
+            ));
+        }
 
-    // Check if we need EXIF IFD for UserComment/DateTimeOriginal
-    let needs_exif_ifd = options.user_comment.is_some() || options.date_time_original.is_some();
+        if let Some(ref dt) = self.date_time_original {
+            tags.push(ExifTagWrite::new(
+                ExifIfd::Exif,
+                exif_tags::DATE_TIME_ORIGINAL,
+                ExifValue::Ascii(dt.clone()),
+            ));
+        }
+        if let Some(ref comment) = self.user_comment {
+            // UserComment has special encoding: 8 bytes charset + data
+            let mut uc_data = b"ASCII\0\0\0".to_vec();
+            uc_data.extend_from_slice(comment.as_bytes());
+            tags.push(ExifTagWrite::new(
+                ExifIfd::Exif,
+                exif_tags::USER_COMMENT,
+                ExifValue::Undefined(uc_data),
+            ));
+        }
 
-    // Count IFD0 entries (add 1 for EXIF IFD pointer if needed)
-    let ifd0_count = ifd0_entries.len() as u16 + if needs_exif_ifd { 1 } else { 0 };
+        tags.extend(self.gps_tags());
+        tags.extend(self.tags.iter().cloned());
+        tags
+    }
 
-    // IFD0 entry count
-    exif.extend_from_slice(&ifd0_count.to_le_bytes());
+    /// GPS IFD tags (coordinates as degree/minute/second rationals)
+    fn gps_tags(&self) -> Vec<ExifTagWrite> {
+        let mut tags = Vec::new();
+        if !self.has_gps() {
+            return tags;
+        }
+        let gps = |tag, value| ExifTagWrite::new(ExifIfd::Gps, tag, value);
 
-    // Calculate data offset (after IFD0 entries and next IFD pointer)
-    // Each entry is 12 bytes, plus 2 bytes for count, plus 4 bytes for next IFD
-    let mut data_offset = 8 + 2 + (ifd0_count as u32 * 12) + 4;
+        // GPSVersionID 2.3.0.0
+        tags.push(gps(exif_tags::GPS_VERSION_ID, ExifValue::Byte(vec![2, 3, 0, 0])));
 
-    // Collect data that doesn't fit inline (> 4 bytes)
-    let mut extra_data: Vec<u8> = Vec::new();
+        if let Some(latitude) = self.gps_latitude {
+            let reference = if latitude < 0.0 { "S" } else { "N" };
+            tags.push(gps(
+                exif_tags::GPS_LATITUDE_REF,
+                ExifValue::Ascii(reference.to_string()),
+            ));
+            tags.push(gps(
+                exif_tags::GPS_LATITUDE,
+                ExifValue::Rational(dms_rationals(latitude.abs())),
+            ));
+        }
+        if let Some(longitude) = self.gps_longitude {
+            let reference = if longitude < 0.0 { "W" } else { "E" };
+            tags.push(gps(
+                exif_tags::GPS_LONGITUDE_REF,
+                ExifValue::Ascii(reference.to_string()),
+            ));
+            tags.push(gps(
+                exif_tags::GPS_LONGITUDE,
+                ExifValue::Rational(dms_rationals(longitude.abs())),
+            ));
+        }
+        if let Some(altitude) = self.gps_altitude {
+            // AltitudeRef 1 = below sea level
+            tags.push(gps(
+                exif_tags::GPS_ALTITUDE_REF,
+                ExifValue::Byte(vec![(altitude < 0.0) as u8]),
+            ));
+            tags.push(gps(
+                exif_tags::GPS_ALTITUDE,
+                ExifValue::Rational(vec![rational(altitude.abs(), 1000)]),
+            ));
+        }
+        if let Some(ref timestamp) = self.gps_timestamp {
+            let time = vec![
+                rational(timestamp.hour as f64, 1),
+                rational(timestamp.minute as f64, 1),
+                rational(timestamp.second, 1000),
+            ];
+            tags.push(gps(exif_tags::GPS_TIME_STAMP, ExifValue::Rational(time)));
+            tags.push(gps(exif_tags::GPS_DATE_STAMP, ExifValue::Ascii(timestamp.date.clone())));
+        }
 
-    // Write IFD0 entries
-    for (tag, data) in ifd0_entries {
-        let entry = build_ifd_entry(*tag, data, &mut data_offset, &mut extra_data);
-        exif.extend_from_slice(&entry);
+        tags
     }
+}
 
-    // Add EXIF IFD pointer if needed
-    let exif_ifd_offset = if needs_exif_ifd {
-        let offset = data_offset + extra_data.len() as u32;
-        let entry = [
-            (exif_tags::EXIF_IFD & 0xFF) as u8,
-            ((exif_tags::EXIF_IFD >> 8) & 0xFF) as u8,
-            4, 0,  // Type = LONG
-            1, 0, 0, 0,  // Count = 1
-            (offset & 0xFF) as u8,
-            ((offset >> 8) & 0xFF) as u8,
-            ((offset >> 16) & 0xFF) as u8,
-            ((offset >> 24) & 0xFF) as u8,
-        ];
-        exif.extend_from_slice(&entry);
-        Some(offset)
-    } else {
-        None
-    };
-
-    // Next IFD offset (0 = no more IFDs)
-    exif.extend_from_slice(&0u32.to_le_bytes());
+/// Encode an unsigned RATIONAL with a fixed denominator
+fn rational(value: f64, denominator: u32) -> (u32, u32) {
+    let numerator = (value * denominator as f64).round().clamp(0.0, u32::MAX as f64) as u32;
+    (numerator, denominator)
+}
 
-    // Append extra data
-    exif.extend_from_slice(&extra_data);
+/// Encode decimal degrees as three RATIONALs (degrees, minutes, seconds)
+fn dms_rationals(degrees: f64) -> Vec<(u32, u32)> {
+    let whole_degrees = degrees.trunc();
+    let total_minutes = (degrees - whole_degrees) * 60.0;
+    let whole_minutes = total_minutes.trunc();
+    let seconds = (total_minutes - whole_minutes) * 60.0;
 
-    // Write EXIF IFD if needed
-    if let Some(_offset) = exif_ifd_offset {
-        let mut exif_entries: Vec<(u16, Vec<u8>)> = Vec::new();
+    // Seconds to 1/10000 - about 3mm at the equator
+    vec![
+        rational(whole_degrees, 1),
+        rational(whole_minutes, 1),
+        rational(seconds, 10000),
+    ]
+}
 
-        if let Some(ref dt) = options.date_time_original {
-            exif_entries.push((exif_tags::DATE_TIME_ORIGINAL, string_to_ascii(dt)));
+/// Closest fraction to a non-negative value with numerator and denominator at most `max`
+/// (continued fraction convergents)
+pub fn approximate_fraction(value: f64, max: u32) -> (u32, u32) {
+    let max = max as u64;
+    let (mut num, mut den) = (1u64, 0u64);
+    let (mut prev_num, mut prev_den) = (0u64, 1u64);
+    let mut x = value;
+    for _ in 0..64 {
+        let whole = x.floor();
+        if !(0.0..=max as f64).contains(&whole) {
+            break;
         }
-
-        if let Some(ref comment) = options.user_comment {
-            // UserComment has special encoding: 8 bytes charset + data
-            let mut uc_data = vec![0x41, 0x53, 0x43, 0x49, 0x49, 0x00, 0x00, 0x00]; // "ASCII\0\0\0"
-            uc_data.extend_from_slice(comment.as_bytes());
-            exif_entries.push((exif_tags::USER_COMMENT, uc_data));
+        let next_num = whole as u64 * num + prev_num;
+        let next_den = whole as u64 * den + prev_den;
+        if next_num > max || next_den > max {
+            break;
         }
+        (prev_num, prev_den, num, den) = (num, den, next_num, next_den);
 
-        // EXIF IFD entry count
-        let exif_ifd_count = exif_entries.len() as u16;
-        exif.extend_from_slice(&exif_ifd_count.to_le_bytes());
-
-        // Calculate data offset for EXIF IFD
-        let mut exif_data_offset = exif.len() as u32 + (exif_ifd_count as u32 * 12) + 4;
-        let mut exif_extra_data: Vec<u8> = Vec::new();
-
-        for (tag, data) in &exif_entries {
-            let entry = build_ifd_entry(*tag, data, &mut exif_data_offset, &mut exif_extra_data);
-            exif.extend_from_slice(&entry);
+        let fraction = x - whole;
+        if fraction < 1e-12 {
+            break;
         }
+        x = 1.0 / fraction;
+    }
 
-        // Next IFD offset (0 = end)
-        exif.extend_from_slice(&0u32.to_le_bytes());
-
-        // Append EXIF IFD extra data
-        exif.extend_from_slice(&exif_extra_data);
+    if den == 0 {
+        // Larger than max
+        (max as u32, 1)
+    } else {
+        (num as u32, den as u32)
     }
+}
 
-    exif
+/// A rewritten IFD entry: kept verbatim from the existing payload, or newly encoded
+enum IfdSlot {
+    Raw([u8; 12]),
+    Value(ExifValue),
 }
 
-/// Build a single IFD entry (12 bytes)
-fn build_ifd_entry(tag: u16, data: &[u8], data_offset: &mut u32, extra_data: &mut Vec<u8>) -> [u8; 12] {
-    let mut entry = [0u8; 12];
+/// Append an IFD (entries in tag order, next-IFD pointer, then out-of-line values)
+/// Returns its offset
+fn append_ifd(tiff: &mut Vec<u8>, slots: &BTreeMap<u16, IfdSlot>, next: u32, big_endian: bool) -> u32 {
+    // Word alignment
+    if !tiff.len().is_multiple_of(2) {
+        tiff.push(0);
+    }
+    let offset = tiff.len();
+    let data_offset = offset + 2 + slots.len() * 12 + 4;
+    let mut values: Vec<u8> = Vec::new();
 
-    // Tag (2 bytes)
-    entry[0] = (tag & 0xFF) as u8;
-    entry[1] = ((tag >> 8) & 0xFF) as u8;
+    put_u16(tiff, slots.len() as u16, big_endian);
+    for (&tag, slot) in slots {
+        match slot {
+            IfdSlot::Raw(entry) => tiff.extend_from_slice(entry),
+            IfdSlot::Value(value) => {
+                put_u16(tiff, tag, big_endian);
+                put_u16(tiff, value.field_type(), big_endian);
+                put_u32(tiff, value.count(), big_endian);
+                let bytes = value.encode(big_endian);
+                if bytes.len() <= 4 {
+                    // Value fits inline, left-justified
+                    let mut inline = [0u8; 4];
+                    inline[..bytes.len()].copy_from_slice(&bytes);
+                    tiff.extend_from_slice(&inline);
+                } else {
+                    put_u32(tiff, (data_offset + values.len()) as u32, big_endian);
+                    values.extend_from_slice(&bytes);
+                    if !values.len().is_multiple_of(2) {
+                        values.push(0);
+                    }
+                }
+            }
+        }
+    }
+    put_u32(tiff, next, big_endian);
+    tiff.extend_from_slice(&values);
 
-    // Determine type based on tag
-    let (type_id, count) = match tag {
-        exif_tags::ORIENTATION => (3u16, 1u32), // SHORT
-        exif_tags::USER_COMMENT => (7u16, data.len() as u32), // UNDEFINED
-        _ => (2u16, data.len() as u32), // ASCII
-    };
+    offset as u32
+}
 
-    // Type (2 bytes)
-    entry[2] = (type_id & 0xFF) as u8;
-    entry[3] = ((type_id >> 8) & 0xFF) as u8;
+/// Merge tags into a TIFF-structured payload, or a new little-endian one when `existing` is None
+/// Existing bytes never move: rewritten IFDs and new values are appended and the header
+/// repointed, so MakerNote, thumbnail and (for TIFF files) strip offsets stay valid.
+/// The superseded entry tables and the values of replaced tags are zeroed in place.
+/// Works on EXIF payloads and whole TIFF files alike
+pub fn merge_exif(existing: Option<&[u8]>, tags: &[ExifTagWrite]) -> Vec<u8> {
+    let existing = existing.filter(|data| matches!(data.get(0..4), Some(b"MM\0*") | Some(b"II*\0")));
+    if let (Some(existing), true) = (existing, tags.is_empty()) {
+        return existing.to_vec();
+    }
+    let mut tiff = existing
+        .map(|data| data.to_vec())
+        .unwrap_or_else(|| b"II*\0\0\0\0\0".to_vec());
 
-    // Count (4 bytes)
-    entry[4] = (count & 0xFF) as u8;
-    entry[5] = ((count >> 8) & 0xFF) as u8;
-    entry[6] = ((count >> 16) & 0xFF) as u8;
-    entry[7] = ((count >> 24) & 0xFF) as u8;
+    // Existing IFD0, Exif and GPS entries
+    let Some(editor) = TiffEditor::new(&mut tiff) else {
+        return tiff;
+    };
+    let big_endian = editor.big_endian;
+    let ifd0 = editor
+        .u32(4)
+        .filter(|&offset| offset != 0)
+        .map(|offset| offset as usize);
+    let read_entries = |ifd: Option<usize>| -> Vec<(u16, [u8; 12])> {
+        let Some((ifd, (count, _))) = ifd.and_then(|ifd| Some((ifd, editor.ifd_extent(ifd)?))) else {
+            return Vec::new();
+        };
+        (0..count.min(MAX_IFD_ENTRIES))
+            .filter_map(|i| {
+                let entry = ifd + 2 + i * 12;
+                let raw: [u8; 12] = editor.data.get(entry..entry + 12)?.try_into().ok()?;
+                Some((editor.u16(entry)?, raw))
+            })
+            .collect()
+    };
+    let sub_ifd = |tag: u16| {
+        ifd0.and_then(|ifd| editor.find_entry(ifd, tag))
+            .and_then(|entry| editor.u32(entry + 8))
+            .map(|offset| offset as usize)
+    };
+    let (exif_ifd, gps_ifd) = (sub_ifd(exif_tags::EXIF_IFD), sub_ifd(exif_tags::GPS_IFD));
+    let old_ifd0 = read_entries(ifd0);
+    let old_exif = read_entries(exif_ifd);
+    let old_gps = read_entries(gps_ifd);
+    // IFD1 (thumbnail) stays linked behind the new IFD0
+    let next = ifd0
+        .and_then(|ifd| Some(ifd + editor.ifd_extent(ifd)?.1 - 4))
+        .and_then(|pointer| editor.u32(pointer))
+        .unwrap_or(0);
 
-    // Value/Offset (4 bytes)
-    let value_size = match type_id {
-        3 => 2 * count, // SHORT = 2 bytes each
-        _ => count,     // ASCII/UNDEFINED = 1 byte each
+    let slots = |old: Vec<(u16, [u8; 12])>, ifd: ExifIfd| {
+        let mut slots: BTreeMap<u16, IfdSlot> = old.into_iter().map(|(tag, raw)| (tag, IfdSlot::Raw(raw))).collect();
+        for tag in tags.iter().filter(|tag| tag.ifd == ifd) {
+            slots.insert(tag.tag, IfdSlot::Value(tag.value.clone()));
+        }
+        slots
     };
+    let mut ifd0_slots = slots(old_ifd0, ExifIfd::Ifd0);
 
-    if value_size <= 4 {
-        // Value fits inline
-        for (i, &byte) in data.iter().take(4).enumerate() {
-            entry[8 + i] = byte;
+    // Sub-IFDs are only rewritten when they get new tags; otherwise their pointer is kept
+    // Superseded IFDs with the tags replaced in them, to wipe afterwards
+    let mut superseded: Vec<(usize, Vec<u16>)> = Vec::new();
+    for (ifd, pointer, old, old_offset) in [
+        (ExifIfd::Exif, exif_tags::EXIF_IFD, old_exif, exif_ifd),
+        (ExifIfd::Gps, exif_tags::GPS_IFD, old_gps, gps_ifd),
+    ] {
+        if tags.iter().any(|tag| tag.ifd == ifd) {
+            let offset = append_ifd(&mut tiff, &slots(old, ifd), 0, big_endian);
+            ifd0_slots.insert(pointer, IfdSlot::Value(ExifValue::Long(vec![offset])));
+            if let Some(old_offset) = old_offset {
+                superseded.push((old_offset, replaced_tags(tags, ifd)));
+            }
         }
-    } else {
-        // Store offset to data
-        let offset = *data_offset + extra_data.len() as u32;
-        entry[8] = (offset & 0xFF) as u8;
-        entry[9] = ((offset >> 8) & 0xFF) as u8;
-        entry[10] = ((offset >> 16) & 0xFF) as u8;
-        entry[11] = ((offset >> 24) & 0xFF) as u8;
+    }
+    if let Some(ifd0) = ifd0 {
+        superseded.push((ifd0, replaced_tags(tags, ExifIfd::Ifd0)));
+    }
 
-        // Append data to extra_data
-        extra_data.extend_from_slice(data);
+    let ifd0_offset = append_ifd(&mut tiff, &ifd0_slots, next, big_endian);
+    let mut header = Vec::with_capacity(4);
+    put_u32(&mut header, ifd0_offset, big_endian);
+    tiff[4..8].copy_from_slice(&header);
 
-        // Pad to word boundary
-        if data.len() % 2 != 0 {
-            extra_data.push(0);
+    // Don't leave old values (e.g. coordinates, names) recoverable as orphaned bytes:
+    // zero the values of replaced tags and the old entry tables. Values of carried-over
+    // entries are still referenced and stay
+    if let Some(mut editor) = TiffEditor::new(&mut tiff) {
+        for (ifd, replaced) in superseded {
+            let Some((count, len)) = editor.ifd_extent(ifd) else {
+                continue;
+            };
+            for i in 0..count.min(MAX_IFD_ENTRIES) {
+                let entry = ifd + 2 + i * 12;
+                if editor.u16(entry).is_some_and(|tag| replaced.contains(&tag)) {
+                    editor.wipe_value(entry);
+                }
+            }
+            editor.zero(ifd, len);
         }
     }
 
-    entry
+    tiff
+}
+
+/// Tags of an IFD that a merge overwrites
+fn replaced_tags(tags: &[ExifTagWrite], ifd: ExifIfd) -> Vec<u16> {
+    tags.iter().filter(|tag| tag.ifd == ifd).map(|tag| tag.tag).collect()
 }
 
-/// Write EXIF data to a WebP image
+/// Write EXIF data to a WebP image, merged into any existing EXIF
 pub fn write_webp_exif(data: &[u8], exif_options: &ExifWriteOptions) -> Result<Vec<u8>, ImageError> {
     if exif_options.is_empty() {
         return Ok(data.to_vec());
@@ -256,13 +581,17 @@ pub fn write_webp_exif(data: &[u8], exif_options: &ExifWriteOptions) -> Result<V
     let mut webp = WebP::from_bytes(Bytes::copy_from_slice(data))
         .map_err(|e| ImageError::ProcessingError(format!("Failed to parse WebP: {}", e)))?;
 
-    let exif_bytes = build_exif_bytes(exif_options);
+    let exif_bytes = merge_exif(find_exif(data), &exif_options.to_tags());
     webp.set_exif(Some(Bytes::from(exif_bytes)));
+    sync_webp_vp8x(&mut webp)?;
 
     Ok(webp.encoder().bytes().to_vec())
 }
 
-/// Write EXIF data to a JPEG image
+/// Largest EXIF payload that fits one JPEG APP1 segment next to the "Exif\0\0" header
+const MAX_JPEG_EXIF: usize = 65533 - 6;
+
+/// Write EXIF data to a JPEG image, merged into any existing EXIF
 pub fn write_jpeg_exif(data: &[u8], exif_options: &ExifWriteOptions) -> Result<Vec<u8>, ImageError> {
     if exif_options.is_empty() {
         return Ok(data.to_vec());
@@ -271,29 +600,187 @@ pub fn write_jpeg_exif(data: &[u8], exif_options: &ExifWriteOptions) -> Result<V
     let mut jpeg = Jpeg::from_bytes(Bytes::copy_from_slice(data))
         .map_err(|e| ImageError::ProcessingError(format!("Failed to parse JPEG: {}", e)))?;
 
-    let exif_bytes = build_exif_bytes(exif_options);
+    let exif_bytes = merge_exif(find_exif(data), &exif_options.to_tags());
+    if exif_bytes.len() > MAX_JPEG_EXIF {
+        return Err(ImageError::ProcessingError(format!(
+            "EXIF payload is {} bytes, JPEG allows at most {}",
+            exif_bytes.len(),
+            MAX_JPEG_EXIF
+        )));
+    }
     jpeg.set_exif(Some(Bytes::from(exif_bytes)));
 
     Ok(jpeg.encoder().bytes().to_vec())
 }
 
-/// Write metadata to a PNG image using tEXt chunks
-/// PNG doesn't use EXIF, but we can add tEXt/iTXt chunks for metadata
-#[allow(dead_code)]
-pub fn write_png_metadata(data: &[u8], exif_options: &ExifWriteOptions) -> Result<Vec<u8>, ImageError> {
+/// Store a TIFF-structured EXIF payload in a PNG eXIf chunk before the image data,
+/// replacing any existing one
+fn set_png_exif(data: &[u8], tiff: Vec<u8>) -> Result<Vec<u8>, ImageError> {
+    let mut png = Png::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse PNG: {}", e)))?;
+
+    // Not set_exif: img-parts appends eXIf after the image data, where readers may ignore it
+    let chunks = png.chunks_mut();
+    chunks.retain(|chunk| chunk.kind() != *b"eXIf");
+    let position = chunks
+        .iter()
+        .position(|chunk| chunk.kind() == *b"IDAT")
+        .unwrap_or(chunks.len().saturating_sub(1));
+    chunks.insert(position, PngChunk::new(*b"eXIf", Bytes::from(tiff)));
+
+    Ok(png.encoder().bytes().to_vec())
+}
+
+/// Write EXIF data to a PNG eXIf chunk, merged into any existing EXIF
+pub fn write_png_exif(data: &[u8], exif_options: &ExifWriteOptions) -> Result<Vec<u8>, ImageError> {
     if exif_options.is_empty() {
         return Ok(data.to_vec());
     }
+    set_png_exif(data, merge_exif(find_exif(data), &exif_options.to_tags()))
+}
 
-    let png = Png::from_bytes(Bytes::copy_from_slice(data))
+/// Write EXIF data straight into the IFDs of a TIFF file
+/// Image data and strip/tile offsets are left untouched
+pub fn write_tiff_exif(data: &[u8], exif_options: &ExifWriteOptions) -> Result<Vec<u8>, ImageError> {
+    if !matches!(data.get(0..4), Some(b"MM\0*") | Some(b"II*\0")) {
+        return Err(ImageError::ProcessingError("Invalid TIFF header".to_string()));
+    }
+    if exif_options.is_empty() {
+        return Ok(data.to_vec());
+    }
+    Ok(merge_exif(Some(data), &exif_options.to_tags()))
+}
+
+/// Write EXIF data to a PNG: an eXIf chunk with every tag, plus text chunks under
+/// the standard keywords for the descriptive fields
+pub fn write_png_metadata(data: &[u8], exif_options: &ExifWriteOptions) -> Result<Vec<u8>, ImageError> {
+    let data = write_png_exif(data, exif_options)?;
+
+    let source = match (&exif_options.make, &exif_options.model) {
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.clone().or_else(|| model.clone()),
+    };
+    let created = exif_options
+        .date_time_original
+        .clone()
+        .or_else(|| exif_options.date_time.clone());
+    let fields = [
+        ("Title", exif_options.image_description.clone()),
+        ("Author", exif_options.artist.clone()),
+        ("Copyright", exif_options.copyright.clone()),
+        ("Software", exif_options.software.clone()),
+        ("Creation Time", created),
+        ("Source", source),
+        ("Comment", exif_options.user_comment.clone()),
+    ];
+    let entries: Vec<PngTextEntry> = fields
+        .into_iter()
+        .filter_map(|(keyword, text)| {
+            Some(PngTextEntry {
+                keyword: keyword.to_string(),
+                text: text?,
+                language: None,
+                translated_keyword: None,
+                compressed: None,
+            })
+        })
+        .collect();
+
+    write_png_text(&data, &entries)
+}
+
+/// Latin-1 bytes of a string, None if it has characters outside Latin-1
+fn encode_latin1(text: &str) -> Option<Vec<u8>> {
+    text.chars().map(|c| u8::try_from(u32::from(c)).ok()).collect()
+}
+
+fn deflate(data: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Encode a text entry as tEXt/zTXt when it is plain Latin-1, iTXt otherwise
+fn png_text_chunk(entry: &PngTextEntry) -> Result<PngChunk, ImageError> {
+    let mut contents = encode_latin1(&entry.keyword)
+        .filter(|keyword| (1..=79).contains(&keyword.len()) && !keyword.contains(&0))
+        .ok_or_else(|| {
+            ImageError::ProcessingError(format!(
+                "Invalid PNG text keyword '{}': must be 1-79 Latin-1 characters",
+                entry.keyword
+            ))
+        })?;
+    contents.push(0);
+
+    let compressed = entry.compressed.unwrap_or(false);
+    let latin1 = if entry.language.is_none() && entry.translated_keyword.is_none() {
+        encode_latin1(&entry.text)
+    } else {
+        None
+    };
+
+    let kind = match latin1 {
+        Some(text) if compressed => {
+            // compression method (0 = deflate), compressed text
+            contents.push(0);
+            contents.extend_from_slice(&deflate(&text)?);
+            *b"zTXt"
+        }
+        Some(text) => {
+            contents.extend_from_slice(&text);
+            *b"tEXt"
+        }
+        None => {
+            // compression flag, method, language \0 translated keyword \0 text
+            contents.extend_from_slice(&[compressed as u8, 0]);
+            contents.extend_from_slice(entry.language.as_deref().unwrap_or_default().as_bytes());
+            contents.push(0);
+            contents.extend_from_slice(entry.translated_keyword.as_deref().unwrap_or_default().as_bytes());
+            contents.push(0);
+            if compressed {
+                contents.extend_from_slice(&deflate(entry.text.as_bytes())?);
+            } else {
+                contents.extend_from_slice(entry.text.as_bytes());
+            }
+            *b"iTXt"
+        }
+    };
+
+    Ok(PngChunk::new(kind, Bytes::from(contents)))
+}
+
+/// Write PNG text chunks before the image data, replacing chunks with the same keywords
+pub fn write_png_text(data: &[u8], entries: &[PngTextEntry]) -> Result<Vec<u8>, ImageError> {
+    if entries.is_empty() {
+        return Ok(data.to_vec());
+    }
+
+    let new_chunks = entries.iter().map(png_text_chunk).collect::<Result<Vec<_>, _>>()?;
+
+    let mut png = Png::from_bytes(Bytes::copy_from_slice(data))
         .map_err(|e| ImageError::ProcessingError(format!("Failed to parse PNG: {}", e)))?;
 
-    // PNG uses tEXt chunks, but img-parts doesn't directly support them
-    // For now, we'll encode EXIF-like data using XMP or return as-is
-    // TODO: Add proper tEXt chunk support
+    // Chunk contents start with the Latin-1 keyword and a NUL
+    let keywords: HashSet<Vec<u8>> = new_chunks
+        .iter()
+        .filter_map(|chunk| chunk.contents().split(|&b| b == 0).next().map(|k| k.to_vec()))
+        .collect();
+    let chunks = png.chunks_mut();
+    chunks.retain(|chunk| {
+        !(matches!(&chunk.kind(), b"tEXt" | b"zTXt" | b"iTXt")
+            && chunk
+                .contents()
+                .split(|&b| b == 0)
+                .next()
+                .is_some_and(|keyword| keywords.contains(keyword)))
+    });
 
-    // For WebP-style EXIF embedding (some viewers support this)
-    // Most PNG tools expect tEXt chunks instead
+    // Before the image data so streaming readers see it
+    let position = chunks
+        .iter()
+        .position(|chunk| chunk.kind() == *b"IDAT")
+        .unwrap_or(chunks.len().saturating_sub(1));
+    chunks.splice(position..position, new_chunks);
 
     Ok(png.encoder().bytes().to_vec())
 }
@@ -303,7 +790,9 @@ pub fn strip_webp_exif(data: &[u8]) -> Result<Vec<u8>, ImageError> {
     let mut webp = WebP::from_bytes(Bytes::copy_from_slice(data))
         .map_err(|e| ImageError::ProcessingError(format!("Failed to parse WebP: {}", e)))?;
 
-    webp.set_exif(None);
+    // Not set_exif(None): img-parts would drop VP8X even when XMP or animation still need it
+    webp.remove_chunks_by_id(CHUNK_EXIF);
+    sync_webp_vp8x(&mut webp)?;
 
     Ok(webp.encoder().bytes().to_vec())
 }
@@ -318,10 +807,954 @@ pub fn strip_jpeg_exif(data: &[u8]) -> Result<Vec<u8>, ImageError> {
     Ok(jpeg.encoder().bytes().to_vec())
 }
 
+/// Largest packet that fits one JPEG APP1 segment next to the XMP namespace
+const MAX_JPEG_XMP: usize = 65533 - JPEG_XMP_NAMESPACE.len();
+
+/// Wrap a bare `x:xmpmeta` document in an xpacket header/trailer
+fn xmp_packet(xmp: &str) -> String {
+    let xmp = xmp.trim();
+    if xmp.starts_with("<?xpacket") {
+        return xmp.to_string();
+    }
+    format!(
+        "<?xpacket begin=\"\u{FEFF}\" id=\"W5M0MpCehiHzreSzNTczkc9d\"?>\n{}\n<?xpacket end=\"w\"?>",
+        xmp
+    )
+}
+
+/// Embed an XMP packet in a JPEG APP1 segment, replacing any existing (extended) XMP
+pub fn write_jpeg_xmp(data: &[u8], xmp: &str) -> Result<Vec<u8>, ImageError> {
+    let packet = xmp_packet(xmp);
+    if packet.len() > MAX_JPEG_XMP {
+        return Err(ImageError::ProcessingError(format!(
+            "XMP packet is {} bytes, JPEG allows at most {}",
+            packet.len(),
+            MAX_JPEG_XMP
+        )));
+    }
+
+    let mut jpeg = Jpeg::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse JPEG: {}", e)))?;
+
+    let is_xmp = |segment: &JpegSegment| {
+        segment.marker() == APP1
+            && (segment.contents().starts_with(JPEG_XMP_NAMESPACE)
+                || segment.contents().starts_with(JPEG_XMP_EXTENSION_NAMESPACE))
+    };
+    let segments = jpeg.segments_mut();
+    segments.retain(|segment| !is_xmp(segment));
+
+    // XMP goes after JFIF (APP0) and the Exif APP1 segment
+    let position = segments
+        .iter()
+        .position(|segment| {
+            !(segment.marker() == APP0 || (segment.marker() == APP1 && segment.contents().starts_with(b"Exif\0\0")))
+        })
+        .unwrap_or(segments.len());
+    let contents = [JPEG_XMP_NAMESPACE, packet.as_bytes()].concat();
+    segments.insert(position, JpegSegment::new_with_contents(APP1, Bytes::from(contents)));
+
+    Ok(jpeg.encoder().bytes().to_vec())
+}
+
+/// Embed an XMP packet in an uncompressed PNG iTXt chunk, replacing any existing XMP
+pub fn write_png_xmp(data: &[u8], xmp: &str) -> Result<Vec<u8>, ImageError> {
+    let mut png = Png::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse PNG: {}", e)))?;
+
+    let chunks = png.chunks_mut();
+    chunks.retain(|chunk| {
+        !(chunk.kind() == *b"iTXt"
+            && chunk.contents().starts_with(PNG_XMP_KEYWORD)
+            && chunk.contents().get(PNG_XMP_KEYWORD.len()) == Some(&0))
+    });
+
+    // keyword \0 compression-flag method language \0 translated-keyword \0 text
+    let mut contents = PNG_XMP_KEYWORD.to_vec();
+    contents.extend_from_slice(&[0, 0, 0, 0, 0]);
+    contents.extend_from_slice(xmp_packet(xmp).as_bytes());
+
+    // Before the image data so streaming readers see it
+    let position = chunks
+        .iter()
+        .position(|chunk| chunk.kind() == *b"IDAT")
+        .unwrap_or(chunks.len().saturating_sub(1));
+    chunks.insert(position, PngChunk::new(*b"iTXt", Bytes::from(contents)));
+
+    Ok(png.encoder().bytes().to_vec())
+}
+
+/// Embed an XMP packet in a WebP `XMP ` chunk, replacing any existing XMP
+pub fn write_webp_xmp(data: &[u8], xmp: &str) -> Result<Vec<u8>, ImageError> {
+    let mut webp = WebP::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse WebP: {}", e)))?;
+
+    webp.remove_chunks_by_id(CHUNK_XMP);
+    let packet = Bytes::from(xmp_packet(xmp).into_bytes());
+    webp.chunks_mut().push(RiffChunk::new(CHUNK_XMP, RiffContent::Data(packet)));
+    sync_webp_vp8x(&mut webp)?;
+
+    Ok(webp.encoder().bytes().to_vec())
+}
+
+/// Embed an XMP packet in a JPEG, PNG or WebP image
+pub fn write_xmp(data: &[u8], xmp: &str) -> Result<Vec<u8>, ImageError> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        write_jpeg_xmp(data, xmp)
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        write_png_xmp(data, xmp)
+    } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        write_webp_xmp(data, xmp)
+    } else {
+        Err(ImageError::UnsupportedFormat(
+            "XMP writing only supported for JPEG, PNG and WebP formats".to_string(),
+        ))
+    }
+}
+
+/// Create or update the VP8X chunk so its feature flags match the chunks present
+/// img-parts only sets the ICC/EXIF bits, and only when it creates the chunk itself
+fn sync_webp_vp8x(webp: &mut WebP) -> Result<(), ImageError> {
+    const ICC: u8 = 0x20;
+    const ALPHA: u8 = 0x10;
+    const EXIF: u8 = 0x08;
+    const XMP: u8 = 0x04;
+    const ANIMATION: u8 = 0x02;
+
+    let existing = webp
+        .chunks()
+        .iter()
+        .position(|chunk| chunk.id() == CHUNK_VP8X);
+    let old_flags = existing
+        .and_then(|pos| webp.chunks()[pos].content().data())
+        .and_then(|data| data.first().copied())
+        .unwrap_or(0);
+
+    // VP8L header: signature byte, then 14-bit width/height and the alpha_is_used bit
+    let lossless_alpha = webp
+        .chunk_by_id(CHUNK_VP8L)
+        .and_then(|chunk| chunk.content().data())
+        .and_then(|data| data.get(1..5))
+        .is_some_and(|bits| u32::from_le_bytes([bits[0], bits[1], bits[2], bits[3]]) & (1 << 28) != 0);
+
+    let mut flags = old_flags & (ALPHA | ANIMATION);
+    if webp.has_chunk(CHUNK_ICCP) {
+        flags |= ICC;
+    }
+    if webp.has_chunk(CHUNK_ALPH) || lossless_alpha {
+        flags |= ALPHA;
+    }
+    if webp.has_chunk(CHUNK_EXIF) {
+        flags |= EXIF;
+    }
+    if webp.has_chunk(CHUNK_XMP) {
+        flags |= XMP;
+    }
+    if webp.has_chunk(CHUNK_ANIM) {
+        flags |= ANIMATION;
+    }
+
+    match existing {
+        Some(pos) => {
+            let mut contents = webp.chunks()[pos]
+                .content()
+                .data()
+                .map(|data| data.to_vec())
+                .unwrap_or_default();
+            if contents.len() < 10 {
+                return Err(ImageError::ProcessingError("Invalid WebP VP8X chunk".to_string()));
+            }
+            contents[0] = flags;
+            webp.chunks_mut()[pos] = RiffChunk::new(CHUNK_VP8X, RiffContent::Data(Bytes::from(contents)));
+        }
+        None if flags & (ICC | EXIF | XMP | ANIMATION) != 0 => {
+            let (width, height) = webp
+                .dimensions()
+                .ok_or_else(|| ImageError::ProcessingError("WebP has no image data".to_string()))?;
+            let mut contents = vec![flags, 0, 0, 0];
+            contents.extend_from_slice(&(width - 1).to_le_bytes()[..3]);
+            contents.extend_from_slice(&(height - 1).to_le_bytes()[..3]);
+            webp.chunks_mut().insert(0, RiffChunk::new(CHUNK_VP8X, RiffContent::Data(Bytes::from(contents))));
+        }
+        None => {}
+    }
+
+    Ok(())
+}
+
+/// ICC data per JPEG APP2 segment - 65533 bytes minus the signature, sequence number and count
+const MAX_JPEG_ICC_CHUNK: usize = 65533 - JPEG_ICC_SIGNATURE.len() - 2;
+/// Signature opening each JPEG APP2 ICC segment
+const JPEG_ICC_SIGNATURE: &[u8] = b"ICC_PROFILE\0";
+
+/// Embed an ICC profile in JPEG APP2 segments, replacing any existing profile
+pub fn write_jpeg_icc(data: &[u8], profile: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let count = profile.len().div_ceil(MAX_JPEG_ICC_CHUNK);
+    if count > 255 {
+        return Err(ImageError::ProcessingError(format!(
+            "ICC profile is {} bytes, JPEG allows at most {}",
+            profile.len(),
+            MAX_JPEG_ICC_CHUNK * 255
+        )));
+    }
+
+    let mut jpeg = Jpeg::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse JPEG: {}", e)))?;
+
+    let segments = jpeg.segments_mut();
+    segments.retain(|segment| !(segment.marker() == APP2 && segment.contents().starts_with(JPEG_ICC_SIGNATURE)));
+
+    // ICC goes after JFIF (APP0) and Exif/XMP (APP1), in sequence order
+    let position = segments
+        .iter()
+        .position(|segment| !matches!(segment.marker(), APP0 | APP1))
+        .unwrap_or(segments.len());
+    let icc_segments = profile.chunks(MAX_JPEG_ICC_CHUNK).enumerate().map(|(i, chunk)| {
+        let header = [JPEG_ICC_SIGNATURE, &[i as u8 + 1, count as u8]].concat();
+        JpegSegment::new_with_contents(APP2, Bytes::from([header.as_slice(), chunk].concat()))
+    });
+    segments.splice(position..position, icc_segments);
+
+    Ok(jpeg.encoder().bytes().to_vec())
+}
+
+/// Embed an ICC profile in a PNG iCCP chunk, replacing any existing profile
+/// The sRGB chunk is dropped - the PNG spec forbids it next to iCCP
+pub fn write_png_icc(data: &[u8], profile: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let mut png = Png::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse PNG: {}", e)))?;
+
+    png.remove_chunks_by_type(*b"sRGB");
+    png.set_icc_profile(Some(Bytes::copy_from_slice(profile)));
+
+    Ok(png.encoder().bytes().to_vec())
+}
+
+/// Embed an ICC profile in a WebP ICCP chunk, replacing any existing profile
+pub fn write_webp_icc(data: &[u8], profile: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let mut webp = WebP::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse WebP: {}", e)))?;
+
+    // Not set_icc_profile: img-parts places the chunk after ALPH/VP8L, ICCP must directly follow VP8X
+    webp.remove_chunks_by_id(CHUNK_ICCP);
+    let position = webp
+        .chunks()
+        .iter()
+        .position(|chunk| chunk.id() == CHUNK_VP8X)
+        .map_or(0, |pos| pos + 1);
+    let chunk = RiffChunk::new(CHUNK_ICCP, RiffContent::Data(Bytes::copy_from_slice(profile)));
+    webp.chunks_mut().insert(position, chunk);
+    sync_webp_vp8x(&mut webp)?;
+
+    Ok(webp.encoder().bytes().to_vec())
+}
+
+/// Embed an ICC profile in a JPEG, PNG or WebP image
+pub fn write_icc_profile(data: &[u8], profile: &[u8]) -> Result<Vec<u8>, ImageError> {
+    if data.starts_with(&[0xFF, 0xD8]) {
+        write_jpeg_icc(data, profile)
+    } else if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        write_png_icc(data, profile)
+    } else if data.len() >= 12 && &data[0..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        write_webp_icc(data, profile)
+    } else {
+        Err(ImageError::UnsupportedFormat(
+            "ICC profile embedding only supported for JPEG, PNG and WebP formats".to_string(),
+        ))
+    }
+}
+
+/// Copy the source's ICC profile into encoded output
+/// Output that already has a profile, or can't carry one, is returned unchanged
+pub fn copy_icc_profile(source: &[u8], output: Vec<u8>) -> Result<Vec<u8>, ImageError> {
+    match find_icc_profile(source) {
+        Some(profile) => add_icc_profile(output, &profile),
+        None => Ok(output),
+    }
+}
+
+/// Embed an ICC profile in encoded output that has none
+/// Output that already has a profile, or can't carry one, is returned unchanged
+pub fn add_icc_profile(output: Vec<u8>, profile: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let supported = output.starts_with(&[0xFF, 0xD8])
+        || output.starts_with(b"\x89PNG\r\n\x1a\n")
+        || (output.len() >= 12 && &output[0..4] == b"RIFF" && &output[8..12] == b"WEBP");
+    if !supported || find_icc_profile(&output).is_some() {
+        return Ok(output);
+    }
+    write_icc_profile(&output, profile)
+}
+
+/// Append one IIM dataset (standard, non-extended)
+fn push_dataset(record: &mut Vec<u8>, number: u8, dataset: u8, value: &[u8]) -> Result<(), ImageError> {
+    if value.len() > 0x7FFF {
+        return Err(ImageError::ProcessingError(format!(
+            "IPTC dataset {}:{} is {} bytes, at most 32767 are allowed",
+            number,
+            dataset,
+            value.len()
+        )));
+    }
+    record.extend_from_slice(&[0x1C, number, dataset]);
+    record.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    record.extend_from_slice(value);
+    Ok(())
+}
+
+/// Build a UTF-8 IPTC-NAA record from the given datasets
+pub fn build_iptc_record(fields: &IptcData) -> Result<Vec<u8>, ImageError> {
+    let mut record = Vec::new();
+    push_dataset(&mut record, 1, 90, iptc::UTF8_ESCAPE)?;
+    push_dataset(&mut record, 2, datasets::RECORD_VERSION, &[0, 4])?;
+
+    // Datasets in ascending number order
+    let lists = [(datasets::KEYWORDS, &fields.keywords), (datasets::BYLINE, &fields.byline)];
+    let texts = [
+        (datasets::OBJECT_NAME, &fields.title),
+        (datasets::BYLINE_TITLE, &fields.byline_title),
+        (datasets::CITY, &fields.city),
+        (datasets::STATE, &fields.state),
+        (datasets::COUNTRY_CODE, &fields.country_code),
+        (datasets::COUNTRY, &fields.country),
+        (datasets::HEADLINE, &fields.headline),
+        (datasets::CREDIT, &fields.credit),
+        (datasets::SOURCE, &fields.source),
+        (datasets::COPYRIGHT, &fields.copyright),
+        (datasets::CAPTION, &fields.caption),
+    ];
+    let mut values: Vec<(u8, &str)> = Vec::new();
+    for (dataset, list) in lists {
+        values.extend(list.iter().flatten().map(|v| (dataset, v.as_str())));
+    }
+    for (dataset, text) in texts {
+        values.extend(text.as_deref().map(|v| (dataset, v)));
+    }
+    // Stable: repeated keywords/bylines keep their order
+    values.sort_by_key(|(dataset, _)| *dataset);
+
+    for (dataset, value) in values {
+        push_dataset(&mut record, 2, dataset, value.as_bytes())?;
+    }
+    Ok(record)
+}
+
+/// Write IPTC-IIM datasets to the JPEG APP13 segment
+/// Replaces the existing IPTC record and keeps the other Photoshop resources
+pub fn write_jpeg_iptc(data: &[u8], iptc_data: &IptcData) -> Result<Vec<u8>, ImageError> {
+    write_jpeg_iptc_record(data, &build_iptc_record(iptc_data)?)
+}
+
+/// Embed an encoded IIM record in the Photoshop APP13 segment, keeping other resources
+pub fn write_jpeg_iptc_record(data: &[u8], record: &[u8]) -> Result<Vec<u8>, ImageError> {
+    let existing = iptc::find_photoshop_resources(data).unwrap_or_default();
+    let mut contents = iptc::PHOTOSHOP_SIGNATURE.to_vec();
+    for resource in iptc::parse_resources(&existing) {
+        // A stale IPTC digest makes Photoshop ignore the new record
+        if resource.id != iptc::IPTC_RESOURCE_ID && resource.id != iptc::IPTC_DIGEST_RESOURCE_ID {
+            contents.extend_from_slice(resource.raw);
+        }
+    }
+    contents.extend_from_slice(b"8BIM");
+    contents.extend_from_slice(&iptc::IPTC_RESOURCE_ID.to_be_bytes());
+    contents.extend_from_slice(&[0, 0]); // Empty name
+    contents.extend_from_slice(&(record.len() as u32).to_be_bytes());
+    contents.extend_from_slice(record);
+    if record.len() % 2 != 0 {
+        contents.push(0);
+    }
+    if contents.len() > 65533 {
+        return Err(ImageError::ProcessingError(format!(
+            "IPTC data is {} bytes, a JPEG APP13 segment holds at most 65533",
+            contents.len()
+        )));
+    }
+
+    let mut jpeg = Jpeg::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse JPEG: {}", e)))?;
+
+    let segments = jpeg.segments_mut();
+    segments.retain(|segment| {
+        !(segment.marker() == APP13 && segment.contents().starts_with(iptc::PHOTOSHOP_SIGNATURE))
+    });
+    // After JFIF, Exif and XMP
+    let position = segments
+        .iter()
+        .position(|segment| segment.marker() != APP0 && segment.marker() != APP1)
+        .unwrap_or(segments.len());
+    segments.insert(position, JpegSegment::new_with_contents(APP13, Bytes::from(contents)));
+
+    Ok(jpeg.encoder().bytes().to_vec())
+}
+
+/// Tag groups for selective EXIF stripping
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ExifCategory {
+    Gps,
+    MakerNote,
+    Serial,
+    Copyright,
+    Artist,
+    Date,
+    Camera,
+    Description,
+    Software,
+    Orientation,
+    Thumbnail,
+    Other,
+}
+
+impl ExifCategory {
+    pub const ALL: [ExifCategory; 12] = [
+        ExifCategory::Gps,
+        ExifCategory::MakerNote,
+        ExifCategory::Serial,
+        ExifCategory::Copyright,
+        ExifCategory::Artist,
+        ExifCategory::Date,
+        ExifCategory::Camera,
+        ExifCategory::Description,
+        ExifCategory::Software,
+        ExifCategory::Orientation,
+        ExifCategory::Thumbnail,
+        ExifCategory::Other,
+    ];
+
+    pub fn parse(name: &str) -> Result<Self, ImageError> {
+        Ok(match name {
+            "gps" => ExifCategory::Gps,
+            "maker_note" | "makerNote" => ExifCategory::MakerNote,
+            "serial" => ExifCategory::Serial,
+            "copyright" => ExifCategory::Copyright,
+            "artist" => ExifCategory::Artist,
+            "date" => ExifCategory::Date,
+            "camera" => ExifCategory::Camera,
+            "description" => ExifCategory::Description,
+            "software" => ExifCategory::Software,
+            "orientation" => ExifCategory::Orientation,
+            "thumbnail" => ExifCategory::Thumbnail,
+            "other" => ExifCategory::Other,
+            _ => {
+                return Err(ImageError::ProcessingError(format!(
+                    "Unknown EXIF category '{}': expected gps, maker_note, serial, copyright, artist, date, camera, description, software, orientation, thumbnail or other",
+                    name
+                )))
+            }
+        })
+    }
+
+    /// Category of an IFD0/Exif IFD tag
+    /// None for structural tags (resolution, color space, pointers, ...) that are never removed
+    fn of_tag(tag: u16) -> Option<Self> {
+        Some(match tag {
+            0x927C => ExifCategory::MakerNote,
+            0xA431 | 0xA435 => ExifCategory::Serial,
+            exif_tags::COPYRIGHT => ExifCategory::Copyright,
+            exif_tags::ARTIST | 0x9C9D | 0xA430 => ExifCategory::Artist,
+            exif_tags::DATE_TIME | 0x9003 | 0x9004 | 0x9010..=0x9012 | 0x9290..=0x9292 => ExifCategory::Date,
+            exif_tags::MAKE | exif_tags::MODEL | 0xA432..=0xA434 => ExifCategory::Camera,
+            exif_tags::IMAGE_DESCRIPTION | exif_tags::USER_COMMENT | 0x9C9B | 0x9C9C | 0x9C9E | 0x9C9F => {
+                ExifCategory::Description
+            }
+            exif_tags::SOFTWARE => ExifCategory::Software,
+            exif_tags::ORIENTATION => ExifCategory::Orientation,
+            // XResolution, YResolution, ResolutionUnit, YCbCrPositioning, ExifVersion,
+            // FlashpixVersion, ColorSpace, PixelX/YDimension, ComponentsConfiguration
+            0x011A | 0x011B | 0x0128 | 0x0213 | 0x9000 | 0xA000..=0xA003 | 0x9101 => return None,
+            exif_tags::EXIF_IFD | exif_tags::GPS_IFD | INTEROP_IFD => return None,
+            _ => ExifCategory::Other,
+        })
+    }
+}
+
+/// Interoperability sub-IFD pointer (Exif IFD)
+const INTEROP_IFD: u16 = 0xA005;
+
+/// Which EXIF categories selective stripping removes
+#[derive(Debug, Clone, Default)]
+pub struct ExifFilter {
+    remove: HashSet<ExifCategory>,
+}
+
+impl ExifFilter {
+    /// `keep` removes every category not listed (except orientation), `remove` adds to that
+    pub fn new(keep: Option<&[String]>, remove: Option<&[String]>) -> Result<Self, ImageError> {
+        let mut removed = HashSet::new();
+
+        if let Some(keep) = keep {
+            let kept = keep
+                .iter()
+                .map(|name| ExifCategory::parse(name))
+                .collect::<Result<HashSet<_>, _>>()?;
+            // Dropping orientation silently rotates the image, so it needs an explicit remove
+            removed.extend(
+                ExifCategory::ALL
+                    .into_iter()
+                    .filter(|c| !kept.contains(c) && *c != ExifCategory::Orientation),
+            );
+        }
+        if let Some(remove) = remove {
+            for name in remove {
+                removed.insert(ExifCategory::parse(name)?);
+            }
+        }
+
+        Ok(Self { remove: removed })
+    }
+
+    pub fn removes(&self, category: ExifCategory) -> bool {
+        self.remove.contains(&category)
+    }
+}
+
+/// Byte-order aware in-place edits of a TIFF payload
+/// Entries are removed without moving any value data, so offsets elsewhere
+/// (including inside MakerNotes) stay valid
+struct TiffEditor<'a> {
+    data: &'a mut [u8],
+    big_endian: bool,
+}
+
+impl<'a> TiffEditor<'a> {
+    fn new(data: &'a mut [u8]) -> Option<Self> {
+        let big_endian = match data.get(0..4)? {
+            b"MM\0*" => true,
+            b"II*\0" => false,
+            _ => return None,
+        };
+        Some(TiffEditor { data, big_endian })
+    }
+
+    fn u16(&self, offset: usize) -> Option<u16> {
+        let bytes: [u8; 2] = self.data.get(offset..offset + 2)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(bytes)
+        } else {
+            u16::from_le_bytes(bytes)
+        })
+    }
+
+    fn u32(&self, offset: usize) -> Option<u32> {
+        let bytes: [u8; 4] = self.data.get(offset..offset + 4)?.try_into().ok()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(bytes)
+        } else {
+            u32::from_le_bytes(bytes)
+        })
+    }
+
+    fn set_u16(&mut self, offset: usize, value: u16) {
+        let bytes = if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        };
+        self.data[offset..offset + 2].copy_from_slice(&bytes);
+    }
+
+    fn set_u32(&mut self, offset: usize, value: u32) {
+        let bytes = if self.big_endian {
+            value.to_be_bytes()
+        } else {
+            value.to_le_bytes()
+        };
+        self.data[offset..offset + 4].copy_from_slice(&bytes);
+    }
+
+    fn zero(&mut self, start: usize, len: usize) {
+        let end = start.saturating_add(len).min(self.data.len());
+        if start < end {
+            self.data[start..end].fill(0);
+        }
+    }
+
+    /// Entry count and byte length of a complete IFD (count, entries, next pointer)
+    fn ifd_extent(&self, ifd: usize) -> Option<(usize, usize)> {
+        let count = self.u16(ifd)? as usize;
+        let len = 2 + count * 12 + 4;
+        (ifd + len <= self.data.len()).then_some((count, len))
+    }
+
+    /// Zero the out-of-line value of an entry
+    fn wipe_value(&mut self, entry: usize) {
+        let (Some(field_type), Some(count)) = (self.u16(entry + 2), self.u32(entry + 4)) else {
+            return;
+        };
+        let Some(size) = crate::metadata::tiff_type_size(field_type) else {
+            return;
+        };
+        let total = size.saturating_mul(count as usize);
+        if total > 4 {
+            if let Some(offset) = self.u32(entry + 8) {
+                self.zero(offset as usize, total);
+            }
+        }
+    }
+
+    /// Remove entry `index` of an IFD, shifting the following entries and the next-IFD pointer up
+    fn remove_entry(&mut self, ifd: usize, index: usize) {
+        let Some((count, len)) = self.ifd_extent(ifd) else {
+            return;
+        };
+        let entry = ifd + 2 + index * 12;
+        self.wipe_value(entry);
+        self.data.copy_within(entry + 12..ifd + len, entry);
+        self.zero(ifd + len - 12, 12);
+        self.set_u16(ifd, (count - 1) as u16);
+    }
+
+    /// Zero a whole IFD including its out-of-line values
+    fn wipe_ifd(&mut self, ifd: usize) {
+        let Some((count, len)) = self.ifd_extent(ifd) else {
+            return;
+        };
+        for i in 0..count.min(MAX_IFD_ENTRIES) {
+            self.wipe_value(ifd + 2 + i * 12);
+        }
+        self.zero(ifd, len);
+    }
+
+    fn filter_ifd(&mut self, ifd: usize, filter: &ExifFilter, visited: &mut HashSet<usize>) {
+        // Guard against IFD loops in malformed files
+        if !visited.insert(ifd) {
+            return;
+        }
+        let Some((count, _)) = self.ifd_extent(ifd) else {
+            return;
+        };
+
+        let mut count = count.min(MAX_IFD_ENTRIES);
+        let mut index = 0;
+        while index < count {
+            let entry = ifd + 2 + index * 12;
+            let Some(tag) = self.u16(entry) else {
+                break;
+            };
+            let pointer = self.u32(entry + 8).map(|p| p as usize);
+
+            let remove = match tag {
+                exif_tags::EXIF_IFD => {
+                    if let Some(pointer) = pointer {
+                        self.filter_ifd(pointer, filter, visited);
+                    }
+                    false
+                }
+                exif_tags::GPS_IFD => {
+                    let remove = filter.removes(ExifCategory::Gps);
+                    if remove {
+                        if let Some(pointer) = pointer {
+                            self.wipe_ifd(pointer);
+                        }
+                    }
+                    remove
+                }
+                _ => ExifCategory::of_tag(tag).is_some_and(|c| filter.removes(c)),
+            };
+
+            if remove {
+                self.remove_entry(ifd, index);
+                count -= 1;
+            } else {
+                index += 1;
+            }
+        }
+    }
+
+    /// Offset of the entry for `tag` in an IFD
+    fn find_entry(&self, ifd: usize, tag: u16) -> Option<usize> {
+        let (count, _) = self.ifd_extent(ifd)?;
+        (0..count.min(MAX_IFD_ENTRIES))
+            .map(|i| ifd + 2 + i * 12)
+            .find(|entry| self.u16(*entry) == Some(tag))
+    }
+
+    /// Overwrite a single SHORT or LONG value, widening SHORT when the value needs it
+    fn set_dimension(&mut self, ifd: usize, tag: u16, value: u32) {
+        let Some(entry) = self.find_entry(ifd, tag) else {
+            return;
+        };
+        if self.u32(entry + 4) != Some(1) {
+            return;
+        }
+        match self.u16(entry + 2) {
+            Some(field_types::SHORT) if value <= u16::MAX as u32 => {
+                self.zero(entry + 8, 4);
+                self.set_u16(entry + 8, value as u16);
+            }
+            Some(field_types::SHORT) | Some(field_types::LONG) => {
+                self.set_u16(entry + 2, field_types::LONG);
+                self.set_u32(entry + 8, value);
+            }
+            _ => {}
+        }
+    }
+
+    /// Drop IFD1 (the embedded thumbnail) and its JPEG data
+    fn remove_thumbnail(&mut self, ifd0: usize) {
+        let Some((_, len)) = self.ifd_extent(ifd0) else {
+            return;
+        };
+        let next = ifd0 + len - 4;
+        let Some(ifd1) = self.u32(next).map(|p| p as usize).filter(|p| *p != 0) else {
+            return;
+        };
+
+        if let Some((count, _)) = self.ifd_extent(ifd1) {
+            let value = |editor: &Self, tag: u16| {
+                (0..count.min(MAX_IFD_ENTRIES))
+                    .map(|i| ifd1 + 2 + i * 12)
+                    .find(|entry| editor.u16(*entry) == Some(tag))
+                    .and_then(|entry| editor.u32(entry + 8))
+            };
+            // JPEGInterchangeFormat / JPEGInterchangeFormatLength
+            if let (Some(offset), Some(length)) = (value(self, 0x0201), value(self, 0x0202)) {
+                self.zero(offset as usize, length as usize);
+            }
+        }
+        self.wipe_ifd(ifd1);
+        self.set_u32(next, 0);
+    }
+}
+
+/// Entries visited per IFD at most - real files have well under 100
+const MAX_IFD_ENTRIES: usize = 512;
+
+/// Remove the filtered EXIF categories from a TIFF-structured payload in place
+pub fn filter_tiff_exif(data: &mut [u8], filter: &ExifFilter) {
+    let Some(mut editor) = TiffEditor::new(data) else {
+        return;
+    };
+    let Some(ifd0) = editor.u32(4).map(|p| p as usize) else {
+        return;
+    };
+
+    editor.filter_ifd(ifd0, filter, &mut HashSet::new());
+    if filter.removes(ExifCategory::Thumbnail) {
+        editor.remove_thumbnail(ifd0);
+    }
+}
+
+/// Update the pixel dimension tags in place: ImageWidth/ImageLength (IFD0) and
+/// PixelXDimension/PixelYDimension (Exif IFD)
+pub fn set_tiff_dimensions(data: &mut [u8], width: u32, height: u32) {
+    let Some(mut editor) = TiffEditor::new(data) else {
+        return;
+    };
+    let Some(ifd0) = editor.u32(4).map(|p| p as usize) else {
+        return;
+    };
+
+    editor.set_dimension(ifd0, 0x0100, width);
+    editor.set_dimension(ifd0, 0x0101, height);
+    let exif_ifd = editor
+        .find_entry(ifd0, exif_tags::EXIF_IFD)
+        .and_then(|entry| editor.u32(entry + 8));
+    if let Some(exif_ifd) = exif_ifd {
+        editor.set_dimension(exif_ifd as usize, 0xA002, width);
+        editor.set_dimension(exif_ifd as usize, 0xA003, height);
+    }
+}
+
+/// Selectively strip EXIF from a JPEG, WebP or PNG image, leaving other metadata alone
+pub fn filter_exif(data: &[u8], filter: &ExifFilter) -> Result<Vec<u8>, ImageError> {
+    let mut image = DynImage::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse image: {}", e)))?
+        .ok_or_else(|| {
+            ImageError::UnsupportedFormat("EXIF stripping only supported for JPEG, WebP and PNG formats".to_string())
+        })?;
+
+    if let Some(exif) = image.exif() {
+        let mut tiff = exif.to_vec();
+        filter_tiff_exif(&mut tiff, filter);
+        image.set_exif(Some(Bytes::from(tiff)));
+    }
+
+    Ok(image.encoder().bytes().to_vec())
+}
+
+/// Embed a TIFF-structured EXIF payload in a JPEG, PNG (eXIf) or WebP image, replacing any existing EXIF
+pub fn write_exif_payload(data: &[u8], tiff: Vec<u8>) -> Result<Vec<u8>, ImageError> {
+    if data.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return set_png_exif(data, tiff);
+    }
+
+    let mut image = DynImage::from_bytes(Bytes::copy_from_slice(data))
+        .map_err(|e| ImageError::ProcessingError(format!("Failed to parse image: {}", e)))?
+        .ok_or_else(|| {
+            ImageError::UnsupportedFormat("EXIF writing only supported for JPEG, WebP and PNG formats".to_string())
+        })?;
+
+    image.set_exif(Some(Bytes::from(tiff)));
+    if let DynImage::WebP(ref mut webp) = image {
+        sync_webp_vp8x(webp)?;
+    }
+
+    Ok(image.encoder().bytes().to_vec())
+}
+
+/// Input metadata that transform and thumbnail carry over to the output
+#[derive(Debug, Clone, Default)]
+pub struct MetadataKeep {
+    /// EXIF categories to drop - None drops EXIF altogether
+    pub exif: Option<ExifFilter>,
+    pub icc: bool,
+    pub xmp: bool,
+    pub iptc: bool,
+    pub text: bool,
+}
+
+impl MetadataKeep {
+    /// Everything when `keep` is None; otherwise exif, icc, xmp, iptc, text
+    /// and EXIF categories (copyright, gps, ...) that keep only those tags
+    pub fn new(keep: Option<&[String]>) -> Result<Self, ImageError> {
+        let Some(keep) = keep else {
+            return Ok(Self {
+                exif: Some(ExifFilter::default()),
+                icc: true,
+                xmp: true,
+                iptc: true,
+                text: true,
+            });
+        };
+
+        let mut policy = Self::default();
+        let mut all_exif = false;
+        let mut categories = Vec::new();
+        for name in keep {
+            match name.as_str() {
+                "exif" => all_exif = true,
+                "icc" => policy.icc = true,
+                "xmp" => policy.xmp = true,
+                "iptc" => policy.iptc = true,
+                "text" => policy.text = true,
+                _ => {
+                    ExifCategory::parse(name).map_err(|_| {
+                        ImageError::ProcessingError(format!(
+                            "Unknown metadata category '{}': expected exif, icc, xmp, iptc, text or an EXIF category (gps, copyright, artist, date, camera, ...)",
+                            name
+                        ))
+                    })?;
+                    categories.push(name.clone());
+                }
+            }
+        }
+
+        policy.exif = if all_exif {
+            Some(ExifFilter::default())
+        } else if !categories.is_empty() {
+            Some(ExifFilter::new(Some(&categories), None)?)
+        } else {
+            None
+        };
+        Ok(policy)
+    }
+}
+
+/// Copy the kept metadata from the input to the encoded output, across containers
+/// EXIF dimension tags are set to the output size, and orientation is reset when
+/// the pixels were rotated or flipped. Blocks the output container can't carry
+/// (IPTC outside JPEG, text chunks outside PNG, anything in GIF/AVIF/...) are skipped
+pub fn copy_metadata(
+    input: &[u8],
+    output: Vec<u8>,
+    keep: &MetadataKeep,
+    width: u32,
+    height: u32,
+    reoriented: bool,
+) -> Result<Vec<u8>, ImageError> {
+    let is_jpeg = output.starts_with(&[0xFF, 0xD8]);
+    let is_png = output.starts_with(b"\x89PNG\r\n\x1a\n");
+    let is_webp = output.len() >= 12 && &output[0..4] == b"RIFF" && &output[8..12] == b"WEBP";
+    if !(is_jpeg || is_png || is_webp) {
+        return Ok(output);
+    }
+
+    let mut output = output;
+    if let Some(ref filter) = keep.exif {
+        if let Some(exif) = find_exif(input) {
+            let mut tiff = exif.to_vec();
+            filter_tiff_exif(&mut tiff, filter);
+            set_tiff_dimensions(&mut tiff, width, height);
+            if reoriented {
+                set_tiff_orientation(&mut tiff, 1);
+            }
+            output = write_exif_payload(&output, tiff)?;
+        }
+    }
+    if keep.icc {
+        output = copy_icc_profile(input, output)?;
+    }
+    if keep.xmp {
+        if let Some((packet, _)) = find_xmp(input) {
+            output = write_xmp(&output, &packet)?;
+        }
+    }
+    if keep.iptc && is_jpeg {
+        if let Some(record) = iptc::find_iptc(input) {
+            output = write_jpeg_iptc_record(&output, &record)?;
+        }
+    }
+    if keep.text && is_png {
+        output = write_png_text(&output, &read_png_text(input))?;
+    }
+
+    Ok(output)
+}
+
+/// Overwrite the orientation tag in IFD0 of TIFF/EXIF data in place
+/// Returns false when the data has no orientation tag
+pub fn set_tiff_orientation(data: &mut [u8], orientation: u16) -> bool {
+    if data.len() < 8 {
+        return false;
+    }
+
+    let big_endian = &data[0..2] == b"MM";
+    let read_u16 = |data: &[u8], offset: usize| -> u16 {
+        if big_endian {
+            u16::from_be_bytes([data[offset], data[offset + 1]])
+        } else {
+            u16::from_le_bytes([data[offset], data[offset + 1]])
+        }
+    };
+
+    let ifd_offset = if big_endian {
+        u32::from_be_bytes([data[4], data[5], data[6], data[7]]) as usize
+    } else {
+        u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize
+    };
+    if ifd_offset + 2 > data.len() {
+        return false;
+    }
+
+    let num_entries = read_u16(data, ifd_offset) as usize;
+    for i in 0..num_entries {
+        let entry_offset = ifd_offset + 2 + i * 12;
+        if entry_offset + 12 > data.len() {
+            break;
+        }
+        if read_u16(data, entry_offset) == exif_tags::ORIENTATION {
+            // SHORT value is stored inline in the first two bytes of the value field
+            let value = if big_endian {
+                orientation.to_be_bytes()
+            } else {
+                orientation.to_le_bytes()
+            };
+            data[entry_offset + 8..entry_offset + 10].copy_from_slice(&value);
+            return true;
+        }
+    }
+
+    false
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Fresh EXIF (IFD0, Exif and GPS entries) from write options
+    fn build_exif_bytes(options: &ExifWriteOptions) -> Vec<u8> {
+        merge_exif(None, &options.to_tags())
+    }
+
     #[test]
     fn test_build_exif_bytes() {
         let options = ExifWriteOptions {
@@ -340,6 +1773,385 @@ mod tests {
         assert_eq!(exif[3], 0);
     }
 
+    #[test]
+    fn test_set_tiff_orientation() {
+        let options = ExifWriteOptions {
+            orientation: Some(6),
+            ..Default::default()
+        };
+        let mut exif = build_exif_bytes(&options);
+
+        assert!(set_tiff_orientation(&mut exif, 1));
+        assert_eq!(crate::metadata::utils::parse_exif_orientation(&exif), Some(1));
+
+        let mut no_orientation = build_exif_bytes(&ExifWriteOptions {
+            artist: Some("Test".to_string()),
+            ..Default::default()
+        });
+        assert!(!set_tiff_orientation(&mut no_orientation, 1));
+    }
+
+    fn gps_options() -> ExifWriteOptions {
+        ExifWriteOptions {
+            artist: Some("Jane".to_string()),
+            copyright: Some("(c) Jane".to_string()),
+            make: Some("ACME".to_string()),
+            orientation: Some(6),
+            date_time_original: Some("2024:05:01 12:00:00".to_string()),
+            gps_latitude: Some(-33.8568),
+            gps_longitude: Some(151.2153),
+            gps_altitude: Some(-12.5),
+            gps_timestamp: Some(GpsTimestamp::parse("2024-05-01T02:03:04.5Z").unwrap()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_gps_round_trip() {
+        let exif = build_exif_bytes(&gps_options());
+        let entries = crate::metadata::parse_exif(&exif);
+        let gps = crate::metadata::gps_info(&entries).unwrap();
+
+        assert!((gps.latitude.unwrap() + 33.8568).abs() < 1e-6);
+        assert!((gps.longitude.unwrap() - 151.2153).abs() < 1e-6);
+        assert_eq!(gps.altitude, Some(-12.5));
+        assert_eq!(gps.timestamp.as_deref(), Some("2024-05-01T02:03:04.500Z"));
+    }
+
+    #[test]
+    fn test_gps_timestamp_parse() {
+        let parsed = GpsTimestamp::parse("2024:05:01 23:59:07").unwrap();
+        assert_eq!(parsed.date, "2024:05:01");
+        assert_eq!((parsed.hour, parsed.minute, parsed.second), (23, 59, 7.0));
+
+        assert!(GpsTimestamp::parse("2024-05-01").is_err());
+        assert!(GpsTimestamp::parse("2024-13-01T00:00:00Z").is_err());
+    }
+
+    #[test]
+    fn test_filter_tiff_exif() {
+        let names = |exif: &[u8]| -> Vec<String> {
+            crate::metadata::parse_exif(exif).into_iter().map(|e| e.name).collect()
+        };
+
+        let remove = ["gps".to_string()];
+        let mut exif = build_exif_bytes(&gps_options());
+        filter_tiff_exif(&mut exif, &ExifFilter::new(None, Some(&remove)).unwrap());
+        let kept = names(&exif);
+        assert!(!kept.iter().any(|n| n.starts_with("GPS")));
+        assert!(kept.contains(&"Copyright".to_string()));
+        assert!(kept.contains(&"Artist".to_string()));
+
+        let keep = ["copyright".to_string(), "date".to_string()];
+        let mut exif = build_exif_bytes(&gps_options());
+        filter_tiff_exif(&mut exif, &ExifFilter::new(Some(&keep), None).unwrap());
+        let mut kept = names(&exif);
+        kept.sort();
+        assert_eq!(kept, ["Copyright", "DateTimeOriginal", "Orientation"]);
+
+        assert!(ExifFilter::new(None, Some(&["location".to_string()])).is_err());
+    }
+
+    #[test]
+    fn test_build_iptc_record() {
+        let fields = IptcData {
+            keywords: Some(vec!["harbour".to_string(), "sunset".to_string()]),
+            byline: Some(vec!["Jane Doe".to_string()]),
+            caption: Some("Quai à Marseille".to_string()),
+            title: None,
+            byline_title: None,
+            city: Some("Marseille".to_string()),
+            state: None,
+            country_code: None,
+            country: None,
+            headline: None,
+            credit: Some("Example Agency".to_string()),
+            source: None,
+            copyright: None,
+        };
+
+        let record = build_iptc_record(&fields).unwrap();
+        let parsed = crate::metadata::parse_iptc(&record).unwrap();
+        assert_eq!(parsed.keywords, fields.keywords);
+        assert_eq!(parsed.byline, fields.byline);
+        assert_eq!(parsed.caption, fields.caption);
+        assert_eq!(parsed.credit, fields.credit);
+        assert!(parsed.headline.is_none());
+    }
+
+    #[test]
+    fn test_write_jpeg_icc() {
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE0, 0x00, 0x06];
+        jpeg.extend_from_slice(b"JFIF");
+        jpeg.extend_from_slice(&[0xFF, 0xDB, 0x00, 0x04, 0x00, 0x00]);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x04, 0x00, 0x00, 0x01, 0x02, 0xFF, 0xD9]);
+
+        // Larger than one APP2 segment
+        let profile: Vec<u8> = (0..70_000u32).map(|i| (i % 251) as u8).collect();
+        let output = write_jpeg_icc(&jpeg, &profile).unwrap();
+        let output = write_jpeg_icc(&output, &profile).unwrap();
+
+        let parsed = Jpeg::from_bytes(Bytes::from(output.clone())).unwrap();
+        let markers: Vec<u8> = parsed.segments().iter().map(|segment| segment.marker()).collect();
+        assert_eq!(&markers[..4], &[APP0, APP2, APP2, 0xDB]);
+        assert_eq!(&parsed.segments()[1].contents()[12..14], &[1, 2]);
+        assert_eq!(find_icc_profile(&output), Some(profile));
+    }
+
+    #[test]
+    fn test_write_png_text() {
+        let mut png = Vec::new();
+        image::DynamicImage::new_rgb8(1, 1)
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let entry = |keyword: &str, text: &str, compressed| PngTextEntry {
+            keyword: keyword.to_string(),
+            text: text.to_string(),
+            language: None,
+            translated_keyword: None,
+            compressed: Some(compressed),
+        };
+        let output = write_png_text(&png, &[entry("parameters", "old", false)]).unwrap();
+        let output = write_png_text(
+            &output,
+            &[
+                entry("parameters", "a cat, steps: 20", false),
+                entry("workflow", "{\"nodes\":[]}", true),
+                entry("Title", "猫", false),
+            ],
+        )
+        .unwrap();
+
+        let kinds: Vec<[u8; 4]> = Png::from_bytes(Bytes::from(output.clone()))
+            .unwrap()
+            .chunks()
+            .iter()
+            .map(|chunk| chunk.kind())
+            .collect();
+        assert_eq!(&kinds[1..4], &[*b"tEXt", *b"zTXt", *b"iTXt"]);
+
+        let text = crate::metadata::read_png_text(&output);
+        let pairs: Vec<(&str, &str, Option<bool>)> = text
+            .iter()
+            .map(|e| (e.keyword.as_str(), e.text.as_str(), e.compressed))
+            .collect();
+        assert_eq!(
+            pairs,
+            [
+                ("parameters", "a cat, steps: 20", Some(false)),
+                ("workflow", "{\"nodes\":[]}", Some(true)),
+                ("Title", "猫", Some(false)),
+            ]
+        );
+
+        assert!(write_png_text(&png, &[entry("", "empty keyword", false)]).is_err());
+    }
+
+    #[test]
+    fn test_set_tiff_dimensions() {
+        let mut tiff = merge_exif(
+            None,
+            &[
+                ExifTagWrite::new(ExifIfd::Exif, 0xA002, ExifValue::Short(vec![4000])),
+                ExifTagWrite::new(ExifIfd::Exif, 0xA003, ExifValue::Long(vec![3000])),
+            ],
+        );
+        set_tiff_dimensions(&mut tiff, 70_000, 300);
+
+        let entries = crate::metadata::parse_exif(&tiff);
+        let dimension = |name: &str| {
+            entries
+                .iter()
+                .find(|entry| entry.name == name)
+                .and_then(|entry| entry.numbers.clone())
+        };
+        // SHORT is widened to LONG when the new size doesn't fit
+        assert_eq!(dimension("PixelXDimension"), Some(vec![70_000.0]));
+        assert_eq!(dimension("PixelYDimension"), Some(vec![300.0]));
+    }
+
+    #[test]
+    fn test_metadata_keep() {
+        let all = MetadataKeep::new(None).unwrap();
+        assert!(all.exif.is_some() && all.icc && all.xmp && all.iptc && all.text);
+
+        let none = MetadataKeep::new(Some(&[])).unwrap();
+        assert!(none.exif.is_none() && !none.icc && !none.xmp);
+
+        let keep = ["copyright".to_string(), "icc".to_string()];
+        let some = MetadataKeep::new(Some(&keep)).unwrap();
+        let filter = some.exif.unwrap();
+        assert!(!filter.removes(ExifCategory::Copyright) && filter.removes(ExifCategory::Gps));
+        assert!(some.icc && !some.xmp);
+
+        assert!(MetadataKeep::new(Some(&["colour".to_string()])).is_err());
+    }
+
+    fn find_entry<'a>(entries: &'a [crate::ExifEntry], ifd: &str, tag: u32) -> Option<&'a crate::ExifEntry> {
+        entries.iter().find(|entry| entry.ifd == ifd && entry.tag == tag)
+    }
+
+    #[test]
+    fn test_typed_tags_round_trip() {
+        let tags = [
+            ExifTagWrite::new(ExifIfd::Ifd0, 0x0110, ExifValue::Ascii("Model X".to_string())),
+            ExifTagWrite::new(ExifIfd::Ifd0, 0x011A, ExifValue::Rational(vec![(300, 1)])),
+            ExifTagWrite::new(ExifIfd::Exif, 0x8827, ExifValue::Short(vec![400])),
+            ExifTagWrite::new(ExifIfd::Exif, 0xA002, ExifValue::Long(vec![6000])),
+            ExifTagWrite::new(ExifIfd::Exif, 0x9204, ExifValue::SRational(vec![(-2, 3)])),
+            ExifTagWrite::new(
+                ExifIfd::Exif,
+                0xA432,
+                ExifValue::Rational(vec![(24, 1), (70, 1), (28, 10), (4, 1)]),
+            ),
+            ExifTagWrite::new(ExifIfd::Exif, 0x927C, ExifValue::Undefined(vec![1, 2, 3, 4, 5, 6])),
+            ExifTagWrite::new(ExifIfd::Gps, 0x0000, ExifValue::Byte(vec![2, 3, 0, 0])),
+        ];
+
+        for base in [None, Some(b"MM\0*\0\0\0\0".to_vec())] {
+            let exif = merge_exif(base.as_deref(), &tags);
+            // Byte order of an existing payload is kept
+            assert_eq!(&exif[0..2], if base.is_some() { b"MM" } else { b"II" });
+
+            let entries = crate::metadata::parse_exif(&exif);
+            let numbers = |ifd, tag| find_entry(&entries, ifd, tag).and_then(|entry| entry.numbers.clone());
+            assert_eq!(
+                find_entry(&entries, "ifd0", 0x0110).unwrap().text.as_deref(),
+                Some("Model X")
+            );
+            assert_eq!(numbers("ifd0", 0x011A), Some(vec![300.0]));
+            assert_eq!(numbers("exif", 0x8827), Some(vec![400.0]));
+            assert_eq!(numbers("exif", 0xA002), Some(vec![6000.0]));
+            let bias = numbers("exif", 0x9204).unwrap();
+            assert!((bias[0] + 2.0 / 3.0).abs() < 1e-9);
+            assert_eq!(numbers("exif", 0xA432), Some(vec![24.0, 70.0, 2.8, 4.0]));
+            assert_eq!(
+                find_entry(&entries, "exif", 0x927C).unwrap().bytes,
+                Some(vec![1, 2, 3, 4, 5, 6])
+            );
+            assert_eq!(numbers("gps", 0x0000), Some(vec![2.0, 3.0, 0.0, 0.0]));
+        }
+    }
+
+    #[test]
+    fn test_merge_keeps_existing_tags() {
+        let original = build_exif_bytes(&ExifWriteOptions {
+            copyright: Some("(c) Someone".to_string()),
+            artist: Some("Old".to_string()),
+            date_time_original: Some("2020:01:01 00:00:00".to_string()),
+            ..Default::default()
+        });
+        let merged = merge_exif(
+            Some(&original),
+            &ExifWriteOptions {
+                artist: Some("New".to_string()),
+                gps_latitude: Some(10.5),
+                ..Default::default()
+            }
+            .to_tags(),
+        );
+
+        // Existing bytes stay in place
+        assert_eq!(&merged[8..original.len()], &original[8..]);
+
+        let entries = crate::metadata::parse_exif(&merged);
+        let text = |ifd, tag| find_entry(&entries, ifd, tag).and_then(|entry| entry.text.clone());
+        assert_eq!(
+            text("ifd0", exif_tags::COPYRIGHT as u32).as_deref(),
+            Some("(c) Someone")
+        );
+        assert_eq!(text("ifd0", exif_tags::ARTIST as u32).as_deref(), Some("New"));
+        assert_eq!(
+            text("exif", exif_tags::DATE_TIME_ORIGINAL as u32).as_deref(),
+            Some("2020:01:01 00:00:00")
+        );
+        assert_eq!(text("gps", exif_tags::GPS_LATITUDE_REF as u32).as_deref(), Some("N"));
+        // Replaced, not duplicated
+        assert_eq!(
+            entries
+                .iter()
+                .filter(|entry| entry.tag == exif_tags::ARTIST as u32)
+                .count(),
+            1
+        );
+
+        // Nothing to write leaves the payload alone
+        assert_eq!(merge_exif(Some(&original), &[]), original);
+    }
+
+    #[test]
+    fn test_merge_wipes_replaced_values() {
+        let contains = |data: &[u8], needle: &[u8]| data.windows(needle.len()).any(|window| window == needle);
+        let options = ExifWriteOptions {
+            artist: Some("Original Author".to_string()),
+            copyright: Some("(c) Kept".to_string()),
+            ..gps_options()
+        };
+        let original = build_exif_bytes(&options);
+        let old_latitude = ExifValue::Rational(dms_rationals(options.gps_latitude.unwrap())).encode(false);
+        assert!(contains(&original, &old_latitude) && contains(&original, b"Original Author"));
+
+        let merged = merge_exif(
+            Some(&original),
+            &ExifWriteOptions {
+                artist: Some("Someone Else".to_string()),
+                gps_latitude: Some(1.5),
+                ..Default::default()
+            }
+            .to_tags(),
+        );
+        assert!(!contains(&merged, &old_latitude));
+        assert!(!contains(&merged, b"Original Author"));
+
+        let entries = crate::metadata::parse_exif(&merged);
+        let text = |ifd, tag| find_entry(&entries, ifd, tag).and_then(|entry| entry.text.clone());
+        assert_eq!(text("ifd0", exif_tags::COPYRIGHT as u32).as_deref(), Some("(c) Kept"));
+        assert_eq!(text("ifd0", exif_tags::ARTIST as u32).as_deref(), Some("Someone Else"));
+        // Carried-over GPS tags still resolve
+        assert!(find_entry(&entries, "gps", exif_tags::GPS_LONGITUDE as u32).is_some());
+        assert!(find_entry(&entries, "gps", exif_tags::GPS_TIME_STAMP as u32).is_some());
+    }
+
+    #[test]
+    fn test_write_tiff_exif() {
+        let mut tiff = Vec::new();
+        image::DynamicImage::new_rgb8(4, 3)
+            .write_to(&mut std::io::Cursor::new(&mut tiff), image::ImageFormat::Tiff)
+            .unwrap();
+
+        let options = ExifWriteOptions {
+            copyright: Some("(c) Someone".to_string()),
+            tags: vec![ExifTagWrite::new(
+                ExifIfd::Exif,
+                0xA434,
+                ExifValue::Ascii("50mm".into()),
+            )],
+            ..Default::default()
+        };
+        let written = write_tiff_exif(&tiff, &options).unwrap();
+
+        let entries = crate::metadata::parse_exif(&written);
+        let text = |ifd, tag| find_entry(&entries, ifd, tag).and_then(|entry| entry.text.clone());
+        assert_eq!(
+            text("ifd0", exif_tags::COPYRIGHT as u32).as_deref(),
+            Some("(c) Someone")
+        );
+        assert_eq!(text("exif", 0xA434).as_deref(), Some("50mm"));
+        // Strip offsets still point at the pixels
+        assert_eq!(image::load_from_memory(&written).unwrap().width(), 4);
+
+        assert!(write_tiff_exif(b"not a tiff", &options).is_err());
+    }
+
+    #[test]
+    fn test_approximate_fraction() {
+        assert_eq!(approximate_fraction(2.8, u32::MAX), (14, 5));
+        assert_eq!(approximate_fraction(0.0, u32::MAX), (0, 1));
+        assert_eq!(approximate_fraction(1.0 / 3.0, 1000), (1, 3));
+        assert_eq!(approximate_fraction(1e12, 1000), (1000, 1));
+    }
+
     #[test]
     fn test_empty_options() {
         let options = ExifWriteOptions::default();
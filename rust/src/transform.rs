@@ -1,20 +1,98 @@
 //! Image transformation functions (all-in-one processing)
 //! Optimized pipeline: crop → resize → rotate → flip → effects → encode
 
-use image::DynamicImage;
+use image::{DynamicImage, GenericImageView};
 use napi::bindgen_prelude::Buffer;
 
+use crate::animation;
 use crate::crop;
 use crate::decode;
 use crate::encode;
+use crate::jpeg_lossless;
+use crate::metadata;
+use crate::metadata_write;
 use crate::resize;
 use crate::error::ImageError;
-use crate::TransformOptions;
+use crate::{ColorManagementOptions, ImageFormat, TransformOptions, TransformResult};
 
 /// Apply all transformations to an image
 /// Pipeline order: crop → resize → rotate → flip → grayscale → blur → sharpen → brightness → contrast → encode
 #[inline]
 pub fn transform_image(input: &[u8], options: &TransformOptions) -> Result<Buffer, ImageError> {
+  transform_image_with_info(input, options).map(|result| Buffer::from(result.data))
+}
+
+/// Apply all transformations and report the output dimensions and max_bytes/target_quality search result
+pub fn transform_image_with_info(input: &[u8], options: &TransformOptions) -> Result<TransformResult, ImageError> {
+  let mut result = transform_pixels(input, options)?;
+
+  // Metadata is embedded after encoding - it is not counted against max_bytes
+  if let Some(ref policy) = options.metadata {
+    let mut keep = metadata_write::MetadataKeep::new(policy.keep.as_deref())?;
+    // Converted pixels are no longer in the input's profile
+    keep.icc &= options.color_management.is_none();
+    let reoriented =
+      options.rotate.unwrap_or(0) % 360 != 0 || options.flip_h.unwrap_or(false) || options.flip_v.unwrap_or(false);
+    result.data = metadata_write::copy_metadata(input, result.data, &keep, result.width, result.height, reoriented)?;
+  }
+  // Explicit fields replace any EXIF kept from the input
+  if let Some(ref exif) = options.exif {
+    result.data = crate::write_exif_internal(&result.data, exif)?;
+  }
+  if let Some(ref xmp) = options.xmp {
+    result.data = metadata_write::write_xmp(&result.data, xmp)?;
+  }
+  result.data = embed_output_profile(input, result.data, options.keep_icc, options.color_management.as_ref())?;
+
+  Ok(result)
+}
+
+/// Tag the output with the profile its pixels are in
+/// Converted pixels get the custom target profile (sRGB is left untagged);
+/// otherwise keep_icc copies the input's profile
+pub(crate) fn embed_output_profile(
+  input: &[u8],
+  output: Vec<u8>,
+  keep_icc: Option<bool>,
+  color: Option<&ColorManagementOptions>,
+) -> Result<Vec<u8>, ImageError> {
+  match color {
+    Some(color) => match color.target_profile.as_deref() {
+      Some(profile) => metadata_write::add_icc_profile(output, profile),
+      None => Ok(output),
+    },
+    None if keep_icc.unwrap_or(false) => metadata_write::copy_icc_profile(input, output),
+    None => Ok(output),
+  }
+}
+
+/// Decode, process and encode - everything except embedding metadata
+fn transform_pixels(input: &[u8], options: &TransformOptions) -> Result<TransformResult, ImageError> {
+  // Animated output: process every frame and re-encode as an animation
+  if options.animated.unwrap_or(false) && animation::is_animated(input) {
+    let format = options.output.as_ref().map(|o| &o.format);
+    if matches!(format, Some(ImageFormat::WebP) | Some(ImageFormat::Gif) | Some(ImageFormat::Png)) {
+      let output = options.output.as_ref();
+      if output.is_some_and(|o| o.max_bytes.is_some() || o.target_quality.is_some()) {
+        return Err(ImageError::ProcessingError(
+          "maxBytes and targetQuality are not supported for animated output".to_string(),
+        ));
+      }
+      return transform_animation(input, options).and_then(header_result);
+    }
+  }
+
+  // JPEG → JPEG with only rotate/flip/MCU-aligned crop: transform losslessly without re-encoding
+  // A byte budget or quality target needs the re-encoding path
+  let max_bytes = options.output.as_ref().and_then(|o| o.max_bytes);
+  let target_quality = options.output.as_ref().and_then(|o| o.target_quality.as_ref());
+  // Color management changes the pixels, so it needs the re-encoding path too
+  if max_bytes.is_none() && target_quality.is_none() && options.color_management.is_none() {
+    if let Some(output) = jpeg_lossless::try_lossless_transform(input, options) {
+      return header_result(output);
+    }
+  }
+
   // Use scale-on-decode for JPEG images when resize is specified - massive speedup
   // Note: If cropping, we decode at full size first (crop needs full pixels)
   let (target_width, target_height) = if options.crop.is_some() {
@@ -28,8 +106,160 @@ pub fn transform_image(input: &[u8], options: &TransformOptions) -> Result<Buffe
     )
   };
 
-  let mut img = decode::decode_image_with_target(input, target_width, target_height)?;
+  let img = decode::decode_image_with_target_fast(
+    input,
+    target_width,
+    target_height,
+    false,
+    options.color_management.as_ref(),
+  )?;
+  let img = apply_operations(img, options)?;
+  let (width, height) = img.dimensions();
+
+  // `format: Auto` becomes a concrete format chosen from the processed pixels
+  let resolved;
+  let output_opts = match options.output.as_ref() {
+    Some(o) if matches!(o.format, ImageFormat::Auto) => {
+      resolved = encode::resolve_auto_format(&img, o)?;
+      Some(&resolved)
+    }
+    other => other,
+  };
+  let format = encode::format_name(output_opts.map_or(&ImageFormat::Png, |o| &o.format)).to_string();
+
+  // Encode output
+  let output = if let Some(output_opts) = output_opts {
+    // Lowest quality meeting the target - max_bytes still caps it below
+    let mut attempts = 0;
+    if let Some(target) = target_quality {
+      let fitted = encode::encode_to_target_quality(
+        &img,
+        &output_opts.format,
+        output_opts.jpeg.as_ref(),
+        output_opts.webp.as_ref(),
+        target,
+      )?;
+      if max_bytes.is_none_or(|max| fitted.data.len() <= max as usize) {
+        return Ok(TransformResult {
+          data: fitted.data,
+          width: fitted.width,
+          height: fitted.height,
+          quality: Some(fitted.quality as u32),
+          attempts: Some(fitted.attempts),
+          score: fitted.score,
+          channels: None,
+          format,
+        });
+      }
+      attempts = fitted.attempts;
+    }
+
+    if let Some(max_bytes) = max_bytes {
+      let fitted = encode::encode_to_max_bytes(
+        &img,
+        &output_opts.format,
+        output_opts.jpeg.as_ref(),
+        output_opts.webp.as_ref(),
+        max_bytes,
+        output_opts.downscale_to_fit.unwrap_or(false),
+      )?;
+      return Ok(TransformResult {
+        data: fitted.data,
+        width: fitted.width,
+        height: fitted.height,
+        quality: Some(fitted.quality as u32),
+        attempts: Some(attempts + fitted.attempts),
+        score: None,
+        channels: None,
+        format,
+      });
+    }
+
+    if matches!(output_opts.format, ImageFormat::Raw) {
+      let raw = encode::encode_raw(&img);
+      return Ok(TransformResult {
+        data: raw.data,
+        width: raw.width,
+        height: raw.height,
+        quality: None,
+        attempts: None,
+        score: None,
+        channels: Some(raw.channels),
+        format,
+      });
+    }
+
+    let output = encode::encode_to_format(
+      &img,
+      &output_opts.format,
+      output_opts.jpeg.as_ref(),
+      output_opts.png.as_ref(),
+      output_opts.webp.as_ref(),
+      output_opts.gif.as_ref(),
+    )?;
+
+    // Optimized PNG drops ancillary chunks - copy them back from a PNG source if asked
+    if matches!(output_opts.format, ImageFormat::Png) {
+      encode::keep_png_metadata(input, output, output_opts.png.as_ref())
+    } else {
+      output
+    }
+  } else {
+    // Default to PNG
+    encode::encode_png(&img, None)?
+  };
+
+  Ok(TransformResult {
+    data: output,
+    width,
+    height,
+    quality: None,
+    attempts: None,
+    score: None,
+    channels: None,
+    format,
+  })
+}
+
+/// Result for output that was not decoded here - dimensions come from the header
+fn header_result(data: Vec<u8>) -> Result<TransformResult, ImageError> {
+  let meta = decode::get_metadata(&data)?;
+  Ok(TransformResult {
+    data,
+    width: meta.width,
+    height: meta.height,
+    quality: None,
+    attempts: None,
+    score: None,
+    channels: None,
+    format: meta.format,
+  })
+}
+
+/// Apply the same transformations to every frame of an animated image
+/// Frames are processed in parallel; output is animated WebP, GIF or PNG (APNG)
+fn transform_animation(input: &[u8], options: &TransformOptions) -> Result<Vec<u8>, ImageError> {
+  let anim = animation::decode_animation(input)?;
+  let profile = options.color_management.as_ref().and_then(|_| metadata::find_icc_profile(input));
+  let mut anim = anim.map_frames(|frame| {
+    let frame = match options.color_management {
+      Some(ref color) => decode::convert_color(frame, profile.as_deref(), color)?,
+      None => frame,
+    };
+    apply_operations(frame, options)
+  })?;
+  anim.apply_options(options.animation.as_ref());
+
+  let output_opts = options.output.as_ref();
+  match output_opts.map(|o| &o.format) {
+    Some(ImageFormat::Gif) => animation::encode_gif_animation(&anim, output_opts.and_then(|o| o.gif.as_ref())),
+    Some(ImageFormat::Png) => animation::encode_png_animation(&anim, output_opts.and_then(|o| o.png.as_ref())),
+    _ => animation::encode_webp_animation(&anim, output_opts.and_then(|o| o.webp.as_ref())),
+  }
+}
 
+/// Apply pixel operations (everything except decode and encode)
+fn apply_operations(mut img: DynamicImage, options: &TransformOptions) -> Result<DynamicImage, ImageError> {
   // Apply transformations in optimal order
 
   // 1. Crop FIRST (reduces pixels for all subsequent operations)
@@ -98,19 +328,5 @@ pub fn transform_image(input: &[u8], options: &TransformOptions) -> Result<Buffe
     }
   }
 
-  // Encode output
-  let output = if let Some(ref output_opts) = options.output {
-    encode::encode_to_format(
-      &img,
-      &output_opts.format,
-      output_opts.jpeg.as_ref(),
-      output_opts.png.as_ref(),
-      output_opts.webp.as_ref(),
-    )?
-  } else {
-    // Default to PNG
-    encode::encode_png(&img, None)?
-  };
-
-  Ok(Buffer::from(output))
+  Ok(img)
 }
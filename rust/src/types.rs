@@ -17,6 +17,13 @@ pub enum ImageFormat {
   Tiff,
   Heic,
   Avif,
+  Qoi,
+  Pnm,
+  Tga,
+  /// Tightly packed 8-bit pixels (gray, gray+alpha, RGB or RGBA following the source)
+  Raw,
+  /// Pick JPEG, PNG(-8) or WebP from the image content and `accept` (transform only)
+  Auto,
 }
 
 /// Resize filter/algorithm
@@ -110,30 +117,172 @@ pub struct ResizeOptions {
   pub background: Option<Vec<u8>>,
 }
 
-/// JPEG encode options
+/// JPEG output color space
+#[derive(Clone, Copy, PartialEq)]
+#[napi(string_enum)]
+pub enum JpegColorSpace {
+  /// YCbCr from RGB
+  Rgb,
+  /// Single luma channel
+  Gray,
+  /// Adobe YCCK from CMYK (print)
+  Cmyk,
+}
+
+/// ICC rendering intent used when converting between color profiles
+#[derive(Clone, Copy, PartialEq)]
+#[napi(string_enum)]
+pub enum RenderingIntent {
+  /// Compress the whole gamut to fit, keeping relations between colors (photos)
+  Perceptual,
+  /// Keep in-gamut colors exact, clip the rest, adapt the white point
+  RelativeColorimetric,
+  /// Keep colors vivid at the expense of accuracy (charts, graphics)
+  Saturation,
+  /// Keep in-gamut colors exact including the white point (proofing)
+  AbsoluteColorimetric,
+}
+
+/// Color management - convert decoded pixels from the embedded ICC profile
+/// Images without a profile are treated as sRGB
 #[napi(object)]
+#[derive(Clone, Default)]
+pub struct ColorManagementOptions {
+  /// RGB ICC profile to convert to, embedded in the output (default: sRGB, not embedded)
+  pub target_profile: Option<Vec<u8>>,
+  /// Rendering intent (default: Perceptual)
+  pub intent: Option<RenderingIntent>,
+}
+
+/// mozjpeg base quantization table preset
 #[derive(Clone)]
+#[napi(string_enum)]
+pub enum JpegQuantTable {
+  /// JPEG Annex K (libjpeg default)
+  AnnexK,
+  /// Flat table
+  Flat,
+  /// Tuned for MS-SSIM
+  MsSsim,
+  /// ImageMagick table by N. Robidoux (mozjpeg default)
+  ImageMagick,
+  /// Tuned for PSNR-HVS-M
+  PsnrHvsM,
+  /// Klein, Silverstein and Carney (1992)
+  KleinSilversteinCarney,
+  /// Watson, Taylor and Borthwick (1997)
+  WatsonTaylorBorthwick,
+  /// Ahumada, Watson and Peterson (1993)
+  AhumadaWatsonPeterson,
+  /// Peterson, Ahumada and Watson (1993)
+  PetersonAhumadaWatson,
+}
+
+/// JPEG encode options
+#[napi(object)]
+#[derive(Clone, Default)]
 pub struct JpegOptions {
   /// Quality 1-100 (default: 80)
   pub quality: Option<u8>,
+  /// Progressive scans (default: false, or true with mozjpeg)
+  pub progressive: Option<bool>,
+  /// Chroma subsampling: "4:4:4", "4:2:2" or "4:2:0" (default: "4:2:0")
+  pub chroma_subsampling: Option<String>,
+  /// Optimize Huffman tables (default: false, or true with mozjpeg)
+  pub optimize_coding: Option<bool>,
+  /// Trellis quantisation - mozjpeg only (default: true)
+  pub trellis_quantisation: Option<bool>,
+  /// Overshoot deringing for hard edges on white - mozjpeg only (default: true)
+  pub overshoot_deringing: Option<bool>,
+  /// Base quantization table preset - mozjpeg only (default: ImageMagick)
+  pub quant_table: Option<JpegQuantTable>,
+  /// Encode with mozjpeg instead of turbojpeg (default: false)
+  /// Slower, but typically produces 10-20% smaller files
+  pub mozjpeg: Option<bool>,
+  /// Output color space (default: Gray for grayscale images, Rgb otherwise)
+  pub color_space: Option<JpegColorSpace>,
+  /// ICC profile embedded in the output - Cmyk output is converted with it
+  pub icc_profile: Option<Vec<u8>>,
 }
 
 /// PNG encode options
 #[napi(object)]
-#[derive(Clone)]
+#[derive(Clone, Default)]
 pub struct PngOptions {
   /// Compression level 0-9 (default: 6)
   pub compression: Option<u8>,
+  /// Quantize to an indexed palette (PNG-8) - implied by colors/dither/quality (default: false)
+  pub palette: Option<bool>,
+  /// Maximum palette size 2-256 (default: 256)
+  pub colors: Option<u32>,
+  /// Floyd-Steinberg dithering amount 0.0-1.0 (default: 1.0)
+  pub dither: Option<f64>,
+  /// Target quality 0-100 - stops adding colors once reached (default: 100)
+  pub quality: Option<u8>,
+  /// Minimum quality 0-100 - below it a truecolor PNG is written instead (default: 0)
+  pub min_quality: Option<u8>,
+  /// Lossless optimization level 0-6 - reduces color type/bit depth and tries more filters (default: 0)
+  pub optimize: Option<u8>,
+  /// Keep ancillary chunks (text, EXIF, ICC) from a PNG source when optimizing (default: false)
+  pub keep_metadata: Option<bool>,
+  /// ICC profile embedded in the output as an iCCP chunk
+  pub icc_profile: Option<Vec<u8>>,
 }
 
-/// WebP encode options
+/// GIF encode options
 #[napi(object)]
 #[derive(Clone)]
+pub struct GifOptions {
+  /// Maximum palette size 2-256 (default: 256)
+  pub colors: Option<u32>,
+  /// Floyd-Steinberg dithering amount 0.0-1.0 (default: 1.0)
+  pub dither: Option<f64>,
+}
+
+/// libwebp encoder preset (tunes filtering and spatial noise shaping for the content)
+#[derive(Clone)]
+#[napi(string_enum)]
+pub enum WebPPreset {
+  /// General purpose (libwebp default)
+  Default,
+  /// Digital picture, like portrait or indoor shot
+  Picture,
+  /// Outdoor photograph with natural lighting
+  Photo,
+  /// Hand or line drawing with high-contrast details
+  Drawing,
+  /// Small-sized colorful image
+  Icon,
+  /// Text-like content
+  Text,
+}
+
+/// WebP encode options
+#[napi(object)]
+#[derive(Clone, Default)]
 pub struct WebPOptions {
   /// Quality 1-100 for lossy, ignored for lossless (default: 80)
   pub quality: Option<u8>,
   /// Use lossless compression (default: false)
   pub lossless: Option<bool>,
+  /// Speed/size trade-off 0-6, higher is slower and smaller (default: 4)
+  pub method: Option<u8>,
+  /// Alpha plane quality 0-100 (default: 100)
+  pub alpha_quality: Option<u8>,
+  /// Near-lossless preprocessing 0-100, lower is lossier; implies lossless (default: 100 = off)
+  pub near_lossless: Option<u8>,
+  /// Sharp (slower) RGB to YUV conversion for crisper chroma edges (default: false)
+  pub smart_subsample: Option<bool>,
+  /// Content preset applied before the other options (default: Default)
+  pub preset: Option<WebPPreset>,
+  /// Keep RGB values under fully transparent pixels (default: false)
+  pub exact: Option<bool>,
+  /// Target output size in bytes - lossy only, overrides quality (default: off)
+  pub target_size: Option<u32>,
+  /// Target PSNR in dB - lossy only, takes precedence over target_size (default: off)
+  pub target_psnr: Option<f64>,
+  /// ICC profile embedded in the output as an ICCP chunk (still images only)
+  pub icc_profile: Option<Vec<u8>>,
 }
 
 /// Output format options
@@ -148,16 +297,39 @@ pub struct OutputOptions {
   pub png: Option<PngOptions>,
   /// WebP options (if format is WebP)
   pub webp: Option<WebPOptions>,
+  /// GIF options (if format is GIF)
+  pub gif: Option<GifOptions>,
+  /// Maximum output size in bytes - searches the JPEG/WebP quality until it fits
+  pub max_bytes: Option<u32>,
+  /// Step dimensions down when max_bytes can't be met at quality 1 (default: false)
+  pub downscale_to_fit: Option<bool>,
+  /// Pick the lowest JPEG/WebP quality that still meets a perceptual metric
+  pub target_quality: Option<TargetQuality>,
+  /// Formats the client accepts, for `format: Auto` (default: Jpeg, Png, WebP)
+  pub accept: Option<Vec<ImageFormat>>,
+}
+
+/// Perceptual quality target for adaptive encoding
+#[napi(object)]
+#[derive(Clone)]
+pub struct TargetQuality {
+  /// Metric to measure (default: Ssim)
+  pub metric: Option<CompareMetric>,
+  /// Score to reach - minimum for Ssim/MsSsim/Psnr, maximum for Dssim
+  pub value: f64,
+  /// Color space the metric is computed in (default: Luma)
+  pub color_space: Option<CompareColorSpace>,
 }
 
 /// Image metadata (similar to sharp's output)
+#[derive(Default)]
 #[napi(object)]
 pub struct ImageMetadata {
   /// Image width in pixels
   pub width: u32,
   /// Image height in pixels
   pub height: u32,
-  /// Detected format (jpeg, png, webp, gif, bmp, ico, tiff)
+  /// Detected format (jpeg, png, webp, gif, bmp, ico, tiff, heic, jxl, qoi, pnm, tga, hdr, exr, dds)
   pub format: String,
   /// File size in bytes (if available)
   pub size: Option<u32>,
@@ -179,18 +351,174 @@ pub struct ImageMetadata {
   pub has_profile: bool,
   /// EXIF orientation value (1-8, if present)
   pub orientation: Option<u8>,
-  /// Page/frame count for multi-page images (GIF, TIFF)
+  /// Page/frame count for multi-page images (GIF, WebP, APNG, TIFF)
   pub pages: Option<u32>,
   /// Loop count for animated images
   pub loop_count: Option<u32>,
   /// Delay between frames in ms (for animated images)
   pub delay: Option<Vec<u32>>,
+  /// Disposal method per frame: none, background or previous (GIF, WebP, APNG)
+  pub disposal: Option<Vec<String>>,
+  /// Total animation duration in ms (one loop)
+  pub duration: Option<u32>,
   /// Background color (for GIF)
   pub background: Option<Vec<u8>>,
   /// Compression type used
   pub compression: Option<String>,
   /// Density/DPI info
   pub density: Option<u32>,
+  /// Decoded EXIF tags (`exif: true` only)
+  pub exif: Option<Vec<ExifEntry>>,
+  /// TIFF-structured EXIF payload (`exif_raw: true` only)
+  pub exif_raw: Option<Vec<u8>>,
+  /// GPS position and fix time (`exif: true` or `gps: true` only)
+  pub gps: Option<GpsInfo>,
+  /// XMP packet and its common properties (`xmp: true` only)
+  pub xmp: Option<XmpData>,
+  /// IPTC-IIM datasets from JPEG APP13 (`iptc: true` only)
+  pub iptc: Option<IptcData>,
+  /// Embedded ICC profile - JPEG APP2 segments are reassembled (`icc: true` only)
+  pub icc_profile: Option<Vec<u8>>,
+  /// Description tag of the ICC profile, e.g. "Display P3" (`icc: true` only)
+  pub icc_description: Option<String>,
+  /// PNG tEXt, zTXt and iTXt chunks in file order (`text: true` only)
+  pub text: Option<Vec<PngTextEntry>>,
+}
+
+/// Metadata read options
+#[napi(object)]
+pub struct MetadataOptions {
+  /// Decode all EXIF tags (IFD0, Exif, GPS, Interop) (default: false)
+  pub exif: Option<bool>,
+  /// Return the raw EXIF payload (TIFF header onwards) (default: false)
+  pub exif_raw: Option<bool>,
+  /// Summarize the GPS IFD as decimal coordinates (default: false)
+  pub gps: Option<bool>,
+  /// Read the XMP packet and its common properties (default: false)
+  pub xmp: Option<bool>,
+  /// Read the IPTC-IIM record of JPEG files (default: false)
+  pub iptc: Option<bool>,
+  /// Return the embedded ICC profile (JPEG, PNG, WebP) and its description (default: false)
+  pub icc: Option<bool>,
+  /// Read PNG tEXt, zTXt and iTXt chunks (default: false)
+  pub text: Option<bool>,
+}
+
+/// GPS data decoded from the EXIF GPS IFD
+#[napi(object)]
+pub struct GpsInfo {
+  /// Decimal degrees, negative = south
+  pub latitude: Option<f64>,
+  /// Decimal degrees, negative = west
+  pub longitude: Option<f64>,
+  /// Meters, negative = below sea level
+  pub altitude: Option<f64>,
+  /// UTC fix time as ISO 8601 (GPSDateStamp + GPSTimeStamp)
+  pub timestamp: Option<String>,
+}
+
+/// XMP packet with the common Dublin Core, Photoshop, IPTC Core and XMP Rights properties
+#[napi(object)]
+pub struct XmpData {
+  /// Raw XMP packet
+  pub packet: String,
+  /// Reassembled JPEG extended XMP (packets over 64 KB)
+  pub extended: Option<String>,
+  /// dc:title (x-default)
+  pub title: Option<String>,
+  /// dc:description (x-default)
+  pub description: Option<String>,
+  /// dc:creator
+  pub creator: Option<Vec<String>>,
+  /// dc:rights (x-default)
+  pub rights: Option<String>,
+  /// dc:subject (keywords)
+  pub subject: Option<Vec<String>>,
+  /// photoshop:Headline
+  pub headline: Option<String>,
+  /// photoshop:Credit
+  pub credit: Option<String>,
+  /// photoshop:Source
+  pub source: Option<String>,
+  /// photoshop:City
+  pub city: Option<String>,
+  /// photoshop:State
+  pub state: Option<String>,
+  /// photoshop:Country
+  pub country: Option<String>,
+  /// Iptc4xmpCore:CountryCode
+  pub country_code: Option<String>,
+  /// Iptc4xmpCore:Location
+  pub location: Option<String>,
+  /// xmpRights:UsageTerms (x-default)
+  pub usage_terms: Option<String>,
+  /// xmpRights:WebStatement
+  pub web_statement: Option<String>,
+}
+
+/// IPTC-IIM application record (record 2) datasets
+#[napi(object)]
+#[derive(Clone)]
+pub struct IptcData {
+  /// Object Name (2:05)
+  pub title: Option<String>,
+  /// Keywords (2:25)
+  pub keywords: Option<Vec<String>>,
+  /// By-line / creator (2:80)
+  pub byline: Option<Vec<String>>,
+  /// By-line Title (2:85)
+  pub byline_title: Option<String>,
+  /// City (2:90)
+  pub city: Option<String>,
+  /// Province/State (2:95)
+  pub state: Option<String>,
+  /// Country Code (2:100)
+  pub country_code: Option<String>,
+  /// Country Name (2:101)
+  pub country: Option<String>,
+  /// Headline (2:105)
+  pub headline: Option<String>,
+  /// Credit Line (2:110)
+  pub credit: Option<String>,
+  /// Source (2:115)
+  pub source: Option<String>,
+  /// Copyright Notice (2:116)
+  pub copyright: Option<String>,
+  /// Caption/Abstract (2:120)
+  pub caption: Option<String>,
+}
+
+/// PNG text chunk (tEXt, zTXt or iTXt)
+#[napi(object)]
+#[derive(Clone)]
+pub struct PngTextEntry {
+  /// Keyword, e.g. Title, Author, parameters (Stable Diffusion), prompt/workflow (ComfyUI)
+  pub keyword: String,
+  /// Text, decompressed
+  pub text: String,
+  /// Language tag (iTXt only, e.g. "en-US")
+  pub language: Option<String>,
+  /// Keyword translated into the language (iTXt only)
+  pub translated_keyword: Option<String>,
+  /// Stored deflate-compressed (zTXt, or iTXt with the compression flag) (default: false)
+  pub compressed: Option<bool>,
+}
+
+/// One decoded EXIF tag - exactly one of text, numbers and bytes is set
+#[napi(object)]
+pub struct ExifEntry {
+  /// Directory: ifd0, exif, gps or interop
+  pub ifd: String,
+  /// Tag id
+  pub tag: u32,
+  /// Tag name (e.g. DateTimeOriginal), or hex id for unknown tags
+  pub name: String,
+  /// ASCII values and text-like UNDEFINED tags (versions, UserComment)
+  pub text: Option<String>,
+  /// Integer, float and rational values (rationals divided out)
+  pub numbers: Option<Vec<f64>>,
+  /// Other UNDEFINED values (MakerNote, ...)
+  pub bytes: Option<Vec<u8>>,
 }
 
 /// Raw hash result for blurhash
@@ -254,8 +582,114 @@ pub struct TransformOptions {
   pub brightness: Option<i32>,
   /// Contrast adjustment (-100 to 100)
   pub contrast: Option<i32>,
-  /// EXIF metadata to write (for JPEG/WebP output)
+  /// EXIF metadata to write - merged into the EXIF kept by `metadata`
   pub exif: Option<ExifOptions>,
+  /// XMP packet to embed in the output (JPEG, PNG and WebP)
+  pub xmp: Option<String>,
+  /// Copy the input's ICC profile into the output (JPEG, PNG and WebP) (default: false)
+  /// Ignored with color_management - the output is in the target profile
+  pub keep_icc: Option<bool>,
+  /// Convert pixels from the embedded ICC profile to sRGB or a target profile
+  pub color_management: Option<ColorManagementOptions>,
+  /// Copy input metadata to the output - without it the output carries none
+  pub metadata: Option<MetadataPolicy>,
+  /// Process every frame of animated input (GIF/WebP/APNG) and keep the animation
+  /// Only applies when the output format is WebP, GIF or PNG (default: false)
+  /// Cannot be combined with output.maxBytes or output.targetQuality
+  pub animated: Option<bool>,
+  /// Animation output options (loop count, frame delays)
+  pub animation: Option<AnimationOptions>,
+}
+
+/// Input metadata transform and thumbnail copy to the output, across formats
+#[napi(object)]
+#[derive(Clone)]
+pub struct MetadataPolicy {
+  /// What to keep - everything when unset, nothing when empty
+  /// exif, icc, xmp, iptc (JPEG output), text (PNG output), or EXIF categories
+  /// (gps, copyright, artist, date, ...) to keep only those EXIF tags
+  pub keep: Option<Vec<String>>,
+}
+
+/// Transform result with encoder details
+#[napi(object)]
+pub struct TransformResult {
+  /// The output image data
+  pub data: Vec<u8>,
+  /// Output width
+  pub width: u32,
+  /// Output height
+  pub height: u32,
+  /// Quality chosen by the max_bytes or target_quality search
+  pub quality: Option<u32>,
+  /// Number of encodes tried by the search
+  pub attempts: Option<u32>,
+  /// Metric score reached by the target_quality search
+  pub score: Option<f64>,
+  /// Channels per pixel (raw output only)
+  pub channels: Option<u32>,
+  /// Output format (jpeg, png, webp, ...) - the chosen one for `format: Auto`
+  pub format: String,
+}
+
+/// Raw pixel output
+#[napi(object)]
+pub struct RawImage {
+  /// Tightly packed 8-bit pixels, row-major
+  pub data: Vec<u8>,
+  /// Width in pixels
+  pub width: u32,
+  /// Height in pixels
+  pub height: u32,
+  /// Channels per pixel (1 = gray, 2 = gray+alpha, 3 = RGB, 4 = RGBA)
+  pub channels: u32,
+}
+
+// ============================================
+// ANIMATION TYPES
+// ============================================
+
+/// Animation output options
+#[napi(object)]
+#[derive(Clone)]
+pub struct AnimationOptions {
+  /// Number of loops (0 = infinite, default: same as input)
+  pub loop_count: Option<u32>,
+  /// Frame delays in milliseconds (default: same as input)
+  /// A single value applies to every frame; otherwise the last value repeats
+  pub delay: Option<Vec<u32>>,
+}
+
+// ============================================
+// LOSSLESS JPEG TYPES
+// ============================================
+
+/// Lossless JPEG transform options (no decode/re-encode)
+/// Applied in order: crop → rotate → flip
+#[napi(object)]
+#[derive(Clone, Default)]
+pub struct JpegLosslessOptions {
+  /// Crop region - x/y must be multiples of the MCU size (8 or 16 px)
+  pub crop: Option<CropOptions>,
+  /// Rotate degrees (90, 180, 270)
+  pub rotate: Option<i32>,
+  /// Flip horizontally
+  pub flip_h: Option<bool>,
+  /// Flip vertically
+  pub flip_v: Option<bool>,
+  /// Drop partial MCU blocks on the edges that cannot be transformed
+  /// When false, transforms that would leave them in place fail instead (default: false)
+  pub trim: Option<bool>,
+  /// Discard color data and output grayscale (default: false)
+  pub grayscale: Option<bool>,
+  /// Write progressive output (default: false)
+  pub progressive: Option<bool>,
+  /// Optimize Huffman tables (default: false)
+  pub optimize_coding: Option<bool>,
+  /// Set the EXIF orientation tag to 1 (normal) after transforming (default: false)
+  pub reset_orientation: Option<bool>,
+  /// Drop all extra markers including EXIF and ICC (default: false - markers are copied)
+  pub strip_metadata: Option<bool>,
 }
 
 // ============================================
@@ -380,6 +814,61 @@ pub struct ImageHashResult {
   pub algorithm: String,
 }
 
+// ============================================
+// COMPARE TYPES
+// ============================================
+
+/// Image comparison metric
+#[derive(Clone)]
+#[napi(string_enum)]
+pub enum CompareMetric {
+  /// Structural similarity, 0-1 (1 = identical)
+  Ssim,
+  /// Multi-scale structural similarity, 0-1 (1 = identical)
+  MsSsim,
+  /// Peak signal-to-noise ratio in dB (Infinity = identical)
+  Psnr,
+  /// Structural dissimilarity, 1/SSIM - 1 (0 = identical)
+  Dssim,
+}
+
+/// Color space the comparison is computed in
+#[derive(Clone)]
+#[napi(string_enum)]
+pub enum CompareColorSpace {
+  /// Luma only (Rec. 601), like the reference SSIM
+  Luma,
+  /// CIELAB, weighted towards lightness
+  Lab,
+}
+
+/// Image comparison options
+#[napi(object)]
+#[derive(Clone)]
+pub struct CompareOptions {
+  /// Color space (default: Luma)
+  pub color_space: Option<CompareColorSpace>,
+  /// Return a per-pixel difference heatmap as PNG (default: false)
+  pub heatmap: Option<bool>,
+}
+
+/// Image comparison result
+#[napi(object)]
+pub struct CompareResult {
+  /// Metric used
+  pub metric: CompareMetric,
+  /// Score (see CompareMetric for the range)
+  pub score: f64,
+  /// Compared width (dimensions of the first image)
+  pub width: u32,
+  /// Compared height
+  pub height: u32,
+  /// Whether the second image was resized to match the first
+  pub resized: bool,
+  /// Difference heatmap PNG (black = identical, white = largest difference)
+  pub heatmap: Option<Vec<u8>>,
+}
+
 // ============================================
 // SMART CROP TYPES
 // ============================================
@@ -429,6 +918,57 @@ pub struct SmartCropAnalysis {
   pub score: f64,
 }
 
+/// EXIF directory a tag is written to
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[napi(string_enum)]
+pub enum ExifIfd {
+  /// Main image directory (Make, Model, Copyright, ...)
+  Ifd0,
+  /// Exif sub-IFD (DateTimeOriginal, ExposureTime, LensModel, ...)
+  Exif,
+  /// GPS sub-IFD
+  Gps,
+}
+
+/// TIFF field type of an EXIF tag
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[napi(string_enum)]
+pub enum ExifFieldType {
+  /// 8-bit unsigned integers
+  Byte,
+  /// NUL-terminated text
+  Ascii,
+  /// 16-bit unsigned integers
+  Short,
+  /// 32-bit unsigned integers
+  Long,
+  /// Unsigned fractions
+  Rational,
+  /// Signed fractions
+  SRational,
+  /// Opaque bytes
+  Undefined,
+}
+
+/// Arbitrary EXIF tag to write - `text` for Ascii, `numbers` for numeric types,
+/// `bytes` (or `text`) for Undefined
+#[napi(object)]
+#[derive(Clone)]
+pub struct ExifWriteTag {
+  /// Directory (default: Ifd0)
+  pub ifd: Option<ExifIfd>,
+  /// Tag id, e.g. 0xA434 (LensModel)
+  pub tag: u32,
+  /// Field type
+  pub field_type: ExifFieldType,
+  /// Ascii value (Undefined takes its UTF-8 bytes)
+  pub text: Option<String>,
+  /// Byte, Short, Long, Rational and SRational values - rationals as decimals
+  pub numbers: Option<Vec<f64>>,
+  /// Undefined or Byte values
+  pub bytes: Option<Vec<u8>>,
+}
+
 /// EXIF metadata options for writing
 #[napi(object)]
 #[derive(Clone)]
@@ -453,6 +993,34 @@ pub struct ExifOptions {
   pub model: Option<String>,
   /// Orientation (1-8)
   pub orientation: Option<u16>,
+  /// GPS latitude in decimal degrees (-90 to 90, negative = south)
+  pub gps_latitude: Option<f64>,
+  /// GPS longitude in decimal degrees (-180 to 180, negative = west)
+  pub gps_longitude: Option<f64>,
+  /// GPS altitude in meters (negative = below sea level)
+  pub gps_altitude: Option<f64>,
+  /// GPS fix time in UTC (YYYY:MM:DD HH:MM:SS or ISO 8601 with Z)
+  pub gps_timestamp: Option<String>,
+  /// Arbitrary typed tags, written after (and over) the fields above
+  pub tags: Option<Vec<ExifWriteTag>>,
+  /// XMP packet to embed (JPEG, PNG and WebP)
+  pub xmp: Option<String>,
+  /// IPTC-IIM datasets to write (JPEG only), replacing any existing IPTC record
+  pub iptc: Option<IptcData>,
+  /// PNG text chunks to write (PNG only), replacing chunks with the same keyword
+  pub text: Option<Vec<PngTextEntry>>,
+}
+
+/// Selective EXIF stripping
+/// Categories: gps, maker_note, serial, copyright, artist, date, camera,
+/// description, software, orientation, thumbnail, other
+#[napi(object)]
+#[derive(Clone)]
+pub struct StripExifOptions {
+  /// Keep only these categories (orientation is kept unless removed explicitly)
+  pub keep: Option<Vec<String>>,
+  /// Remove these categories (takes precedence over keep)
+  pub remove: Option<Vec<String>>,
 }
 
 // ============================================
@@ -525,6 +1093,22 @@ pub struct ThumbnailOptions {
   /// - Uses lower quality (70 instead of 80)
   /// This can be 2-4x faster than normal mode with slight quality tradeoff
   pub fast_mode: Option<bool>,
+  /// Keep animation for animated GIF/WebP/APNG input (default: false)
+  /// Output defaults to animated WebP; PNG output is APNG; JPEG uses the first frame
+  /// Cannot be combined with max_bytes
+  pub animated: Option<bool>,
+  /// Maximum output size in bytes - searches the quality (still JPEG/WebP only)
+  /// PNG input defaults to WebP output when set
+  pub max_bytes: Option<u32>,
+  /// Step dimensions down when max_bytes can't be met at quality 1 (default: false)
+  pub downscale_to_fit: Option<bool>,
+  /// Copy the input's ICC profile into the output (default: false)
+  /// Ignored with color_management - the output is in the target profile
+  pub keep_icc: Option<bool>,
+  /// Convert pixels from the embedded ICC profile to sRGB or a target profile
+  pub color_management: Option<ColorManagementOptions>,
+  /// Copy input metadata to the output - without it the output carries none
+  pub metadata: Option<MetadataPolicy>,
 }
 
 /// Fast thumbnail result with metadata
@@ -543,4 +1127,8 @@ pub struct ThumbnailResult {
   /// Original image dimensions
   pub original_width: u32,
   pub original_height: u32,
+  /// Quality chosen by the max_bytes search
+  pub quality: Option<u32>,
+  /// Number of encodes tried by the max_bytes search
+  pub attempts: Option<u32>,
 }
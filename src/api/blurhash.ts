@@ -2,15 +2,16 @@
  * Blurhash API functions
  */
 
-import type { BlurHashResult } from "../types";
+import type { BlurHashResult, ImageInput } from "../types";
 import { native } from "../loader";
+import { toNativeInput } from "../converters";
 
 /**
  * Generate blurhash from image asynchronously
  *
  * Blurhash is a compact representation of a placeholder for an image.
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param componentsX - Number of X components (default: 4)
  * @param componentsY - Number of Y components (default: 3)
  * @returns Promise resolving to blurhash result
@@ -22,20 +23,20 @@ import { native } from "../loader";
  * ```
  */
 export async function blurhash(
-  input: Buffer,
+  input: ImageInput,
   componentsX?: number,
   componentsY?: number
 ): Promise<BlurHashResult> {
-  return native.blurhash(input, componentsX, componentsY);
+  return native.blurhash(toNativeInput(input), componentsX, componentsY);
 }
 
 /**
  * Generate blurhash from image synchronously
  */
 export function blurhashSync(
-  input: Buffer,
+  input: ImageInput,
   componentsX?: number,
   componentsY?: number
 ): BlurHashResult {
-  return native.blurhashSync(input, componentsX, componentsY);
+  return native.blurhashSync(toNativeInput(input), componentsX, componentsY);
 }
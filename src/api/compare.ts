@@ -0,0 +1,79 @@
+/**
+ * Perceptual quality metrics API
+ *
+ * Measures how close an encoded image is to its source with SSIM, MS-SSIM,
+ * PSNR or DSSIM - useful for tuning encoder quality per image category.
+ *
+ * @example
+ * ```typescript
+ * import { compare, toJpeg } from 'imgkit';
+ *
+ * const encoded = await toJpeg(original, { quality: 70 });
+ * const { score } = await compare(original, encoded, 'Ssim');
+ *
+ * if (score < 0.95) {
+ *   console.log('Quality too low for this image');
+ * }
+ * ```
+ */
+
+import type { CompareMetric, CompareOptions, CompareResult, ImageInput } from "../types";
+import { native } from "../loader";
+import { toNativeInput } from "../converters";
+
+/**
+ * Compare two images asynchronously
+ *
+ * The second image is resized to the dimensions of the first when they differ.
+ *
+ * @param a - Reference image buffer or raw pixels
+ * @param b - Image buffer or raw pixels to compare against the reference
+ * @param metric - Metric to compute (default: "Ssim")
+ * @param options - Color space and heatmap options
+ * @returns Promise resolving to the score and optional heatmap PNG
+ *
+ * @example
+ * ```typescript
+ * const result = await compare(original, encoded, 'MsSsim', {
+ *   colorSpace: 'Lab',
+ *   heatmap: true,
+ * });
+ * await Bun.write('diff.png', result.heatmap!);
+ * ```
+ */
+export async function compare(
+  a: ImageInput,
+  b: ImageInput,
+  metric?: CompareMetric,
+  options?: CompareOptions
+): Promise<CompareResult> {
+  const result = await native.compare(toNativeInput(a), toNativeInput(b), metric, options);
+  // Convert heatmap array to Buffer
+  return {
+    ...result,
+    heatmap: result.heatmap ? Buffer.from(result.heatmap) : undefined,
+  };
+}
+
+/**
+ * Compare two images synchronously
+ *
+ * @param a - Reference image buffer or raw pixels
+ * @param b - Image buffer or raw pixels to compare against the reference
+ * @param metric - Metric to compute (default: "Ssim")
+ * @param options - Color space and heatmap options
+ * @returns Score and optional heatmap PNG
+ */
+export function compareSync(
+  a: ImageInput,
+  b: ImageInput,
+  metric?: CompareMetric,
+  options?: CompareOptions
+): CompareResult {
+  const result = native.compareSync(toNativeInput(a), toNativeInput(b), metric, options);
+  // Convert heatmap array to Buffer
+  return {
+    ...result,
+    heatmap: result.heatmap ? Buffer.from(result.heatmap) : undefined,
+  };
+}
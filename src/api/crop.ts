@@ -8,8 +8,8 @@
  */
 
 import { native } from "../loader";
-import { toNapiCropOptions } from "../converters";
-import type { CropOptions } from "../types";
+import { toNapiCropOptions, toNativeInput } from "../converters";
+import type { CropOptions, ImageInput } from "../types";
 
 /**
  * Crop an image asynchronously
@@ -19,7 +19,7 @@ import type { CropOptions } from "../types";
  * 2. Aspect ratio: { aspectRatio: "16:9", gravity: "center" }
  * 3. Dimensions with gravity: { width, height, gravity: "north" }
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - Crop options
  * @returns Cropped image as PNG buffer
  *
@@ -39,19 +39,19 @@ import type { CropOptions } from "../types";
  * ```
  */
 export async function crop(
-  input: Buffer,
+  input: ImageInput,
   options: CropOptions
 ): Promise<Buffer> {
-  return native.crop(input, toNapiCropOptions(options));
+  return native.crop(toNativeInput(input), toNapiCropOptions(options));
 }
 
 /**
  * Crop an image synchronously
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - Crop options
  * @returns Cropped image as PNG buffer
  */
-export function cropSync(input: Buffer, options: CropOptions): Buffer {
-  return native.cropSync(input, toNapiCropOptions(options));
+export function cropSync(input: ImageInput, options: CropOptions): Buffer {
+  return native.cropSync(toNativeInput(input), toNapiCropOptions(options));
 }
@@ -24,8 +24,9 @@
  * ```
  */
 
-import type { DominantColorsResult } from "../types";
+import type { DominantColorsResult, ImageInput } from "../types";
 import { native } from "../loader";
+import { toNativeInput } from "../converters";
 
 /**
  * Extract dominant colors from an image asynchronously
@@ -48,10 +49,10 @@ import { native } from "../loader";
  * ```
  */
 export async function dominantColors(
-  input: Buffer,
+  input: ImageInput,
   count?: number
 ): Promise<DominantColorsResult> {
-  const result = await native.dominantColors(input, count);
+  const result = await native.dominantColors(toNativeInput(input), count);
   return {
     colors: result.colors.map((c: { r: number; g: number; b: number; hex: string }) => ({
       r: c.r,
@@ -82,10 +83,10 @@ export async function dominantColors(
  * ```
  */
 export function dominantColorsSync(
-  input: Buffer,
+  input: ImageInput,
   count?: number
 ): DominantColorsResult {
-  const result = native.dominantColorsSync(input, count);
+  const result = native.dominantColorsSync(toNativeInput(input), count);
   return {
     colors: result.colors.map((c: { r: number; g: number; b: number; hex: string }) => ({
       r: c.r,
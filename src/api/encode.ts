@@ -2,13 +2,26 @@
  * Image encoding/format conversion API functions
  */
 
-import type { JpegOptions, PngOptions, WebPOptions } from "../types";
+import type {
+  AnimationOptions,
+  JpegOptions,
+  PngOptions,
+  WebPOptions,
+  ImageInput,
+  RawImage,
+} from "../types";
 import { native } from "../loader";
+import {
+  toNapiJpegOptions,
+  toNapiPngOptions,
+  toNapiWebPOptions,
+  toNativeInput,
+} from "../converters";
 
 /**
  * Convert image to JPEG asynchronously
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - JPEG encoding options
  * @returns Promise resolving to JPEG buffer
  *
@@ -18,23 +31,23 @@ import { native } from "../loader";
  * ```
  */
 export async function toJpeg(
-  input: Buffer,
+  input: ImageInput,
   options?: JpegOptions
 ): Promise<Buffer> {
-  return native.toJpeg(input, options);
+  return native.toJpeg(toNativeInput(input), toNapiJpegOptions(options));
 }
 
 /**
  * Convert image to JPEG synchronously
  */
-export function toJpegSync(input: Buffer, options?: JpegOptions): Buffer {
-  return native.toJpegSync(input, options);
+export function toJpegSync(input: ImageInput, options?: JpegOptions): Buffer {
+  return native.toJpegSync(toNativeInput(input), toNapiJpegOptions(options));
 }
 
 /**
  * Convert image to PNG asynchronously
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - PNG encoding options
  * @returns Promise resolving to PNG buffer
  *
@@ -44,23 +57,23 @@ export function toJpegSync(input: Buffer, options?: JpegOptions): Buffer {
  * ```
  */
 export async function toPng(
-  input: Buffer,
+  input: ImageInput,
   options?: PngOptions
 ): Promise<Buffer> {
-  return native.toPng(input, options);
+  return native.toPng(toNativeInput(input), toNapiPngOptions(options));
 }
 
 /**
  * Convert image to PNG synchronously
  */
-export function toPngSync(input: Buffer, options?: PngOptions): Buffer {
-  return native.toPngSync(input, options);
+export function toPngSync(input: ImageInput, options?: PngOptions): Buffer {
+  return native.toPngSync(toNativeInput(input), toNapiPngOptions(options));
 }
 
 /**
  * Convert image to WebP asynchronously
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - WebP encoding options
  * @returns Promise resolving to WebP buffer
  *
@@ -74,15 +87,85 @@ export function toPngSync(input: Buffer, options?: PngOptions): Buffer {
  * ```
  */
 export async function toWebp(
-  input: Buffer,
+  input: ImageInput,
   options?: WebPOptions
 ): Promise<Buffer> {
-  return native.toWebp(input, options);
+  return native.toWebp(toNativeInput(input), toNapiWebPOptions(options));
 }
 
 /**
  * Convert image to WebP synchronously
  */
-export function toWebpSync(input: Buffer, options?: WebPOptions): Buffer {
-  return native.toWebpSync(input, options);
+export function toWebpSync(input: ImageInput, options?: WebPOptions): Buffer {
+  return native.toWebpSync(toNativeInput(input), toNapiWebPOptions(options));
+}
+
+/**
+ * Decode image to raw pixels asynchronously
+ *
+ * Pixels are 8-bit and tightly packed, keeping the source layout
+ * (gray, gray+alpha, RGB or RGBA). The result can be passed back as input.
+ *
+ * @param input - Image buffer or raw pixels
+ * @returns Promise resolving to pixels with width, height and channels
+ *
+ * @example
+ * ```typescript
+ * const { data, width, height, channels } = await toRaw(imageBuffer);
+ * const imageData = new ImageData(new Uint8ClampedArray(data), width, height); // channels === 4
+ * ```
+ */
+export async function toRaw(input: ImageInput): Promise<RawImage> {
+  const result = await native.toRaw(toNativeInput(input));
+  // Convert data array to Buffer
+  return { ...result, data: Buffer.from(result.data) };
+}
+
+/**
+ * Decode image to raw pixels synchronously
+ */
+export function toRawSync(input: ImageInput): RawImage {
+  const result = native.toRawSync(toNativeInput(input));
+  return { ...result, data: Buffer.from(result.data) };
+}
+
+/**
+ * Convert image to animated WebP asynchronously
+ *
+ * Animated GIF/WebP input keeps every frame; static input becomes a single frame.
+ *
+ * @param input - Image buffer or raw pixels
+ * @param options - WebP encoding options (applied to every frame)
+ * @param animation - Loop count and frame delays (default: same as input)
+ * @returns Promise resolving to animated WebP buffer
+ *
+ * @example
+ * ```typescript
+ * // GIF to animated WebP
+ * const webp = await toAnimatedWebp(gifBuffer, { quality: 75 });
+ *
+ * // Lossless, loop twice, 100ms per frame
+ * const sticker = await toAnimatedWebp(gifBuffer, { lossless: true }, {
+ *   loopCount: 2,
+ *   delay: [100],
+ * });
+ * ```
+ */
+export async function toAnimatedWebp(
+  input: ImageInput,
+  options?: WebPOptions,
+  animation?: AnimationOptions
+): Promise<Buffer> {
+  return native.toAnimatedWebp(toNativeInput(input), toNapiWebPOptions(options), animation);
+}
+
+/**
+ * Convert image to animated WebP synchronously
+ */
+export function toAnimatedWebpSync(
+  input: ImageInput,
+  options?: WebPOptions,
+  animation?: AnimationOptions
+): Buffer {
+  return native.toAnimatedWebpSync(toNativeInput(input), toNapiWebPOptions(options), animation);
 }
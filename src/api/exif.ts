@@ -2,13 +2,18 @@
  * EXIF metadata API functions
  */
 
-import type { ExifOptions } from "../types";
+import type { ExifOptions, StripExifOptions } from "../types";
 import { native } from "../loader";
+import { toNapiExifOptions } from "../converters";
 
 /**
  * Write EXIF metadata to an image asynchronously
  *
- * Supports JPEG and WebP formats.
+ * Supports JPEG, WebP, PNG (eXIf chunk) and TIFF. Tags are merged into the
+ * existing EXIF, so anything not given is kept. PNG additionally gets the
+ * descriptive fields as text chunks under the standard keywords (Title, Author,
+ * Copyright, Software, Creation Time, Source, Comment).
+ * `xmp` works on JPEG, PNG and WebP, `iptc` is JPEG only and `text` is PNG only.
  *
  * @param input - Image buffer
  * @param options - EXIF metadata options
@@ -22,46 +27,78 @@ import { native } from "../loader";
  *   software: 'My AI App v1.0',
  *   userComment: JSON.stringify({ model: 'stable-diffusion', seed: 12345 })
  * });
+ *
+ * // Geotag (written to the GPS IFD)
+ * const tagged = await writeExif(imageBuffer, {
+ *   gpsLatitude: 48.8584,
+ *   gpsLongitude: 2.2945,
+ *   gpsTimestamp: new Date()
+ * });
+ *
+ * // Any tag with an explicit type, e.g. lens details in the Exif IFD
+ * const lens = await writeExif(imageBuffer, {
+ *   tags: [
+ *     { ifd: 'Exif', tag: 0xA434, fieldType: 'Ascii', text: 'RF 24-70mm F2.8' },
+ *     { ifd: 'Exif', tag: 0x829D, fieldType: 'Rational', numbers: [2.8] },
+ *     { ifd: 'Exif', tag: 0x9204, fieldType: 'SRational', numbers: [-0.7] }
+ *   ]
+ * });
+ *
+ * // IPTC-IIM for news/stock partners
+ * const captioned = await writeExif(jpegBuffer, {
+ *   iptc: { caption: 'Harbour at dusk', keywords: ['harbour', 'sunset'], byline: ['Jane Doe'] }
+ * });
+ *
+ * // Arbitrary PNG text chunks, e.g. Stable Diffusion generation parameters
+ * const annotated = await writeExif(pngBuffer, {
+ *   text: [{ keyword: 'parameters', text: 'a cat\nSteps: 20, Sampler: Euler a' }]
+ * });
  * ```
  */
 export async function writeExif(
   input: Buffer,
   options: ExifOptions
 ): Promise<Buffer> {
-  return native.writeExif(input, options);
+  return native.writeExif(input, toNapiExifOptions(options));
 }
 
 /**
  * Write EXIF metadata to an image synchronously
  *
- * Supports JPEG and WebP formats.
+ * Supports JPEG, WebP, PNG and TIFF, see `writeExif`.
  */
 export function writeExifSync(input: Buffer, options: ExifOptions): Buffer {
-  return native.writeExifSync(input, options);
+  return native.writeExifSync(input, toNapiExifOptions(options));
 }
 
 /**
- * Strip all EXIF metadata from an image asynchronously
+ * Strip EXIF metadata from an image asynchronously
  *
- * Supports JPEG and WebP formats.
+ * Without options all EXIF is removed (JPEG and WebP). With `keep`/`remove`
+ * only the selected categories are dropped (JPEG, WebP and PNG); the
+ * remaining tags, including MakerNote offsets, are left intact.
  *
  * @param input - Image buffer
- * @returns Promise resolving to image buffer without EXIF data
+ * @param options - Categories to keep or remove
+ * @returns Promise resolving to image buffer without (the selected) EXIF data
  *
  * @example
  * ```typescript
  * const stripped = await stripExif(imageBuffer);
+ *
+ * // Drop location and device identifiers, keep copyright and capture date
+ * const safe = await stripExif(imageBuffer, { remove: ['gps', 'maker_note', 'serial'] });
  * ```
  */
-export async function stripExif(input: Buffer): Promise<Buffer> {
-  return native.stripExif(input);
+export async function stripExif(input: Buffer, options?: StripExifOptions): Promise<Buffer> {
+  return native.stripExif(input, options);
 }
 
 /**
- * Strip all EXIF metadata from an image synchronously
+ * Strip EXIF metadata from an image synchronously
  *
- * Supports JPEG and WebP formats.
+ * See {@link stripExif} for the selective mode.
  */
-export function stripExifSync(input: Buffer): Buffer {
-  return native.stripExifSync(input);
+export function stripExifSync(input: Buffer, options?: StripExifOptions): Buffer {
+  return native.stripExifSync(input, options);
 }
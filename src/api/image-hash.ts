@@ -28,8 +28,9 @@
  * ```
  */
 
-import type { ImageHashOptions, ImageHashResult } from "../types";
+import type { ImageHashOptions, ImageHashResult, ImageInput } from "../types";
 import { native } from "../loader";
+import { toNativeInput } from "../converters";
 
 /**
  * Generate a perceptual hash from an image asynchronously
@@ -52,10 +53,10 @@ import { native } from "../loader";
  * ```
  */
 export async function imageHash(
-  input: Buffer,
+  input: ImageInput,
   options?: ImageHashOptions
 ): Promise<ImageHashResult> {
-  const result = await native.imageHash(input, options?.algorithm, options?.size);
+  const result = await native.imageHash(toNativeInput(input), options?.algorithm, options?.size);
   return {
     hash: result.hash,
     width: result.width,
@@ -73,10 +74,10 @@ export async function imageHash(
  * @returns ImageHashResult with hash string
  */
 export function imageHashSync(
-  input: Buffer,
+  input: ImageInput,
   options?: ImageHashOptions
 ): ImageHashResult {
-  const result = native.imageHashSync(input, options?.algorithm, options?.size);
+  const result = native.imageHashSync(toNativeInput(input), options?.algorithm, options?.size);
   return {
     hash: result.hash,
     width: result.width,
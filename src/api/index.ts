@@ -7,8 +7,29 @@
 export { metadata, metadataSync } from "./metadata";
 export { resize, resizeSync } from "./resize";
 export { crop, cropSync } from "./crop";
-export { toJpeg, toJpegSync, toPng, toPngSync, toWebp, toWebpSync } from "./encode";
-export { transform, transformSync } from "./transform";
+export {
+  toJpeg,
+  toJpegSync,
+  toPng,
+  toPngSync,
+  toWebp,
+  toWebpSync,
+  toRaw,
+  toRawSync,
+  toAnimatedWebp,
+  toAnimatedWebpSync,
+} from "./encode";
+export {
+  transform,
+  transformSync,
+  transformWithInfo,
+  transformWithInfoSync,
+  acceptedFormats,
+} from "./transform";
+export {
+  jpegLosslessTransform,
+  jpegLosslessTransformSync,
+} from "./jpeg-lossless";
 export { blurhash, blurhashSync } from "./blurhash";
 export {
   thumbhash,
@@ -26,6 +47,7 @@ export {
   imageHashDistance,
   imageHashDistanceSync,
 } from "./image-hash";
+export { compare, compareSync } from "./compare";
 export {
   smartCrop,
   smartCropSync,
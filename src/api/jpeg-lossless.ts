@@ -0,0 +1,55 @@
+/**
+ * Lossless JPEG transform API functions
+ *
+ * Rotates, flips and crops JPEGs on the DCT coefficients (libjpeg-turbo),
+ * so there is no quality loss from decoding and re-encoding.
+ */
+
+import { native } from "../loader";
+import { toNapiJpegLosslessOptions } from "../converters";
+import type { JpegLosslessOptions } from "../types";
+
+/**
+ * Rotate, flip or crop a JPEG losslessly asynchronously
+ *
+ * Crop x/y must be multiples of the MCU size (8 or 16 px depending on
+ * chroma subsampling). EXIF and ICC markers are preserved.
+ *
+ * @param input - JPEG buffer
+ * @param options - Lossless transform options
+ * @returns Transformed JPEG buffer
+ *
+ * @example
+ * ```typescript
+ * // Rotate and reset the EXIF orientation
+ * const rotated = await jpegLosslessTransform(input, {
+ *   rotate: 90,
+ *   resetOrientation: true,
+ * });
+ *
+ * // Crop without recompressing
+ * const cropped = await jpegLosslessTransform(input, {
+ *   crop: { x: 64, y: 32, width: 800, height: 600 },
+ * });
+ * ```
+ */
+export async function jpegLosslessTransform(
+  input: Buffer,
+  options: JpegLosslessOptions
+): Promise<Buffer> {
+  return native.jpegLosslessTransform(input, toNapiJpegLosslessOptions(options));
+}
+
+/**
+ * Rotate, flip or crop a JPEG losslessly synchronously
+ *
+ * @param input - JPEG buffer
+ * @param options - Lossless transform options
+ * @returns Transformed JPEG buffer
+ */
+export function jpegLosslessTransformSync(
+  input: Buffer,
+  options: JpegLosslessOptions
+): Buffer {
+  return native.jpegLosslessTransformSync(input, toNapiJpegLosslessOptions(options));
+}
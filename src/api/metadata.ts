@@ -2,28 +2,39 @@
  * Metadata API functions
  */
 
-import type { ImageMetadata } from "../types";
+import type { ImageMetadata, ImageInput, MetadataOptions } from "../types";
 import { native } from "../loader";
+import { fromNapiMetadata, toNativeInput } from "../converters";
 
 /**
  * Get image metadata asynchronously
  *
- * @param input - Image buffer
+ * Header-only by default. With `exif: true`, every EXIF tag is decoded
+ * into `exif`; `exifRaw: true` returns the payload bytes.
+ *
+ * @param input - Image buffer or raw pixels
+ * @param options - EXIF options
  * @returns Promise resolving to image metadata
  *
  * @example
  * ```typescript
  * const info = await metadata(imageBuffer);
  * console.log(`${info.width}x${info.height} ${info.format}`);
+ *
+ * const { exif } = await metadata(imageBuffer, { exif: true });
+ * console.log(exif?.ifd0.Model, exif?.exif.FNumber, exif?.exif.DateTimeOriginal);
  * ```
  */
-export async function metadata(input: Buffer): Promise<ImageMetadata> {
-  return native.metadata(input);
+export async function metadata(
+  input: ImageInput,
+  options?: MetadataOptions
+): Promise<ImageMetadata> {
+  return fromNapiMetadata(await native.metadata(toNativeInput(input), options));
 }
 
 /**
  * Get image metadata synchronously
  */
-export function metadataSync(input: Buffer): ImageMetadata {
-  return native.metadataSync(input);
+export function metadataSync(input: ImageInput, options?: MetadataOptions): ImageMetadata {
+  return fromNapiMetadata(native.metadataSync(toNativeInput(input), options));
 }
@@ -2,14 +2,14 @@
  * Resize API functions
  */
 
-import type { ResizeOptions } from "../types";
+import type { ResizeOptions, ImageInput } from "../types";
 import { native } from "../loader";
-import { toNapiResizeOptions } from "../converters";
+import { toNapiResizeOptions, toNativeInput } from "../converters";
 
 /**
  * Resize image asynchronously
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - Resize options
  * @returns Promise resolving to resized image buffer (PNG)
  *
@@ -30,15 +30,15 @@ import { toNapiResizeOptions } from "../converters";
  * ```
  */
 export async function resize(
-  input: Buffer,
+  input: ImageInput,
   options: ResizeOptions
 ): Promise<Buffer> {
-  return native.resize(input, toNapiResizeOptions(options));
+  return native.resize(toNativeInput(input), toNapiResizeOptions(options));
 }
 
 /**
  * Resize image synchronously
  */
-export function resizeSync(input: Buffer, options: ResizeOptions): Buffer {
-  return native.resizeSync(input, toNapiResizeOptions(options));
+export function resizeSync(input: ImageInput, options: ResizeOptions): Buffer {
+  return native.resizeSync(toNativeInput(input), toNapiResizeOptions(options));
 }
@@ -26,8 +26,9 @@
  * ```
  */
 
-import type { SmartCropOptions, SmartCropAnalysis } from "../types";
+import type { SmartCropOptions, SmartCropAnalysis, ImageInput } from "../types";
 import { native } from "../loader";
+import { toNativeInput } from "../converters";
 
 /**
  * Smart crop an image using content-aware detection asynchronously
@@ -52,10 +53,10 @@ import { native } from "../loader";
  * ```
  */
 export async function smartCrop(
-  input: Buffer,
+  input: ImageInput,
   options: SmartCropOptions
 ): Promise<Buffer> {
-  return native.smartCrop(input, {
+  return native.smartCrop(toNativeInput(input), {
     width: options.width,
     height: options.height,
     aspectRatio: options.aspectRatio,
@@ -71,10 +72,10 @@ export async function smartCrop(
  * @returns Cropped image buffer (PNG format)
  */
 export function smartCropSync(
-  input: Buffer,
+  input: ImageInput,
   options: SmartCropOptions
 ): Buffer {
-  return native.smartCropSync(input, {
+  return native.smartCropSync(toNativeInput(input), {
     width: options.width,
     height: options.height,
     aspectRatio: options.aspectRatio,
@@ -102,10 +103,10 @@ export function smartCropSync(
  * ```
  */
 export async function smartCropAnalyze(
-  input: Buffer,
+  input: ImageInput,
   options: SmartCropOptions
 ): Promise<SmartCropAnalysis> {
-  const result = await native.smartCropAnalyze(input, {
+  const result = await native.smartCropAnalyze(toNativeInput(input), {
     width: options.width,
     height: options.height,
     aspectRatio: options.aspectRatio,
@@ -128,10 +129,10 @@ export async function smartCropAnalyze(
  * @returns SmartCropAnalysis with crop coordinates and score
  */
 export function smartCropAnalyzeSync(
-  input: Buffer,
+  input: ImageInput,
   options: SmartCropOptions
 ): SmartCropAnalysis {
-  const result = native.smartCropAnalyzeSync(input, {
+  const result = native.smartCropAnalyzeSync(toNativeInput(input), {
     width: options.width,
     height: options.height,
     aspectRatio: options.aspectRatio,
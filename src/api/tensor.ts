@@ -4,7 +4,8 @@
  */
 
 import { native } from '../loader';
-import type { TensorOptions, TensorResult, NapiTensorOptions } from '../types';
+import { toNativeInput } from '../converters';
+import type { TensorOptions, TensorResult, NapiTensorOptions, ImageInput } from '../types';
 
 /**
  * Convert tensor options to native format
@@ -79,8 +80,8 @@ function enhanceTensorResult(result: TensorResult): EnhancedTensorResult {
  * const ortTensor = new ort.Tensor('float32', float32Data, tensor.shape);
  * ```
  */
-export async function toTensor(input: Buffer, options?: TensorOptions): Promise<EnhancedTensorResult> {
-  const result = await native.toTensor(input, toNapiTensorOptions(options));
+export async function toTensor(input: ImageInput, options?: TensorOptions): Promise<EnhancedTensorResult> {
+  const result = await native.toTensor(toNativeInput(input), toNapiTensorOptions(options));
   return enhanceTensorResult(result);
 }
 
@@ -91,7 +92,7 @@ export async function toTensor(input: Buffer, options?: TensorOptions): Promise<
  * @param options - Tensor conversion options
  * @returns Tensor data with shape and metadata
  */
-export function toTensorSync(input: Buffer, options?: TensorOptions): EnhancedTensorResult {
-  const result = native.toTensorSync(input, toNapiTensorOptions(options));
+export function toTensorSync(input: ImageInput, options?: TensorOptions): EnhancedTensorResult {
+  const result = native.toTensorSync(toNativeInput(input), toNapiTensorOptions(options));
   return enhanceTensorResult(result);
 }
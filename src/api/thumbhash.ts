@@ -11,8 +11,9 @@
  * - Smoother gradients
  */
 
-import type { ThumbHashResult, ThumbHashDecodeResult } from "../types";
+import type { ThumbHashResult, ThumbHashDecodeResult, ImageInput } from "../types";
 import { native } from "../loader";
+import { toNativeInput } from "../converters";
 
 /**
  * Convert thumbhash RGBA to PNG data URL
@@ -130,8 +131,8 @@ function getCrc32Table(): Uint32Array {
  * await db.insert({ thumbhash: hash });
  * ```
  */
-export async function thumbhash(input: Buffer): Promise<ThumbHashResult> {
-  const result = await native.thumbhash(input);
+export async function thumbhash(input: ImageInput): Promise<ThumbHashResult> {
+  const result = await native.thumbhash(toNativeInput(input));
 
   // Decode to get RGBA for data URL generation
   const decoded = await native.thumbhashToRgba(Buffer.from(result.hash));
@@ -153,8 +154,8 @@ export async function thumbhash(input: Buffer): Promise<ThumbHashResult> {
 /**
  * Generate thumbhash from image synchronously
  */
-export function thumbhashSync(input: Buffer): ThumbHashResult {
-  const result = native.thumbhashSync(input);
+export function thumbhashSync(input: ImageInput): ThumbHashResult {
+  const result = native.thumbhashSync(toNativeInput(input));
 
   // Decode to get RGBA for data URL generation
   const decoded = native.thumbhashToRgbaSync(Buffer.from(result.hash));
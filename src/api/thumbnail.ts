@@ -8,7 +8,8 @@
  */
 
 import { native } from "../loader";
-import type { ThumbnailOptions, ThumbnailResult } from "../types";
+import { toNapiColorManagementOptions, toNapiMetadataPolicy, toNativeInput } from "../converters";
+import type { ThumbnailOptions, ThumbnailResult, ImageInput } from "../types";
 
 /**
  * Generate a fast thumbnail synchronously
@@ -40,10 +41,10 @@ import type { ThumbnailOptions, ThumbnailResult } from "../types";
  * ```
  */
 export function thumbnailSync(
-  input: Buffer,
+  input: ImageInput,
   options: ThumbnailOptions
 ): ThumbnailResult {
-  const result = native.thumbnailSync(input, {
+  const result = native.thumbnailSync(toNativeInput(input), {
     width: options.width,
     height: options.height,
     format: options.format,
@@ -51,6 +52,12 @@ export function thumbnailSync(
     shrinkOnLoad: options.shrinkOnLoad,
     filter: options.filter,
     fastMode: options.fastMode,
+    animated: options.animated,
+    maxBytes: options.maxBytes,
+    downscaleToFit: options.downscaleToFit,
+    keepIcc: options.keepIcc,
+    colorManagement: toNapiColorManagementOptions(options.colorManagement),
+    metadata: toNapiMetadataPolicy(options.metadata),
   });
   // Convert data array to Buffer
   return {
@@ -88,10 +95,10 @@ export function thumbnailSync(
  * ```
  */
 export async function thumbnail(
-  input: Buffer,
+  input: ImageInput,
   options: ThumbnailOptions
 ): Promise<ThumbnailResult> {
-  const result = await native.thumbnail(input, {
+  const result = await native.thumbnail(toNativeInput(input), {
     width: options.width,
     height: options.height,
     format: options.format,
@@ -99,6 +106,12 @@ export async function thumbnail(
     shrinkOnLoad: options.shrinkOnLoad,
     filter: options.filter,
     fastMode: options.fastMode,
+    animated: options.animated,
+    maxBytes: options.maxBytes,
+    downscaleToFit: options.downscaleToFit,
+    keepIcc: options.keepIcc,
+    colorManagement: toNapiColorManagementOptions(options.colorManagement),
+    metadata: toNapiMetadataPolicy(options.metadata),
   });
   // Convert data array to Buffer
   return {
@@ -112,7 +125,7 @@ export async function thumbnail(
  *
  * Same optimizations as thumbnailSync() but returns only the image data.
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - Thumbnail options
  * @returns Image buffer
  *
@@ -125,10 +138,10 @@ export async function thumbnail(
  * ```
  */
 export function thumbnailBufferSync(
-  input: Buffer,
+  input: ImageInput,
   options: ThumbnailOptions
 ): Buffer {
-  return native.thumbnailBufferSync(input, {
+  return native.thumbnailBufferSync(toNativeInput(input), {
     width: options.width,
     height: options.height,
     format: options.format,
@@ -136,6 +149,12 @@ export function thumbnailBufferSync(
     shrinkOnLoad: options.shrinkOnLoad,
     filter: options.filter,
     fastMode: options.fastMode,
+    animated: options.animated,
+    maxBytes: options.maxBytes,
+    downscaleToFit: options.downscaleToFit,
+    keepIcc: options.keepIcc,
+    colorManagement: toNapiColorManagementOptions(options.colorManagement),
+    metadata: toNapiMetadataPolicy(options.metadata),
   });
 }
 
@@ -144,7 +163,7 @@ export function thumbnailBufferSync(
  *
  * Same optimizations as thumbnail() but returns only the image data.
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - Thumbnail options
  * @returns Promise resolving to image buffer
  *
@@ -157,10 +176,10 @@ export function thumbnailBufferSync(
  * ```
  */
 export async function thumbnailBuffer(
-  input: Buffer,
+  input: ImageInput,
   options: ThumbnailOptions
 ): Promise<Buffer> {
-  return native.thumbnailBuffer(input, {
+  return native.thumbnailBuffer(toNativeInput(input), {
     width: options.width,
     height: options.height,
     format: options.format,
@@ -168,5 +187,11 @@ export async function thumbnailBuffer(
     shrinkOnLoad: options.shrinkOnLoad,
     filter: options.filter,
     fastMode: options.fastMode,
+    animated: options.animated,
+    maxBytes: options.maxBytes,
+    downscaleToFit: options.downscaleToFit,
+    keepIcc: options.keepIcc,
+    colorManagement: toNapiColorManagementOptions(options.colorManagement),
+    metadata: toNapiMetadataPolicy(options.metadata),
   });
 }
@@ -2,9 +2,9 @@
  * Transform API functions
  */
 
-import type { TransformOptions } from "../types";
+import type { TransformOptions, TransformResult, ImageInput, ImageFormat } from "../types";
 import { native } from "../loader";
-import { toNapiTransformOptions } from "../converters";
+import { toNapiTransformOptions, toNativeInput } from "../converters";
 
 /**
  * Transform image with multiple operations asynchronously
@@ -12,7 +12,7 @@ import { toNapiTransformOptions } from "../converters";
  * This is the most efficient way to apply multiple transformations
  * as it processes the image only once.
  *
- * @param input - Image buffer
+ * @param input - Image buffer or raw pixels
  * @param options - Transform options
  * @returns Promise resolving to transformed image buffer
  *
@@ -27,18 +27,110 @@ import { toNapiTransformOptions } from "../converters";
  * ```
  */
 export async function transform(
-  input: Buffer,
+  input: ImageInput,
   options: TransformOptions
 ): Promise<Buffer> {
-  return native.transform(input, toNapiTransformOptions(options));
+  return native.transform(toNativeInput(input), toNapiTransformOptions(options));
 }
 
 /**
  * Transform image with multiple operations synchronously
  */
 export function transformSync(
-  input: Buffer,
+  input: ImageInput,
   options: TransformOptions
 ): Buffer {
-  return native.transformSync(input, toNapiTransformOptions(options));
+  return native.transformSync(toNativeInput(input), toNapiTransformOptions(options));
+}
+
+/**
+ * Transform image asynchronously and return encoder details with the buffer
+ *
+ * Reports the output dimensions and, with `output.maxBytes` or
+ * `output.targetQuality`, the chosen quality, how many encodes the search
+ * took and the metric score reached.
+ *
+ * @param input - Image buffer or raw pixels
+ * @param options - Transform options
+ * @returns Promise resolving to the output buffer and details
+ *
+ * @example
+ * ```typescript
+ * const { data, quality, attempts } = await transformWithInfo(imageBuffer, {
+ *   resize: { width: 1200 },
+ *   output: { format: 'jpeg', maxBytes: 100_000 }
+ * });
+ *
+ * // Lowest WebP quality that keeps SSIM >= 0.98
+ * const { quality, score } = await transformWithInfo(imageBuffer, {
+ *   output: { format: 'webp', targetQuality: { metric: 'Ssim', value: 0.98 } }
+ * });
+ *
+ * // Let the content and the client's Accept header pick the format
+ * const { data, format } = await transformWithInfo(imageBuffer, {
+ *   output: { format: 'auto', accept: acceptedFormats(req.headers.get('accept')) }
+ * });
+ * ```
+ */
+export async function transformWithInfo(
+  input: ImageInput,
+  options: TransformOptions
+): Promise<TransformResult> {
+  const result = await native.transformWithInfo(toNativeInput(input), toNapiTransformOptions(options));
+  // Convert data array to Buffer
+  return {
+    ...result,
+    data: Buffer.from(result.data),
+  };
+}
+
+/**
+ * Transform image synchronously and return encoder details with the buffer
+ */
+export function transformWithInfoSync(
+  input: ImageInput,
+  options: TransformOptions
+): TransformResult {
+  const result = native.transformWithInfoSync(toNativeInput(input), toNapiTransformOptions(options));
+  // Convert data array to Buffer
+  return {
+    ...result,
+    data: Buffer.from(result.data),
+  };
+}
+
+/** Image MIME types understood by `acceptedFormats` */
+const MIME_FORMATS: Record<string, ImageFormat> = {
+  "image/jpeg": "jpeg",
+  "image/png": "png",
+  "image/webp": "webp",
+  "image/avif": "avif",
+};
+
+/**
+ * Build `output.accept` for `format: "auto"` from an HTTP `Accept` header (helper function)
+ *
+ * JPEG and PNG are always included - every browser decodes them, even when
+ * the header only lists `image/webp` and wildcards. Types with `q=0` are excluded.
+ *
+ * @param header - Value of the `Accept` request header
+ * @returns Accepted formats
+ *
+ * @example
+ * ```typescript
+ * acceptedFormats("image/avif,image/webp,image/apng,image/*,*\/*;q=0.8");
+ * // ["jpeg", "png", "avif", "webp"]
+ * ```
+ */
+export function acceptedFormats(header?: string | null): ImageFormat[] {
+  const formats: ImageFormat[] = ["jpeg", "png"];
+  for (const part of (header ?? "").split(",")) {
+    const [type, ...params] = part.trim().toLowerCase().split(";");
+    const format = MIME_FORMATS[type!.trim()];
+    const rejected = params.some((p) => /^\s*q\s*=\s*0(\.0*)?\s*$/.test(p));
+    if (format && !rejected && !formats.includes(format)) {
+      formats.push(format);
+    }
+  }
+  return formats;
 }
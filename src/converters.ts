@@ -5,14 +5,58 @@
  */
 
 import type {
+  ColorManagementOptions,
   CropOptions,
+  ExifData,
+  ExifOptions,
+  ExifValue,
+  ImageMetadata,
+  ImageInput,
+  JpegLosslessOptions,
+  JpegOptions,
+  MetadataPolicy,
+  PngOptions,
   ResizeOptions,
   TransformOptions,
+  WebPOptions,
+  NapiColorManagementOptions,
   NapiCropOptions,
+  NapiExifEntry,
+  NapiExifOptions,
+  NapiImageMetadata,
+  NapiJpegLosslessOptions,
+  NapiJpegOptions,
+  NapiMetadataPolicy,
+  NapiPngOptions,
   NapiResizeOptions,
   NapiTransformOptions,
+  NapiWebPOptions,
 } from "./types";
 
+/** Magic of the native raw pixel container */
+const RAW_MAGIC = "IMGKRAW1";
+
+/** Raw pixel container header length (magic, width, height, channels, reserved) */
+const RAW_HEADER_LEN = 20;
+
+/**
+ * Convert image input to the buffer passed to the native module
+ *
+ * Raw pixels get a small header so the native side wraps them
+ * without running a decoder.
+ */
+export function toNativeInput(input: ImageInput): Buffer {
+  if (Buffer.isBuffer(input)) return input;
+
+  const { data, width, height, channels } = input;
+  const header = Buffer.alloc(RAW_HEADER_LEN);
+  header.write(RAW_MAGIC, 0, "latin1");
+  header.writeUInt32LE(width, 8);
+  header.writeUInt32LE(height, 12);
+  header.writeUInt8(channels, 16);
+  return Buffer.concat([header, new Uint8Array(data.buffer, data.byteOffset, data.byteLength)]);
+}
+
 /**
  * Convert resize filter to napi format
  */
@@ -80,10 +124,70 @@ export function toNapiFormat(format: string): string {
     tiff: "Tiff",
     heic: "Heic",
     avif: "Avif",
+    qoi: "Qoi",
+    pnm: "Pnm",
+    tga: "Tga",
+    raw: "Raw",
+    auto: "Auto",
   };
   return formatMap[format.toLowerCase()] || format;
 }
 
+/**
+ * Convert JPEG options to napi format (ICC profile as a byte array)
+ */
+export function toNapiJpegOptions(options?: JpegOptions): NapiJpegOptions | undefined {
+  if (!options?.iccProfile) {
+    return options as NapiJpegOptions | undefined;
+  }
+  return { ...options, iccProfile: Array.from(options.iccProfile) };
+}
+
+/**
+ * Convert PNG options to napi format (ICC profile as a byte array)
+ */
+export function toNapiPngOptions(options?: PngOptions): NapiPngOptions | undefined {
+  if (!options?.iccProfile) {
+    return options as NapiPngOptions | undefined;
+  }
+  return { ...options, iccProfile: Array.from(options.iccProfile) };
+}
+
+/**
+ * Convert WebP options to napi format (ICC profile as a byte array)
+ */
+export function toNapiWebPOptions(options?: WebPOptions): NapiWebPOptions | undefined {
+  if (!options?.iccProfile) {
+    return options as NapiWebPOptions | undefined;
+  }
+  return { ...options, iccProfile: Array.from(options.iccProfile) };
+}
+
+/**
+ * Convert color management options to napi format (target profile as a byte array)
+ */
+export function toNapiColorManagementOptions(
+  options?: ColorManagementOptions
+): NapiColorManagementOptions | undefined {
+  if (!options?.targetProfile) {
+    return options as NapiColorManagementOptions | undefined;
+  }
+  return { ...options, targetProfile: Array.from(options.targetProfile) };
+}
+
+/**
+ * Convert a metadata policy to napi format ("keep" = no keep list, "strip" = an empty one)
+ */
+export function toNapiMetadataPolicy(policy?: MetadataPolicy): NapiMetadataPolicy | undefined {
+  if (policy === undefined) {
+    return undefined;
+  }
+  if (policy === "strip") {
+    return { keep: [] };
+  }
+  return policy === "keep" ? {} : { keep: policy.keep };
+}
+
 /**
  * Convert transform options to napi format
  */
@@ -103,9 +207,14 @@ export function toNapiTransformOptions(
   if (options.output) {
     result.output = {
       format: toNapiFormat(options.output.format),
-      jpeg: options.output.jpeg,
-      png: options.output.png,
-      webp: options.output.webp,
+      jpeg: toNapiJpegOptions(options.output.jpeg),
+      png: toNapiPngOptions(options.output.png),
+      webp: toNapiWebPOptions(options.output.webp),
+      gif: options.output.gif,
+      maxBytes: options.output.maxBytes,
+      downscaleToFit: options.output.downscaleToFit,
+      targetQuality: options.output.targetQuality,
+      accept: options.output.accept?.map(toNapiFormat),
     };
   }
 
@@ -119,8 +228,125 @@ export function toNapiTransformOptions(
   result.contrast = options.contrast;
 
   if (options.exif) {
-    result.exif = options.exif;
+    result.exif = toNapiExifOptions(options.exif);
+  }
+  result.xmp = options.xmp;
+  result.keepIcc = options.keepIcc;
+  result.colorManagement = toNapiColorManagementOptions(options.colorManagement);
+  result.metadata = toNapiMetadataPolicy(options.metadata);
+
+  result.animated = options.animated;
+  if (options.animation) {
+    result.animation = options.animation;
   }
 
   return result;
 }
+
+/**
+ * Convert lossless JPEG transform options to napi format
+ */
+export function toNapiJpegLosslessOptions(
+  options: JpegLosslessOptions
+): NapiJpegLosslessOptions {
+  return {
+    ...options,
+    crop: options.crop ? toNapiCropOptions(options.crop) : undefined,
+  };
+}
+
+/**
+ * Convert ExifOptions to NAPI format (Date GPS timestamps become ISO strings,
+ * Buffer tag bytes become arrays)
+ */
+export function toNapiExifOptions(options: ExifOptions): NapiExifOptions {
+  const { gpsTimestamp, tags, ...rest } = options;
+  return {
+    ...rest,
+    gpsTimestamp: gpsTimestamp instanceof Date ? gpsTimestamp.toISOString() : gpsTimestamp,
+    tags: tags?.map(({ bytes, ...tag }) => ({
+      ...tag,
+      bytes: bytes ? Array.from(bytes) : undefined,
+    })),
+  };
+}
+
+/** Date tags and the Exif 2.31 offset tags that give their time zone */
+const EXIF_DATE_TAGS: Record<string, string> = {
+  DateTime: "OffsetTime",
+  DateTimeOriginal: "OffsetTimeOriginal",
+  DateTimeDigitized: "OffsetTimeDigitized",
+};
+
+/**
+ * Parse an EXIF date ("YYYY:MM:DD HH:MM:SS")
+ *
+ * Uses the offset tag when present, UTC otherwise. Returns undefined for
+ * blank or malformed dates so the original string is kept.
+ */
+function parseExifDate(value: string, offset?: string): Date | undefined {
+  const match = /^(\d{4}):(\d{2}):(\d{2}) (\d{2}):(\d{2}):(\d{2})$/.exec(value.trim());
+  if (!match) {
+    return undefined;
+  }
+  const [, year, month, day, hour, minute, second] = match;
+  const zone = offset && /^[+-]\d{2}:\d{2}$/.test(offset) ? offset : "Z";
+  const date = new Date(`${year}-${month}-${day}T${hour}:${minute}:${second}${zone}`);
+  return Number.isNaN(date.getTime()) ? undefined : date;
+}
+
+/**
+ * Convert native EXIF entries to tags keyed by name per directory
+ */
+export function toExifData(entries: NapiExifEntry[]): ExifData {
+  const data: ExifData = { ifd0: {}, exif: {}, gps: {}, interop: {} };
+
+  for (const entry of entries) {
+    let value: ExifValue | undefined;
+    if (entry.text !== undefined && entry.text !== null) {
+      value = entry.text;
+    } else if (entry.numbers) {
+      value = entry.numbers.length === 1 ? entry.numbers[0]! : entry.numbers;
+    } else if (entry.bytes) {
+      value = Buffer.from(entry.bytes);
+    }
+    if (value !== undefined) {
+      data[entry.ifd as keyof ExifData][entry.name] = value;
+    }
+  }
+
+  // Dates live in IFD0 (DateTime) and the Exif IFD, offsets in the Exif IFD
+  for (const tags of [data.ifd0, data.exif]) {
+    for (const [name, offsetName] of Object.entries(EXIF_DATE_TAGS)) {
+      const value = tags[name];
+      const offset = data.exif[offsetName];
+      if (typeof value === "string") {
+        tags[name] = parseExifDate(value, typeof offset === "string" ? offset : undefined) ?? value;
+      }
+    }
+  }
+
+  return data;
+}
+
+/**
+ * Convert native metadata (EXIF entries and raw bytes) to the public shape
+ */
+export function fromNapiMetadata(metadata: NapiImageMetadata): ImageMetadata {
+  const { exif, exifRaw, gps, iccProfile, ...rest } = metadata;
+  const result: ImageMetadata = rest;
+  if (exif) {
+    result.exif = toExifData(exif);
+  }
+  if (gps) {
+    const { timestamp, ...position } = gps;
+    result.gps = timestamp ? { ...position, timestamp: new Date(timestamp) } : position;
+  }
+  if (exifRaw) {
+    result.exifRaw = Buffer.from(exifRaw);
+  }
+  if (iccProfile) {
+    result.iccProfile = Buffer.from(iccProfile);
+  }
+  return result;
+}
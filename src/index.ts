@@ -32,16 +32,44 @@ export type {
   CropOptions,
   ResizeOptions,
   JpegOptions,
+  JpegColorSpace,
+  JpegQuantTable,
   PngOptions,
+  GifOptions,
   WebPOptions,
+  WebPPreset,
   AvifOptions,
   OutputOptions,
   ImageMetadata,
+  MetadataOptions,
+  ExifData,
+  ExifTags,
+  ExifValue,
+  GpsInfo,
+  XmpData,
+  IptcData,
+  PngTextEntry,
   BlurHashResult,
   ThumbHashResult,
   ThumbHashDecodeResult,
   TransformOptions,
+  TransformResult,
+  ColorManagementOptions,
+  RenderingIntent,
+  MetadataCategory,
+  MetadataPolicy,
+  TargetQuality,
+  ImageInput,
+  RawImageInput,
+  RawImage,
+  AnimationOptions,
+  JpegLosslessOptions,
   ExifOptions,
+  ExifWriteTag,
+  ExifIfd,
+  ExifFieldType,
+  ExifCategory,
+  StripExifOptions,
   TensorDtype,
   TensorLayout,
   TensorNormalization,
@@ -51,6 +79,10 @@ export type {
   HashSize,
   ImageHashOptions,
   ImageHashResult,
+  CompareMetric,
+  CompareColorSpace,
+  CompareOptions,
+  CompareResult,
   AspectRatio,
   SmartCropBoostRegion,
   SmartCropOptions,
@@ -77,8 +109,17 @@ export {
   toPngSync,
   toWebp,
   toWebpSync,
+  toRaw,
+  toRawSync,
+  toAnimatedWebp,
+  toAnimatedWebpSync,
   transform,
   transformSync,
+  transformWithInfo,
+  transformWithInfoSync,
+  acceptedFormats,
+  jpegLosslessTransform,
+  jpegLosslessTransformSync,
   blurhash,
   blurhashSync,
   thumbhash,
@@ -96,6 +137,8 @@ export {
   imageHashSync,
   imageHashDistance,
   imageHashDistanceSync,
+  compare,
+  compareSync,
   smartCrop,
   smartCropSync,
   smartCropAnalyze,
@@ -132,8 +175,17 @@ import {
   toPngSync,
   toWebp,
   toWebpSync,
+  toRaw,
+  toRawSync,
+  toAnimatedWebp,
+  toAnimatedWebpSync,
   transform,
   transformSync,
+  transformWithInfo,
+  transformWithInfoSync,
+  acceptedFormats,
+  jpegLosslessTransform,
+  jpegLosslessTransformSync,
   blurhash,
   blurhashSync,
   thumbhash,
@@ -151,6 +203,8 @@ import {
   imageHashSync,
   imageHashDistance,
   imageHashDistanceSync,
+  compare,
+  compareSync,
   smartCrop,
   smartCropSync,
   smartCropAnalyze,
@@ -177,8 +231,17 @@ export default {
   toPngSync,
   toWebp,
   toWebpSync,
+  toRaw,
+  toRawSync,
+  toAnimatedWebp,
+  toAnimatedWebpSync,
   transform,
   transformSync,
+  transformWithInfo,
+  transformWithInfoSync,
+  acceptedFormats,
+  jpegLosslessTransform,
+  jpegLosslessTransformSync,
   blurhash,
   blurhashSync,
   thumbhash,
@@ -196,6 +259,8 @@ export default {
   imageHashSync,
   imageHashDistance,
   imageHashDistanceSync,
+  compare,
+  compareSync,
   smartCrop,
   smartCropSync,
   smartCropAnalyze,
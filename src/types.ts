@@ -12,7 +12,39 @@ export type ImageFormat =
   | "ico"
   | "tiff"
   | "heic"
-  | "avif";
+  | "avif"
+  | "qoi"
+  | "pnm"
+  | "tga"
+  | "raw" // Tightly packed 8-bit pixels (see TransformResult.channels)
+  | "auto"; // Chosen from the image content (transform only, see OutputOptions.accept)
+
+/** Raw 8-bit pixels, tightly packed row by row */
+export interface RawImageInput {
+  /** Pixel data (width * height * channels bytes) */
+  data: Buffer | Uint8Array | Uint8ClampedArray;
+  /** Width in pixels */
+  width: number;
+  /** Height in pixels */
+  height: number;
+  /** Channels per pixel: 1 = gray, 2 = gray+alpha, 3 = RGB, 4 = RGBA */
+  channels: 1 | 2 | 3 | 4;
+}
+
+/** Encoded image buffer or raw pixels */
+export type ImageInput = Buffer | RawImageInput;
+
+/** Raw pixel output */
+export interface RawImage {
+  /** Tightly packed 8-bit pixels, row-major */
+  data: Buffer;
+  /** Width in pixels */
+  width: number;
+  /** Height in pixels */
+  height: number;
+  /** Channels per pixel: 1 = gray, 2 = gray+alpha, 3 = RGB, 4 = RGBA */
+  channels: number;
+}
 
 /** Resize filter/algorithm */
 export type ResizeFilter =
@@ -72,24 +104,140 @@ export interface ResizeOptions {
   background?: number[];
 }
 
+/** JPEG output color space */
+export type JpegColorSpace =
+  | "Rgb" // YCbCr from RGB
+  | "Gray" // Single luma channel (default for grayscale images)
+  | "Cmyk"; // Adobe YCCK from CMYK, for print
+
+/** ICC rendering intent used when converting between color profiles */
+export type RenderingIntent =
+  | "Perceptual" // Compress the whole gamut to fit (photos)
+  | "RelativeColorimetric" // Keep in-gamut colors exact, clip the rest
+  | "Saturation" // Keep colors vivid (charts, graphics)
+  | "AbsoluteColorimetric"; // Keep in-gamut colors exact including the white point (proofing)
+
+/**
+ * Color management - convert decoded pixels from the embedded ICC profile
+ * (Display P3, Adobe RGB, ProPhoto, CMYK, ...) before any resize.
+ * Images without a profile are treated as sRGB.
+ */
+export interface ColorManagementOptions {
+  /** RGB ICC profile to convert to, embedded in the output (default: sRGB, not embedded) */
+  targetProfile?: Buffer;
+  /** Rendering intent (default: "Perceptual") */
+  intent?: RenderingIntent;
+}
+
+/** mozjpeg base quantization table preset */
+export type JpegQuantTable =
+  | "AnnexK" // JPEG Annex K (libjpeg default)
+  | "Flat" // Flat table
+  | "MsSsim" // Tuned for MS-SSIM
+  | "ImageMagick" // N. Robidoux (mozjpeg default)
+  | "PsnrHvsM" // Tuned for PSNR-HVS-M
+  | "KleinSilversteinCarney"
+  | "WatsonTaylorBorthwick"
+  | "AhumadaWatsonPeterson"
+  | "PetersonAhumadaWatson";
+
 /** JPEG encode options */
 export interface JpegOptions {
   /** Quality 1-100 (default: 80) */
   quality?: number;
+  /** Progressive scans (default: false, or true with mozjpeg) */
+  progressive?: boolean;
+  /** Chroma subsampling (default: "4:2:0") */
+  chromaSubsampling?: "4:4:4" | "4:2:2" | "4:2:0";
+  /** Optimize Huffman tables (default: false, or true with mozjpeg) */
+  optimizeCoding?: boolean;
+  /** Trellis quantisation - mozjpeg only (default: true) */
+  trellisQuantisation?: boolean;
+  /** Overshoot deringing for hard edges on white - mozjpeg only (default: true) */
+  overshootDeringing?: boolean;
+  /** Base quantization table preset - mozjpeg only (default: "ImageMagick") */
+  quantTable?: JpegQuantTable;
+  /**
+   * Encode with mozjpeg instead of turbojpeg (default: false)
+   *
+   * Slower, but typically produces 10-20% smaller files.
+   */
+  mozjpeg?: boolean;
+  /** Output color space (default: "Gray" for grayscale images, "Rgb" otherwise) */
+  colorSpace?: JpegColorSpace;
+  /**
+   * ICC profile embedded in the output
+   *
+   * With `colorSpace: "Cmyk"` this must be a CMYK profile (e.g. your printer's
+   * FOGRA/GRACoL profile); sRGB pixels are converted through it. Without a
+   * profile, CMYK uses a naive device conversion.
+   */
+  iccProfile?: Buffer;
 }
 
 /** PNG encode options */
 export interface PngOptions {
   /** Compression level 0-9 (default: 6) */
   compression?: number;
+  /** Quantize to an indexed palette (PNG-8) - implied by colors/dither/quality (default: false) */
+  palette?: boolean;
+  /** Maximum palette size 2-256 (default: 256) */
+  colors?: number;
+  /** Floyd-Steinberg dithering amount 0.0-1.0 (default: 1.0) */
+  dither?: number;
+  /** Target quality 0-100 - stops adding colors once reached (default: 100) */
+  quality?: number;
+  /** Minimum quality 0-100 - below it a truecolor PNG is written instead (default: 0) */
+  minQuality?: number;
+  /** Lossless optimization level 0-6 - reduces color type/bit depth and tries more filters (default: 0) */
+  optimize?: number;
+  /** Keep ancillary chunks (text, EXIF, ICC) from a PNG source when optimizing (default: false) */
+  keepMetadata?: boolean;
+  /** ICC profile embedded in the output as an iCCP chunk */
+  iccProfile?: Buffer;
+}
+
+/** GIF encode options */
+export interface GifOptions {
+  /** Maximum palette size 2-256 (default: 256) */
+  colors?: number;
+  /** Floyd-Steinberg dithering amount 0.0-1.0 (default: 1.0) */
+  dither?: number;
 }
 
+/** libwebp encoder preset (tunes filtering and noise shaping for the content) */
+export type WebPPreset =
+  | "Default" // libwebp default
+  | "Picture" // Digital picture, like portrait or indoor shot
+  | "Photo" // Outdoor photograph with natural lighting
+  | "Drawing" // Hand or line drawing with high-contrast details
+  | "Icon" // Small-sized colorful image
+  | "Text"; // Text-like content
+
 /** WebP encode options */
 export interface WebPOptions {
   /** Quality 1-100 for lossy, ignored for lossless (default: 80) */
   quality?: number;
   /** Use lossless compression (default: false) */
   lossless?: boolean;
+  /** Speed/size trade-off 0-6, higher is slower and smaller (default: 4) */
+  method?: number;
+  /** Alpha plane quality 0-100 (default: 100) */
+  alphaQuality?: number;
+  /** Near-lossless preprocessing 0-100, lower is lossier; implies lossless (default: 100 = off) */
+  nearLossless?: number;
+  /** Sharp (slower) RGB to YUV conversion for crisper chroma edges (default: false) */
+  smartSubsample?: boolean;
+  /** Content preset applied before the other options (default: "Default") */
+  preset?: WebPPreset;
+  /** Keep RGB values under fully transparent pixels (default: false) */
+  exact?: boolean;
+  /** Target output size in bytes - lossy only, overrides quality (default: off) */
+  targetSize?: number;
+  /** Target PSNR in dB - lossy only, takes precedence over targetSize (default: off) */
+  targetPsnr?: number;
+  /** ICC profile embedded in the output as an ICCP chunk (still images only) */
+  iccProfile?: Buffer;
 }
 
 /** AVIF encode options */
@@ -110,8 +258,42 @@ export interface OutputOptions {
   png?: PngOptions;
   /** WebP options (if format is webp) */
   webp?: WebPOptions;
+  /** GIF options (if format is gif) */
+  gif?: GifOptions;
   /** AVIF options (if format is avif) */
   avif?: AvifOptions;
+  /**
+   * Maximum output size in bytes (JPEG/WebP)
+   *
+   * Binary-searches the quality below the format's `quality` (default: 80) until the output fits.
+   */
+  maxBytes?: number;
+  /** Step dimensions down when `maxBytes` can't be met at quality 1 (default: false) */
+  downscaleToFit?: boolean;
+  /**
+   * Perceptual quality target (JPEG/WebP)
+   *
+   * Binary-searches for the lowest quality whose decoded output still meets the
+   * metric, below the format's `quality` (default: 100). Combined with `maxBytes`,
+   * the byte budget wins when the target doesn't fit.
+   */
+  targetQuality?: TargetQuality;
+  /**
+   * Formats the client accepts, for `format: "auto"` (default: jpeg, png, webp)
+   *
+   * Use `acceptedFormats()` to build it from an HTTP `Accept` header.
+   */
+  accept?: ImageFormat[];
+}
+
+/** Perceptual quality target for adaptive encoding */
+export interface TargetQuality {
+  /** Metric to measure (default: Ssim) */
+  metric?: CompareMetric;
+  /** Score to reach - minimum for Ssim/MsSsim/Psnr, maximum for Dssim */
+  value: number;
+  /** Color space the metric is computed in (default: Luma) */
+  colorSpace?: CompareColorSpace;
 }
 
 /** Image metadata (sharp-compatible) */
@@ -120,7 +302,7 @@ export interface ImageMetadata {
   width: number;
   /** Image height in pixels */
   height: number;
-  /** Detected format (jpeg, png, webp, gif, bmp, ico, tiff) */
+  /** Detected format (jpeg, png, webp, gif, bmp, ico, tiff, heic, jxl, qoi, pnm, tga, hdr, exr, dds) */
   format: string;
   /** File size in bytes */
   size?: number;
@@ -142,18 +324,183 @@ export interface ImageMetadata {
   hasProfile: boolean;
   /** EXIF orientation value (1-8, if present) */
   orientation?: number;
-  /** Page/frame count for multi-page images (GIF, TIFF) */
+  /** Page/frame count for multi-page images (GIF, WebP, APNG, TIFF) */
   pages?: number;
   /** Loop count for animated images */
   loopCount?: number;
   /** Delay between frames in ms (for animated images) */
   delay?: number[];
+  /** Disposal method per frame: none, background or previous (GIF, WebP, APNG) */
+  disposal?: string[];
+  /** Total animation duration in ms (one loop) */
+  duration?: number;
   /** Background color (for GIF) */
   background?: number[];
   /** Compression type used */
   compression?: string;
   /** Density/DPI info */
   density?: number;
+  /** Decoded EXIF tags by directory (with `exif: true`) */
+  exif?: ExifData;
+  /** EXIF payload from the TIFF header onwards (with `exifRaw: true`) */
+  exifRaw?: Buffer;
+  /** GPS position and fix time (with `exif: true` or `gps: true`) */
+  gps?: GpsInfo;
+  /** XMP packet and its common properties (with `xmp: true`) */
+  xmp?: XmpData;
+  /** IPTC-IIM datasets from JPEG APP13 (with `iptc: true`) */
+  iptc?: IptcData;
+  /** Embedded ICC profile, reassembled from JPEG APP2 segments (with `icc: true`) */
+  iccProfile?: Buffer;
+  /** ICC profile description, e.g. "Display P3" (with `icc: true`) */
+  iccDescription?: string;
+  /** PNG tEXt, zTXt and iTXt chunks in file order (with `text: true`) */
+  text?: PngTextEntry[];
+}
+
+/** Metadata read options */
+export interface MetadataOptions {
+  /**
+   * Decode all EXIF tags (IFD0, Exif, GPS, Interop) from JPEG, WebP, PNG,
+   * TIFF and HEIC/AVIF (default: false)
+   */
+  exif?: boolean;
+  /** Return the raw EXIF payload as `exifRaw` (default: false) */
+  exifRaw?: boolean;
+  /** Summarize the EXIF GPS tags as `gps` (default: false) */
+  gps?: boolean;
+  /**
+   * Read the XMP packet from JPEG (incl. extended XMP), PNG iTXt and WebP
+   * and parse its common properties (default: false)
+   */
+  xmp?: boolean;
+  /** Read the IPTC-IIM record (Photoshop APP13) of JPEG files as `iptc` (default: false) */
+  iptc?: boolean;
+  /** Return the embedded ICC profile of JPEG, PNG and WebP files and its description (default: false) */
+  icc?: boolean;
+  /** Read all tEXt, zTXt and iTXt chunks of PNG files as `text` (default: false) */
+  text?: boolean;
+}
+
+/** IPTC-IIM application record (record 2) datasets */
+export interface IptcData {
+  /** Object Name (2:05) */
+  title?: string;
+  /** Keywords (2:25) */
+  keywords?: string[];
+  /** By-line / creator (2:80) */
+  byline?: string[];
+  /** By-line Title (2:85) */
+  bylineTitle?: string;
+  /** City (2:90) */
+  city?: string;
+  /** Province/State (2:95) */
+  state?: string;
+  /** Country Code (2:100) */
+  countryCode?: string;
+  /** Country Name (2:101) */
+  country?: string;
+  /** Headline (2:105) */
+  headline?: string;
+  /** Credit Line (2:110) */
+  credit?: string;
+  /** Source (2:115) */
+  source?: string;
+  /** Copyright Notice (2:116) */
+  copyright?: string;
+  /** Caption/Abstract (2:120) */
+  caption?: string;
+}
+
+/** PNG text chunk (tEXt, zTXt or iTXt) */
+export interface PngTextEntry {
+  /**
+   * Keyword, e.g. `Title`, `Author`, `parameters` (Stable Diffusion WebUI)
+   * or `prompt`/`workflow` (ComfyUI). 1-79 Latin-1 characters
+   */
+  keyword: string;
+  /** Text, decompressed */
+  text: string;
+  /** Language tag, e.g. "en-US" (written as iTXt) */
+  language?: string;
+  /** Keyword translated into the language (written as iTXt) */
+  translatedKeyword?: string;
+  /** Stored deflate-compressed as zTXt or compressed iTXt (default: false) */
+  compressed?: boolean;
+}
+
+/** XMP packet with the common Dublin Core, Photoshop, IPTC Core and XMP Rights properties */
+export interface XmpData {
+  /** Raw XMP packet */
+  packet: string;
+  /** Reassembled JPEG extended XMP (packets over 64 KB) */
+  extended?: string;
+  /** dc:title (x-default) */
+  title?: string;
+  /** dc:description (x-default) */
+  description?: string;
+  /** dc:creator */
+  creator?: string[];
+  /** dc:rights (x-default) */
+  rights?: string;
+  /** dc:subject (keywords) */
+  subject?: string[];
+  /** photoshop:Headline */
+  headline?: string;
+  /** photoshop:Credit */
+  credit?: string;
+  /** photoshop:Source */
+  source?: string;
+  /** photoshop:City */
+  city?: string;
+  /** photoshop:State */
+  state?: string;
+  /** photoshop:Country */
+  country?: string;
+  /** Iptc4xmpCore:CountryCode */
+  countryCode?: string;
+  /** Iptc4xmpCore:Location */
+  location?: string;
+  /** xmpRights:UsageTerms (x-default) */
+  usageTerms?: string;
+  /** xmpRights:WebStatement */
+  webStatement?: string;
+}
+
+/** GPS data from the EXIF GPS IFD */
+export interface GpsInfo {
+  /** Decimal degrees, negative = south */
+  latitude?: number;
+  /** Decimal degrees, negative = west */
+  longitude?: number;
+  /** Meters, negative = below sea level */
+  altitude?: number;
+  /** UTC fix time (GPSDateStamp + GPSTimeStamp) */
+  timestamp?: Date;
+}
+
+/**
+ * Decoded EXIF value
+ *
+ * ASCII tags are strings, numeric tags numbers (arrays for multiple values,
+ * rationals divided out), DateTime/DateTimeOriginal/DateTimeDigitized are
+ * `Date`s and opaque UNDEFINED tags (e.g. MakerNote) are Buffers.
+ */
+export type ExifValue = string | number | number[] | Date | Buffer;
+
+/** EXIF tags keyed by tag name (hex id like "0xA401" for unknown tags) */
+export type ExifTags = Record<string, ExifValue>;
+
+/** Decoded EXIF tags by directory */
+export interface ExifData {
+  /** Main image tags (Make, Model, Orientation, DateTime, ...) */
+  ifd0: ExifTags;
+  /** Capture settings (ExposureTime, FNumber, DateTimeOriginal, ...) */
+  exif: ExifTags;
+  /** Location (GPSLatitude, GPSLongitude, ...) */
+  gps: ExifTags;
+  /** Interoperability tags */
+  interop: ExifTags;
 }
 
 /** Blurhash result */
@@ -190,7 +537,48 @@ export interface ThumbHashDecodeResult {
   height: number;
 }
 
-/** EXIF metadata options for writing */
+/** EXIF directory a tag is written to */
+export type ExifIfd =
+  | "Ifd0" // Main image directory (Make, Model, Copyright, ...)
+  | "Exif" // Exif sub-IFD (DateTimeOriginal, ExposureTime, LensModel, ...)
+  | "Gps"; // GPS sub-IFD
+
+/** TIFF field type of an EXIF tag */
+export type ExifFieldType =
+  | "Byte" // 8-bit unsigned integers
+  | "Ascii" // NUL-terminated text
+  | "Short" // 16-bit unsigned integers
+  | "Long" // 32-bit unsigned integers
+  | "Rational" // Unsigned fractions
+  | "SRational" // Signed fractions
+  | "Undefined"; // Opaque bytes
+
+/**
+ * Arbitrary EXIF tag to write
+ *
+ * `text` for Ascii, `numbers` for Byte/Short/Long/Rational/SRational
+ * (rationals as decimals, e.g. 2.8 or -0.33), `bytes` (or `text`) for Undefined.
+ */
+export interface ExifWriteTag {
+  /** Directory (default: "Ifd0") */
+  ifd?: ExifIfd;
+  /** Tag id, e.g. 0xA434 (LensModel) */
+  tag: number;
+  /** Field type */
+  fieldType: ExifFieldType;
+  /** Ascii value (Undefined takes its UTF-8 bytes) */
+  text?: string;
+  /** Numeric values */
+  numbers?: number[];
+  /** Undefined or Byte values */
+  bytes?: number[] | Buffer;
+}
+
+/**
+ * EXIF metadata options for writing
+ *
+ * Tags are merged into the image's existing EXIF: tags not given here are kept.
+ */
 export interface ExifOptions {
   /** Image description / caption / AI prompt */
   imageDescription?: string;
@@ -212,8 +600,91 @@ export interface ExifOptions {
   model?: string;
   /** Orientation (1-8) */
   orientation?: number;
+  /** GPS latitude in decimal degrees (-90 to 90, negative = south) */
+  gpsLatitude?: number;
+  /** GPS longitude in decimal degrees (-180 to 180, negative = west) */
+  gpsLongitude?: number;
+  /** GPS altitude in meters (negative = below sea level) */
+  gpsAltitude?: number;
+  /** GPS fix time, a Date or a UTC string (YYYY:MM:DD HH:MM:SS or ISO 8601) */
+  gpsTimestamp?: Date | string;
+  /** Arbitrary typed tags, written after (and over) the fields above */
+  tags?: ExifWriteTag[];
+  /**
+   * XMP packet to embed (JPEG, PNG and WebP), replacing any existing XMP
+   * A bare `<x:xmpmeta>` document is wrapped in an xpacket
+   */
+  xmp?: string;
+  /** IPTC-IIM datasets to write (JPEG only), replacing any existing IPTC record */
+  iptc?: IptcData;
+  /**
+   * PNG text chunks to write (PNG only), replacing chunks with the same keyword
+   * Latin-1 text is written as tEXt/zTXt, anything else as iTXt
+   */
+  text?: PngTextEntry[];
+}
+
+/**
+ * EXIF tag category for selective stripping
+ *
+ * - `gps`: the whole GPS IFD
+ * - `maker_note`: vendor MakerNote blob
+ * - `serial`: body and lens serial numbers
+ * - `copyright`, `artist` (incl. CameraOwnerName), `software`, `orientation`
+ * - `date`: DateTime* and their offset/sub-second tags
+ * - `camera`: Make, Model and lens make/model
+ * - `description`: ImageDescription, UserComment and Windows XP text tags
+ * - `thumbnail`: the embedded IFD1 thumbnail
+ * - `other`: every remaining tag
+ *
+ * Structural tags (resolution, color space, pixel dimensions, versions) are never removed.
+ */
+export type ExifCategory =
+  | "gps"
+  | "maker_note"
+  | "serial"
+  | "copyright"
+  | "artist"
+  | "date"
+  | "camera"
+  | "description"
+  | "software"
+  | "orientation"
+  | "thumbnail"
+  | "other";
+
+/** Selective EXIF stripping options */
+export interface StripExifOptions {
+  /** Keep only these categories (orientation is kept unless listed in `remove`) */
+  keep?: ExifCategory[];
+  /** Remove these categories (wins over `keep`) */
+  remove?: ExifCategory[];
 }
 
+/**
+ * Metadata block or EXIF category for `metadata: { keep: [...] }`
+ *
+ * - `exif`: the whole EXIF block; an {@link ExifCategory} keeps only those tags
+ * - `icc`: the ICC profile (ignored with `colorManagement`)
+ * - `xmp`: the XMP packet
+ * - `iptc`: the IPTC-IIM record (JPEG output only)
+ * - `text`: PNG tEXt/zTXt/iTXt chunks (PNG output only)
+ */
+export type MetadataCategory = "exif" | "icc" | "xmp" | "iptc" | "text" | ExifCategory;
+
+/**
+ * Which input metadata `transform` and `thumbnail` copy to the output
+ *
+ * - `"strip"`: nothing (the default)
+ * - `"keep"`: everything the output container can carry
+ * - `{ keep: [...] }`: only the listed categories
+ *
+ * Metadata moves across containers (e.g. JPEG EXIF into WebP). EXIF pixel
+ * dimensions are updated to the output size, and orientation is reset to 1
+ * when the image was rotated or flipped.
+ */
+export type MetadataPolicy = "strip" | "keep" | { keep: MetadataCategory[] };
+
 /** Transform options (all-in-one processing) */
 export interface TransformOptions {
   /** Crop options (applied before resize) */
@@ -238,8 +709,91 @@ export interface TransformOptions {
   brightness?: number;
   /** Contrast adjustment (-100 to 100) */
   contrast?: number;
-  /** EXIF metadata to write (for JPEG/WebP output) */
+  /** EXIF metadata to write (JPEG/WebP, text chunks for PNG) - replaces EXIF kept by `metadata` */
   exif?: ExifOptions;
+  /** XMP packet to embed in the output (JPEG, PNG and WebP) */
+  xmp?: string;
+  /**
+   * Copy the input's ICC profile into JPEG, PNG and WebP output (default: false)
+   *
+   * Keeps wide-gamut photos (e.g. Display P3 from iPhones) from looking dull.
+   * A profile set in the encoder options takes precedence. Ignored with
+   * `colorManagement` - the output is in the target profile.
+   */
+  keepIcc?: boolean;
+  /** Convert pixels from the embedded ICC profile to sRGB or a target profile */
+  colorManagement?: ColorManagementOptions;
+  /** Copy input metadata to the output (default: "strip") */
+  metadata?: MetadataPolicy;
+  /**
+   * Process every frame of animated input (GIF/WebP/APNG) and keep the animation
+   * Only applies when the output format is WebP, GIF or PNG (default: false)
+   * Cannot be combined with output.maxBytes or output.targetQuality
+   */
+  animated?: boolean;
+  /** Animation output options (loop count, frame delays) */
+  animation?: AnimationOptions;
+}
+
+/** Transform result with encoder details */
+export interface TransformResult {
+  /** The output image data */
+  data: Buffer;
+  /** Output width */
+  width: number;
+  /** Output height */
+  height: number;
+  /** Quality chosen by the `maxBytes` or `targetQuality` search */
+  quality?: number;
+  /** Number of encodes tried by the search */
+  attempts?: number;
+  /** Metric score reached by the `targetQuality` search */
+  score?: number;
+  /** Channels per pixel (raw output only) */
+  channels?: number;
+  /** Output format (jpeg, png, webp, ...) - the chosen one for `format: "auto"` */
+  format: string;
+}
+
+/** Animation output options */
+export interface AnimationOptions {
+  /** Number of loops (0 = infinite, default: same as input) */
+  loopCount?: number;
+  /**
+   * Frame delays in milliseconds (default: same as input)
+   * A single value applies to every frame; otherwise the last value repeats
+   */
+  delay?: number[];
+}
+
+/**
+ * Lossless JPEG transform options (no decode/re-encode)
+ * Applied in order: crop → rotate → flip
+ */
+export interface JpegLosslessOptions {
+  /** Crop region - x/y must be multiples of the MCU size (8 or 16 px) */
+  crop?: CropOptions;
+  /** Rotate degrees (90, 180, 270) */
+  rotate?: number;
+  /** Flip horizontally */
+  flipH?: boolean;
+  /** Flip vertically */
+  flipV?: boolean;
+  /**
+   * Drop partial MCU blocks on the edges that cannot be transformed
+   * When false, transforms that would leave them in place fail instead (default: false)
+   */
+  trim?: boolean;
+  /** Discard color data and output grayscale (default: false) */
+  grayscale?: boolean;
+  /** Write progressive output (default: false) */
+  progressive?: boolean;
+  /** Optimize Huffman tables (default: false) */
+  optimizeCoding?: boolean;
+  /** Set the EXIF orientation tag to 1 (normal) after transforming (default: false) */
+  resetOrientation?: boolean;
+  /** Drop all extra markers including EXIF and ICC (default: false - markers are copied) */
+  stripMetadata?: boolean;
 }
 
 /** Native module options (internal) */
@@ -260,12 +814,57 @@ export interface NapiResizeOptions {
   background?: number[];
 }
 
+export interface NapiExifEntry {
+  ifd: string;
+  tag: number;
+  name: string;
+  text?: string;
+  numbers?: number[];
+  bytes?: number[];
+}
+
+export interface NapiGpsInfo extends Omit<GpsInfo, "timestamp"> {
+  timestamp?: string;
+}
+
+export interface NapiImageMetadata extends Omit<ImageMetadata, "exif" | "exifRaw" | "gps" | "iccProfile"> {
+  exif?: NapiExifEntry[];
+  exifRaw?: number[];
+  gps?: NapiGpsInfo;
+  iccProfile?: number[];
+}
+
+export interface NapiJpegOptions extends Omit<JpegOptions, "iccProfile"> {
+  iccProfile?: number[];
+}
+
+export interface NapiColorManagementOptions extends Omit<ColorManagementOptions, "targetProfile"> {
+  targetProfile?: number[];
+}
+
+export interface NapiMetadataPolicy {
+  keep?: string[];
+}
+
+export interface NapiPngOptions extends Omit<PngOptions, "iccProfile"> {
+  iccProfile?: number[];
+}
+
+export interface NapiWebPOptions extends Omit<WebPOptions, "iccProfile"> {
+  iccProfile?: number[];
+}
+
 export interface NapiOutputOptions {
   format: string;
-  jpeg?: JpegOptions;
-  png?: PngOptions;
-  webp?: WebPOptions;
+  jpeg?: NapiJpegOptions;
+  png?: NapiPngOptions;
+  webp?: NapiWebPOptions;
+  gif?: GifOptions;
   avif?: AvifOptions;
+  maxBytes?: number;
+  downscaleToFit?: boolean;
+  targetQuality?: TargetQuality;
+  accept?: string[];
 }
 
 export interface NapiExifOptions {
@@ -279,6 +878,23 @@ export interface NapiExifOptions {
   make?: string;
   model?: string;
   orientation?: number;
+  gpsLatitude?: number;
+  gpsLongitude?: number;
+  gpsAltitude?: number;
+  gpsTimestamp?: string;
+  tags?: NapiExifWriteTag[];
+  xmp?: string;
+  iptc?: IptcData;
+  text?: PngTextEntry[];
+}
+
+export interface NapiExifWriteTag {
+  ifd?: ExifIfd;
+  tag: number;
+  fieldType: ExifFieldType;
+  text?: string;
+  numbers?: number[];
+  bytes?: number[];
 }
 
 export interface NapiTransformOptions {
@@ -294,6 +910,25 @@ export interface NapiTransformOptions {
   brightness?: number;
   contrast?: number;
   exif?: NapiExifOptions;
+  xmp?: string;
+  keepIcc?: boolean;
+  colorManagement?: NapiColorManagementOptions;
+  metadata?: NapiMetadataPolicy;
+  animated?: boolean;
+  animation?: AnimationOptions;
+}
+
+export interface NapiJpegLosslessOptions {
+  crop?: NapiCropOptions;
+  rotate?: number;
+  flipH?: boolean;
+  flipV?: boolean;
+  trim?: boolean;
+  grayscale?: boolean;
+  progressive?: boolean;
+  optimizeCoding?: boolean;
+  resetOrientation?: boolean;
+  stripMetadata?: boolean;
 }
 
 // ============================================
@@ -397,6 +1032,46 @@ export interface ImageHashResult {
   algorithm: string;
 }
 
+// ============================================
+// COMPARE TYPES
+// ============================================
+
+/** Image comparison metric */
+export type CompareMetric =
+  | "Ssim" // Structural similarity, 0-1 (1 = identical)
+  | "MsSsim" // Multi-scale structural similarity, 0-1 (1 = identical)
+  | "Psnr" // Peak signal-to-noise ratio in dB (Infinity = identical)
+  | "Dssim"; // Structural dissimilarity, 1/SSIM - 1 (0 = identical)
+
+/** Color space the comparison is computed in */
+export type CompareColorSpace =
+  | "Luma" // Luma only (Rec. 601), like the reference SSIM
+  | "Lab"; // CIELAB, weighted towards lightness
+
+/** Image comparison options */
+export interface CompareOptions {
+  /** Color space (default: Luma) */
+  colorSpace?: CompareColorSpace;
+  /** Return a per-pixel difference heatmap as PNG (default: false) */
+  heatmap?: boolean;
+}
+
+/** Image comparison result */
+export interface CompareResult {
+  /** Metric used */
+  metric: CompareMetric;
+  /** Score (see CompareMetric for the range) */
+  score: number;
+  /** Compared width (dimensions of the first image) */
+  width: number;
+  /** Compared height */
+  height: number;
+  /** Whether the second image was resized to match the first */
+  resized: boolean;
+  /** Difference heatmap PNG (black = identical, white = largest difference) */
+  heatmap?: Buffer;
+}
+
 // ============================================
 // SMART CROP TYPES
 // ============================================
@@ -517,6 +1192,28 @@ export interface ThumbnailOptions {
    * Best for generating preview thumbnails where exact dimensions don't matter.
    */
   fastMode?: boolean;
+  /**
+   * Keep animation for animated GIF/WebP/APNG input (default: false)
+   *
+   * Output defaults to animated WebP; PNG output is APNG; JPEG uses the first frame.
+   * Cannot be combined with `maxBytes`.
+   */
+  animated?: boolean;
+  /**
+   * Maximum output size in bytes (still JPEG/WebP output)
+   *
+   * Binary-searches the quality below `quality` until the output fits.
+   * PNG input defaults to WebP output when set.
+   */
+  maxBytes?: number;
+  /** Step dimensions down when `maxBytes` can't be met at quality 1 (default: false) */
+  downscaleToFit?: boolean;
+  /** Copy the input's ICC profile into the thumbnail - ignored with `colorManagement` (default: false) */
+  keepIcc?: boolean;
+  /** Convert pixels from the embedded ICC profile to sRGB or a target profile */
+  colorManagement?: ColorManagementOptions;
+  /** Copy input metadata to the thumbnail (default: "strip") */
+  metadata?: MetadataPolicy;
 }
 
 /** Fast thumbnail result with metadata */
@@ -535,6 +1232,10 @@ export interface ThumbnailResult {
   originalWidth: number;
   /** Original image height */
   originalHeight: number;
+  /** Quality chosen by the `maxBytes` search */
+  quality?: number;
+  /** Number of encodes tried by the `maxBytes` search */
+  attempts?: number;
 }
 
 /** Native thumbnail options (internal) */
@@ -546,4 +1247,10 @@ export interface NapiThumbnailOptions {
   shrinkOnLoad?: boolean;
   filter?: string;
   fastMode?: boolean;
+  animated?: boolean;
+  maxBytes?: number;
+  downscaleToFit?: boolean;
+  keepIcc?: boolean;
+  colorManagement?: NapiColorManagementOptions;
+  metadata?: NapiMetadataPolicy;
 }
@@ -0,0 +1,163 @@
+/**
+ * Animation Tests
+ *
+ * Tests for animated WebP decode/encode and GIF to animated WebP conversion.
+ */
+
+import { describe, it, expect, beforeAll } from "bun:test";
+import {
+  toAnimatedWebp,
+  toAnimatedWebpSync,
+  transform,
+  thumbnail,
+  metadata,
+} from "../../dist";
+
+// Test images
+let animatedWebp: Buffer;
+let animatedGif: Buffer;
+
+beforeAll(async () => {
+  const webpResponse = await fetch("https://www.gstatic.com/webp/animated/1.webp");
+  animatedWebp = Buffer.from(await webpResponse.arrayBuffer());
+
+  const gifResponse = await fetch("https://www.gstatic.com/webp/animated/1.gif");
+  animatedGif = Buffer.from(await gifResponse.arrayBuffer());
+});
+
+describe("toAnimatedWebp", () => {
+  it("should convert animated GIF to animated WebP (async)", async () => {
+    const result = await toAnimatedWebp(animatedGif, { quality: 75 });
+
+    expect(result.subarray(0, 4).toString()).toBe("RIFF");
+    expect(result.subarray(8, 12).toString()).toBe("WEBP");
+    // VP8X header with animation flag
+    expect(result.subarray(12, 16).toString()).toBe("VP8X");
+    expect(result[20] & 0x02).toBe(0x02);
+  });
+
+  it("should convert animated GIF to animated WebP (sync)", () => {
+    const result = toAnimatedWebpSync(animatedGif, { lossless: true });
+    expect(result[20] & 0x02).toBe(0x02);
+  });
+
+  it("should re-encode animated WebP", async () => {
+    const result = await toAnimatedWebp(animatedWebp);
+    const meta = await metadata(result);
+
+    expect(meta.format).toBe("webp");
+    expect(result[20] & 0x02).toBe(0x02);
+  });
+
+  it("should apply loop count", async () => {
+    const result = await toAnimatedWebp(animatedGif, undefined, {
+      loopCount: 3,
+      delay: [50],
+    });
+    const meta = await metadata(result);
+
+    expect(meta.loopCount).toBe(3);
+  });
+});
+
+describe("animated transform", () => {
+  it("should resize every frame and keep animation", async () => {
+    const result = await transform(animatedGif, {
+      resize: { width: 100, height: 100, fit: "fill" },
+      output: { format: "webp" },
+      animated: true,
+    });
+    const meta = await metadata(result);
+
+    expect(meta.width).toBe(100);
+    expect(meta.height).toBe(100);
+    expect(result[20] & 0x02).toBe(0x02);
+  });
+
+  it("should reject a byte budget or quality target for animated output", async () => {
+    for (const output of [
+      { format: "webp" as const, maxBytes: 10_000 },
+      { format: "webp" as const, targetQuality: { value: 0.95 } },
+    ]) {
+      await expect(transform(animatedGif, { output, animated: true })).rejects.toThrow(
+        "not supported for animated output"
+      );
+    }
+  });
+
+  it("should output a single frame when animated is not set", async () => {
+    const result = await transform(animatedGif, {
+      resize: { width: 100 },
+      output: { format: "webp" },
+    });
+
+    const isExtended = result.subarray(12, 16).toString() === "VP8X";
+    expect(isExtended ? result[20] & 0x02 : 0).toBe(0);
+  });
+});
+
+describe("APNG", () => {
+  it("should encode animated PNG and report frames in metadata", async () => {
+    const apng = await transform(animatedGif, {
+      resize: { width: 64, height: 64, fit: "fill" },
+      output: { format: "png" },
+      animated: true,
+      animation: { loopCount: 2, delay: [80] },
+    });
+    const meta = await metadata(apng);
+
+    expect(meta.format).toBe("png");
+    expect(meta.pages).toBeGreaterThan(1);
+    expect(meta.loopCount).toBe(2);
+    expect(meta.delay?.every((d) => d === 80)).toBe(true);
+  });
+
+  it("should convert APNG to animated WebP", async () => {
+    const apng = await transform(animatedGif, {
+      resize: { width: 64, height: 64, fit: "fill" },
+      output: { format: "png" },
+      animated: true,
+    });
+    const webp = await toAnimatedWebp(apng);
+
+    expect(webp[20] & 0x02).toBe(0x02);
+  });
+});
+
+describe("animated thumbnail", () => {
+  it("should generate animated WebP thumbnail", async () => {
+    const result = await thumbnail(animatedWebp, { width: 80, animated: true });
+
+    expect(result.format).toBe("webp");
+    expect(result.width).toBe(80);
+    expect(result.data[20] & 0x02).toBe(0x02);
+  });
+
+  it("should reject maxBytes", async () => {
+    await expect(thumbnail(animatedWebp, { width: 80, animated: true, maxBytes: 10_000 })).rejects.toThrow(
+      "not supported for animated thumbnails"
+    );
+  });
+});
+
+describe("animation metadata", () => {
+  it("should report every GIF frame with its delay and disposal", async () => {
+    const meta = await metadata(animatedGif);
+
+    expect(meta.pages).toBeGreaterThan(1);
+    expect(meta.delay).toHaveLength(meta.pages!);
+    expect(meta.disposal).toHaveLength(meta.pages!);
+    expect(meta.duration).toBe(meta.delay!.reduce((total, d) => total + d, 0));
+  });
+
+  it("should report animated WebP frames from ANMF chunks", async () => {
+    const gifMeta = await metadata(animatedGif);
+    const webp = await toAnimatedWebp(animatedGif, undefined, { loopCount: 2, delay: [50] });
+    const meta = await metadata(webp);
+
+    expect(meta.pages).toBe(gifMeta.pages);
+    expect(meta.loopCount).toBe(2);
+    expect(meta.delay?.every((d) => d === 50)).toBe(true);
+    expect(meta.duration).toBe(50 * meta.pages!);
+  });
+});
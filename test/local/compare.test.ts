@@ -0,0 +1,81 @@
+/**
+ * Compare Tests
+ *
+ * Tests for SSIM, MS-SSIM, PSNR and DSSIM quality metrics.
+ */
+
+import { describe, it, expect, beforeAll } from "bun:test";
+import { compare, compareSync, toJpeg, resize, metadata } from "../../dist";
+
+let original: Buffer;
+let highQuality: Buffer;
+let lowQuality: Buffer;
+
+beforeAll(async () => {
+  const response = await fetch("https://picsum.photos/seed/compare/640/480.jpg");
+  original = Buffer.from(await response.arrayBuffer());
+
+  highQuality = await toJpeg(original, { quality: 95 });
+  lowQuality = await toJpeg(original, { quality: 20 });
+});
+
+describe("compare", () => {
+  it("should score identical images as 1 (async)", async () => {
+    const result = await compare(original, original);
+
+    expect(result.metric).toBe("Ssim");
+    expect(result.score).toBeCloseTo(1, 6);
+    expect(result.resized).toBe(false);
+    expect(result.width).toBe(640);
+  });
+
+  it("should rank higher quality closer to the original (sync)", () => {
+    const high = compareSync(original, highQuality, "Ssim");
+    const low = compareSync(original, lowQuality, "Ssim");
+
+    expect(high.score).toBeGreaterThan(low.score);
+    expect(high.score).toBeLessThan(1);
+  });
+
+  it("should compute MS-SSIM in Lab", async () => {
+    const high = await compare(original, highQuality, "MsSsim", { colorSpace: "Lab" });
+    const low = await compare(original, lowQuality, "MsSsim", { colorSpace: "Lab" });
+
+    expect(high.score).toBeGreaterThan(low.score);
+  });
+
+  it("should compute PSNR in dB", async () => {
+    const identical = await compare(original, original, "Psnr");
+    const low = await compare(original, lowQuality, "Psnr");
+
+    expect(identical.score).toBe(Infinity);
+    expect(low.score).toBeGreaterThan(15);
+    expect(low.score).toBeLessThan(50);
+  });
+
+  it("should compute DSSIM with 0 for identical images", async () => {
+    const identical = await compare(original, original, "Dssim");
+    const low = await compare(original, lowQuality, "Dssim");
+
+    expect(identical.score).toBeCloseTo(0, 6);
+    expect(low.score).toBeGreaterThan(0);
+  });
+
+  it("should resize the second image when dimensions differ", async () => {
+    const smaller = await resize(original, { width: 320, height: 240 });
+    const result = await compare(original, smaller);
+
+    expect(result.resized).toBe(true);
+    expect(result.width).toBe(640);
+    expect(result.score).toBeGreaterThan(0.5);
+  });
+
+  it("should return a heatmap PNG", async () => {
+    const result = await compare(original, lowQuality, "Ssim", { heatmap: true });
+    const meta = await metadata(result.heatmap!);
+
+    expect(meta.format).toBe("png");
+    expect(meta.width).toBe(640);
+    expect(meta.height).toBe(480);
+  });
+});
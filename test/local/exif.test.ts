@@ -5,6 +5,14 @@ import {
   stripExif,
   stripExifSync,
   toWebp,
+  toPng,
+  toJpeg,
+  toRaw,
+  transform,
+  thumbnail,
+  metadata,
+  metadataSync,
+  type ExifWriteTag,
 } from "../../src";
 
 let testImage: Buffer;
@@ -133,3 +141,573 @@ describe("EXIF Write", () => {
     expect(result.length).toBe(testImage.length);
   });
 });
+
+describe("EXIF Read", () => {
+  const fields = {
+    imageDescription: "Read test",
+    artist: "Jane Doe",
+    copyright: "Copyright 2026 Jane Doe",
+    dateTime: "2026:03:14 09:26:53",
+    dateTimeOriginal: "2026:03:14 09:00:00",
+    userComment: "hello exif",
+    make: "TestCamera",
+    model: "Model Y",
+    orientation: 6,
+  };
+
+  test("metadata decodes all tags from JPEG", async () => {
+    const jpeg = await writeExif(testImage, fields);
+    const { exif, orientation } = await metadata(jpeg, { exif: true });
+
+    expect(exif!.ifd0.Orientation).toBe(6);
+    expect(exif!.ifd0.Make).toBe("TestCamera");
+    expect(exif!.ifd0.Model).toBe("Model Y");
+    expect(exif!.ifd0.Artist).toBe("Jane Doe");
+    expect(exif!.ifd0.Orientation).toBe(6);
+    expect(exif!.exif.UserComment).toBe("hello exif");
+    expect(exif!.exif.DateTimeOriginal).toEqual(new Date("2026-03-14T09:00:00Z"));
+    expect(exif!.ifd0.DateTime).toBeInstanceOf(Date);
+  });
+
+  test("metadata decodes EXIF from WebP (sync)", async () => {
+    const webp = await writeExif(await toWebp(testImage), fields);
+    const { exif } = metadataSync(webp, { exif: true });
+
+    expect(exif!.ifd0.Copyright).toBe("Copyright 2026 Jane Doe");
+  });
+
+  test("metadata returns the raw payload", async () => {
+    const jpeg = await writeExif(testImage, fields);
+    const { exif, exifRaw } = await metadata(jpeg, { exifRaw: true });
+
+    expect(exif).toBeUndefined();
+    expect(["II", "MM"]).toContain(exifRaw!.subarray(0, 2).toString());
+  });
+
+  test("metadata without EXIF leaves the fields unset", async () => {
+    const plain = await metadata(testImage, { exif: true });
+    const headerOnly = await metadata(await writeExif(testImage, fields));
+
+    expect(plain.exif).toBeUndefined();
+    expect(headerOnly.exif).toBeUndefined();
+  });
+});
+
+describe("EXIF GPS", () => {
+  const located = {
+    copyright: "Copyright 2026 Jane Doe",
+    dateTimeOriginal: "2026:03:14 09:00:00",
+    make: "ACME",
+    orientation: 6,
+    gpsLatitude: -33.8568,
+    gpsLongitude: 151.2153,
+    gpsAltitude: 58.5,
+    gpsTimestamp: new Date("2026-03-14T08:59:30Z"),
+  };
+
+  test("writeExif writes GPS and metadata reads it back", async () => {
+    const jpeg = await writeExif(testImage, located);
+    const { gps, exif } = await metadata(jpeg, { gps: true });
+
+    expect(exif).toBeUndefined();
+    expect(gps!.latitude).toBeCloseTo(-33.8568, 6);
+    expect(gps!.longitude).toBeCloseTo(151.2153, 6);
+    expect(gps!.altitude).toBe(58.5);
+    expect(gps!.timestamp).toEqual(new Date("2026-03-14T08:59:30Z"));
+  });
+
+  test("writeExif rejects out of range coordinates", async () => {
+    await expect(writeExif(testImage, { gpsLatitude: 91 })).rejects.toThrow();
+    expect(() => writeExifSync(testImage, { gpsTimestamp: "yesterday" })).toThrow();
+  });
+
+  test("stripExif removes selected categories only", async () => {
+    const jpeg = await writeExif(testImage, located);
+    const stripped = await stripExif(jpeg, { remove: ["gps", "maker_note", "serial"] });
+    const { exif, gps } = await metadata(stripped, { exif: true });
+
+    expect(gps).toBeUndefined();
+    expect(Object.keys(exif!.gps)).toHaveLength(0);
+    expect(exif!.ifd0.Copyright).toBe("Copyright 2026 Jane Doe");
+    expect(exif!.ifd0.Make).toBe("ACME");
+    expect(exif!.exif.DateTimeOriginal).toEqual(new Date("2026-03-14T09:00:00Z"));
+  });
+
+  test("stripExif keep mode drops unlisted categories but keeps orientation", async () => {
+    const webp = await writeExif(await toWebp(testImage), located);
+    const stripped = stripExifSync(webp, { keep: ["copyright", "date"] });
+    const { exif } = metadataSync(stripped, { exif: true });
+
+    expect(exif!.ifd0.Copyright).toBe("Copyright 2026 Jane Doe");
+    expect(exif!.ifd0.Make).toBeUndefined();
+    expect(Object.keys(exif!.gps)).toHaveLength(0);
+    expect(exif!.ifd0.Orientation).toBe(6);
+  });
+
+  test("stripExif rejects unknown categories", async () => {
+    // @ts-expect-error - not an ExifCategory
+    await expect(stripExif(testImage, { remove: ["location"] })).rejects.toThrow();
+  });
+});
+
+describe("XMP", () => {
+  const packet = `<x:xmpmeta xmlns:x="adobe:ns:meta/">
+ <rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+  <rdf:Description rdf:about=""
+    xmlns:dc="http://purl.org/dc/elements/1.1/"
+    xmlns:photoshop="http://ns.adobe.com/photoshop/1.0/"
+    photoshop:Credit="Example Agency">
+   <dc:rights><rdf:Alt><rdf:li xml:lang="x-default">(c) 2026 Jane Doe</rdf:li></rdf:Alt></dc:rights>
+   <dc:subject><rdf:Bag><rdf:li>harbour</rdf:li><rdf:li>sunset</rdf:li></rdf:Bag></dc:subject>
+  </rdf:Description>
+ </rdf:RDF>
+</x:xmpmeta>`;
+
+  test("writeExif embeds XMP in JPEG alongside EXIF", async () => {
+    const jpeg = await writeExif(testImage, { artist: "Jane Doe", xmp: packet });
+    const { xmp, exif } = await metadata(jpeg, { xmp: true, exif: true });
+
+    expect(exif!.ifd0.Artist).toBe("Jane Doe");
+    expect(xmp!.packet).toStartWith("<?xpacket begin=");
+    expect(xmp!.rights).toBe("(c) 2026 Jane Doe");
+    expect(xmp!.subject).toEqual(["harbour", "sunset"]);
+    expect(xmp!.credit).toBe("Example Agency");
+  });
+
+  test("writeExif replaces existing XMP", async () => {
+    const once = await writeExif(testImage, { xmp: packet });
+    const twice = await writeExif(once, { xmp: packet.replace("Example Agency", "Other") });
+    const { xmp } = await metadata(twice, { xmp: true });
+
+    expect(xmp!.credit).toBe("Other");
+  });
+
+  test("XMP round-trips through PNG and WebP", async () => {
+    for (const input of [await toPng(testImage), await toWebp(testImage)]) {
+      const tagged = writeExifSync(input, { xmp: packet });
+      const { xmp } = metadataSync(tagged, { xmp: true });
+
+      expect(xmp!.subject).toEqual(["harbour", "sunset"]);
+    }
+  });
+
+  test("transform embeds XMP in the output", async () => {
+    const output = await transform(testImage, {
+      resize: { width: 50 },
+      output: { format: "webp" },
+      xmp: packet,
+    });
+    const { xmp, format } = await metadata(output, { xmp: true });
+
+    expect(format).toBe("webp");
+    expect(xmp!.rights).toBe("(c) 2026 Jane Doe");
+  });
+
+  test("metadata without XMP leaves the field unset", async () => {
+    const { xmp } = await metadata(testImage, { xmp: true });
+    expect(xmp).toBeUndefined();
+  });
+});
+
+describe("IPTC", () => {
+  const iptc = {
+    caption: "Quai à Marseille au crépuscule",
+    keywords: ["harbour", "sunset"],
+    byline: ["Jane Doe"],
+    credit: "Example Agency",
+    city: "Marseille",
+    country: "France",
+    headline: "Harbour at dusk",
+  };
+
+  test("writeExif writes IPTC to JPEG and metadata reads it back", async () => {
+    const jpeg = await writeExif(testImage, { copyright: "(c) Jane Doe", iptc });
+    const result = await metadata(jpeg, { iptc: true, exif: true });
+
+    expect(result.iptc).toEqual(iptc);
+    expect(result.exif!.ifd0.Copyright).toBe("(c) Jane Doe");
+  });
+
+  test("writing IPTC replaces the previous record", async () => {
+    const once = await writeExif(testImage, { iptc });
+    const twice = writeExifSync(once, { iptc: { headline: "Updated" } });
+    const { iptc: read } = metadataSync(twice, { iptc: true });
+
+    expect(read).toEqual({ headline: "Updated" });
+  });
+
+  test("IPTC writing is JPEG only", async () => {
+    await expect(writeExif(await toWebp(testImage), { iptc })).rejects.toThrow();
+  });
+
+  test("metadata without IPTC leaves the field unset", async () => {
+    const { iptc: read } = await metadata(testImage, { iptc: true });
+    expect(read).toBeUndefined();
+  });
+});
+
+describe("PNG text chunks", () => {
+  const workflow = JSON.stringify({ "3": { class_type: "KSampler", inputs: { seed: 42, steps: 20 } } });
+
+  test("writeExif writes arbitrary text chunks and metadata reads them back", async () => {
+    const png = await writeExif(await toPng(testImage), {
+      text: [
+        { keyword: "parameters", text: "a cat on a sofa\nSteps: 20, Sampler: Euler a, Seed: 42" },
+        { keyword: "workflow", text: workflow, compressed: true },
+        { keyword: "Description", text: "Chat sur un canapé — 猫", language: "fr" },
+      ],
+    });
+    const { text } = await metadata(png, { text: true });
+
+    expect(text).toEqual([
+      { keyword: "parameters", text: "a cat on a sofa\nSteps: 20, Sampler: Euler a, Seed: 42", compressed: false },
+      { keyword: "workflow", text: workflow, compressed: true },
+      { keyword: "Description", text: "Chat sur un canapé — 猫", language: "fr", compressed: false },
+    ]);
+  });
+
+  test("writing a keyword again replaces the chunk", async () => {
+    const once = writeExifSync(await toPng(testImage), { text: [{ keyword: "prompt", text: "old" }] });
+    const twice = writeExifSync(once, { text: [{ keyword: "prompt", text: "new" }] });
+    const { text } = metadataSync(twice, { text: true });
+
+    expect(text!.map((entry) => [entry.keyword, entry.text])).toEqual([["prompt", "new"]]);
+  });
+
+  test("EXIF fields map to the standard PNG keywords", async () => {
+    const png = await writeExif(await toPng(testImage), {
+      imageDescription: "Harbour at dusk",
+      artist: "Jane Doe",
+      copyright: "(c) 2026 Jane Doe",
+      software: "imgkit",
+      dateTime: "2026:01:02 03:04:05",
+    });
+    const { text } = await metadata(png, { text: true });
+    const fields = Object.fromEntries(text!.map((entry) => [entry.keyword, entry.text]));
+
+    expect(fields).toEqual({
+      Title: "Harbour at dusk",
+      Author: "Jane Doe",
+      Copyright: "(c) 2026 Jane Doe",
+      Software: "imgkit",
+      "Creation Time": "2026:01:02 03:04:05",
+    });
+  });
+
+  test("text chunks are PNG only and keywords are validated", async () => {
+    await expect(writeExif(testImage, { text: [{ keyword: "prompt", text: "x" }] })).rejects.toThrow();
+    await expect(writeExif(await toPng(testImage), { text: [{ keyword: "", text: "x" }] })).rejects.toThrow();
+  });
+});
+
+describe("EXIF tags", () => {
+  const tags: ExifWriteTag[] = [
+    { ifd: "Exif", tag: 0xa434, fieldType: "Ascii", text: "RF 24-70mm F2.8" },
+    { ifd: "Exif", tag: 0x829d, fieldType: "Rational", numbers: [2.8] },
+    { ifd: "Exif", tag: 0x9204, fieldType: "SRational", numbers: [-0.7] },
+    { ifd: "Exif", tag: 0x8827, fieldType: "Short", numbers: [400] },
+    { ifd: "Exif", tag: 0xa002, fieldType: "Long", numbers: [100000] },
+    { ifd: "Exif", tag: 0x927c, fieldType: "Undefined", bytes: Buffer.from([1, 2, 3, 4, 5, 6]) },
+    { ifd: "Gps", tag: 0x001b, fieldType: "Undefined", text: "GPS" },
+    { tag: 0x0131, fieldType: "Ascii", text: "imgkit" },
+  ];
+
+  test("typed tags round-trip through metadata", async () => {
+    const jpeg = await writeExif(testImage, { tags });
+    const { exif } = await metadata(jpeg, { exif: true });
+
+    expect(exif!.exif.LensModel).toBe("RF 24-70mm F2.8");
+    expect(exif!.exif.FNumber).toBeCloseTo(2.8, 6);
+    expect(exif!.exif.ExposureBiasValue).toBeCloseTo(-0.7, 6);
+    expect(exif!.exif.ISOSpeedRatings).toBe(400);
+    expect(exif!.exif.PixelXDimension).toBe(100000);
+    expect(exif!.ifd0.Software).toBe("imgkit");
+  });
+
+  test("writing merges into the existing EXIF", async () => {
+    const first = await writeExif(testImage, { copyright: "(c) 2026 Jane Doe", artist: "Jane Doe" });
+    const second = await writeExif(first, {
+      artist: "Someone Else",
+      tags: [{ ifd: "Exif", tag: 0xa434, fieldType: "Ascii", text: "50mm" }],
+    });
+    const { exif } = await metadata(second, { exif: true });
+
+    expect(exif!.ifd0.Copyright).toBe("(c) 2026 Jane Doe");
+    expect(exif!.ifd0.Artist).toBe("Someone Else");
+    expect(exif!.exif.LensModel).toBe("50mm");
+  });
+
+  test("PNG gets an eXIf chunk including orientation and GPS", async () => {
+    const png = await writeExif(await toPng(testImage), {
+      copyright: "(c) 2026 Jane Doe",
+      orientation: 6,
+      gpsLatitude: 48.8584,
+      gpsLongitude: 2.2945,
+      tags: [{ ifd: "Exif", tag: 0xa434, fieldType: "Ascii", text: "50mm" }],
+    });
+    const { exif, gps, orientation, text } = await metadata(png, { exif: true, text: true });
+
+    expect(exif!.ifd0.Copyright).toBe("(c) 2026 Jane Doe");
+    expect(exif!.exif.LensModel).toBe("50mm");
+    expect(gps!.latitude).toBeCloseTo(48.8584, 4);
+    expect(orientation).toBe(6);
+    expect(text!.find((entry) => entry.keyword === "Copyright")?.text).toBe("(c) 2026 Jane Doe");
+  });
+
+  test("invalid tags are rejected", async () => {
+    await expect(writeExif(testImage, { tags: [{ tag: 0x10000, fieldType: "Ascii", text: "x" }] })).rejects.toThrow();
+    await expect(writeExif(testImage, { tags: [{ tag: 0x8827, fieldType: "Short", numbers: [70000] }] })).rejects.toThrow();
+    await expect(writeExif(testImage, { tags: [{ tag: 0x829d, fieldType: "Rational", numbers: [-1] }] })).rejects.toThrow();
+    await expect(writeExif(testImage, { tags: [{ tag: 0x0131, fieldType: "Ascii" }] })).rejects.toThrow();
+  });
+});
+
+describe("ICC profile", () => {
+  /** Minimal profile: 128-byte header, one v2 'desc' tag */
+  function fakeProfile(description: string, padding = 0): Buffer {
+    const desc = Buffer.alloc(12 + description.length + 1);
+    desc.write("desc", 0, "latin1");
+    desc.writeUInt32BE(description.length + 1, 8);
+    desc.write(description, 12, "latin1");
+    const table = Buffer.alloc(16);
+    table.writeUInt32BE(1, 0);
+    table.write("desc", 4, "latin1");
+    table.writeUInt32BE(128 + 16, 8);
+    table.writeUInt32BE(desc.length, 12);
+    return Buffer.concat([Buffer.alloc(128), table, desc, Buffer.alloc(padding)]);
+  }
+
+  test("encoders embed a profile that metadata reads back", async () => {
+    const profile = fakeProfile("Display P3");
+    for (const image of [
+      await toJpeg(testImage, { iccProfile: profile }),
+      await toPng(testImage, { iccProfile: profile }),
+      await toWebp(testImage, { iccProfile: profile }),
+    ]) {
+      const result = await metadata(image, { icc: true });
+      expect(result.hasProfile).toBe(true);
+      expect(result.iccProfile).toEqual(profile);
+      expect(result.iccDescription).toBe("Display P3");
+    }
+  });
+
+  test("multi-segment JPEG profiles are reassembled", async () => {
+    const profile = fakeProfile("Large", 150_000);
+    const jpeg = await toJpeg(testImage, { iccProfile: profile });
+    const { iccProfile } = metadataSync(jpeg, { icc: true });
+    expect(iccProfile).toEqual(profile);
+  });
+
+  test("transform and thumbnail keep the profile with keepIcc", async () => {
+    const profile = fakeProfile("Display P3");
+    const jpeg = await toJpeg(testImage, { iccProfile: profile });
+
+    const dropped = await transform(jpeg, { resize: { width: 50 } });
+    expect((await metadata(dropped, { icc: true })).iccProfile).toBeUndefined();
+
+    const kept = await transform(jpeg, {
+      resize: { width: 50 },
+      output: { format: "webp" },
+      keepIcc: true,
+    });
+    expect((await metadata(kept, { icc: true })).iccProfile).toEqual(profile);
+
+    const thumb = await thumbnail(jpeg, { width: 40, format: "Png", keepIcc: true });
+    expect((await metadata(thumb.data, { icc: true })).iccDescription).toBe("Display P3");
+  });
+
+  test("metadata without a profile leaves the fields unset", async () => {
+    const result = await metadata(testImage, { icc: true });
+    expect(result.iccProfile).toBeUndefined();
+    expect(result.iccDescription).toBeUndefined();
+  });
+});
+
+describe("Color management", () => {
+  /** s15Fixed16 XYZ triple */
+  function xyz(x: number, y: number, z: number): Buffer {
+    const out = Buffer.alloc(20);
+    out.write("XYZ ", 0, "latin1");
+    [x, y, z].forEach((v, i) => out.writeInt32BE(Math.round(v * 65536), 8 + i * 4));
+    return out;
+  }
+
+  /** Matrix/TRC RGB display profile (v2) with D50-adapted primaries and a gamma curve */
+  function rgbProfile(description: string, primaries: number[][], gamma = 2.2): Buffer {
+    const curve = Buffer.alloc(14);
+    curve.write("curv", 0, "latin1");
+    curve.writeUInt32BE(1, 8);
+    curve.writeUInt16BE(Math.round(gamma * 256), 12);
+    const desc = Buffer.alloc(12 + description.length + 1 + 79);
+    desc.write("desc", 0, "latin1");
+    desc.writeUInt32BE(description.length + 1, 8);
+    desc.write(description, 12, "latin1");
+
+    const tags: [string, Buffer][] = [
+      ["desc", desc],
+      ["wtpt", xyz(0.9642, 1.0, 0.8249)],
+      ["rXYZ", xyz(primaries[0][0], primaries[0][1], primaries[0][2])],
+      ["gXYZ", xyz(primaries[1][0], primaries[1][1], primaries[1][2])],
+      ["bXYZ", xyz(primaries[2][0], primaries[2][1], primaries[2][2])],
+      ["rTRC", curve],
+      ["gTRC", curve],
+      ["bTRC", curve],
+    ];
+    const table = Buffer.alloc(4 + tags.length * 12);
+    table.writeUInt32BE(tags.length, 0);
+    const elements: Buffer[] = [];
+    let offset = 128 + table.length;
+    tags.forEach(([signature, data], i) => {
+      table.write(signature, 4 + i * 12, "latin1");
+      table.writeUInt32BE(offset, 8 + i * 12);
+      table.writeUInt32BE(data.length, 12 + i * 12);
+      const padded = Buffer.concat([data, Buffer.alloc((4 - (data.length % 4)) % 4)]);
+      elements.push(padded);
+      offset += padded.length;
+    });
+
+    const header = Buffer.alloc(128);
+    header.writeUInt32BE(offset, 0);
+    header.writeUInt32BE(0x02100000, 8);
+    header.write("mntrRGB XYZ ", 12, "latin1");
+    header.write("acsp", 36, "latin1");
+    // D50 illuminant
+    header.writeInt32BE(Math.round(0.9642 * 65536), 68);
+    header.writeInt32BE(65536, 72);
+    header.writeInt32BE(Math.round(0.8249 * 65536), 76);
+    return Buffer.concat([header, table, ...elements]);
+  }
+
+  const displayP3 = rgbProfile("Test P3", [
+    [0.5151, 0.2412, -0.0011],
+    [0.292, 0.6922, 0.0419],
+    [0.1571, 0.0666, 0.7841],
+  ]);
+
+  // Solid sRGB red
+  const red = { data: Buffer.alloc(16 * 16 * 3).fill(Buffer.from([255, 0, 0])), width: 16, height: 16, channels: 3 as const };
+
+  test("transform converts to a target profile and embeds it", async () => {
+    const source = await toPng(red);
+    const result = await transform(source, {
+      output: { format: "png" },
+      colorManagement: { targetProfile: displayP3, intent: "RelativeColorimetric" },
+    });
+
+    const info = await metadata(result, { icc: true });
+    expect(info.iccDescription).toBe("Test P3");
+
+    // sRGB red is less saturated in the wider P3 gamut
+    const { data } = await toRaw(result);
+    expect(data[0]).toBeLessThan(250);
+    expect(data[1]).toBeGreaterThan(20);
+  });
+
+  test("embedded profiles are converted to untagged sRGB", async () => {
+    const p3 = await transform(await toPng(red), {
+      output: { format: "png" },
+      colorManagement: { targetProfile: displayP3 },
+    });
+
+    const thumb = await thumbnail(p3, { width: 8, format: "Png", colorManagement: {}, keepIcc: true });
+    expect((await metadata(thumb.data, { icc: true })).iccProfile).toBeUndefined();
+
+    const { data } = await toRaw(thumb.data);
+    expect(data[0]).toBeGreaterThan(250);
+    expect(data[1]).toBeLessThan(5);
+  });
+
+  test("non-RGB target profiles are rejected", async () => {
+    await expect(
+      transform(testImage, { colorManagement: { targetProfile: Buffer.alloc(200) } })
+    ).rejects.toThrow();
+  });
+});
+
+describe("Metadata preservation", () => {
+  const tags = {
+    copyright: "(c) 2026 Jane Doe",
+    artist: "Jane Doe",
+    orientation: 6,
+    gpsLatitude: 43.2965,
+    gpsLongitude: 5.3698,
+  };
+
+  test("transform strips metadata by default", async () => {
+    const jpeg = await writeExif(testImage, tags);
+    const output = await transform(jpeg, { resize: { width: 50 }, output: { format: "jpeg" } });
+    const { exif } = await metadata(output, { exif: true });
+
+    expect(exif).toBeUndefined();
+  });
+
+  test("lossless JPEG rotation strips GPS-tagged EXIF", async () => {
+    const jpeg = await writeExif(testImage, tags);
+    for (const policy of [undefined, "strip" as const]) {
+      const output = await transform(jpeg, { rotate: 90, output: { format: "jpeg" }, metadata: policy });
+      const { exif, gps } = await metadata(output, { exif: true });
+
+      expect(exif).toBeUndefined();
+      expect(gps).toBeUndefined();
+    }
+  });
+
+  test('"keep" copies EXIF across formats', async () => {
+    const jpeg = await writeExif(testImage, tags);
+    const output = await transform(jpeg, {
+      resize: { width: 50 },
+      output: { format: "webp" },
+      metadata: "keep",
+    });
+    const { exif, gps, orientation } = await metadata(output, { exif: true });
+
+    expect(exif!.ifd0.Copyright).toBe(tags.copyright);
+    expect(exif!.ifd0.Artist).toBe(tags.artist);
+    expect(gps!.latitude).toBeCloseTo(tags.gpsLatitude, 4);
+    expect(orientation).toBe(6);
+  });
+
+  test("a keep list copies only the selected categories", async () => {
+    const jpeg = await writeExif(testImage, { ...tags, xmp: "<x:xmpmeta xmlns:x=\"adobe:ns:meta/\"/>" });
+    const output = await transform(jpeg, {
+      output: { format: "png" },
+      metadata: { keep: ["copyright", "xmp"] },
+    });
+    const { exif, gps, xmp } = await metadata(output, { exif: true, xmp: true });
+
+    expect(exif!.ifd0.Copyright).toBe(tags.copyright);
+    expect(exif!.ifd0.Artist).toBeUndefined();
+    expect(gps).toBeUndefined();
+    expect(xmp).toBeDefined();
+  });
+
+  test("rotating resets the kept orientation", async () => {
+    const jpeg = await writeExif(testImage, tags);
+    const output = await transform(jpeg, {
+      rotate: 90,
+      output: { format: "webp" },
+      metadata: { keep: ["exif"] },
+    });
+
+    expect((await metadata(output)).orientation).toBe(1);
+  });
+
+  test("thumbnail keeps metadata and explicit EXIF wins in transform", async () => {
+    const jpeg = await writeExif(testImage, tags);
+    const thumb = await thumbnail(jpeg, { width: 40, format: "Webp", metadata: { keep: ["artist"] } });
+    expect((await metadata(thumb.data, { exif: true })).exif!.ifd0.Artist).toBe(tags.artist);
+
+    const output = await transform(jpeg, {
+      output: { format: "jpeg" },
+      metadata: "keep",
+      exif: { artist: "Someone Else" },
+    });
+    expect((await metadata(output, { exif: true })).exif!.ifd0.Artist).toBe("Someone Else");
+  });
+
+  test("unknown categories are rejected", async () => {
+    await expect(
+      transform(testImage, { metadata: { keep: ["colour" as never] } })
+    ).rejects.toThrow();
+  });
+});
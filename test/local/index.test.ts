@@ -14,10 +14,14 @@ import {
   toWebpSync,
   transform,
   transformSync,
+  transformWithInfo,
+  transformWithInfoSync,
+  acceptedFormats,
   blurhash,
   blurhashSync,
   toTensor,
   toTensorSync,
+  writeExif,
   version,
 } from "../../dist/index.mjs";
 
@@ -273,6 +277,90 @@ describe("imgkit", () => {
 
       expect(highQuality.length).toBeGreaterThan(lowQuality.length);
     });
+
+    it("should encode progressive JPEG", async () => {
+      const jpeg = await toJpeg(testImage, { quality: 80, progressive: true });
+      const meta = await metadata(jpeg);
+
+      expect(meta.isProgressive).toBe(true);
+    });
+
+    it("should produce larger files with 4:4:4 chroma subsampling", async () => {
+      const full = await toJpeg(testImage, { quality: 80, chromaSubsampling: "4:4:4" });
+      const subsampled = await toJpeg(testImage, { quality: 80, chromaSubsampling: "4:2:0" });
+
+      expect(full.length).toBeGreaterThan(subsampled.length);
+    });
+
+    it("should encode with mozjpeg", async () => {
+      const turbo = await toJpeg(testImage, { quality: 75 });
+      const moz = await toJpeg(testImage, { quality: 75, mozjpeg: true });
+      const meta = await metadata(moz);
+
+      expect(meta.format).toBe("jpeg");
+      expect(meta.isProgressive).toBe(true);
+      expect(moz.length).toBeLessThan(turbo.length);
+    });
+
+    it("should encode baseline mozjpeg with custom settings", async () => {
+      const jpeg = await toJpeg(testImage, {
+        quality: 75,
+        mozjpeg: true,
+        progressive: false,
+        trellisQuantisation: false,
+        overshootDeringing: false,
+        quantTable: "AnnexK",
+      });
+      const meta = await metadata(jpeg);
+
+      expect(meta.isProgressive).toBe(false);
+    });
+
+    it("should reject invalid chroma subsampling", async () => {
+      await expect(
+        toJpeg(testImage, { chromaSubsampling: "4:1:1" as "4:2:0" })
+      ).rejects.toThrow();
+    });
+
+    it("should write grayscale transforms as single-channel JPEG", async () => {
+      const options = { resize: { width: 400 }, output: { format: "jpeg" as const } };
+      const color = await transform(testImage, options);
+      const gray = await transform(testImage, { ...options, grayscale: true });
+      const meta = await metadata(gray);
+
+      expect(meta.channels).toBe(1);
+      expect(meta.space).toBe("grayscale");
+      expect(gray.length).toBeLessThan(color.length);
+    });
+
+    it("should encode CMYK (turbojpeg and mozjpeg)", async () => {
+      for (const mozjpeg of [false, true]) {
+        const cmyk = await toJpeg(testImage, { colorSpace: "Cmyk", mozjpeg });
+        const meta = await metadata(cmyk);
+
+        expect(meta.channels).toBe(4);
+        expect(meta.space).toBe("cmyk");
+      }
+    });
+
+    it("should re-encode CMYK on a lossless-eligible JPEG rotation", async () => {
+      const jpeg = await toJpeg(testImage);
+      const rotated = await transform(jpeg, {
+        rotate: 90,
+        output: { format: "jpeg", jpeg: { colorSpace: "Cmyk" } },
+      });
+      const meta = await metadata(rotated);
+
+      expect(meta.channels).toBe(4);
+      expect(meta.space).toBe("cmyk");
+    });
+
+    it("should reject an invalid CMYK profile", async () => {
+      const notAProfile = Buffer.alloc(128);
+      await expect(
+        toJpeg(testImage, { colorSpace: "Cmyk", iccProfile: notAProfile })
+      ).rejects.toThrow();
+    });
   });
 
   describe("toPng", () => {
@@ -289,6 +377,78 @@ describe("imgkit", () => {
 
       expect(meta.format).toBe("png");
     });
+
+    it("should write palette PNG smaller than truecolor", async () => {
+      const truecolor = await toPng(testImage);
+      const palette = await toPng(testImage, { palette: true });
+      const meta = await metadata(palette);
+
+      expect(meta.isPalette).toBe(true);
+      expect(palette.length).toBeLessThan(truecolor.length);
+    });
+
+    it("should limit palette colors and dithering", async () => {
+      const small = await toPng(testImage, { colors: 16, dither: 0 });
+      const large = await toPng(testImage, { colors: 256, dither: 0 });
+      const meta = await metadata(small);
+
+      expect(meta.isPalette).toBe(true);
+      expect(meta.bitsPerSample).toBe(4);
+      expect(small.length).toBeLessThan(large.length);
+    });
+
+    it("should fall back to truecolor below minQuality", async () => {
+      const png = await toPng(testImage, { colors: 2, minQuality: 95 });
+      const meta = await metadata(png);
+
+      expect(meta.isPalette).toBe(false);
+    });
+
+    it("should reject invalid palette size", async () => {
+      await expect(toPng(testImage, { colors: 1 })).rejects.toThrow();
+    });
+
+    it("should optimize losslessly to a smaller PNG", async () => {
+      const png = await toPng(testImage, { compression: 9 });
+      const optimized = await toPng(testImage, { optimize: 4 });
+      const meta = await metadata(optimized);
+
+      expect(meta.format).toBe("png");
+      expect(meta.hasAlpha).toBe(false);
+      expect(optimized.length).toBeLessThanOrEqual(png.length);
+    });
+
+    it("should reduce grayscale input to a gray PNG", async () => {
+      const gray = await transform(testImage, {
+        resize: { width: 200 },
+        grayscale: true,
+        output: { format: "png", png: { optimize: 2 } },
+      });
+      const meta = await metadata(gray);
+
+      expect(meta.channels).toBe(1);
+      expect(meta.width).toBe(200);
+    });
+
+    it("should strip ancillary chunks unless keepMetadata is set", async () => {
+      const source = await writeExif(await toPng(testImage), { artist: "imgkit" });
+      const stripped = await toPng(source, { optimize: 1 });
+      const kept = await toPng(source, { optimize: 1, keepMetadata: true });
+
+      expect(stripped.includes("eXIf")).toBe(false);
+      expect(kept.includes("eXIf")).toBe(true);
+    });
+
+    it("should output quantized GIF via transform", async () => {
+      const gif = await transform(testImage, {
+        resize: { width: 200 },
+        output: { format: "gif", gif: { colors: 32, dither: 0.5 } },
+      });
+      const meta = await metadata(gif);
+
+      expect(meta.format).toBe("gif");
+      expect(meta.width).toBe(200);
+    });
   });
 
   describe("toWebp", () => {
@@ -312,6 +472,34 @@ describe("imgkit", () => {
 
       expect(webp.length).toBeLessThan(jpeg.length);
     });
+
+    it("should produce smaller output with a slower method", async () => {
+      const fast = await toWebp(testImage, { quality: 80, method: 0 });
+      const slow = await toWebp(testImage, { quality: 80, method: 6 });
+
+      expect(slow.length).toBeLessThan(fast.length);
+    });
+
+    it("should approach targetSize", async () => {
+      const webp = await toWebp(testImage, { targetSize: 20000 });
+      const meta = await metadata(webp);
+
+      expect(meta.format).toBe("webp");
+      expect(webp.length).toBeLessThan(20000 * 1.2);
+    });
+
+    it("should accept preset, sharp YUV and near-lossless", async () => {
+      const drawing = await toWebp(testImage, { preset: "Drawing", smartSubsample: true });
+      const nearLossless = await toWebp(testImage, { nearLossless: 60 });
+      const lossless = await toWebp(testImage, { lossless: true });
+
+      expect((await metadata(drawing)).format).toBe("webp");
+      expect(nearLossless.length).toBeLessThan(lossless.length);
+    });
+
+    it("should reject invalid method", async () => {
+      await expect(toWebp(testImage, { method: 9 })).rejects.toThrow();
+    });
   });
 
   describe("transform", () => {
@@ -383,6 +571,198 @@ describe("imgkit", () => {
       expect(meta.width).toBe(100);
       expect(meta.height).toBe(100);
     });
+
+    it("should fit output under maxBytes (async)", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 600 },
+        output: { format: "jpeg", jpeg: { quality: 95 }, maxBytes: 25000 },
+      });
+
+      expect(result.data.length).toBeLessThanOrEqual(25000);
+      expect(result.width).toBe(600);
+      expect(result.quality).toBeLessThan(95);
+      expect(result.attempts).toBeGreaterThan(1);
+    });
+
+    it("should report dimensions without maxBytes (sync)", () => {
+      const result = transformWithInfoSync(testImage, {
+        resize: { width: 300 },
+        output: { format: "webp" },
+      });
+
+      expect(result.width).toBe(300);
+      expect(result.quality).toBeUndefined();
+      expect(result.attempts).toBeUndefined();
+    });
+
+    it("should reject maxBytes for lossless formats", async () => {
+      await expect(
+        transform(testImage, { output: { format: "png", maxBytes: 10000 } })
+      ).rejects.toThrow();
+    });
+  });
+
+  describe("targetQuality", () => {
+    it("should pick the lowest quality meeting the SSIM target", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 400 },
+        output: { format: "jpeg", targetQuality: { metric: "Ssim", value: 0.95 } },
+      });
+
+      expect(result.score).toBeGreaterThanOrEqual(0.95);
+      expect(result.quality).toBeLessThan(100);
+      expect(result.attempts).toBeGreaterThan(1);
+    });
+
+    it("should need a higher quality for a stricter target (sync)", () => {
+      const loose = transformWithInfoSync(testImage, {
+        resize: { width: 400 },
+        output: { format: "webp", targetQuality: { value: 0.9 } },
+      });
+      const strict = transformWithInfoSync(testImage, {
+        resize: { width: 400 },
+        output: { format: "webp", targetQuality: { value: 0.99 } },
+      });
+
+      expect(strict.quality!).toBeGreaterThanOrEqual(loose.quality!);
+      expect(strict.data.length).toBeGreaterThanOrEqual(loose.data.length);
+    });
+
+    it("should treat Dssim as an upper bound", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 400 },
+        output: { format: "jpeg", targetQuality: { metric: "Dssim", value: 0.02 } },
+      });
+
+      expect(result.score).toBeLessThanOrEqual(0.02);
+    });
+
+    it("should let maxBytes cap the target", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 400 },
+        output: { format: "jpeg", maxBytes: 5000, targetQuality: { value: 0.999 } },
+      });
+
+      expect(result.data.length).toBeLessThanOrEqual(5000);
+      expect(result.score).toBeUndefined();
+    });
+  });
+
+  describe("additional formats", () => {
+    for (const format of ["qoi", "pnm", "tga"] as const) {
+      it(`should round-trip ${format}`, async () => {
+        const encoded = await transform(testImage, {
+          resize: { width: 64 },
+          output: { format },
+        });
+        const meta = await metadata(encoded);
+
+        expect(meta.format).toBe(format);
+        expect(meta.width).toBe(64);
+
+        const png = await toPng(encoded);
+        expect((await metadata(png)).width).toBe(64);
+      });
+    }
+
+    it("should keep grayscale single-channel in pnm", async () => {
+      const pgm = await transform(testImage, {
+        resize: { width: 32 },
+        grayscale: true,
+        output: { format: "pnm" },
+      });
+
+      expect(pgm.subarray(0, 2).toString()).toBe("P5");
+      expect((await metadata(pgm)).channels).toBe(1);
+    });
+
+    it("should decode Radiance HDR", async () => {
+      const hdr = Buffer.concat([
+        Buffer.from("#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 2 +X 2\n"),
+        Buffer.from([128, 64, 32, 129, 128, 64, 32, 129, 128, 64, 32, 129, 128, 64, 32, 129]),
+      ]);
+      const meta = await metadata(hdr);
+
+      expect(meta.format).toBe("hdr");
+      expect(meta.depth).toBe("float");
+
+      const jpeg = await toJpeg(hdr);
+      expect((await metadata(jpeg)).width).toBe(2);
+    });
+  });
+
+  describe("auto format", () => {
+    // Two flat colors with transparency, like an icon
+    const icon = {
+      data: Buffer.from(
+        Array.from({ length: 32 * 32 }, (_, i) => (i % 32 < 16 ? [255, 0, 0, 255] : [0, 0, 0, 0])).flat()
+      ),
+      width: 32,
+      height: 32,
+      channels: 4 as const,
+    };
+
+    it("should pick WebP for a photo", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 400 },
+        output: { format: "auto" },
+      });
+
+      expect(result.format).toBe("webp");
+      expect((await metadata(result.data)).format).toBe("webp");
+    });
+
+    it("should fall back to JPEG when WebP is not accepted", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 400 },
+        output: { format: "auto", accept: ["jpeg", "png"] },
+      });
+
+      expect(result.format).toBe("jpeg");
+    });
+
+    it("should pick PNG-8 for few colors (sync)", () => {
+      const result = transformWithInfoSync(icon, { output: { format: "auto" } });
+      const meta = metadataSync(result.data);
+
+      expect(result.format).toBe("png");
+      expect(meta.hasAlpha).toBe(true);
+    });
+
+    it("should stay lossy with maxBytes", async () => {
+      const result = await transformWithInfo(icon, {
+        output: { format: "auto", accept: ["png", "jpeg"], maxBytes: 10_000 },
+      });
+
+      expect(result.format).toBe("jpeg");
+      expect(result.quality).toBeDefined();
+    });
+
+    it("should reject when only AVIF is accepted", async () => {
+      await expect(
+        transformWithInfo(testImage, { output: { format: "auto", accept: ["avif"] } })
+      ).rejects.toThrow();
+    });
+
+    it("should parse an Accept header", () => {
+      expect(acceptedFormats("image/avif,image/webp,image/apng,image/*,*/*;q=0.8")).toEqual([
+        "jpeg",
+        "png",
+        "avif",
+        "webp",
+      ]);
+      expect(acceptedFormats("image/webp;q=0")).toEqual(["jpeg", "png"]);
+      expect(acceptedFormats(undefined)).toEqual(["jpeg", "png"]);
+    });
+
+    it("should report the format for fixed outputs", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 64 },
+        output: { format: "jpeg" },
+      });
+
+      expect(result.format).toBe("jpeg");
+    });
   });
 
   describe("blurhash", () => {
@@ -0,0 +1,143 @@
+/**
+ * Lossless JPEG Transform Tests
+ *
+ * Tests for rotate/flip/crop on JPEG DCT coefficients without recompression.
+ */
+
+import { describe, it, expect, beforeAll } from "bun:test";
+import {
+  jpegLosslessTransform,
+  jpegLosslessTransformSync,
+  transform,
+  writeExif,
+  metadata,
+} from "../../dist";
+
+// 640x480 is a multiple of the 16x16 MCU, so every transform is perfect
+let testJpeg: Buffer;
+
+beforeAll(async () => {
+  const response = await fetch("https://picsum.photos/800/600");
+  const source = Buffer.from(await response.arrayBuffer());
+
+  const resized = await transform(source, {
+    resize: { width: 640, height: 480, fit: "fill" },
+    output: { format: "jpeg", jpeg: { quality: 90 } },
+  });
+  testJpeg = await writeExif(resized, { orientation: 6 });
+});
+
+describe("jpegLosslessTransform", () => {
+  it("should rotate and swap dimensions (async)", async () => {
+    const result = await jpegLosslessTransform(testJpeg, { rotate: 90 });
+    const meta = await metadata(result);
+
+    expect(meta.format).toBe("jpeg");
+    expect(meta.width).toBe(480);
+    expect(meta.height).toBe(640);
+  });
+
+  it("should flip (sync)", () => {
+    const result = jpegLosslessTransformSync(testJpeg, { flipH: true, flipV: true });
+    expect(result[0]).toBe(0xff);
+    expect(result[1]).toBe(0xd8);
+  });
+
+  it("should preserve EXIF markers", async () => {
+    const result = await jpegLosslessTransform(testJpeg, { rotate: 180 });
+    const meta = await metadata(result);
+
+    expect(meta.orientation).toBe(6);
+  });
+
+  it("should reset orientation", async () => {
+    const result = await jpegLosslessTransform(testJpeg, {
+      rotate: 90,
+      resetOrientation: true,
+    });
+    const meta = await metadata(result);
+
+    expect(meta.orientation).toBe(1);
+  });
+
+  it("should strip metadata", async () => {
+    const result = await jpegLosslessTransform(testJpeg, {
+      flipH: true,
+      stripMetadata: true,
+    });
+    const meta = await metadata(result);
+
+    expect(meta.orientation).toBeUndefined();
+  });
+
+  it("should crop on MCU boundaries", async () => {
+    const result = await jpegLosslessTransform(testJpeg, {
+      crop: { x: 64, y: 48, width: 320, height: 240 },
+    });
+    const meta = await metadata(result);
+
+    expect(meta.width).toBe(320);
+    expect(meta.height).toBe(240);
+  });
+
+  it("should crop before rotating", async () => {
+    const result = await jpegLosslessTransform(testJpeg, {
+      crop: { x: 0, y: 0, width: 320, height: 160 },
+      rotate: 90,
+    });
+    const meta = await metadata(result);
+
+    expect(meta.width).toBe(160);
+    expect(meta.height).toBe(320);
+  });
+
+  it("should reject unaligned crop origin", async () => {
+    await expect(
+      jpegLosslessTransform(testJpeg, {
+        crop: { x: 3, y: 5, width: 100, height: 100 },
+      })
+    ).rejects.toThrow();
+  });
+
+  it("should reject invalid rotation", () => {
+    expect(() => jpegLosslessTransformSync(testJpeg, { rotate: 45 })).toThrow();
+  });
+});
+
+describe("transform lossless fast path", () => {
+  it("should keep EXIF when only rotating JPEG to JPEG", async () => {
+    const result = await transform(testJpeg, {
+      rotate: 90,
+      output: { format: "jpeg" },
+    });
+    const meta = await metadata(result);
+
+    expect(meta.width).toBe(480);
+    expect(meta.height).toBe(640);
+    // Re-encoding drops EXIF, the lossless path copies it
+    expect(meta.orientation).toBe(6);
+  });
+
+  it("should fall back to re-encoding for unaligned crops", async () => {
+    const result = await transform(testJpeg, {
+      crop: { x: 3, y: 5, width: 100, height: 100 },
+      output: { format: "jpeg" },
+    });
+    const meta = await metadata(result);
+
+    expect(meta.width).toBe(100);
+    expect(meta.height).toBe(100);
+  });
+
+  it("should re-encode when resizing", async () => {
+    const result = await transform(testJpeg, {
+      rotate: 90,
+      resize: { width: 120 },
+      output: { format: "jpeg" },
+    });
+    const meta = await metadata(result);
+
+    expect(meta.width).toBe(120);
+    expect(meta.orientation).toBeUndefined();
+  });
+});
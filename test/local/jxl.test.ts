@@ -0,0 +1,75 @@
+import { describe, it, expect, beforeAll } from "bun:test";
+import { existsSync } from "fs";
+import { join } from "path";
+import { metadata, toJpeg, transform } from "../../dist/index.mjs";
+
+const jxlPath = join(import.meta.dir, "..", "examples", "image.jxl");
+let jxlImage: Buffer | null = null;
+
+// Smallest codestream: 8x8, default 8-bit sRGB image metadata
+const codestreamHeader = Buffer.from([0xff, 0x0a, 0x41, 0x02]);
+
+// Same header wrapped in the ISOBMFF container (signature, ftyp, jxlc)
+const containerHeader = Buffer.concat([
+  Buffer.from([0, 0, 0, 0x0c, 0x4a, 0x58, 0x4c, 0x20, 0x0d, 0x0a, 0x87, 0x0a]),
+  Buffer.from([0, 0, 0, 20]),
+  Buffer.from("ftypjxl \0\0\0\0jxl ", "binary"),
+  Buffer.from([0, 0, 0, 12]),
+  Buffer.from("jxlc"),
+  codestreamHeader,
+]);
+
+beforeAll(async () => {
+  if (existsSync(jxlPath)) {
+    jxlImage = Buffer.from(await Bun.file(jxlPath).arrayBuffer());
+  }
+});
+
+describe("JPEG XL Support", () => {
+  it("should read metadata from a bare codestream header", async () => {
+    const meta = await metadata(codestreamHeader);
+
+    expect(meta.format).toBe("jxl");
+    expect(meta.width).toBe(8);
+    expect(meta.height).toBe(8);
+    expect(meta.bitsPerSample).toBe(8);
+    expect(meta.hasAlpha).toBe(false);
+    expect(meta.hasProfile).toBe(false);
+  });
+
+  it("should read metadata from a container header", async () => {
+    const meta = await metadata(containerHeader);
+
+    expect(meta.format).toBe("jxl");
+    expect(meta.width).toBe(8);
+    expect(meta.height).toBe(8);
+  });
+
+  it("should convert JXL to JPEG", async () => {
+    if (!jxlImage) {
+      console.log("Skipping: No JXL test file found");
+      return;
+    }
+
+    const meta = await metadata(jxlImage);
+    const jpeg = await toJpeg(jxlImage, { quality: 85 });
+    const jpegMeta = await metadata(jpeg);
+
+    expect(jpegMeta.format).toBe("jpeg");
+    expect(jpegMeta.width).toBe(meta.width);
+    expect(jpegMeta.height).toBe(meta.height);
+  });
+
+  it("should resize JXL", async () => {
+    if (!jxlImage) return;
+
+    const result = await transform(jxlImage, {
+      resize: { width: 200 },
+      output: { format: "webp" },
+    });
+    const meta = await metadata(result);
+
+    expect(meta.format).toBe("webp");
+    expect(meta.width).toBe(200);
+  });
+});
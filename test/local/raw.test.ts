@@ -0,0 +1,101 @@
+import { describe, it, expect, beforeAll } from "bun:test";
+import {
+  metadata,
+  resize,
+  toPng,
+  toRaw,
+  toRawSync,
+  transform,
+  transformWithInfo,
+} from "../../dist";
+
+let testImage: Buffer;
+
+// 2x2 RGBA: red, green, blue, transparent white
+const rgba = {
+  data: new Uint8ClampedArray([255, 0, 0, 255, 0, 255, 0, 255, 0, 0, 255, 255, 255, 255, 255, 0]),
+  width: 2,
+  height: 2,
+  channels: 4 as const,
+};
+
+beforeAll(async () => {
+  const response = await fetch("https://picsum.photos/400/300");
+  testImage = Buffer.from(await response.arrayBuffer());
+});
+
+describe("raw pixels", () => {
+  describe("input", () => {
+    it("should read metadata from raw pixels", async () => {
+      const meta = await metadata(rgba);
+
+      expect(meta.format).toBe("raw");
+      expect(meta.width).toBe(2);
+      expect(meta.channels).toBe(4);
+      expect(meta.hasAlpha).toBe(true);
+    });
+
+    it("should encode raw pixels without a codec round-trip", async () => {
+      const png = await toPng(rgba);
+      const meta = await metadata(png);
+
+      expect(meta.format).toBe("png");
+      expect(meta.width).toBe(2);
+      expect(meta.hasAlpha).toBe(true);
+    });
+
+    it("should resize grayscale raw pixels", async () => {
+      const gray = { data: Buffer.alloc(16 * 8, 128), width: 16, height: 8, channels: 1 as const };
+      const resized = await resize(gray, { width: 8 });
+      const meta = await metadata(resized);
+
+      expect(meta.width).toBe(8);
+      expect(meta.height).toBe(4);
+    });
+
+    it("should reject a buffer that doesn't match the dimensions", async () => {
+      await expect(
+        toPng({ data: Buffer.alloc(10), width: 2, height: 2, channels: 4 })
+      ).rejects.toThrow();
+    });
+  });
+
+  describe("output", () => {
+    it("should decode to raw pixels", async () => {
+      const raw = await toRaw(testImage);
+
+      expect(raw.width).toBe(400);
+      expect(raw.height).toBe(300);
+      expect(raw.channels).toBe(3);
+      expect(raw.data.length).toBe(400 * 300 * 3);
+    });
+
+    it("should round-trip raw pixels unchanged", () => {
+      const raw = toRawSync(rgba);
+
+      expect(raw.channels).toBe(4);
+      expect([...raw.data]).toEqual([...rgba.data]);
+    });
+
+    it("should return raw output with channels from transformWithInfo", async () => {
+      const result = await transformWithInfo(testImage, {
+        resize: { width: 100 },
+        grayscale: true,
+        output: { format: "raw" },
+      });
+
+      expect(result.width).toBe(100);
+      expect(result.channels).toBe(1);
+      expect(result.data.length).toBe(100 * result.height);
+    });
+
+    it("should return tightly packed pixels from transform", async () => {
+      const data = await transform(testImage, {
+        resize: { width: 10, height: 10, fit: "fill" },
+        output: { format: "raw" },
+      });
+
+      expect(data.length).toBe(10 * 10 * 3);
+    });
+  });
+});
@@ -202,6 +202,68 @@ describe("thumbnail", () => {
     });
   });
 
+  describe("maxBytes", () => {
+    it("should search quality to fit the byte budget", async () => {
+      const result = await thumbnail(testImageJpeg, {
+        width: 800,
+        format: "Jpeg",
+        quality: 95,
+        maxBytes: 30000,
+      });
+
+      expect(result.data.length).toBeLessThanOrEqual(30000);
+      expect(result.quality).toBeLessThan(95);
+      expect(result.attempts).toBeGreaterThan(1);
+      expect(result.width).toBe(800);
+    });
+
+    it("should keep quality when already under budget", async () => {
+      const result = await thumbnail(testImageJpeg, {
+        width: 100,
+        format: "Webp",
+        quality: 80,
+        maxBytes: 1_000_000,
+      });
+
+      expect(result.quality).toBe(80);
+      expect(result.attempts).toBe(1);
+    });
+
+    it("should step dimensions down with downscaleToFit", async () => {
+      const result = await thumbnail(testImageJpeg, {
+        width: 1600,
+        format: "Jpeg",
+        maxBytes: 8000,
+        downscaleToFit: true,
+      });
+
+      expect(result.data.length).toBeLessThanOrEqual(8000);
+      expect(result.width).toBeLessThan(1600);
+    });
+
+    it("should fail when the budget can't be met", async () => {
+      await expect(
+        thumbnail(testImageJpeg, { width: 1600, format: "Jpeg", maxBytes: 500 })
+      ).rejects.toThrow();
+    });
+
+    it("should reject explicit PNG output", async () => {
+      await expect(
+        thumbnail(testImageJpeg, { width: 300, format: "Png", maxBytes: 20000 })
+      ).rejects.toThrow("requires lossy thumbnail output");
+    });
+
+    it("should default PNG input to WebP output", async () => {
+      if (!testImagePng || testImagePng.length === 0) {
+        return;
+      }
+      const result = await thumbnail(testImagePng, { width: 300, maxBytes: 20000 });
+
+      expect(result.format).toBe("webp");
+      expect(result.data.length).toBeLessThanOrEqual(20000);
+    });
+  });
+
   describe("different input formats", () => {
     it("should handle JPEG input", async () => {
       const result = await thumbnail(testImageJpeg, { width: 200 });